@@ -485,8 +485,11 @@ impl E2EContext {
         // address_type: 0 = P2PKH (legacy), 1 = P2SH
         let address_type = 0u32;
 
+        // each user withdraws once, so the first receipt nonce is always free
+        let receipt_nonce = 0u64;
+
         let sig = self.bridge_client
-            .request_withdrawal(&user_keypair, recipient_doge_address, amount_sats, address_type)
+            .request_withdrawal(&user_keypair, recipient_doge_address, amount_sats, address_type, receipt_nonce)
             .await?;
 
         println!("Withdrawal requested! Signature: {}", sig);
@@ -19,7 +19,7 @@ use psy_doge_solana_core::{
     instructions::doge_bridge::InitializeBridgeParams,
     program_state::{PsyBridgeConfig, PsyReturnTxOutput},
 };
-use doge_bridge_client::instructions::initialize_bridge;
+use doge_bridge_client::instructions::initialize_bridge_with_authorities;
 
 /// JSON structure for InitializeBridgeInstructionData configuration file
 #[derive(serde::Deserialize, Debug)]
@@ -28,6 +28,12 @@ pub struct InitializeBridgeConfigFile {
     pub operator_pubkey: String,
     /// Fee spender public key (base58 string)
     pub fee_spender_pubkey: String,
+    /// Guardian public key allowed to pause the bridge (base58 string, defaults to the operator)
+    #[serde(default)]
+    pub guardian_pubkey: Option<String>,
+    /// Admin public key allowed to force key rotations (base58 string, optional)
+    #[serde(default)]
+    pub admin_pubkey: Option<String>,
     /// DOGE token mint address (base58 string)
     pub doge_mint: String,
     /// Bridge header configuration
@@ -178,6 +184,24 @@ pub fn execute(rpc_url: &str, keypair_path: Option<PathBuf>, args: InitializeBri
     };
     println!("  Fee Spender: {}", fee_spender_pubkey);
 
+    // Resolve guardian pubkey (defaults to the operator)
+    let guardian_pubkey = match &config.guardian_pubkey {
+        Some(guardian) => Pubkey::from_str(guardian)
+            .with_context(|| format!("Invalid guardian pubkey: {}", guardian))?,
+        None => operator_pubkey,
+    };
+    println!("  Guardian: {}", guardian_pubkey);
+
+    let admin_pubkey = match &config.admin_pubkey {
+        Some(admin) => Some(
+            Pubkey::from_str(admin).with_context(|| format!("Invalid admin pubkey: {}", admin))?,
+        ),
+        None => None,
+    };
+    if let Some(admin) = admin_pubkey {
+        println!("  Admin: {}", admin);
+    }
+
     // Resolve DOGE mint (CLI override or from config)
     let doge_mint = if let Some(mint_str) = &args.doge_mint {
         Pubkey::from_str(mint_str)
@@ -236,10 +260,12 @@ pub fn execute(rpc_url: &str, keypair_path: Option<PathBuf>, args: InitializeBri
     };
 
     // Create initialize instruction
-    let init_ix = initialize_bridge(
+    let init_ix = initialize_bridge_with_authorities(
         payer.pubkey(),
         operator_pubkey,
         fee_spender_pubkey,
+        guardian_pubkey,
+        admin_pubkey,
         doge_mint,
         &init_params,
     );
@@ -28,6 +28,8 @@ import {
   blockUpdate,
   processReorgBlocks,
   requestWithdrawal,
+  requestWithdrawalsBatch,
+  requestScriptWithdrawal,
   processWithdrawal,
   processReplayWithdrawal,
   processManualDeposit,
@@ -36,6 +38,23 @@ import {
   processMintGroupAutoAdvance,
   operatorWithdrawFees,
   snapshotWithdrawals,
+  getWithdrawalReceiptPda,
+  markWithdrawalReceiptsFulfilled,
+  closeWithdrawalReceipt,
+  cancelWithdrawal,
+  getFeeSplitPda,
+  setFeeSplit,
+  distributeFees,
+  getBlockHashArchiveSegmentPda,
+  blockHashArchiveAccounts,
+  reorgBlockHashArchiveAccounts,
+  initBlockHashArchive,
+  migrateState,
+  getCustodianWalletConfigPda,
+  storeCustodianWalletConfig,
+  scheduleCustodianRotation,
+  cancelCustodianRotation,
+  processCustodianSweep,
 } from "./instructions";
 import {
   createGenericBuffer,
@@ -49,21 +68,42 @@ import {
   PsyReturnTxOutput,
   PsyBridgeProgramState,
   PsyWithdrawalChainSnapshot,
+  PsyWithdrawalRequest,
   InitializeBridgeParams,
   FinalizedBlockMintTxoInfo,
   PendingMint,
   CompactBridgeZKProof,
   ProcessMintsResult,
   DepositTxOutputRecord,
+  WithdrawalReceipt,
+  FeeSplitTable,
+  BlockHashArchiveEntry,
+  BlockHashArchiveSegment,
+  Bridge7MultisigCustodianWalletConfig,
   emptyProcessMintsResult,
   decodePsyBridgeHeader,
   decodePsyReturnTxOutput,
   decodePsyBridgeConfig,
+  decodeWithdrawalReceipt,
+  decodeFeeSplitTable,
+  decodeBlockHashArchiveSegment,
+  decodeBridgeStateLayout,
+  decodeBridge7MultisigCustodianWalletConfig,
+  decodeCustodianRotation,
+  CustodianRotation,
   PSY_BRIDGE_HEADER_SIZE,
   PSY_BRIDGE_CONFIG_SIZE,
   PSY_RETURN_TX_OUTPUT_SIZE,
 } from "./types";
-import { PM_MAX_PENDING_MINTS_PER_GROUP } from "./constants";
+import {
+  BRIDGE_STATE_NEXT_WITHDRAWAL_INDEX_OFFSET,
+  BRIDGE_STATE_BLOCK_HASH_ARCHIVE_LEN_OFFSET,
+  BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY,
+  BRIDGE_STATE_CURRENT_VERSION,
+  PM_MAX_PENDING_MINTS_PER_GROUP,
+  MAX_WITHDRAWAL_REQUESTS_PER_BATCH,
+  MAX_WITHDRAWAL_SCRIPT_SIZE,
+} from "./constants";
 
 /**
  * Main client for interacting with the Doge bridge on Solana.
@@ -145,9 +185,8 @@ export class BridgeClient {
     if (!account) {
       throw BridgeError.accountNotFound(this.config.bridgeStatePda.toString());
     }
-    // DOGE mint is stored after the header, config, and other fields
-    // The exact offset depends on the state layout
-    return new PublicKey(account.data.subarray(account.data.length - 32));
+    const { dogeMintOffset } = decodeBridgeStateLayout(account.data);
+    return new PublicKey(account.data.subarray(dogeMintOffset, dogeMintOffset + 32));
   }
 
   /**
@@ -202,7 +241,7 @@ export class BridgeClient {
     }
 
     const data = account.data;
-    let offset = 0;
+    let offset = decodeBridgeStateLayout(data).coreOffset;
 
     const header = decodePsyBridgeHeader(data, offset);
     offset += PSY_BRIDGE_HEADER_SIZE;
@@ -394,22 +433,41 @@ export class BridgeClient {
       this.config.pendingMintProgramId,
       this.config.txoBufferProgramId
     );
+    ix.keys.push(...blockHashArchiveAccounts(this.config.programId, await this.getBlockHashArchiveLen()));
 
     return this.sendWithRetry([ix], [this.config.operator]);
   }
 
   /**
    * Process a block reorganization.
+   *
+   * While the block hash archive is enabled, a reorg that finalizes more than one height also takes
+   * reorgBlockHashes: the block merkle tree siblings of the current finalized block followed by the
+   * hashes of the heights between it and the new finalized block.
    */
   async processBlockReorg(
     proof: CompactBridgeZKProof,
     header: PsyBridgeHeader,
     extraBlocks: FinalizedBlockMintTxoInfo[],
+    reorgBlockHashes: Uint8Array[],
     mintBufferAccount: PublicKey,
     mintBufferBump: number,
     txoBufferAccount: PublicKey,
     txoBufferBump: number
   ): Promise<TransactionSignature> {
+    const blockHashArchiveLen = await this.getBlockHashArchiveLen();
+    const finalizedBlocksCount = extraBlocks.length + 1;
+    let reorgBlockHashesBuffer: PublicKey | null = null;
+    if (blockHashArchiveLen !== 0n && finalizedBlocksCount > 1) {
+      const data = new Uint8Array(reorgBlockHashes.length * 32);
+      reorgBlockHashes.forEach((hash, i) => data.set(hash, i * 32));
+      reorgBlockHashesBuffer = await createGenericBuffer(
+        this.connection,
+        this.config.genericBufferProgramId,
+        this.config.payer,
+        data
+      );
+    }
     const ix = processReorgBlocks(
       this.config.programId,
       this.config.payer.publicKey,
@@ -424,6 +482,14 @@ export class BridgeClient {
       this.config.pendingMintProgramId,
       this.config.txoBufferProgramId
     );
+    ix.keys.push(
+      ...reorgBlockHashArchiveAccounts(
+        this.config.programId,
+        blockHashArchiveLen,
+        finalizedBlocksCount,
+        reorgBlockHashesBuffer
+      )
+    );
 
     return this.sendWithRetry([ix], [this.config.operator]);
   }
@@ -477,29 +543,217 @@ export class BridgeClient {
 
   /**
    * Request a withdrawal from Solana to Dogecoin.
+   * receiptNonce keys the request's receipt and must not be used by another open receipt of the user.
    */
   async requestWithdrawal(
     userAuthority: Keypair,
     recipientAddress: Uint8Array,
     amountSats: bigint,
-    addressType: number
+    addressType: number,
+    receiptNonce: bigint
   ): Promise<TransactionSignature> {
     const mint = await this.getDogeMint();
     const userTokenAccount = await getAssociatedTokenAddress(mint, userAuthority.publicKey);
 
     const ix = requestWithdrawal(
       this.config.programId,
+      this.config.payer.publicKey,
       userAuthority.publicKey,
       mint,
       userTokenAccount,
       recipientAddress,
       amountSats,
-      addressType
+      addressType,
+      receiptNonce
+    );
+
+    return this.sendAndConfirm([ix], [userAuthority]);
+  }
+
+  /**
+   * Request several withdrawals in one transaction, burning their total once.
+   * Each request is charged its own fee and gets its own receipt, keyed by consecutive nonces
+   * starting at firstReceiptNonce.
+   */
+  async requestWithdrawalsBatch(
+    userAuthority: Keypair,
+    requests: PsyWithdrawalRequest[],
+    firstReceiptNonce: bigint
+  ): Promise<TransactionSignature> {
+    if (requests.length === 0 || requests.length > MAX_WITHDRAWAL_REQUESTS_PER_BATCH) {
+      throw BridgeError.invalidInput(
+        `withdrawal batch must hold 1 to ${MAX_WITHDRAWAL_REQUESTS_PER_BATCH} requests, got ${requests.length}`
+      );
+    }
+    const mint = await this.getDogeMint();
+    const userTokenAccount = await getAssociatedTokenAddress(mint, userAuthority.publicKey);
+
+    const ix = requestWithdrawalsBatch(
+      this.config.programId,
+      this.config.payer.publicKey,
+      userAuthority.publicKey,
+      mint,
+      userTokenAccount,
+      requests,
+      firstReceiptNonce
+    );
+
+    return this.sendAndConfirm([ix], [userAuthority]);
+  }
+
+  /**
+   * Request a withdrawal to an arbitrary scriptPubKey, e.g. one carrying an exchange memo/tag.
+   * The queued request commits to the script's hash160.
+   */
+  async requestScriptWithdrawal(
+    userAuthority: Keypair,
+    scriptPubKey: Uint8Array,
+    amountSats: bigint,
+    receiptNonce: bigint
+  ): Promise<TransactionSignature> {
+    if (scriptPubKey.length === 0 || scriptPubKey.length > MAX_WITHDRAWAL_SCRIPT_SIZE) {
+      throw BridgeError.invalidInput(
+        `withdrawal script must be 1 to ${MAX_WITHDRAWAL_SCRIPT_SIZE} bytes, got ${scriptPubKey.length}`
+      );
+    }
+    const mint = await this.getDogeMint();
+    const userTokenAccount = await getAssociatedTokenAddress(mint, userAuthority.publicKey);
+
+    const ix = requestScriptWithdrawal(
+      this.config.programId,
+      this.config.payer.publicKey,
+      userAuthority.publicKey,
+      mint,
+      userTokenAccount,
+      scriptPubKey,
+      amountSats,
+      receiptNonce
     );
 
     return this.sendAndConfirm([ix], [userAuthority]);
   }
 
+  /**
+   * Get the index the next withdrawal request will be assigned.
+   */
+  async getNextWithdrawalIndex(): Promise<bigint> {
+    const account = await this.connection.getAccountInfo(this.config.bridgeStatePda);
+    if (!account) {
+      throw BridgeError.accountNotFound(this.config.bridgeStatePda.toString());
+    }
+    const { coreOffset } = decodeBridgeStateLayout(account.data);
+    const view = new DataView(account.data.buffer, account.data.byteOffset);
+    return view.getBigUint64(coreOffset + BRIDGE_STATE_NEXT_WITHDRAWAL_INDEX_OFFSET, true);
+  }
+
+  /**
+   * Get the number of blocks in the block hash archive, 0 if it is not enabled.
+   */
+  async getBlockHashArchiveLen(): Promise<bigint> {
+    const account = await this.connection.getAccountInfo(this.config.bridgeStatePda);
+    if (!account) {
+      throw BridgeError.accountNotFound(this.config.bridgeStatePda.toString());
+    }
+    const { version, coreOffset } = decodeBridgeStateLayout(account.data);
+    // the field was added in version 1
+    if (version === 0) {
+      return 0n;
+    }
+    const view = new DataView(account.data.buffer, account.data.byteOffset);
+    return view.getBigUint64(coreOffset + BRIDGE_STATE_BLOCK_HASH_ARCHIVE_LEN_OFFSET, true);
+  }
+
+  /**
+   * Look up a finalized block in the block hash archive. Returns null if the archive is disabled,
+   * the height predates it, or it is not finalized yet.
+   */
+  async getArchivedBlock(blockHeight: number): Promise<BlockHashArchiveEntry | null> {
+    const archiveLen = await this.getBlockHashArchiveLen();
+    if (archiveLen === 0n) {
+      return null;
+    }
+    const segmentCount = Number((archiveLen - 1n) / BigInt(BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY)) + 1;
+
+    // segments are filled in height order, find the last one starting at or below blockHeight
+    let low = 0;
+    let high = segmentCount;
+    let candidate: BlockHashArchiveSegment | null = null;
+    while (low < high) {
+      const mid = Math.floor((low + high) / 2);
+      const [segmentPda] = getBlockHashArchiveSegmentPda(mid, this.config.programId);
+      const account = await this.connection.getAccountInfo(segmentPda);
+      if (!account) {
+        throw BridgeError.accountNotFound(segmentPda.toString());
+      }
+      const segment = decodeBlockHashArchiveSegment(account.data);
+      if (segment.firstBlockHeight <= blockHeight) {
+        candidate = segment;
+        low = mid + 1;
+      } else {
+        high = mid;
+      }
+    }
+
+    return candidate?.entries.find((entry) => entry.blockHeight === blockHeight) ?? null;
+  }
+
+  /**
+   * Get the receipt of a withdrawal request, null if it does not exist or has been closed.
+   */
+  async getWithdrawalReceipt(
+    requester: PublicKey,
+    receiptNonce: bigint
+  ): Promise<WithdrawalReceipt | null> {
+    const [receiptPda] = getWithdrawalReceiptPda(requester, receiptNonce, this.config.programId);
+    const account = await this.connection.getAccountInfo(receiptPda);
+    if (!account) {
+      return null;
+    }
+    return decodeWithdrawalReceipt(account.data);
+  }
+
+  /**
+   * Mark receipts fulfilled once their withdrawals have been processed.
+   */
+  async markWithdrawalReceiptsFulfilled(
+    withdrawalReceipts: PublicKey[]
+  ): Promise<TransactionSignature> {
+    const ix = markWithdrawalReceiptsFulfilled(this.config.programId, withdrawalReceipts);
+    return this.sendAndConfirm([ix]);
+  }
+
+  /**
+   * Cancel a withdrawal request that has not been snapshotted yet.
+   * The burned amount, minus the cancellation fee, is minted back to the requester's ATA.
+   */
+  async cancelWithdrawal(
+    requester: Keypair,
+    receiptNonce: bigint
+  ): Promise<TransactionSignature> {
+    const mint = await this.getDogeMint();
+    const userTokenAccount = await getAssociatedTokenAddress(mint, requester.publicKey);
+
+    const ix = cancelWithdrawal(
+      this.config.programId,
+      requester.publicKey,
+      mint,
+      userTokenAccount,
+      receiptNonce
+    );
+    return this.sendAndConfirm([ix], [requester]);
+  }
+
+  /**
+   * Close a fulfilled or cancelled receipt, returning its rent to the requester.
+   */
+  async closeWithdrawalReceipt(
+    requester: Keypair,
+    receiptNonce: bigint
+  ): Promise<TransactionSignature> {
+    const ix = closeWithdrawalReceipt(this.config.programId, requester.publicKey, receiptNonce);
+    return this.sendAndConfirm([ix], [requester]);
+  }
+
   /**
    * Process a withdrawal transaction.
    */
@@ -532,6 +786,37 @@ export class BridgeClient {
     return this.sendWithRetry([ix]);
   }
 
+  /**
+   * Process the tx sweeping the return output to the scheduled custodian.
+   * Accepted once the rotation timelock has elapsed, the bridge then commits to the new custodian config.
+   */
+  async processCustodianSweep(
+    proof: CompactBridgeZKProof,
+    newReturnOutput: PsyReturnTxOutput,
+    newSpentTxoTreeRoot: Uint8Array,
+    dogeTxBytes: Uint8Array
+  ): Promise<TransactionSignature> {
+    const genericBuffer = await createGenericBuffer(
+      this.connection,
+      this.config.genericBufferProgramId,
+      this.config.payer,
+      dogeTxBytes
+    );
+
+    const ix = processCustodianSweep(
+      this.config.programId,
+      this.config.payer.publicKey,
+      genericBuffer,
+      this.config.wormholeShimProgramId,
+      this.config.wormholeCoreProgramId,
+      proof,
+      newReturnOutput,
+      newSpentTxoTreeRoot
+    );
+
+    return this.sendWithRetry([ix]);
+  }
+
   /**
    * Replay a withdrawal message (for Wormhole integration).
    */
@@ -638,6 +923,138 @@ export class BridgeClient {
     return this.sendWithRetry([ix], [this.config.operator]);
   }
 
+  /**
+   * Get the fee split table, null if none has been set.
+   */
+  async getFeeSplit(): Promise<FeeSplitTable | null> {
+    const [feeSplitPda] = getFeeSplitPda(this.config.programId);
+    const account = await this.connection.getAccountInfo(feeSplitPda);
+    if (!account) {
+      return null;
+    }
+    return decodeFeeSplitTable(account.data);
+  }
+
+  /**
+   * Set the fee split table (fee spender only).
+   * Once set, fees can only be paid out through distributeFees.
+   */
+  async setFeeSplit(
+    feeSpender: Keypair,
+    feeSplit: FeeSplitTable
+  ): Promise<TransactionSignature> {
+    const ix = setFeeSplit(
+      this.config.programId,
+      feeSpender.publicKey,
+      this.config.payer.publicKey,
+      feeSplit
+    );
+    return this.sendWithRetry([ix], [feeSpender]);
+  }
+
+  /**
+   * Distribute the withdrawable fees according to the fee split table.
+   */
+  async distributeFees(): Promise<TransactionSignature> {
+    const mint = await this.getDogeMint();
+    const feeSplit = await this.getFeeSplit();
+    if (!feeSplit) {
+      throw new Error("Fee split table has not been set");
+    }
+    const ix = distributeFees(this.config.programId, mint, feeSplit);
+    return this.sendWithRetry([ix]);
+  }
+
+  /**
+   * Start the block hash archive at the current finalized block (settings authority only).
+   * Every later block update and reorg appends to it, paid for by the payer.
+   */
+  async enableBlockHashArchive(authority: Keypair): Promise<TransactionSignature> {
+    const ix = initBlockHashArchive(this.config.programId, authority.publicKey, this.config.payer.publicKey);
+    return this.sendWithRetry([ix], [authority]);
+  }
+
+  /**
+   * Get the layout version of the bridge state account.
+   */
+  async getBridgeStateVersion(): Promise<number> {
+    const account = await this.connection.getAccountInfo(this.config.bridgeStatePda);
+    if (!account) {
+      throw BridgeError.accountNotFound(this.config.bridgeStatePda.toString());
+    }
+    return decodeBridgeStateLayout(account.data).version;
+  }
+
+  /**
+   * Migrate the bridge state account to the current layout version, one transaction per version.
+   * Permissionless, returns null if the account is already current.
+   */
+  async migrateState(): Promise<TransactionSignature | null> {
+    let signature: TransactionSignature | null = null;
+    while ((await this.getBridgeStateVersion()) < BRIDGE_STATE_CURRENT_VERSION) {
+      signature = await this.sendWithRetry([migrateState(this.config.programId, this.config.payer.publicKey)]);
+    }
+    return signature;
+  }
+
+  /**
+   * Get the stored custodian wallet config, or null if none has been stored yet.
+   * The account keeps the last stored config, compare its hash with the bridge state to detect a rotation.
+   */
+  async getCustodianWalletConfig(): Promise<Bridge7MultisigCustodianWalletConfig | null> {
+    const [configPda] = getCustodianWalletConfigPda(this.config.programId);
+    const account = await this.connection.getAccountInfo(configPda);
+    if (!account) {
+      return null;
+    }
+    return decodeBridge7MultisigCustodianWalletConfig(account.data);
+  }
+
+  /**
+   * Store the preimage of the bridge's custodian wallet config hash.
+   * Permissionless, the program rejects any other config.
+   */
+  async storeCustodianWalletConfig(config: Bridge7MultisigCustodianWalletConfig): Promise<TransactionSignature> {
+    return this.sendWithRetry([
+      storeCustodianWalletConfig(this.config.programId, this.config.payer.publicKey, config),
+    ]);
+  }
+
+  /**
+   * Get the scheduled custodian rotation, or null if none is scheduled.
+   */
+  async getCustodianRotation(): Promise<CustodianRotation | null> {
+    const account = await this.connection.getAccountInfo(this.config.bridgeStatePda);
+    if (!account) {
+      throw BridgeError.accountNotFound(this.config.bridgeStatePda.toString());
+    }
+    const { version, dogeMintOffset } = decodeBridgeStateLayout(account.data);
+    if (version === 0) {
+      return null;
+    }
+    // the rotation is the first field after the doge mint, version 0 accounts have none
+    const rotation = decodeCustodianRotation(account.data, dogeMintOffset + 32);
+    return rotation.pendingCustodianWalletConfigHash.some((b) => b !== 0) ? rotation : null;
+  }
+
+  /**
+   * Schedule a rotation to the custodian config with the given hash, signed by the settings authority.
+   */
+  async scheduleCustodianRotation(
+    authority: Keypair,
+    newCustodianWalletConfigHash: Uint8Array
+  ): Promise<TransactionSignature> {
+    const ix = scheduleCustodianRotation(this.config.programId, authority.publicKey, newCustodianWalletConfigHash);
+    return this.sendWithRetry([ix], [authority]);
+  }
+
+  /**
+   * Cancel the scheduled custodian rotation, signed by the settings authority or the guardian.
+   */
+  async cancelCustodianRotation(authority: Keypair): Promise<TransactionSignature> {
+    return this.sendWithRetry([cancelCustodianRotation(this.config.programId, authority.publicKey)], [authority]);
+  }
+
   /**
    * Execute snapshot withdrawals.
    */
@@ -8,6 +8,12 @@ export const BRIDGE_STATE_SEED = "bridge_state";
 export const MANUAL_CLAIM_SEED = "manual-claim";
 export const MINT_BUFFER_SEED = "mint_buffer";
 export const TXO_BUFFER_SEED = "txo_buffer";
+export const VK_REGISTRY_SEED = "vk_registry";
+export const WITHDRAWAL_RECEIPT_SEED = "withdrawal_receipt";
+export const FEE_SPLIT_SEED = "fee_split";
+export const FINALIZED_BLOCK_HISTORY_SEED = "finalized_block_history";
+export const BLOCK_HASH_ARCHIVE_SEED = "block_hash_archive";
+export const CUSTODIAN_WALLET_CONFIG_SEED = "custodian_wallet_config";
 
 export const DOGE_BRIDGE_PROGRAM_ID = new PublicKey("DBjo5tqf2uwt4sg9JznSk9SBbEvsLixknN58y3trwCxJ");
 export const MANUAL_CLAIM_PROGRAM_ID = new PublicKey("MCdYbqiK3uj36tohbMjsh3Ssg8iRSJmSHToNxW8TWWE");
@@ -27,9 +33,62 @@ export const DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP = 7;
 export const DOGE_BRIDGE_INSTRUCTION_PROCESS_REORG_BLOCKS = 8;
 export const DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP_AUTO_ADVANCE = 9;
 export const DOGE_BRIDGE_INSTRUCTION_SNAPSHOT_WITHDRAWALS = 10;
+export const DOGE_BRIDGE_INSTRUCTION_MARK_WITHDRAWAL_RECEIPTS_FULFILLED = 22;
+export const DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT = 23;
+export const DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL = 24;
+export const DOGE_BRIDGE_INSTRUCTION_SET_FEE_SPLIT = 25;
+export const DOGE_BRIDGE_INSTRUCTION_DISTRIBUTE_FEES = 26;
+export const DOGE_BRIDGE_INSTRUCTION_INIT_FINALIZED_BLOCK_HISTORY = 27;
+export const DOGE_BRIDGE_INSTRUCTION_VERIFY_TX_INCLUSION = 28;
+export const DOGE_BRIDGE_INSTRUCTION_INIT_BLOCK_HASH_ARCHIVE = 29;
+export const DOGE_BRIDGE_INSTRUCTION_MIGRATE_STATE = 30;
+export const DOGE_BRIDGE_INSTRUCTION_STORE_CUSTODIAN_WALLET_CONFIG = 31;
+export const DOGE_BRIDGE_INSTRUCTION_SCHEDULE_CUSTODIAN_ROTATION = 32;
+export const DOGE_BRIDGE_INSTRUCTION_CANCEL_CUSTODIAN_ROTATION = 33;
+export const DOGE_BRIDGE_INSTRUCTION_PROCESS_CUSTODIAN_SWEEP = 34;
+export const DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWALS_BATCH = 35;
+export const DOGE_BRIDGE_INSTRUCTION_REQUEST_SCRIPT_WITHDRAWAL = 36;
+
+// Bridge state layout versions, version 0 accounts predate the version header
+export const BRIDGE_STATE_VERSION_MAGIC = "PSYS";
+export const BRIDGE_STATE_CURRENT_VERSION = 1;
+export const BRIDGE_STATE_VERSION_HEADER_SIZE = 8;
+export const BRIDGE_STATE_RESERVED_SIZE = 512;
+// a scheduled custodian rotation can be swept after this delay
+export const CUSTODIAN_ROTATION_DELAY_SECS = 60 * 60 * 24 * 2;
+// Offsets below are from the start of the core state, add decodeBridgeStateLayout(data).coreOffset
+// Offset of requested_withdrawals_tree.next_index, the same in every layout version
+export const BRIDGE_STATE_NEXT_WITHDRAWAL_INDEX_OFFSET = 4880;
+// Offset of block_hash_archive_len, version 1 and later
+export const BRIDGE_STATE_BLOCK_HASH_ARCHIVE_LEN_OFFSET = 6064;
 
 export const MC_MANUAL_CLAIM_TRANSACTION_DISCRIMINATOR = 0;
 
 // Buffer constants
 export const CHUNK_SIZE = 900;
 export const PM_MAX_PENDING_MINTS_PER_GROUP = 24;
+
+// Batched withdrawal requests, each request needs its own receipt account
+export const MAX_WITHDRAWAL_REQUESTS_PER_BATCH = 8;
+
+// Script withdrawals commit to hash160(scriptPubKey) with this address_type
+export const WITHDRAWAL_ADDRESS_TYPE_SCRIPT = 2;
+export const MAX_WITHDRAWAL_SCRIPT_SIZE = 256;
+
+// Fee split constants
+export const FEE_SPLIT_MAX_RECIPIENTS = 8;
+export const FEE_SPLIT_TOTAL_BPS = 10_000n;
+
+// Finalized block history constants
+export const FINALIZED_BLOCK_HISTORY_MIN_CAPACITY = 16;
+export const FINALIZED_BLOCK_HISTORY_MAX_CAPACITY = 4096;
+export const FINALIZED_BLOCK_HISTORY_HEADER_SIZE = 16;
+export const FINALIZED_BLOCK_HISTORY_ENTRY_SIZE = 72;
+// accounts can grow by at most this many bytes per instruction
+export const MAX_PERMITTED_DATA_INCREASE = 10_240;
+
+// Tx inclusion oracle constants
+export const DOGE_BLOCK_MERKLE_TREE_HEIGHT = 28;
+
+// Block hash archive constants
+export const BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY = 65_536;
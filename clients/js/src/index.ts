@@ -44,15 +44,25 @@ export {
   PsyReturnTxOutput,
   PsyBridgeConfig,
   BridgeCustodianWalletConfig,
+  Bridge7MultisigCustodianWalletConfig,
+  CustodianRotation,
   PsyWithdrawalRequest,
   PsyWithdrawalChainSnapshot,
   PsyBridgeProgramState,
+  WithdrawalReceipt,
+  FeeSplitRecipient,
+  FeeSplitTable,
+  TxInclusionResult,
+  BlockHashArchiveEntry,
+  BlockHashArchiveSegment,
+  BridgeStateLayout,
   // Instruction parameters
   InitializeBridgeParams,
   FinalizedBlockMintTxoInfo,
   PendingMint,
   CompactBridgeZKProof,
   ManualClaimInstructionData,
+  VerifyTxInclusionParams,
   // Result types
   DepositTxOutputRecord,
   ProcessMintsResult,
@@ -69,6 +79,14 @@ export {
   PENDING_MINT_SIZE,
   COMPACT_ZK_PROOF_SIZE,
   MANUAL_CLAIM_INSTRUCTION_DATA_SIZE,
+  WITHDRAWAL_RECEIPT_SIZE,
+  FEE_SPLIT_RECIPIENT_SIZE,
+  FEE_SPLIT_TABLE_SIZE,
+  VERIFY_TX_INCLUSION_FIXED_DATA_SIZE,
+  TX_INCLUSION_RESULT_SIZE,
+  BLOCK_HASH_ARCHIVE_SEGMENT_HEADER_SIZE,
+  BLOCK_HASH_ARCHIVE_ENTRY_SIZE,
+  BRIDGE_7_MULTISIG_CUSTODIAN_WALLET_CONFIG_SIZE,
   // Encoders
   encodePsyBridgeStateCommitment,
   encodePsyBridgeHeader,
@@ -78,13 +96,24 @@ export {
   encodeFinalizedBlockMintTxoInfo,
   encodePendingMint,
   encodeManualClaimInstructionData,
+  encodeFeeSplitTable,
+  encodeVerifyTxInclusionParams,
+  encodeBridge7MultisigCustodianWalletConfig,
   // Decoders
   decodePsyBridgeStateCommitment,
   decodePsyBridgeHeader,
   decodePsyReturnTxOutput,
   decodePsyBridgeConfig,
+  decodeWithdrawalReceipt,
+  decodeFeeSplitTable,
+  decodeTxInclusionResult,
+  decodeBlockHashArchiveSegment,
+  decodeBridgeStateLayout,
+  decodeBridge7MultisigCustodianWalletConfig,
+  decodeCustodianRotation,
   // Helpers
   emptyProcessMintsResult,
+  custodianCompressedPublicKeys,
 } from "./types";
 
 // Constants
@@ -93,6 +122,12 @@ export {
   MANUAL_CLAIM_SEED,
   MINT_BUFFER_SEED,
   TXO_BUFFER_SEED,
+  VK_REGISTRY_SEED,
+  WITHDRAWAL_RECEIPT_SEED,
+  FEE_SPLIT_SEED,
+  FINALIZED_BLOCK_HISTORY_SEED,
+  BLOCK_HASH_ARCHIVE_SEED,
+  CUSTODIAN_WALLET_CONFIG_SEED,
   DOGE_BRIDGE_PROGRAM_ID,
   MANUAL_CLAIM_PROGRAM_ID,
   PENDING_MINT_BUFFER_BUILDER_PROGRAM_ID,
@@ -100,16 +135,38 @@ export {
   TXO_BUFFER_BUILDER_PROGRAM_ID,
   CHUNK_SIZE,
   PM_MAX_PENDING_MINTS_PER_GROUP,
+  FEE_SPLIT_MAX_RECIPIENTS,
+  FEE_SPLIT_TOTAL_BPS,
+  FINALIZED_BLOCK_HISTORY_MIN_CAPACITY,
+  FINALIZED_BLOCK_HISTORY_MAX_CAPACITY,
+  DOGE_BLOCK_MERKLE_TREE_HEIGHT,
+  BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY,
+  BRIDGE_STATE_VERSION_MAGIC,
+  BRIDGE_STATE_CURRENT_VERSION,
+  CUSTODIAN_ROTATION_DELAY_SECS,
+  MAX_WITHDRAWAL_REQUESTS_PER_BATCH,
+  WITHDRAWAL_ADDRESS_TYPE_SCRIPT,
+  MAX_WITHDRAWAL_SCRIPT_SIZE,
 } from "./constants";
 
 // Instructions
 export {
   getBridgeStatePda,
   getManualClaimPda,
+  getVkRegistryPda,
+  getWithdrawalReceiptPda,
+  getFeeSplitPda,
+  getFinalizedBlockHistoryPda,
+  getBlockHashArchiveSegmentPda,
+  blockHashArchiveAccounts,
+  reorgBlockHashArchiveAccounts,
+  getCustodianWalletConfigPda,
   initializeBridge,
   blockUpdate,
   processReorgBlocks,
   requestWithdrawal,
+  requestWithdrawalsBatch,
+  requestScriptWithdrawal,
   processWithdrawal,
   processReplayWithdrawal,
   processManualDeposit,
@@ -118,6 +175,20 @@ export {
   processMintGroupAutoAdvance,
   operatorWithdrawFees,
   snapshotWithdrawals,
+  markWithdrawalReceiptsFulfilled,
+  closeWithdrawalReceipt,
+  cancelWithdrawal,
+  setFeeSplit,
+  distributeFees,
+  initFinalizedBlockHistory,
+  initFinalizedBlockHistorySteps,
+  verifyTxInclusion,
+  initBlockHashArchive,
+  migrateState,
+  storeCustodianWalletConfig,
+  scheduleCustodianRotation,
+  cancelCustodianRotation,
+  processCustodianSweep,
   // Buffer instructions
   genericBufferInit,
   genericBufferWrite,
@@ -19,28 +19,63 @@ import {
   DOGE_BRIDGE_INSTRUCTION_PROCESS_REORG_BLOCKS,
   DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP_AUTO_ADVANCE,
   DOGE_BRIDGE_INSTRUCTION_SNAPSHOT_WITHDRAWALS,
+  DOGE_BRIDGE_INSTRUCTION_MARK_WITHDRAWAL_RECEIPTS_FULFILLED,
+  DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT,
+  DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL,
+  DOGE_BRIDGE_INSTRUCTION_SET_FEE_SPLIT,
+  DOGE_BRIDGE_INSTRUCTION_DISTRIBUTE_FEES,
+  DOGE_BRIDGE_INSTRUCTION_INIT_FINALIZED_BLOCK_HISTORY,
+  DOGE_BRIDGE_INSTRUCTION_VERIFY_TX_INCLUSION,
+  DOGE_BRIDGE_INSTRUCTION_INIT_BLOCK_HASH_ARCHIVE,
+  DOGE_BRIDGE_INSTRUCTION_MIGRATE_STATE,
+  DOGE_BRIDGE_INSTRUCTION_STORE_CUSTODIAN_WALLET_CONFIG,
+  DOGE_BRIDGE_INSTRUCTION_SCHEDULE_CUSTODIAN_ROTATION,
+  DOGE_BRIDGE_INSTRUCTION_CANCEL_CUSTODIAN_ROTATION,
+  DOGE_BRIDGE_INSTRUCTION_PROCESS_CUSTODIAN_SWEEP,
+  DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWALS_BATCH,
+  DOGE_BRIDGE_INSTRUCTION_REQUEST_SCRIPT_WITHDRAWAL,
   MC_MANUAL_CLAIM_TRANSACTION_DISCRIMINATOR,
   BRIDGE_STATE_SEED,
   MANUAL_CLAIM_SEED,
+  VK_REGISTRY_SEED,
+  WITHDRAWAL_RECEIPT_SEED,
+  FEE_SPLIT_SEED,
+  FINALIZED_BLOCK_HISTORY_SEED,
+  FINALIZED_BLOCK_HISTORY_HEADER_SIZE,
+  FINALIZED_BLOCK_HISTORY_ENTRY_SIZE,
+  MAX_PERMITTED_DATA_INCREASE,
+  BLOCK_HASH_ARCHIVE_SEED,
+  BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY,
+  CUSTODIAN_WALLET_CONFIG_SEED,
 } from "./constants";
 import {
   PsyBridgeHeader,
   PsyReturnTxOutput,
   PsyBridgeConfig,
   BridgeCustodianWalletConfig,
+  Bridge7MultisigCustodianWalletConfig,
   FinalizedBlockMintTxoInfo,
   InitializeBridgeParams,
   CompactBridgeZKProof,
+  FeeSplitTable,
+  VerifyTxInclusionParams,
   PSY_BRIDGE_HEADER_SIZE,
   FINALIZED_BLOCK_MINT_TXO_INFO_SIZE,
   PSY_RETURN_TX_OUTPUT_SIZE,
+  PSY_BRIDGE_CONFIG_SIZE,
   MANUAL_CLAIM_INSTRUCTION_DATA_SIZE,
+  FEE_SPLIT_TABLE_SIZE,
+  VERIFY_TX_INCLUSION_FIXED_DATA_SIZE,
+  BRIDGE_7_MULTISIG_CUSTODIAN_WALLET_CONFIG_SIZE,
+  PsyWithdrawalRequest,
   encodePsyBridgeHeader,
   encodePsyReturnTxOutput,
   encodePsyBridgeConfig,
   encodeCustodianWalletConfig,
   encodeFinalizedBlockMintTxoInfo,
   encodeManualClaimInstructionData,
+  encodeFeeSplitTable,
+  encodeBridge7MultisigCustodianWalletConfig,
 } from "./types";
 
 // =============================================================================
@@ -81,6 +116,102 @@ export function getBridgeStatePda(programId: PublicKey = DOGE_BRIDGE_PROGRAM_ID)
   );
 }
 
+// the registry may not exist yet, the program then uses its built-in verifier keys
+export function getVkRegistryPda(programId: PublicKey = DOGE_BRIDGE_PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [new TextEncoder().encode(VK_REGISTRY_SEED)],
+    programId
+  );
+}
+
+export function getWithdrawalReceiptPda(
+  requester: PublicKey,
+  receiptNonce: bigint,
+  programId: PublicKey = DOGE_BRIDGE_PROGRAM_ID
+): [PublicKey, number] {
+  const nonceBytes = new Uint8Array(8);
+  new DataView(nonceBytes.buffer).setBigUint64(0, receiptNonce, true);
+  return PublicKey.findProgramAddressSync(
+    [new TextEncoder().encode(WITHDRAWAL_RECEIPT_SEED), requester.toBuffer(), nonceBytes],
+    programId
+  );
+}
+
+export function getFeeSplitPda(programId: PublicKey = DOGE_BRIDGE_PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [new TextEncoder().encode(FEE_SPLIT_SEED)],
+    programId
+  );
+}
+
+// the history may not exist yet, block updates and manual claims then ignore it
+export function getFinalizedBlockHistoryPda(programId: PublicKey = DOGE_BRIDGE_PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [new TextEncoder().encode(FINALIZED_BLOCK_HISTORY_SEED)],
+    programId
+  );
+}
+
+export function getBlockHashArchiveSegmentPda(
+  segmentIndex: number,
+  programId: PublicKey = DOGE_BRIDGE_PROGRAM_ID
+): [PublicKey, number] {
+  const indexBytes = new Uint8Array(4);
+  new DataView(indexBytes.buffer).setUint32(0, segmentIndex, true);
+  return PublicKey.findProgramAddressSync(
+    [new TextEncoder().encode(BLOCK_HASH_ARCHIVE_SEED), indexBytes],
+    programId
+  );
+}
+
+// accounts to append to blockUpdate once the block hash archive is enabled,
+// blockHashArchiveLen is read from the bridge state
+export function blockHashArchiveAccounts(
+  programId: PublicKey,
+  blockHashArchiveLen: bigint
+): AccountMeta[] {
+  return reorgBlockHashArchiveAccounts(programId, blockHashArchiveLen, 1, null);
+}
+
+// accounts to append to processReorgBlocks once the block hash archive is enabled. A reorg that finalizes more than
+// one height passes a generic buffer with the block merkle tree siblings of the current finalized block followed by
+// the hashes of the heights in between, and the next segment when the entries don't fit in the current one
+export function reorgBlockHashArchiveAccounts(
+  programId: PublicKey,
+  blockHashArchiveLen: bigint,
+  finalizedBlocksCount: number,
+  reorgBlockHashesBuffer: PublicKey | null
+): AccountMeta[] {
+  if (blockHashArchiveLen === 0n) {
+    return [];
+  }
+  const capacity = BigInt(BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY);
+  const segmentIndex = Number(blockHashArchiveLen / capacity);
+  const accounts = [
+    { pubkey: getBlockHashArchiveSegmentPda(segmentIndex, programId)[0], isSigner: false, isWritable: true },
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+  ];
+  if (reorgBlockHashesBuffer) {
+    accounts.push({ pubkey: reorgBlockHashesBuffer, isSigner: false, isWritable: false });
+    const lastSegmentIndex = Number((blockHashArchiveLen + BigInt(finalizedBlocksCount) - 1n) / capacity);
+    if (lastSegmentIndex !== segmentIndex) {
+      accounts.push({
+        pubkey: getBlockHashArchiveSegmentPda(lastSegmentIndex, programId)[0],
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+  }
+  return accounts;
+}
+
+export function getCustodianWalletConfigPda(programId: PublicKey = DOGE_BRIDGE_PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [new TextEncoder().encode(CUSTODIAN_WALLET_CONFIG_SEED)],
+    programId
+  );
+}
+
 export function getManualClaimPda(
   userPubkey: PublicKey,
   manualClaimProgramId: PublicKey
@@ -101,16 +232,24 @@ export function initializeBridge(
   feeSpenderPubkey: PublicKey,
   dogeMint: PublicKey,
   params: InitializeBridgeParams,
-  programId: PublicKey = DOGE_BRIDGE_PROGRAM_ID
+  programId: PublicKey = DOGE_BRIDGE_PROGRAM_ID,
+  guardianPubkey: PublicKey = operatorPubkey,
+  adminPubkey: PublicKey | null = null
 ): TransactionInstruction {
   const [bridgeState] = getBridgeStatePda(programId);
 
-  const dataSize = 32 * 3 + PSY_BRIDGE_HEADER_SIZE + PSY_RETURN_TX_OUTPUT_SIZE + 48 + 32;
+  const dataSize = 32 * 5 + PSY_BRIDGE_HEADER_SIZE + PSY_RETURN_TX_OUTPUT_SIZE + PSY_BRIDGE_CONFIG_SIZE + 32;
   const instructionData = new Uint8Array(dataSize);
   let offset = 0;
 
   instructionData.set(operatorPubkey.toBuffer(), offset); offset += 32;
   instructionData.set(feeSpenderPubkey.toBuffer(), offset); offset += 32;
+  instructionData.set(guardianPubkey.toBuffer(), offset); offset += 32;
+  // all zeros = no admin
+  if (adminPubkey) {
+    instructionData.set(adminPubkey.toBuffer(), offset);
+  }
+  offset += 32;
   instructionData.set(dogeMint.toBuffer(), offset); offset += 32;
   offset += encodePsyBridgeHeader(params.bridgeHeader, instructionData, offset);
   offset += encodePsyReturnTxOutput(params.startReturnTxoOutput, instructionData, offset);
@@ -163,6 +302,8 @@ export function blockUpdate(
       { pubkey: payer, isSigner: true, isWritable: true },
       { pubkey: pendingMintPid, isSigner: false, isWritable: false },
       { pubkey: txoBufferPid, isSigner: false, isWritable: false },
+      { pubkey: getVkRegistryPda(programId)[0], isSigner: false, isWritable: false },
+      { pubkey: getFinalizedBlockHistoryPda(programId)[0], isSigner: false, isWritable: true },
     ],
     programId,
     data: Buffer.from(data),
@@ -210,20 +351,25 @@ export function processReorgBlocks(
       { pubkey: payer, isSigner: true, isWritable: true },
       { pubkey: pendingMintPid, isSigner: false, isWritable: false },
       { pubkey: txoBufferPid, isSigner: false, isWritable: false },
+      { pubkey: getVkRegistryPda(programId)[0], isSigner: false, isWritable: false },
+      { pubkey: getFinalizedBlockHistoryPda(programId)[0], isSigner: false, isWritable: true },
     ],
     programId,
     data: Buffer.from(data),
   });
 }
 
+// receiptNonce keys the receipt PDA, any value the user has no open receipt under works
 export function requestWithdrawal(
   programId: PublicKey,
+  payer: PublicKey,
   userAuthority: PublicKey,
   mint: PublicKey,
   userTokenAccount: PublicKey,
   recipientAddress: Uint8Array,
   amountSats: bigint,
-  addressType: number
+  addressType: number,
+  receiptNonce: bigint
 ): TransactionInstruction {
   const [bridgeState] = getBridgeStatePda(programId);
 
@@ -232,7 +378,8 @@ export function requestWithdrawal(
   //   - amount_sats: u64 (8 bytes) at offset 0
   //   - address_type: u32 (4 bytes) at offset 8
   //   - recipient_address: [u8; 20] at offset 12
-  const dataSize = 32;
+  // receipt_nonce: u64 (8 bytes) at offset 32
+  const dataSize = 40;
   const instructionData = new Uint8Array(dataSize);
   const view = new DataView(instructionData.buffer);
 
@@ -240,6 +387,7 @@ export function requestWithdrawal(
   view.setBigUint64(0, amountSats, true);
   view.setUint32(8, addressType, true);
   instructionData.set(recipientAddress, 12);
+  view.setBigUint64(32, receiptNonce, true);
 
   const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWAL);
   const data = concatBytes(header, instructionData);
@@ -251,12 +399,106 @@ export function requestWithdrawal(
       { pubkey: mint, isSigner: false, isWritable: true },
       { pubkey: userAuthority, isSigner: true, isWritable: false },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: getWithdrawalReceiptPda(userAuthority, receiptNonce, programId)[0], isSigner: false, isWritable: true },
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
     programId,
     data: Buffer.from(data),
   });
 }
 
+// each request gets its own receipt PDA, keyed by consecutive nonces starting at firstReceiptNonce
+export function requestWithdrawalsBatch(
+  programId: PublicKey,
+  payer: PublicKey,
+  userAuthority: PublicKey,
+  mint: PublicKey,
+  userTokenAccount: PublicKey,
+  requests: PsyWithdrawalRequest[],
+  firstReceiptNonce: bigint
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+
+  // one 40-byte RequestWithdrawalInstructionData per request, same layout as in requestWithdrawal
+  const instructionData = new Uint8Array(requests.length * 40);
+  const view = new DataView(instructionData.buffer);
+  requests.forEach((request, i) => {
+    view.setBigUint64(i * 40, request.amountSats, true);
+    view.setUint32(i * 40 + 8, request.addressType, true);
+    instructionData.set(request.recipientAddress, i * 40 + 12);
+    view.setBigUint64(i * 40 + 32, firstReceiptNonce + BigInt(i), true);
+  });
+
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWALS_BATCH);
+  const data = concatBytes(header, instructionData);
+
+  const receiptKeys: AccountMeta[] = requests.map((_, i) => ({
+    pubkey: getWithdrawalReceiptPda(userAuthority, firstReceiptNonce + BigInt(i), programId)[0],
+    isSigner: false,
+    isWritable: true,
+  }));
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: bridgeState, isSigner: false, isWritable: true },
+      { pubkey: userTokenAccount, isSigner: false, isWritable: true },
+      { pubkey: mint, isSigner: false, isWritable: true },
+      { pubkey: userAuthority, isSigner: true, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      ...receiptKeys,
+    ],
+    programId,
+    data: Buffer.from(data),
+  });
+}
+
+// the queued request commits to hash160(scriptPubKey), pass an empty script and scriptBuffer to read it from a generic buffer
+export function requestScriptWithdrawal(
+  programId: PublicKey,
+  payer: PublicKey,
+  userAuthority: PublicKey,
+  mint: PublicKey,
+  userTokenAccount: PublicKey,
+  scriptPubKey: Uint8Array,
+  amountSats: bigint,
+  receiptNonce: bigint,
+  scriptBuffer?: PublicKey
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+
+  // RequestScriptWithdrawalFixedData: amount_sats u64, receipt_nonce u64, followed by the script bytes
+  const fixedData = new Uint8Array(16);
+  const fixedView = new DataView(fixedData.buffer);
+  fixedView.setBigUint64(0, amountSats, true);
+  fixedView.setBigUint64(8, receiptNonce, true);
+
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_REQUEST_SCRIPT_WITHDRAWAL);
+  const data = concatBytes(header, fixedData, scriptPubKey);
+
+  const keys: AccountMeta[] = [
+    { pubkey: bridgeState, isSigner: false, isWritable: true },
+    { pubkey: userTokenAccount, isSigner: false, isWritable: true },
+    { pubkey: mint, isSigner: false, isWritable: true },
+    { pubkey: userAuthority, isSigner: true, isWritable: false },
+    { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    { pubkey: getWithdrawalReceiptPda(userAuthority, receiptNonce, programId)[0], isSigner: false, isWritable: true },
+    { pubkey: payer, isSigner: true, isWritable: true },
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+  ];
+  if (scriptBuffer) {
+    keys.push({ pubkey: scriptBuffer, isSigner: false, isWritable: false });
+  }
+
+  return new TransactionInstruction({
+    keys,
+    programId,
+    data: Buffer.from(data),
+  });
+}
+
 export function processWithdrawal(
   programId: PublicKey,
   payer: PublicKey,
@@ -304,6 +546,7 @@ export function processWithdrawal(
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
       { pubkey: wormholeCoreProgramId, isSigner: false, isWritable: false },
       { pubkey: eventAuthority, isSigner: false, isWritable: false },
+      { pubkey: getVkRegistryPda(programId)[0], isSigner: false, isWritable: false },
     ],
     programId,
     data: Buffer.from(data),
@@ -385,6 +628,7 @@ export function processManualDeposit(
       { pubkey: mint, isSigner: false, isWritable: true },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: manualClaimProgramId, isSigner: true, isWritable: false },
+      { pubkey: getFinalizedBlockHistoryPda(programId)[0], isSigner: false, isWritable: false },
     ],
     programId,
     data: Buffer.from(data),
@@ -434,6 +678,8 @@ export function manualClaimDeposit(
       { pubkey: userSigner, isSigner: true, isWritable: false },
       { pubkey: payer, isSigner: true, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: getVkRegistryPda(bridgeProgramId)[0], isSigner: false, isWritable: false },
+      { pubkey: getFinalizedBlockHistoryPda(bridgeProgramId)[0], isSigner: false, isWritable: false },
     ],
     programId,
     data: Buffer.from(data),
@@ -566,6 +812,317 @@ export function snapshotWithdrawals(
   });
 }
 
+// permissionless, marks receipts whose withdrawals have been processed
+export function markWithdrawalReceiptsFulfilled(
+  programId: PublicKey,
+  withdrawalReceipts: PublicKey[]
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_MARK_WITHDRAWAL_RECEIPTS_FULFILLED);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: bridgeState, isSigner: false, isWritable: false },
+      ...withdrawalReceipts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })),
+    ],
+    programId,
+    data: Buffer.from(header),
+  });
+}
+
+// closes a fulfilled or cancelled receipt, its rent goes back to the requester
+export function closeWithdrawalReceipt(
+  programId: PublicKey,
+  requester: PublicKey,
+  receiptNonce: bigint
+): TransactionInstruction {
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: getWithdrawalReceiptPda(requester, receiptNonce, programId)[0], isSigner: false, isWritable: true },
+      { pubkey: requester, isSigner: true, isWritable: true },
+    ],
+    programId,
+    data: Buffer.from(header),
+  });
+}
+
+// cancels a request not yet covered by a withdrawal snapshot, the burned amount minus the
+// cancellation fee is minted back to userTokenAccount
+export function cancelWithdrawal(
+  programId: PublicKey,
+  requester: PublicKey,
+  mint: PublicKey,
+  userTokenAccount: PublicKey,
+  receiptNonce: bigint
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: bridgeState, isSigner: false, isWritable: true },
+      { pubkey: getWithdrawalReceiptPda(requester, receiptNonce, programId)[0], isSigner: false, isWritable: true },
+      { pubkey: userTokenAccount, isSigner: false, isWritable: true },
+      { pubkey: mint, isSigner: false, isWritable: true },
+      { pubkey: requester, isSigner: true, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    programId,
+    data: Buffer.from(header),
+  });
+}
+
+// fee spender only, payer funds the fee split account the first time it is set
+export function setFeeSplit(
+  programId: PublicKey,
+  feeSpender: PublicKey,
+  payer: PublicKey,
+  feeSplit: FeeSplitTable
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+  const [feeSplitPda] = getFeeSplitPda(programId);
+
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_SET_FEE_SPLIT);
+  const body = new Uint8Array(FEE_SPLIT_TABLE_SIZE);
+  encodeFeeSplitTable(feeSplit, body);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: bridgeState, isSigner: false, isWritable: true },
+      { pubkey: feeSplitPda, isSigner: false, isWritable: true },
+      { pubkey: feeSpender, isSigner: true, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    programId,
+    data: Buffer.from(concatBytes(header, body)),
+  });
+}
+
+// permissionless, mints the withdrawable fees to the token accounts of the fee split table
+export function distributeFees(
+  programId: PublicKey,
+  dogeMint: PublicKey,
+  feeSplit: FeeSplitTable
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+  const [feeSplitPda] = getFeeSplitPda(programId);
+
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_DISTRIBUTE_FEES);
+
+  const keys: AccountMeta[] = [
+    { pubkey: bridgeState, isSigner: false, isWritable: true },
+    { pubkey: feeSplitPda, isSigner: false, isWritable: false },
+    { pubkey: dogeMint, isSigner: false, isWritable: true },
+    { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    { pubkey: new PublicKey(feeSplit.feeSpenderTokenAccount), isSigner: false, isWritable: true },
+  ];
+  for (const recipient of feeSplit.recipients) {
+    keys.push({ pubkey: new PublicKey(recipient.tokenAccount), isSigner: false, isWritable: true });
+  }
+
+  return new TransactionInstruction({ keys, programId, data: Buffer.from(header) });
+}
+
+// number of initFinalizedBlockHistory instructions needed to allocate a history of the given capacity
+export function initFinalizedBlockHistorySteps(capacity: number): number {
+  const size = FINALIZED_BLOCK_HISTORY_HEADER_SIZE + capacity * FINALIZED_BLOCK_HISTORY_ENTRY_SIZE;
+  return Math.ceil(size / MAX_PERMITTED_DATA_INCREASE);
+}
+
+// settings authority only, send it for each step below initFinalizedBlockHistorySteps(capacity), the last one enables the history
+export function initFinalizedBlockHistory(
+  programId: PublicKey,
+  authority: PublicKey,
+  payer: PublicKey,
+  capacity: number,
+  step: number
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+  const [historyPda] = getFinalizedBlockHistoryPda(programId);
+
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_INIT_FINALIZED_BLOCK_HISTORY);
+  const body = new Uint8Array(8);
+  new DataView(body.buffer).setUint32(0, capacity, true);
+  new DataView(body.buffer).setUint32(4, step, true);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: bridgeState, isSigner: false, isWritable: true },
+      { pubkey: historyPda, isSigner: false, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    programId,
+    data: Buffer.from(concatBytes(header, body)),
+  });
+}
+
+// settings authority only, starts the archive at the current finalized block
+export function initBlockHashArchive(
+  programId: PublicKey,
+  authority: PublicKey,
+  payer: PublicKey
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+  const [segmentPda] = getBlockHashArchiveSegmentPda(0, programId);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: bridgeState, isSigner: false, isWritable: true },
+      { pubkey: segmentPda, isSigner: false, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    programId,
+    data: Buffer.from(createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_INIT_BLOCK_HASH_ARCHIVE)),
+  });
+}
+
+// permissionless, moves the bridge state account forward one layout version with the payer covering any extra rent
+export function migrateState(programId: PublicKey, payer: PublicKey): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: bridgeState, isSigner: false, isWritable: true },
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    programId,
+    data: Buffer.from(createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_MIGRATE_STATE)),
+  });
+}
+
+// permissionless, the program only stores a config that hashes to the bridge's custodian wallet config hash
+export function storeCustodianWalletConfig(
+  programId: PublicKey,
+  payer: PublicKey,
+  config: Bridge7MultisigCustodianWalletConfig
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+  const [configPda] = getCustodianWalletConfigPda(programId);
+
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_STORE_CUSTODIAN_WALLET_CONFIG);
+  const body = new Uint8Array(BRIDGE_7_MULTISIG_CUSTODIAN_WALLET_CONFIG_SIZE);
+  encodeBridge7MultisigCustodianWalletConfig(config, body);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: bridgeState, isSigner: false, isWritable: false },
+      { pubkey: configPda, isSigner: false, isWritable: true },
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    programId,
+    data: Buffer.from(concatBytes(header, body)),
+  });
+}
+
+// settings authority only, the bridge switches to the new config once processCustodianSweep lands
+export function scheduleCustodianRotation(
+  programId: PublicKey,
+  authority: PublicKey,
+  newCustodianWalletConfigHash: Uint8Array
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_SCHEDULE_CUSTODIAN_ROTATION);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: bridgeState, isSigner: false, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+    ],
+    programId,
+    data: Buffer.from(concatBytes(header, newCustodianWalletConfigHash)),
+  });
+}
+
+// signed by the settings authority or the guardian
+export function cancelCustodianRotation(programId: PublicKey, authority: PublicKey): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: bridgeState, isSigner: false, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+    ],
+    programId,
+    data: Buffer.from(createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_CANCEL_CUSTODIAN_ROTATION)),
+  });
+}
+
+// same accounts as processWithdrawal, the sweep tx is published over wormhole like a withdrawal tx
+export function processCustodianSweep(
+  programId: PublicKey,
+  payer: PublicKey,
+  genericBufferAccount: PublicKey,
+  wormholeShimProgramId: PublicKey,
+  wormholeCoreProgramId: PublicKey,
+  proof: CompactBridgeZKProof,
+  newReturnOutput: PsyReturnTxOutput,
+  newSpentTxoTreeRoot: Uint8Array
+): TransactionInstruction {
+  const ix = processWithdrawal(
+    programId,
+    payer,
+    genericBufferAccount,
+    wormholeShimProgramId,
+    wormholeCoreProgramId,
+    proof,
+    newReturnOutput,
+    newSpentTxoTreeRoot,
+    0n
+  );
+
+  const instructionData = new Uint8Array(256 + PSY_RETURN_TX_OUTPUT_SIZE + 32);
+  let offset = 0;
+  instructionData.set(proof, offset); offset += 256;
+  offset += encodePsyReturnTxOutput(newReturnOutput, instructionData, offset);
+  instructionData.set(newSpentTxoTreeRoot, offset);
+
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_PROCESS_CUSTODIAN_SWEEP);
+  ix.data = Buffer.from(concatBytes(header, instructionData));
+  return ix;
+}
+
+// read-only, the result is set as return data (see decodeTxInclusionResult). siblings is the tx merkle branch
+// followed by the block merkle tree siblings, then comes the raw transaction. Leave siblings and txBytes empty
+// and pass a generic buffer holding them, in that order, when they don't fit
+export function verifyTxInclusion(
+  programId: PublicKey,
+  params: VerifyTxInclusionParams,
+  siblings: Uint8Array[],
+  txBytes: Uint8Array,
+  proofBuffer?: PublicKey
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+
+  const header = createInstructionHeader(DOGE_BRIDGE_INSTRUCTION_VERIFY_TX_INCLUSION);
+  const siblingsEnd = VERIFY_TX_INCLUSION_FIXED_DATA_SIZE + siblings.length * 32;
+  const body = new Uint8Array(siblingsEnd + txBytes.length);
+  encodeVerifyTxInclusionParams(params, body, 0);
+  siblings.forEach((sibling, i) => body.set(sibling, VERIFY_TX_INCLUSION_FIXED_DATA_SIZE + i * 32));
+  body.set(txBytes, siblingsEnd);
+
+  const keys: AccountMeta[] = [{ pubkey: bridgeState, isSigner: false, isWritable: false }];
+  if (proofBuffer) {
+    keys.push({ pubkey: proofBuffer, isSigner: false, isWritable: false });
+  }
+  return new TransactionInstruction({
+    keys,
+    programId,
+    data: Buffer.from(concatBytes(header, body)),
+  });
+}
+
 // =============================================================================
 // Buffer Instructions
 // =============================================================================
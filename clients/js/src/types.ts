@@ -3,6 +3,12 @@
  */
 
 import { PublicKey, TransactionSignature } from "@solana/web3.js";
+import {
+  FEE_SPLIT_MAX_RECIPIENTS,
+  BRIDGE_STATE_VERSION_MAGIC,
+  BRIDGE_STATE_VERSION_HEADER_SIZE,
+  BRIDGE_STATE_RESERVED_SIZE,
+} from "./constants";
 
 // =============================================================================
 // Core Bridge Types
@@ -47,12 +53,95 @@ export interface BridgeCustodianWalletConfig {
   configHash: Uint8Array; // 32 bytes
 }
 
+// the preimage of configHash, a 5 of 7 multisig of compressed secp256k1 keys
+export interface Bridge7MultisigCustodianWalletConfig {
+  signerPublicKeys: Uint8Array[]; // 7 x-coordinates, 32 bytes each
+  signerPublicKeysYParity: number; // bit i set if key i is odd (0x03 prefix)
+  networkType: number; // 0 = mainnet, 1 = testnet, 2 = regtest
+}
+
 export interface PsyWithdrawalRequest {
   recipientAddress: Uint8Array;
   amountSats: bigint;
   addressType: number;
 }
 
+export interface WithdrawalReceipt {
+  requester: Uint8Array;
+  // the request as appended to the withdrawal tree, amountSats is after fees
+  request: PsyWithdrawalRequest;
+  withdrawalIndex: bigint;
+  amountBurnedSats: bigint;
+  requestedAtSlot: bigint;
+  fulfilledAtSlot: bigint;
+  cancelledAtSlot: bigint;
+  fulfilled: boolean;
+  cancelled: boolean;
+  // keys the receipt PDA together with the requester
+  receiptNonce: bigint;
+}
+
+export interface FeeSplitRecipient {
+  tokenAccount: Uint8Array; // 32 bytes
+  shareBps: bigint;
+}
+
+export interface FeeSplitTable {
+  // receives whatever the recipients' shares leave over, including rounding dust
+  feeSpenderTokenAccount: Uint8Array; // 32 bytes
+  recipients: FeeSplitRecipient[];
+}
+
+// the raw transaction follows the siblings, the txid is computed from it
+export interface VerifyTxInclusionParams {
+  blockHeader: Uint8Array; // 80 bytes
+  blockHeight: number;
+  txIndex: number;
+  txSiblingsCount: number;
+  // 0 to only check inclusion
+  minConfirmations: number;
+  txSize: number;
+}
+
+export interface BlockHashArchiveEntry {
+  blockHash: Uint8Array;
+  blockMerkleTreeRoot: Uint8Array;
+  blockHeight: number;
+}
+
+// one account of the block hash archive, entries are sorted by height
+export interface BlockHashArchiveSegment {
+  segmentIndex: number;
+  firstBlockHeight: number;
+  entries: BlockHashArchiveEntry[];
+}
+
+// return data of a successful verifyTxInclusion instruction
+export interface TxInclusionResult {
+  blockHash: Uint8Array;
+  // internal byte order, i.e. the reverse of the hex shown by dogecoin rpc
+  txid: Uint8Array;
+  blockHeight: number;
+  // 1 when the block is the bridge's tip
+  confirmations: number;
+  finalizedBlockHeight: number;
+  tipBlockHeight: number;
+}
+
+// where the parts of a bridge state account are for its layout version
+// all zeros pendingCustodianWalletConfigHash = no rotation scheduled
+export interface CustodianRotation {
+  pendingCustodianWalletConfigHash: Uint8Array; // 32 bytes
+  scheduledAtSecs: bigint;
+  sweepAfterSecs: bigint;
+}
+
+export interface BridgeStateLayout {
+  version: number;
+  coreOffset: number;
+  dogeMintOffset: number;
+}
+
 export interface PsyWithdrawalChainSnapshot {
   nextWithdrawalIndex: bigint;
   withdrawalsMerkleRoot: Uint8Array;
@@ -141,6 +230,14 @@ export const FINALIZED_BLOCK_MINT_TXO_INFO_SIZE = 64;
 export const PENDING_MINT_SIZE = 40;
 export const COMPACT_ZK_PROOF_SIZE = 256;
 export const MANUAL_CLAIM_INSTRUCTION_DATA_SIZE = 256 + 32 * 4 + 16;
+export const WITHDRAWAL_RECEIPT_SIZE = 120;
+export const FEE_SPLIT_RECIPIENT_SIZE = 40;
+export const FEE_SPLIT_TABLE_SIZE = 32 + FEE_SPLIT_MAX_RECIPIENTS * FEE_SPLIT_RECIPIENT_SIZE + 8;
+export const VERIFY_TX_INCLUSION_FIXED_DATA_SIZE = 100;
+export const TX_INCLUSION_RESULT_SIZE = 80;
+export const BLOCK_HASH_ARCHIVE_SEGMENT_HEADER_SIZE = 16;
+export const BRIDGE_7_MULTISIG_CUSTODIAN_WALLET_CONFIG_SIZE = 232;
+export const BLOCK_HASH_ARCHIVE_ENTRY_SIZE = 72;
 
 // =============================================================================
 // Encoders
@@ -201,14 +298,51 @@ export function encodePsyBridgeConfig(
   buffer: Uint8Array,
   offset: number = 0
 ): number {
-  const view = new DataView(buffer.buffer, buffer.byteOffset + offset, 48);
+  const view = new DataView(buffer.buffer, buffer.byteOffset + offset, PSY_BRIDGE_CONFIG_SIZE);
   view.setBigUint64(0, config.depositFeeRateNumerator, true);
   view.setBigUint64(8, config.depositFeeRateDenominator, true);
   view.setBigUint64(16, config.withdrawalFeeRateNumerator, true);
   view.setBigUint64(24, config.withdrawalFeeRateDenominator, true);
   view.setBigUint64(32, config.depositFlatFeeSats, true);
   view.setBigUint64(40, config.withdrawalFlatFeeSats, true);
-  return 48;
+  return PSY_BRIDGE_CONFIG_SIZE;
+}
+
+export function encodeFeeSplitTable(
+  table: FeeSplitTable,
+  buffer: Uint8Array,
+  offset: number = 0
+): number {
+  if (table.recipients.length > FEE_SPLIT_MAX_RECIPIENTS) {
+    throw new Error(`Fee split cannot have more than ${FEE_SPLIT_MAX_RECIPIENTS} recipients`);
+  }
+  buffer.set(table.feeSpenderTokenAccount, offset);
+  const view = new DataView(buffer.buffer, buffer.byteOffset + offset, FEE_SPLIT_TABLE_SIZE);
+  table.recipients.forEach((recipient, i) => {
+    const pos = 32 + i * FEE_SPLIT_RECIPIENT_SIZE;
+    buffer.set(recipient.tokenAccount, offset + pos);
+    view.setBigUint64(pos + 32, recipient.shareBps, true);
+  });
+  view.setBigUint64(FEE_SPLIT_TABLE_SIZE - 8, BigInt(table.recipients.length), true);
+  return FEE_SPLIT_TABLE_SIZE;
+}
+
+export function encodeVerifyTxInclusionParams(
+  params: VerifyTxInclusionParams,
+  buffer: Uint8Array,
+  offset: number = 0
+): number {
+  if (params.blockHeader.length !== 80) {
+    throw new Error("Dogecoin block headers are 80 bytes");
+  }
+  buffer.set(params.blockHeader, offset);
+  const view = new DataView(buffer.buffer, buffer.byteOffset + offset, VERIFY_TX_INCLUSION_FIXED_DATA_SIZE);
+  view.setUint32(80, params.blockHeight, true);
+  view.setUint32(84, params.txIndex, true);
+  view.setUint32(88, params.txSiblingsCount, true);
+  view.setUint32(92, params.minConfirmations, true);
+  view.setUint32(96, params.txSize, true);
+  return VERIFY_TX_INCLUSION_FIXED_DATA_SIZE;
 }
 
 export function encodeCustodianWalletConfig(
@@ -220,6 +354,44 @@ export function encodeCustodianWalletConfig(
   return 32;
 }
 
+export function encodeBridge7MultisigCustodianWalletConfig(
+  config: Bridge7MultisigCustodianWalletConfig,
+  buffer: Uint8Array,
+  offset: number = 0
+): number {
+  const view = new DataView(buffer.buffer, buffer.byteOffset + offset, BRIDGE_7_MULTISIG_CUSTODIAN_WALLET_CONFIG_SIZE);
+  config.signerPublicKeys.forEach((key, i) => buffer.set(key, offset + i * 32));
+  view.setUint32(224, config.signerPublicKeysYParity, true);
+  view.setUint32(228, config.networkType, true);
+  return BRIDGE_7_MULTISIG_CUSTODIAN_WALLET_CONFIG_SIZE;
+}
+
+export function decodeBridge7MultisigCustodianWalletConfig(
+  buffer: Uint8Array,
+  offset: number = 0
+): Bridge7MultisigCustodianWalletConfig {
+  const view = new DataView(buffer.buffer, buffer.byteOffset + offset, BRIDGE_7_MULTISIG_CUSTODIAN_WALLET_CONFIG_SIZE);
+  const signerPublicKeys: Uint8Array[] = [];
+  for (let i = 0; i < 7; i++) {
+    signerPublicKeys.push(buffer.slice(offset + i * 32, offset + (i + 1) * 32));
+  }
+  return {
+    signerPublicKeys,
+    signerPublicKeysYParity: view.getUint32(224, true),
+    networkType: view.getUint32(228, true),
+  };
+}
+
+// the 33 byte compressed keys in configured signer order
+export function custodianCompressedPublicKeys(config: Bridge7MultisigCustodianWalletConfig): Uint8Array[] {
+  return config.signerPublicKeys.map((key, i) => {
+    const compressed = new Uint8Array(33);
+    compressed[0] = (config.signerPublicKeysYParity >> i) & 1 ? 0x03 : 0x02;
+    compressed.set(key, 1);
+    return compressed;
+  });
+}
+
 export function encodeFinalizedBlockMintTxoInfo(
   info: FinalizedBlockMintTxoInfo,
   buffer: Uint8Array,
@@ -331,11 +503,115 @@ export function decodePsyReturnTxOutput(
   };
 }
 
+export function decodeWithdrawalReceipt(
+  buffer: Uint8Array,
+  offset: number = 0
+): WithdrawalReceipt {
+  const view = new DataView(buffer.buffer, buffer.byteOffset + offset, WITHDRAWAL_RECEIPT_SIZE);
+  return {
+    requester: buffer.slice(offset, offset + 32),
+    request: {
+      amountSats: view.getBigUint64(32, true),
+      addressType: view.getUint32(40, true),
+      recipientAddress: buffer.slice(offset + 44, offset + 64),
+    },
+    withdrawalIndex: view.getBigUint64(64, true),
+    amountBurnedSats: view.getBigUint64(72, true),
+    requestedAtSlot: view.getBigUint64(80, true),
+    fulfilledAtSlot: view.getBigUint64(88, true),
+    cancelledAtSlot: view.getBigUint64(96, true),
+    fulfilled: view.getUint32(104, true) === 1,
+    cancelled: view.getUint32(104, true) === 2,
+    receiptNonce: view.getBigUint64(112, true),
+  };
+}
+
+export function decodeFeeSplitTable(
+  buffer: Uint8Array,
+  offset: number = 0
+): FeeSplitTable {
+  const view = new DataView(buffer.buffer, buffer.byteOffset + offset, FEE_SPLIT_TABLE_SIZE);
+  const recipientCount = Math.min(
+    Number(view.getBigUint64(FEE_SPLIT_TABLE_SIZE - 8, true)),
+    FEE_SPLIT_MAX_RECIPIENTS
+  );
+  const recipients: FeeSplitRecipient[] = [];
+  for (let i = 0; i < recipientCount; i++) {
+    const pos = 32 + i * FEE_SPLIT_RECIPIENT_SIZE;
+    recipients.push({
+      tokenAccount: buffer.slice(offset + pos, offset + pos + 32),
+      shareBps: view.getBigUint64(pos + 32, true),
+    });
+  }
+  return {
+    feeSpenderTokenAccount: buffer.slice(offset, offset + 32),
+    recipients,
+  };
+}
+
+export function decodeTxInclusionResult(
+  buffer: Uint8Array,
+  offset: number = 0
+): TxInclusionResult {
+  const view = new DataView(buffer.buffer, buffer.byteOffset + offset, TX_INCLUSION_RESULT_SIZE);
+  return {
+    blockHash: buffer.slice(offset, offset + 32),
+    txid: buffer.slice(offset + 32, offset + 64),
+    blockHeight: view.getUint32(64, true),
+    confirmations: view.getUint32(68, true),
+    finalizedBlockHeight: view.getUint32(72, true),
+    tipBlockHeight: view.getUint32(76, true),
+  };
+}
+
+// accounts created before layout versioning have no header and are reported as version 0,
+// call migrateState to bring them to BRIDGE_STATE_CURRENT_VERSION
+export function decodeBridgeStateLayout(data: Uint8Array): BridgeStateLayout {
+  const magic = new TextDecoder().decode(data.subarray(0, 4));
+  if (magic !== BRIDGE_STATE_VERSION_MAGIC) {
+    return { version: 0, coreOffset: 0, dogeMintOffset: data.length - 32 };
+  }
+  const view = new DataView(data.buffer, data.byteOffset, BRIDGE_STATE_VERSION_HEADER_SIZE);
+  return {
+    version: view.getUint32(4, true),
+    coreOffset: BRIDGE_STATE_VERSION_HEADER_SIZE,
+    dogeMintOffset: data.length - BRIDGE_STATE_RESERVED_SIZE - 32,
+  };
+}
+
+export function decodeCustodianRotation(buffer: Uint8Array, offset: number = 0): CustodianRotation {
+  const view = new DataView(buffer.buffer, buffer.byteOffset + offset + 32, 16);
+  return {
+    pendingCustodianWalletConfigHash: buffer.slice(offset, offset + 32),
+    scheduledAtSecs: view.getBigUint64(0, true),
+    sweepAfterSecs: view.getBigUint64(8, true),
+  };
+}
+
+export function decodeBlockHashArchiveSegment(buffer: Uint8Array): BlockHashArchiveSegment {
+  const view = new DataView(buffer.buffer, buffer.byteOffset, buffer.length);
+  const len = view.getUint32(4, true);
+  const entries: BlockHashArchiveEntry[] = [];
+  for (let i = 0; i < len; i++) {
+    const pos = BLOCK_HASH_ARCHIVE_SEGMENT_HEADER_SIZE + i * BLOCK_HASH_ARCHIVE_ENTRY_SIZE;
+    entries.push({
+      blockHash: buffer.slice(pos, pos + 32),
+      blockMerkleTreeRoot: buffer.slice(pos + 32, pos + 64),
+      blockHeight: view.getUint32(pos + 64, true),
+    });
+  }
+  return {
+    segmentIndex: view.getUint32(0, true),
+    firstBlockHeight: view.getUint32(8, true),
+    entries,
+  };
+}
+
 export function decodePsyBridgeConfig(
   buffer: Uint8Array,
   offset: number = 0
 ): PsyBridgeConfig {
-  const view = new DataView(buffer.buffer, buffer.byteOffset + offset, 48);
+  const view = new DataView(buffer.buffer, buffer.byteOffset + offset, PSY_BRIDGE_CONFIG_SIZE);
   return {
     depositFeeRateNumerator: view.getBigUint64(0, true),
     depositFeeRateDenominator: view.getBigUint64(8, true),
@@ -130,8 +130,57 @@ fn print_event(event: &BridgeEvent) {
                 println!("  Block Time: {}", block_time);
             }
             println!("  Block Height: {}", e.block_height);
+            println!("  Block Hash: {}", hex::encode(e.block_hash));
+            println!("  Tip Block Height: {}", e.tip_block_height);
             println!("  Is Reorg: {}", e.is_reorg);
             println!("========================\n");
         }
+        BridgeEvent::MintGroupProcessed(e) => {
+            println!("=== Mint Group Processed ===");
+            println!("  Signature: {}", e.signature);
+            println!("  Slot: {}", e.slot);
+            println!("  Mint Buffer: {}", e.mint_buffer);
+            println!("  Group Index: {}", e.mint_group_index);
+            println!("  Mints: {}", e.mints_count);
+            println!("  Total Minted: {} sats", e.total_minted_sats);
+            println!("  Buffer Unlocked: {}", e.buffer_unlocked);
+            println!("============================\n");
+        }
+        BridgeEvent::FeesWithdrawn(e) => {
+            println!("=== Fees Withdrawn ===");
+            println!("  Signature: {}", e.signature);
+            println!("  Slot: {}", e.slot);
+            println!("  Token Account: {}", e.token_account);
+            println!("  Amount: {} sats", e.amount_sats);
+            println!("  Total Withdrawn: {} sats", e.total_fees_withdrawn_sats);
+            println!("======================\n");
+        }
+        BridgeEvent::WithdrawalCancelled(e) => {
+            println!("=== Withdrawal Cancelled ===");
+            println!("  Signature: {}", e.signature);
+            println!("  Slot: {}", e.slot);
+            println!("  User Pubkey: {}", e.user_pubkey);
+            println!("  Withdrawal Index: {}", e.withdrawal_index);
+            println!("  Refund: {} sats", e.refund_amount_sats);
+            println!("============================\n");
+        }
+        BridgeEvent::CustodianRotated(e) => {
+            println!("=== Custodian Rotated ===");
+            println!("  Signature: {}", e.signature);
+            println!("  Slot: {}", e.slot);
+            println!("  Old Config Hash: {}", hex::encode(e.old_custodian_wallet_config_hash));
+            println!("  New Config Hash: {}", hex::encode(e.new_custodian_wallet_config_hash));
+            println!("  Sweep TX Sighash: {}", hex::encode(e.sighash));
+            println!("  New Return Output Amount: {} sats", e.new_return_output_amount);
+            println!("=========================\n");
+        }
+        BridgeEvent::WithdrawalScript(e) => {
+            println!("=== Withdrawal Script ===");
+            println!("  Signature: {}", e.signature);
+            println!("  Slot: {}", e.slot);
+            println!("  Withdrawal Index: {}", e.withdrawal_index);
+            println!("  Script: {}", hex::encode(&e.script_pubkey));
+            println!("=========================\n");
+        }
     }
 }
@@ -19,7 +19,8 @@ impl BridgeClient {
         txo_buffer_account: Pubkey,
         txo_buffer_bump: u8,
     ) -> Result<Signature, BridgeError> {
-        let ix = instructions::block_update(
+        let block_hash_archive_len = self.get_current_bridge_state_impl().await?.block_hash_archive_len;
+        let mut ix = instructions::block_update(
             self.config.program_id,
             self.config.payer.pubkey(),
             proof,
@@ -30,6 +31,10 @@ impl BridgeClient {
             mint_buffer_bump,
             txo_buffer_bump,
         );
+        ix.accounts.extend(instructions::block_hash_archive_accounts(
+            &self.config.program_id,
+            block_hash_archive_len,
+        ));
 
         self.send_and_confirm(&[ix], &[self.config.operator.as_ref()])
             .await
@@ -41,12 +46,28 @@ impl BridgeClient {
         proof: CompactBridgeZKProof,
         header: PsyBridgeHeader,
         extra_blocks: Vec<FinalizedBlockMintTxoInfo>,
+        reorg_block_hashes: Vec<[u8; 32]>,
         mint_buffer_account: Pubkey,
         mint_buffer_bump: u8,
         txo_buffer_account: Pubkey,
         txo_buffer_bump: u8,
     ) -> Result<Signature, BridgeError> {
-        let ix = instructions::process_reorg_blocks(
+        let block_hash_archive_len = self.get_current_bridge_state_impl().await?.block_hash_archive_len;
+        // the archive gets an entry for every height the reorg finalizes
+        let finalized_blocks_count = extra_blocks.len() as u32 + 1;
+        let reorg_block_hashes_buffer = if block_hash_archive_len != 0 && finalized_blocks_count > 1 {
+            Some(
+                self.buffer_manager
+                    .create_generic_buffer(
+                        self.config.generic_buffer_program_id,
+                        bytemuck::cast_slice(&reorg_block_hashes),
+                    )
+                    .await?,
+            )
+        } else {
+            None
+        };
+        let mut ix = instructions::process_reorg_blocks(
             self.config.program_id,
             self.config.payer.pubkey(),
             proof,
@@ -58,6 +79,12 @@ impl BridgeClient {
             mint_buffer_bump,
             txo_buffer_bump,
         );
+        ix.accounts.extend(instructions::reorg_block_hash_archive_accounts(
+            &self.config.program_id,
+            block_hash_archive_len,
+            finalized_blocks_count,
+            reorg_block_hashes_buffer,
+        ));
 
         self.send_and_confirm(&[ix], &[self.config.operator.as_ref()])
             .await
@@ -4,9 +4,13 @@ use crate::{
     client::BridgeClient,
     errors::BridgeError,
     instructions,
-    types::{CompactBridgeZKProof, DepositTxOutputRecord, InitializeBridgeParams},
+    types::{CompactBridgeZKProof, DepositTxOutputRecord, FeeSplitTable, InitializeBridgeParams},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{Keypair, Signature},
+    signer::Signer,
 };
-use solana_sdk::{signature::{Keypair, Signature}, signer::Signer};
 
 impl BridgeClient {
     /// Get manual deposits starting from a specific index.
@@ -109,4 +113,84 @@ impl BridgeClient {
         self.send_and_confirm(&[ix], &[self.config.operator.as_ref()])
             .await
     }
+
+    /// Get the fee split table, if one has been set.
+    pub async fn get_fee_split_impl(&self) -> Result<Option<FeeSplitTable>, BridgeError> {
+        let _guard = self.rate_limiter.acquire().await?;
+
+        let fee_split_pda = instructions::get_fee_split_pda(&self.config.program_id);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&fee_split_pda, CommitmentConfig::confirmed())
+            .await?
+            .value;
+
+        Ok(account.and_then(|account| {
+            bytemuck::try_from_bytes::<FeeSplitTable>(&account.data)
+                .ok()
+                .copied()
+        }))
+    }
+
+    /// Set the fee split table (fee spender only).
+    pub async fn set_fee_split_impl(
+        &self,
+        fee_spender: &Keypair,
+        fee_split: &FeeSplitTable,
+    ) -> Result<Signature, BridgeError> {
+        let ix = instructions::set_fee_split(
+            self.config.program_id,
+            fee_spender.pubkey(),
+            self.config.payer.pubkey(),
+            fee_split,
+        );
+
+        self.send_and_confirm(&[ix], &[fee_spender]).await
+    }
+
+    /// Distribute the withdrawable fees according to the fee split table.
+    pub async fn distribute_fees_impl(&self) -> Result<Signature, BridgeError> {
+        let doge_mint = self.get_doge_mint().await?;
+        let fee_split = self
+            .get_fee_split_impl()
+            .await?
+            .ok_or_else(|| BridgeError::MissingField {
+                field: "fee_split".to_string(),
+            })?;
+
+        let ix = instructions::distribute_fees(self.config.program_id, doge_mint, &fee_split);
+
+        self.send_and_confirm(&[ix], &[]).await
+    }
+
+    /// Allocate and enable the finalized block history (settings authority only).
+    pub async fn enable_finalized_block_history_impl(
+        &self,
+        authority: &Keypair,
+        capacity: u32,
+    ) -> Result<Signature, BridgeError> {
+        let mut signature = Signature::default();
+        for step in 0..instructions::init_finalized_block_history_steps(capacity) {
+            let ix = instructions::init_finalized_block_history(
+                self.config.program_id,
+                authority.pubkey(),
+                self.config.payer.pubkey(),
+                capacity,
+                step,
+            );
+            signature = self.send_and_confirm(&[ix], &[authority]).await?;
+        }
+        Ok(signature)
+    }
+
+    /// Start the block hash archive at the current finalized block (settings authority only).
+    pub async fn enable_block_hash_archive_impl(&self, authority: &Keypair) -> Result<Signature, BridgeError> {
+        let ix = instructions::init_block_hash_archive(
+            self.config.program_id,
+            authority.pubkey(),
+            self.config.payer.pubkey(),
+        );
+
+        self.send_and_confirm(&[ix], &[authority]).await
+    }
 }
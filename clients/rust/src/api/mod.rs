@@ -16,9 +16,10 @@ use solana_sdk::{pubkey::Pubkey, signature::{Keypair, Signature}};
 use crate::{
     errors::BridgeError,
     types::{
-        CompactBridgeZKProof, DepositTxOutputRecord, FinalizedBlockMintTxoInfo,
+        BlockHashArchiveEntry, Bridge7MultisigCustodianWalletConfig, CompactBridgeZKProof, DepositTxOutputRecord, FeeSplitTable, FinalizedBlockMintTxoInfo,
         InitializeBridgeParams, PendingMint, ProcessMintsResult, PsyBridgeHeader,
-        PsyBridgeProgramState, PsyReturnTxOutput, PsyWithdrawalChainSnapshot,
+        PsyBridgeProgramState, PsyCustodianRotation, PsyReturnTxOutput, PsyWithdrawalChainSnapshot,
+        PsyWithdrawalRequest, WithdrawalReceipt,
     },
 };
 
@@ -29,8 +30,29 @@ use crate::{
 #[async_trait]
 pub trait BridgeApi: Send + Sync {
     /// Get the current bridge program state from on-chain.
+    ///
+    /// Accounts still in an older layout version are decoded as well, see `migrate_state`.
     async fn get_current_bridge_state(&self) -> Result<PsyBridgeProgramState, BridgeError>;
 
+    /// Look up a finalized block in the block hash archive.
+    ///
+    /// Returns `None` if the archive is disabled, the height predates it, or the height
+    /// is not finalized yet.
+    async fn get_archived_block(
+        &self,
+        block_height: u32,
+    ) -> Result<Option<BlockHashArchiveEntry>, BridgeError>;
+
+    /// Get the custodian wallet config stored for the bridge's `custodian_wallet_config_hash`.
+    ///
+    /// Returns `None` if no config has been stored yet or the stored one is for an older hash.
+    async fn get_custodian_wallet_config(
+        &self,
+    ) -> Result<Option<Bridge7MultisigCustodianWalletConfig>, BridgeError>;
+
+    /// Get the scheduled custodian rotation, or `None` if no rotation is scheduled.
+    async fn get_custodian_rotation(&self) -> Result<Option<PsyCustodianRotation>, BridgeError>;
+
     /// Get manual deposits starting from a specific index.
     ///
     /// Returns up to `max_count` deposit records starting from the given index.
@@ -78,11 +100,15 @@ pub trait BridgeApi: Send + Sync {
     /// Process a block reorganization.
     ///
     /// Handles chain reorgs by submitting multiple blocks at once.
+    /// While the block hash archive is enabled, a reorg that finalizes more than one height also
+    /// takes `reorg_block_hashes`: the block merkle tree siblings of the current finalized block
+    /// followed by the hashes of the heights between it and the new finalized block.
     async fn process_block_reorg(
         &self,
         proof: CompactBridgeZKProof,
         header: PsyBridgeHeader,
         extra_blocks: Vec<FinalizedBlockMintTxoInfo>,
+        reorg_block_hashes: Vec<[u8; 32]>,
         mint_buffer_account: Pubkey,
         mint_buffer_bump: u8,
         txo_buffer_account: Pubkey,
@@ -118,13 +144,40 @@ pub trait BridgeApi: Send + Sync {
 pub trait WithdrawalApi: Send + Sync {
     /// Request a withdrawal from Solana to Dogecoin.
     ///
-    /// Burns tokens on Solana to receive DOGE on the Dogecoin network.
+    /// Burns tokens on Solana to receive DOGE on the Dogecoin network. `receipt_nonce` keys the
+    /// receipt created for the request and must not be used by another open receipt of the user.
     async fn request_withdrawal(
         &self,
         user_authority: &Keypair,
         recipient_address: [u8; 20],
         amount_sats: u64,
         address_type: u32,
+        receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError>;
+
+    /// Request several withdrawals in a single transaction.
+    ///
+    /// Burns the total of the requested amounts once, each request is charged its own fee and
+    /// gets its own receipt, keyed by consecutive nonces starting at `first_receipt_nonce`.
+    /// At most `MAX_WITHDRAWAL_REQUESTS_PER_BATCH` requests fit in a batch.
+    async fn request_withdrawals_batch(
+        &self,
+        user_authority: &Keypair,
+        requests: &[PsyWithdrawalRequest],
+        first_receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError>;
+
+    /// Request a withdrawal to an arbitrary scriptPubKey.
+    ///
+    /// The queued request commits to the hash160 of the script, which is logged alongside the
+    /// request so the withdrawal can be built. Scripts are at most `MAX_WITHDRAWAL_SCRIPT_SIZE` bytes,
+    /// which leaves room for memo/tag data an exchange credits deposits by.
+    async fn request_script_withdrawal(
+        &self,
+        user_authority: &Keypair,
+        script_pubkey: &[u8],
+        amount_sats: u64,
+        receipt_nonce: u64,
     ) -> Result<Signature, BridgeError>;
 
     /// Process a withdrawal transaction.
@@ -139,8 +192,51 @@ pub trait WithdrawalApi: Send + Sync {
         doge_tx_bytes: &[u8],
     ) -> Result<Signature, BridgeError>;
 
+    /// Process the transaction sweeping the return output to the scheduled custodian.
+    ///
+    /// Accepted once the rotation timelock has elapsed, after which the bridge commits to the
+    /// new custodian wallet config.
+    async fn process_custodian_sweep(
+        &self,
+        proof: CompactBridgeZKProof,
+        new_return_output: PsyReturnTxOutput,
+        new_spent_txo_tree_root: [u8; 32],
+        doge_tx_bytes: &[u8],
+    ) -> Result<Signature, BridgeError>;
+
     /// Replay a withdrawal message (for Wormhole integration).
     async fn replay_withdrawal(&self, doge_tx_bytes: &[u8]) -> Result<Signature, BridgeError>;
+
+    /// Get the receipt created for a withdrawal request.
+    ///
+    /// Returns `None` if the requester has no receipt under that nonce or it has been closed.
+    async fn get_withdrawal_receipt(
+        &self,
+        requester: &Pubkey,
+        receipt_nonce: u64,
+    ) -> Result<Option<WithdrawalReceipt>, BridgeError>;
+
+    /// Mark receipts fulfilled once their withdrawals have been processed.
+    async fn mark_withdrawal_receipts_fulfilled(
+        &self,
+        withdrawal_receipts: &[Pubkey],
+    ) -> Result<Signature, BridgeError>;
+
+    /// Cancel a withdrawal request that no snapshot covers yet.
+    ///
+    /// The burned amount, minus the cancellation fee, is minted back to the requester's ATA.
+    async fn cancel_withdrawal(
+        &self,
+        requester: &Keypair,
+        receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError>;
+
+    /// Close a fulfilled or cancelled receipt and return its rent to the requester.
+    async fn close_withdrawal_receipt(
+        &self,
+        requester: &Keypair,
+        receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError>;
 }
 
 /// API trait for manual claim operations.
@@ -178,6 +274,70 @@ pub trait OperatorApi: Send + Sync {
     /// Operator-only operation to withdraw bridge fees.
     async fn operator_withdraw_fees(&self) -> Result<Signature, BridgeError>;
 
+    /// Get the fee split table, if one has been set.
+    async fn get_fee_split(&self) -> Result<Option<FeeSplitTable>, BridgeError>;
+
+    /// Set the fee split table.
+    ///
+    /// Fee spender-only operation. Once a table is set, fees can only be paid out
+    /// through `distribute_fees`.
+    async fn set_fee_split(
+        &self,
+        fee_spender: &Keypair,
+        fee_split: &FeeSplitTable,
+    ) -> Result<Signature, BridgeError>;
+
+    /// Distribute the withdrawable fees according to the fee split table.
+    ///
+    /// Permissionless, any payer can crank the distribution.
+    async fn distribute_fees(&self) -> Result<Signature, BridgeError>;
+
+    /// Allocate and enable the finalized block history.
+    ///
+    /// Settings authority-only operation. Manual claims can then prove against any of the
+    /// last `capacity` finalized blocks instead of only the last 8. Large capacities take
+    /// several transactions, the signature of the last one is returned.
+    async fn enable_finalized_block_history(
+        &self,
+        authority: &Keypair,
+        capacity: u32,
+    ) -> Result<Signature, BridgeError>;
+
+    /// Start the block hash archive at the current finalized block.
+    ///
+    /// Settings authority-only operation. From then on every block update and reorg appends
+    /// the new finalized block to the archive, growing it at the payer's expense.
+    async fn enable_block_hash_archive(&self, authority: &Keypair) -> Result<Signature, BridgeError>;
+
+    /// Migrate the bridge state account to the current layout version.
+    ///
+    /// Permissionless, the payer covers the rent of any extra space. Sends one transaction per
+    /// version step and returns `None` if the account already uses the current layout.
+    async fn migrate_state(&self) -> Result<Option<Signature>, BridgeError>;
+
+    /// Store the custodian wallet config behind the bridge's `custodian_wallet_config_hash`.
+    ///
+    /// Permissionless, the program only accepts the config the hash commits to.
+    async fn store_custodian_wallet_config(
+        &self,
+        config: &Bridge7MultisigCustodianWalletConfig,
+    ) -> Result<Signature, BridgeError>;
+
+    /// Schedule a rotation to a new custodian wallet config.
+    ///
+    /// Settings authority-only operation. The bridge keeps committing to the current custodian
+    /// until the return output has been swept to the new one with `process_custodian_sweep`.
+    async fn schedule_custodian_rotation(
+        &self,
+        authority: &Keypair,
+        new_config: &Bridge7MultisigCustodianWalletConfig,
+    ) -> Result<Signature, BridgeError>;
+
+    /// Cancel the scheduled custodian rotation.
+    ///
+    /// Can be signed by the settings authority or the guardian.
+    async fn cancel_custodian_rotation(&self, authority: &Keypair) -> Result<Signature, BridgeError>;
+
     /// Execute snapshot withdrawals.
     ///
     /// Operator-only operation to snapshot the current withdrawal chain state.
@@ -3,10 +3,23 @@
 use crate::{
     client::BridgeClient,
     errors::BridgeError,
-    types::{PsyBridgeProgramState, PsyWithdrawalChainSnapshot},
+    instructions,
+    types::{
+        BlockHashArchiveEntry, Bridge7MultisigCustodianWalletConfig,
+        PsyBridgeProgramState, PsyCustodianRotation, PsyWithdrawalChainSnapshot,
+    },
+};
+use psy_doge_solana_core::{
+    data_accounts::block_hash_archive::{block_hash_archive_position, BlockHashArchiveSegment},
+    program_state::{
+        decode_bridge_state_account, BridgeProgramStateWithDogeMint, BRIDGE_STATE_CURRENT_VERSION,
+    },
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{Keypair, Signature},
+    signer::Signer,
 };
-use psy_doge_solana_core::program_state::BridgeProgramStateWithDogeMint;
-use solana_sdk::commitment_config::CommitmentConfig;
 
 impl BridgeClient {
     /// Get the current bridge program state from on-chain.
@@ -27,14 +40,179 @@ impl BridgeClient {
                         address: self.config.bridge_state_pda.to_string(),
                     })?;
 
-                let bridge_state: &BridgeProgramStateWithDogeMint =
-                    bytemuck::from_bytes(&account.data);
+                let bridge_state = self.decode_bridge_state(&account.data)?;
 
-                Ok(bridge_state.core_state.clone())
+                Ok(bridge_state.core_state)
             })
             .await
     }
 
+    /// Look up a finalized block in the block hash archive.
+    ///
+    /// Segments are filled in height order, so the segment holding a height is found by a
+    /// binary search over the segments' first heights.
+    pub async fn get_archived_block_impl(
+        &self,
+        block_height: u32,
+    ) -> Result<Option<BlockHashArchiveEntry>, BridgeError> {
+        let block_hash_archive_len = self.get_current_bridge_state_impl().await?.block_hash_archive_len;
+        if block_hash_archive_len == 0 {
+            return Ok(None);
+        }
+        let (last_segment_index, _) = block_hash_archive_position(block_hash_archive_len - 1);
+
+        // the last segment whose first height is at most block_height
+        let (mut low, mut high) = (0u32, last_segment_index + 1);
+        let mut candidate = None;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let data = self.get_block_hash_archive_segment_data(mid).await?;
+            let (header, _) = BlockHashArchiveSegment::read_account_data(&data).ok_or_else(|| {
+                BridgeError::InvalidBridgeState {
+                    message: format!("malformed block hash archive segment {}", mid),
+                }
+            })?;
+            if header.first_block_height <= block_height {
+                candidate = Some(data);
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        Ok(candidate.and_then(|data| BlockHashArchiveSegment::find_in_account_data(&data, block_height)))
+    }
+
+    async fn get_block_hash_archive_segment_data(&self, segment_index: u32) -> Result<Vec<u8>, BridgeError> {
+        let _guard = self.rate_limiter.acquire().await?;
+
+        let segment_pda = instructions::get_block_hash_archive_segment_pda(&self.config.program_id, segment_index);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&segment_pda, CommitmentConfig::confirmed())
+            .await?
+            .value
+            .ok_or_else(|| BridgeError::AccountNotFound {
+                address: segment_pda.to_string(),
+            })?;
+        Ok(account.data)
+    }
+
+    /// Decode the bridge state account, whichever layout version it is still in.
+    pub fn decode_bridge_state(&self, data: &[u8]) -> Result<BridgeProgramStateWithDogeMint, BridgeError> {
+        decode_bridge_state_account(data).map_err(|err| BridgeError::InvalidBridgeState {
+            message: err.to_string(),
+        })
+    }
+
+    /// Layout version of the on-chain bridge state account.
+    pub async fn get_bridge_state_version_impl(&self) -> Result<u32, BridgeError> {
+        let _guard = self.rate_limiter.acquire().await?;
+
+        let account = self
+            .rpc
+            .get_account_with_commitment(
+                &self.config.bridge_state_pda,
+                CommitmentConfig::confirmed(),
+            )
+            .await?
+            .value
+            .ok_or_else(|| BridgeError::AccountNotFound {
+                address: self.config.bridge_state_pda.to_string(),
+            })?;
+
+        Ok(self.decode_bridge_state(&account.data)?.version_header.version)
+    }
+
+    /// Migrate the bridge state account to the current layout version, one version per transaction.
+    pub async fn migrate_state_impl(&self) -> Result<Option<Signature>, BridgeError> {
+        let mut signature = None;
+        while self.get_bridge_state_version_impl().await? < BRIDGE_STATE_CURRENT_VERSION {
+            let ix = instructions::migrate_state(self.config.program_id, self.config.payer.pubkey());
+            signature = Some(self.send_and_confirm(&[ix], &[]).await?);
+        }
+        Ok(signature)
+    }
+
+    /// Get the custodian wallet config, if the stored one matches the current config hash.
+    pub async fn get_custodian_wallet_config_impl(
+        &self,
+    ) -> Result<Option<Bridge7MultisigCustodianWalletConfig>, BridgeError> {
+        let custodian_wallet_config_hash = self.get_current_bridge_state_impl().await?.custodian_wallet_config_hash;
+        let _guard = self.rate_limiter.acquire().await?;
+
+        let config_pda = instructions::get_custodian_wallet_config_pda(&self.config.program_id);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&config_pda, CommitmentConfig::confirmed())
+            .await?
+            .value;
+
+        Ok(account
+            .and_then(|account| {
+                bytemuck::try_from_bytes::<Bridge7MultisigCustodianWalletConfig>(&account.data)
+                    .ok()
+                    .copied()
+            })
+            .filter(|config| config.get_wallet_config_hash() == custodian_wallet_config_hash))
+    }
+
+    /// Store the custodian wallet config preimage on-chain.
+    pub async fn store_custodian_wallet_config_impl(
+        &self,
+        config: &Bridge7MultisigCustodianWalletConfig,
+    ) -> Result<Signature, BridgeError> {
+        let ix = instructions::store_custodian_wallet_config(
+            self.config.program_id,
+            self.config.payer.pubkey(),
+            config,
+        );
+
+        self.send_and_confirm(&[ix], &[]).await
+    }
+
+    /// Get the scheduled custodian rotation, if any.
+    pub async fn get_custodian_rotation_impl(&self) -> Result<Option<PsyCustodianRotation>, BridgeError> {
+        let _guard = self.rate_limiter.acquire().await?;
+
+        let account = self
+            .rpc
+            .get_account_with_commitment(
+                &self.config.bridge_state_pda,
+                CommitmentConfig::confirmed(),
+            )
+            .await?
+            .value
+            .ok_or_else(|| BridgeError::AccountNotFound {
+                address: self.config.bridge_state_pda.to_string(),
+            })?;
+
+        let custodian_rotation = self.decode_bridge_state(&account.data)?.custodian_rotation;
+        Ok(Some(custodian_rotation).filter(|rotation| rotation.is_scheduled()))
+    }
+
+    /// Schedule a rotation to the given custodian wallet config.
+    pub async fn schedule_custodian_rotation_impl(
+        &self,
+        authority: &Keypair,
+        new_config: &Bridge7MultisigCustodianWalletConfig,
+    ) -> Result<Signature, BridgeError> {
+        let ix = instructions::schedule_custodian_rotation(
+            self.config.program_id,
+            authority.pubkey(),
+            new_config.get_wallet_config_hash(),
+        );
+
+        self.send_and_confirm(&[ix], &[authority]).await
+    }
+
+    /// Cancel the scheduled custodian rotation.
+    pub async fn cancel_custodian_rotation_impl(&self, authority: &Keypair) -> Result<Signature, BridgeError> {
+        let ix = instructions::cancel_custodian_rotation(self.config.program_id, authority.pubkey());
+
+        self.send_and_confirm(&[ix], &[authority]).await
+    }
+
     /// Get the DOGE mint from on-chain state.
     pub async fn get_doge_mint_from_state(&self) -> Result<solana_sdk::pubkey::Pubkey, BridgeError> {
         let _guard = self.rate_limiter.acquire().await?;
@@ -51,7 +229,7 @@ impl BridgeClient {
                 address: self.config.bridge_state_pda.to_string(),
             })?;
 
-        let bridge_state: &BridgeProgramStateWithDogeMint = bytemuck::from_bytes(&account.data);
+        let bridge_state = self.decode_bridge_state(&account.data)?;
 
         Ok(solana_sdk::pubkey::Pubkey::new_from_array(bridge_state.doge_mint))
     }
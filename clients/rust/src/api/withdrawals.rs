@@ -4,9 +4,18 @@ use crate::{
     client::BridgeClient,
     errors::BridgeError,
     instructions,
-    types::{CompactBridgeZKProof, PsyReturnTxOutput},
+    types::{CompactBridgeZKProof, PsyReturnTxOutput, PsyWithdrawalRequest},
+};
+use psy_doge_solana_core::{
+    constants::{MAX_WITHDRAWAL_REQUESTS_PER_BATCH, MAX_WITHDRAWAL_SCRIPT_SIZE},
+    data_accounts::withdrawal_receipt::WithdrawalReceipt,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
 };
-use solana_sdk::{signature::{Keypair, Signature}, signer::Signer};
 
 impl BridgeClient {
     /// Request a withdrawal from Solana to Dogecoin.
@@ -16,6 +25,7 @@ impl BridgeClient {
         recipient_address: [u8; 20],
         amount_sats: u64,
         address_type: u32,
+        receipt_nonce: u64,
     ) -> Result<Signature, BridgeError> {
         let doge_mint = self.get_doge_mint().await?;
 
@@ -26,12 +36,86 @@ impl BridgeClient {
 
         let ix = instructions::request_withdrawal(
             self.config.program_id,
+            self.config.payer.pubkey(),
             user_authority.pubkey(),
             doge_mint,
             user_token_account,
             recipient_address,
             amount_sats,
             address_type,
+            receipt_nonce,
+        );
+
+        self.send_and_confirm(&[ix], &[user_authority]).await
+    }
+
+    /// Request several withdrawals in a single transaction, burning their total once.
+    pub async fn request_withdrawals_batch_impl(
+        &self,
+        user_authority: &Keypair,
+        requests: &[PsyWithdrawalRequest],
+        first_receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError> {
+        if requests.is_empty() || requests.len() > MAX_WITHDRAWAL_REQUESTS_PER_BATCH {
+            return Err(BridgeError::InvalidInput(format!(
+                "withdrawal batch must hold 1 to {} requests, got {}",
+                MAX_WITHDRAWAL_REQUESTS_PER_BATCH,
+                requests.len()
+            )));
+        }
+        let doge_mint = self.get_doge_mint().await?;
+
+        let user_token_account = spl_associated_token_account::get_associated_token_address(
+            &user_authority.pubkey(),
+            &doge_mint,
+        );
+
+        let ix = instructions::request_withdrawals_batch(
+            self.config.program_id,
+            self.config.payer.pubkey(),
+            user_authority.pubkey(),
+            doge_mint,
+            user_token_account,
+            requests,
+            first_receipt_nonce,
+        );
+
+        self.send_and_confirm(&[ix], &[user_authority]).await
+    }
+
+    /// Request a withdrawal to an arbitrary scriptPubKey.
+    pub async fn request_script_withdrawal_impl(
+        &self,
+        user_authority: &Keypair,
+        script_pubkey: &[u8],
+        amount_sats: u64,
+        receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError> {
+        if script_pubkey.is_empty() || script_pubkey.len() > MAX_WITHDRAWAL_SCRIPT_SIZE {
+            return Err(BridgeError::InvalidInput(format!(
+                "withdrawal script must be 1 to {} bytes, got {}",
+                MAX_WITHDRAWAL_SCRIPT_SIZE,
+                script_pubkey.len()
+            )));
+        }
+        let doge_mint = self.get_doge_mint().await?;
+
+        let user_token_account = spl_associated_token_account::get_associated_token_address(
+            &user_authority.pubkey(),
+            &doge_mint,
+        );
+
+        // a bounded script always fits in the instruction data, no buffer needed
+        let ix = instructions::request_script_withdrawal(
+            self.config.program_id,
+            self.config.payer.pubkey(),
+            user_authority.pubkey(),
+            doge_mint,
+            user_token_account,
+            script_pubkey,
+            amount_sats,
+            receipt_nonce,
+            None,
         );
 
         self.send_and_confirm(&[ix], &[user_authority]).await
@@ -68,6 +152,33 @@ impl BridgeClient {
             .await
     }
 
+    /// Process the transaction sweeping the return output to the scheduled custodian.
+    pub async fn process_custodian_sweep_impl(
+        &self,
+        proof: CompactBridgeZKProof,
+        new_return_output: PsyReturnTxOutput,
+        new_spent_txo_tree_root: [u8; 32],
+        doge_tx_bytes: &[u8],
+    ) -> Result<Signature, BridgeError> {
+        let buffer = self
+            .buffer_manager
+            .create_generic_buffer(self.config.generic_buffer_program_id, doge_tx_bytes)
+            .await?;
+
+        let ix = instructions::process_custodian_sweep(
+            self.config.program_id,
+            self.config.payer.pubkey(),
+            buffer,
+            self.config.wormhole_shim_program_id,
+            self.config.wormhole_core_program_id,
+            proof,
+            new_return_output,
+            new_spent_txo_tree_root,
+        );
+
+        self.send_and_confirm(&[ix], &[]).await
+    }
+
     /// Replay a withdrawal message.
     pub async fn replay_withdrawal_impl(
         &self,
@@ -100,4 +211,79 @@ impl BridgeClient {
 
         self.send_and_confirm(&[ix], &[self.config.operator.as_ref()]).await
     }
+
+    /// Get the receipt of a withdrawal request, `None` if it was never created or has been closed.
+    pub async fn get_withdrawal_receipt_impl(
+        &self,
+        requester: &Pubkey,
+        receipt_nonce: u64,
+    ) -> Result<Option<WithdrawalReceipt>, BridgeError> {
+        let _guard = self.rate_limiter.acquire().await?;
+
+        let receipt_pda =
+            instructions::get_withdrawal_receipt_pda(&self.config.program_id, requester, receipt_nonce);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&receipt_pda, CommitmentConfig::confirmed())
+            .await?
+            .value;
+
+        Ok(account.and_then(|account| {
+            bytemuck::try_from_bytes::<WithdrawalReceipt>(&account.data)
+                .ok()
+                .copied()
+        }))
+    }
+
+    /// Mark receipts of processed withdrawals as fulfilled.
+    pub async fn mark_withdrawal_receipts_fulfilled_impl(
+        &self,
+        withdrawal_receipts: &[Pubkey],
+    ) -> Result<Signature, BridgeError> {
+        let ix = instructions::mark_withdrawal_receipts_fulfilled(
+            self.config.program_id,
+            withdrawal_receipts,
+        );
+
+        self.send_and_confirm(&[ix], &[]).await
+    }
+
+    /// Cancel a withdrawal request that has not been snapshotted yet.
+    pub async fn cancel_withdrawal_impl(
+        &self,
+        requester: &Keypair,
+        receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError> {
+        let doge_mint = self.get_doge_mint().await?;
+
+        let user_token_account = spl_associated_token_account::get_associated_token_address(
+            &requester.pubkey(),
+            &doge_mint,
+        );
+
+        let ix = instructions::cancel_withdrawal(
+            self.config.program_id,
+            requester.pubkey(),
+            doge_mint,
+            user_token_account,
+            receipt_nonce,
+        );
+
+        self.send_and_confirm(&[ix], &[requester]).await
+    }
+
+    /// Close a fulfilled or cancelled receipt, returning its rent to the requester.
+    pub async fn close_withdrawal_receipt_impl(
+        &self,
+        requester: &Keypair,
+        receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError> {
+        let ix = instructions::close_withdrawal_receipt(
+            self.config.program_id,
+            requester.pubkey(),
+            receipt_nonce,
+        );
+
+        self.send_and_confirm(&[ix], &[requester]).await
+    }
 }
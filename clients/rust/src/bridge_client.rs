@@ -11,7 +11,7 @@ use psy_bridge_core::{
     crypto::{hash::sha256_impl::hash_impl_sha256_bytes, zk::CompactBridgeZKProof}, header::PsyBridgeHeaderUpdate}
 ;
 use psy_doge_solana_core::{
-    data_accounts::pending_mint::{PM_DA_DEFAULT_PENDING_MINTS_BUFFER_HASH, PM_TXO_DEFAULT_BUFFER_HASH, PendingMint}, instructions::doge_bridge::InitializeBridgeParams, program_state::{FinalizedBlockMintTxoInfo, PsyBridgeProgramState, PsyReturnTxOutput}
+    data_accounts::pending_mint::{PM_DA_DEFAULT_PENDING_MINTS_BUFFER_HASH, PM_TXO_DEFAULT_BUFFER_HASH, PendingMint}, instructions::doge_bridge::InitializeBridgeParams, program_state::{decode_bridge_state_account, FinalizedBlockMintTxoInfo, PsyBridgeProgramState, PsyReturnTxOutput, PsyWithdrawalRequest}
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
@@ -125,14 +125,94 @@ impl DogeBridgeClient {
         amount_sats: u64,
         address_type: u32,
     ) -> Result<(), ClientError> {
+        let bridge_state_pda = Pubkey::find_program_address(&[b"bridge_state"], &self.program_id).0;
+        let bridge_account = self
+            .client
+            .get_account(&bridge_state_pda)
+            .await?;
+        let bridge_state = decode_bridge_state_account(&bridge_account.data).map_err(|err| {
+            ClientError::InvalidBridgeState {
+                message: err.to_string(),
+            }
+        })?;
+
         let ix = instructions::request_withdrawal(
             self.program_id,
+            self.payer.pubkey(),
             user_authority.pubkey(),
             mint,
             user_token_account,
             recipient_address,
             amount_sats,
             address_type,
+            bridge_state.core_state.requested_withdrawals_tree.next_index,
+        );
+        self.buffer_manager()
+            .send_tx(&[ix], &[user_authority])
+            .await
+    }
+
+    pub async fn request_withdrawals_batch(
+        &self,
+        mint: Pubkey,
+        user_token_account: Pubkey,
+        user_authority: &Keypair,
+        requests: &[PsyWithdrawalRequest],
+    ) -> Result<(), ClientError> {
+        let bridge_state_pda = Pubkey::find_program_address(&[b"bridge_state"], &self.program_id).0;
+        let bridge_account = self
+            .client
+            .get_account(&bridge_state_pda)
+            .await?;
+        let bridge_state = decode_bridge_state_account(&bridge_account.data).map_err(|err| {
+            ClientError::InvalidBridgeState {
+                message: err.to_string(),
+            }
+        })?;
+
+        let ix = instructions::request_withdrawals_batch(
+            self.program_id,
+            self.payer.pubkey(),
+            user_authority.pubkey(),
+            mint,
+            user_token_account,
+            requests,
+            bridge_state.core_state.requested_withdrawals_tree.next_index,
+        );
+        self.buffer_manager()
+            .send_tx(&[ix], &[user_authority])
+            .await
+    }
+
+    pub async fn request_script_withdrawal(
+        &self,
+        mint: Pubkey,
+        user_token_account: Pubkey,
+        user_authority: &Keypair,
+        script_pubkey: &[u8],
+        amount_sats: u64,
+    ) -> Result<(), ClientError> {
+        let bridge_state_pda = Pubkey::find_program_address(&[b"bridge_state"], &self.program_id).0;
+        let bridge_account = self
+            .client
+            .get_account(&bridge_state_pda)
+            .await?;
+        let bridge_state = decode_bridge_state_account(&bridge_account.data).map_err(|err| {
+            ClientError::InvalidBridgeState {
+                message: err.to_string(),
+            }
+        })?;
+
+        let ix = instructions::request_script_withdrawal(
+            self.program_id,
+            self.payer.pubkey(),
+            user_authority.pubkey(),
+            mint,
+            user_token_account,
+            script_pubkey,
+            amount_sats,
+            bridge_state.core_state.requested_withdrawals_tree.next_index,
+            None,
         );
         self.buffer_manager()
             .send_tx(&[ix], &[user_authority])
@@ -175,6 +255,7 @@ pub struct BlockUpdateBuilder<'a> {
     pending_mints: &'a [PendingMint],
     txo_indices: &'a [u32],
     extra_blocks: Vec<FinalizedBlockMintTxoInfo>,
+    reorg_block_hashes: Vec<[u8; 32]>,
     pub required_confirmations: u32,
 }
 
@@ -188,6 +269,7 @@ impl<'a> BlockUpdateBuilder<'a> {
             pending_mints: &[],
             txo_indices: &[],
             extra_blocks: vec![],
+            reorg_block_hashes: vec![],
             required_confirmations,
             header_update: None,
         }
@@ -217,6 +299,12 @@ impl<'a> BlockUpdateBuilder<'a> {
         self.extra_blocks = blocks;
         self
     }
+    // the block merkle tree siblings of the current finalized block followed by the hashes of the heights the reorg
+    // skips over, only needed while the block hash archive is enabled
+    pub fn with_reorg_block_hashes(mut self, reorg_block_hashes: Vec<[u8; 32]>) -> Self {
+        self.reorg_block_hashes = reorg_block_hashes;
+        self
+    }
 
     pub async fn execute(self) -> Result<(), ClientError> {
         let proof = self
@@ -262,8 +350,9 @@ impl<'a> BlockUpdateBuilder<'a> {
             )
             .await?;
 
+        let block_hash_archive_len = self.current_state.block_hash_archive_len;
         let ix = if self.extra_blocks.is_empty() {
-            instructions::block_update(
+            let mut ix = instructions::block_update(
                 self.client.program_id,
                 self.client.payer.pubkey(),
                 proof,
@@ -273,9 +362,26 @@ impl<'a> BlockUpdateBuilder<'a> {
                 txo_buffer,
                 mint_buffer_bump,
                 txo_buffer_bump,
-            )
+            );
+            ix.accounts.extend(instructions::block_hash_archive_accounts(
+                &self.client.program_id,
+                block_hash_archive_len,
+            ));
+            ix
         } else {
-            instructions::process_reorg_blocks(
+            let finalized_blocks_count = self.extra_blocks.len() as u32 + 1;
+            let reorg_block_hashes_buffer = if block_hash_archive_len != 0 {
+                Some(
+                    bm.create_generic_buffer(
+                        self.client.generic_buffer_program_id,
+                        bytemuck::cast_slice(&self.reorg_block_hashes),
+                    )
+                    .await?,
+                )
+            } else {
+                None
+            };
+            let mut ix = instructions::process_reorg_blocks(
                 self.client.program_id,
                 self.client.payer.pubkey(),
                 proof,
@@ -286,7 +392,14 @@ impl<'a> BlockUpdateBuilder<'a> {
                 txo_buffer,
                 mint_buffer_bump,
                 txo_buffer_bump,
-            )
+            );
+            ix.accounts.extend(instructions::reorg_block_hash_archive_accounts(
+                &self.client.program_id,
+                block_hash_archive_len,
+                finalized_blocks_count,
+                reorg_block_hashes_buffer,
+            ));
+            ix
         };
 
         bm.send_tx(&[ix], &[]).await
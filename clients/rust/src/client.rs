@@ -17,9 +17,10 @@ use solana_sdk::{
 
 use crate::{
     BridgeEvent, BridgeMonitor, MonitorConfig, api::{BridgeApi, ManualClaimApi, OperatorApi, WithdrawalApi}, buffer::ParallelBufferManager, config::{BridgeClientConfig, BridgeClientConfigBuilder}, errors::BridgeError, rpc::{RetryExecutor, RpcRateLimiter}, types::{
-        CompactBridgeZKProof, DepositTxOutputRecord, FinalizedBlockMintTxoInfo,
+        BlockHashArchiveEntry, Bridge7MultisigCustodianWalletConfig, CompactBridgeZKProof, DepositTxOutputRecord, FeeSplitTable, FinalizedBlockMintTxoInfo,
         InitializeBridgeParams, PendingMint, ProcessMintsResult, PsyBridgeHeader,
-        PsyBridgeProgramState, PsyReturnTxOutput, PsyWithdrawalChainSnapshot,
+        PsyBridgeProgramState, PsyCustodianRotation, PsyReturnTxOutput, PsyWithdrawalChainSnapshot,
+        PsyWithdrawalRequest, WithdrawalReceipt,
     }
 };
 
@@ -207,6 +208,23 @@ impl BridgeApi for BridgeClient {
         self.get_current_bridge_state_impl().await
     }
 
+    async fn get_archived_block(
+        &self,
+        block_height: u32,
+    ) -> Result<Option<BlockHashArchiveEntry>, BridgeError> {
+        self.get_archived_block_impl(block_height).await
+    }
+
+    async fn get_custodian_wallet_config(
+        &self,
+    ) -> Result<Option<Bridge7MultisigCustodianWalletConfig>, BridgeError> {
+        self.get_custodian_wallet_config_impl().await
+    }
+
+    async fn get_custodian_rotation(&self) -> Result<Option<PsyCustodianRotation>, BridgeError> {
+        self.get_custodian_rotation_impl().await
+    }
+
     async fn get_manual_deposits_at(
         &self,
         next_processed_manual_deposit_index: u64,
@@ -273,6 +291,7 @@ impl BridgeApi for BridgeClient {
         proof: CompactBridgeZKProof,
         header: PsyBridgeHeader,
         extra_blocks: Vec<FinalizedBlockMintTxoInfo>,
+        reorg_block_hashes: Vec<[u8; 32]>,
         mint_buffer_account: Pubkey,
         mint_buffer_bump: u8,
         txo_buffer_account: Pubkey,
@@ -282,6 +301,7 @@ impl BridgeApi for BridgeClient {
             proof,
             header,
             extra_blocks,
+            reorg_block_hashes,
             mint_buffer_account,
             mint_buffer_bump,
             txo_buffer_account,
@@ -321,8 +341,36 @@ impl WithdrawalApi for BridgeClient {
         recipient_address: [u8; 20],
         amount_sats: u64,
         address_type: u32,
+        receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError> {
+        self.request_withdrawal_impl(
+            user_authority,
+            recipient_address,
+            amount_sats,
+            address_type,
+            receipt_nonce,
+        )
+        .await
+    }
+
+    async fn request_withdrawals_batch(
+        &self,
+        user_authority: &Keypair,
+        requests: &[PsyWithdrawalRequest],
+        first_receipt_nonce: u64,
     ) -> Result<Signature, BridgeError> {
-        self.request_withdrawal_impl(user_authority, recipient_address, amount_sats, address_type)
+        self.request_withdrawals_batch_impl(user_authority, requests, first_receipt_nonce)
+            .await
+    }
+
+    async fn request_script_withdrawal(
+        &self,
+        user_authority: &Keypair,
+        script_pubkey: &[u8],
+        amount_sats: u64,
+        receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError> {
+        self.request_script_withdrawal_impl(user_authority, script_pubkey, amount_sats, receipt_nonce)
             .await
     }
 
@@ -344,9 +392,55 @@ impl WithdrawalApi for BridgeClient {
         .await
     }
 
+    async fn process_custodian_sweep(
+        &self,
+        proof: CompactBridgeZKProof,
+        new_return_output: PsyReturnTxOutput,
+        new_spent_txo_tree_root: [u8; 32],
+        doge_tx_bytes: &[u8],
+    ) -> Result<Signature, BridgeError> {
+        self.process_custodian_sweep_impl(proof, new_return_output, new_spent_txo_tree_root, doge_tx_bytes)
+            .await
+    }
+
     async fn replay_withdrawal(&self, doge_tx_bytes: &[u8]) -> Result<Signature, BridgeError> {
         self.replay_withdrawal_impl(doge_tx_bytes).await
     }
+
+    async fn get_withdrawal_receipt(
+        &self,
+        requester: &Pubkey,
+        receipt_nonce: u64,
+    ) -> Result<Option<WithdrawalReceipt>, BridgeError> {
+        self.get_withdrawal_receipt_impl(requester, receipt_nonce)
+            .await
+    }
+
+    async fn mark_withdrawal_receipts_fulfilled(
+        &self,
+        withdrawal_receipts: &[Pubkey],
+    ) -> Result<Signature, BridgeError> {
+        self.mark_withdrawal_receipts_fulfilled_impl(withdrawal_receipts)
+            .await
+    }
+
+    async fn cancel_withdrawal(
+        &self,
+        requester: &Keypair,
+        receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError> {
+        self.cancel_withdrawal_impl(requester, receipt_nonce)
+            .await
+    }
+
+    async fn close_withdrawal_receipt(
+        &self,
+        requester: &Keypair,
+        receipt_nonce: u64,
+    ) -> Result<Signature, BridgeError> {
+        self.close_withdrawal_receipt_impl(requester, receipt_nonce)
+            .await
+    }
 }
 
 // Implement the ManualClaimApi trait
@@ -391,6 +485,57 @@ impl OperatorApi for BridgeClient {
         self.operator_withdraw_fees_impl().await
     }
 
+    async fn get_fee_split(&self) -> Result<Option<FeeSplitTable>, BridgeError> {
+        self.get_fee_split_impl().await
+    }
+
+    async fn set_fee_split(
+        &self,
+        fee_spender: &Keypair,
+        fee_split: &FeeSplitTable,
+    ) -> Result<Signature, BridgeError> {
+        self.set_fee_split_impl(fee_spender, fee_split).await
+    }
+
+    async fn distribute_fees(&self) -> Result<Signature, BridgeError> {
+        self.distribute_fees_impl().await
+    }
+
+    async fn enable_finalized_block_history(
+        &self,
+        authority: &Keypair,
+        capacity: u32,
+    ) -> Result<Signature, BridgeError> {
+        self.enable_finalized_block_history_impl(authority, capacity).await
+    }
+
+    async fn enable_block_hash_archive(&self, authority: &Keypair) -> Result<Signature, BridgeError> {
+        self.enable_block_hash_archive_impl(authority).await
+    }
+
+    async fn migrate_state(&self) -> Result<Option<Signature>, BridgeError> {
+        self.migrate_state_impl().await
+    }
+
+    async fn store_custodian_wallet_config(
+        &self,
+        config: &Bridge7MultisigCustodianWalletConfig,
+    ) -> Result<Signature, BridgeError> {
+        self.store_custodian_wallet_config_impl(config).await
+    }
+
+    async fn schedule_custodian_rotation(
+        &self,
+        authority: &Keypair,
+        new_config: &Bridge7MultisigCustodianWalletConfig,
+    ) -> Result<Signature, BridgeError> {
+        self.schedule_custodian_rotation_impl(authority, new_config).await
+    }
+
+    async fn cancel_custodian_rotation(&self, authority: &Keypair) -> Result<Signature, BridgeError> {
+        self.cancel_custodian_rotation_impl(authority).await
+    }
+
     async fn execute_snapshot_withdrawals(&self) -> Result<Signature, BridgeError> {
         self.execute_snapshot_withdrawals_impl().await
     }
@@ -422,12 +567,7 @@ impl BridgeClient {
         // Return the last signature we processed (or default if no events)
         let last_sig = events
             .last()
-            .map(|e| match e {
-                crate::monitor::BridgeEvent::WithdrawalRequested(ev) => ev.signature,
-                crate::monitor::BridgeEvent::WithdrawalProcessed(ev) => ev.signature,
-                crate::monitor::BridgeEvent::ManualDepositClaimed(ev) => ev.signature,
-                crate::monitor::BridgeEvent::BlockTransition(ev) => ev.signature,
-            })
+            .map(|e| e.signature())
             .or(after_signature)
             .unwrap_or_default();
 
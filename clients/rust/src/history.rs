@@ -1630,15 +1630,17 @@ impl BridgeHistorySync {
         accounts: &[u8],
         account_keys: &[Pubkey],
     ) -> Result<Option<HistoryRecord>, BridgeError> {
-        use psy_doge_solana_core::instructions::doge_bridge::RequestWithdrawalInstructionData;
+        use psy_doge_solana_core::program_state::PsyWithdrawalRequest;
 
+        // only the leading request is read, requests made before receipts were keyed by a nonce
+        // carry no trailing receipt_nonce
         let data_offset = 8;
-        let data_size = std::mem::size_of::<RequestWithdrawalInstructionData>();
+        let data_size = std::mem::size_of::<PsyWithdrawalRequest>();
         if ix_data.len() < data_offset + data_size {
             return Ok(None);
         }
 
-        let data: &RequestWithdrawalInstructionData =
+        let request: &PsyWithdrawalRequest =
             bytemuck::from_bytes(&ix_data[data_offset..data_offset + data_size]);
 
         let user_pubkey = if !accounts.is_empty() && (accounts[0] as usize) < account_keys.len() {
@@ -1651,9 +1653,9 @@ impl BridgeHistorySync {
             signature: *signature,
             slot,
             block_time,
-            amount_sats: data.request.amount_sats,
-            recipient_address: data.request.recipient_address,
-            address_type: data.request.address_type,
+            amount_sats: request.amount_sats,
+            recipient_address: request.recipient_address,
+            address_type: request.address_type,
             user_pubkey,
         })))
     }
@@ -1,7 +1,55 @@
+use psy_doge_solana_core::data_accounts::block_hash_archive::{
+    block_hash_archive_position, BLOCK_HASH_ARCHIVE_SEED,
+};
+use psy_doge_solana_core::data_accounts::custodian_config::CUSTODIAN_WALLET_CONFIG_SEED;
+use psy_doge_solana_core::data_accounts::fee_split::{FeeSplitTable, FEE_SPLIT_SEED};
+use psy_doge_solana_core::data_accounts::finalized_block_history::{
+    finalized_block_history_account_size, FINALIZED_BLOCK_HISTORY_SEED,
+};
+use psy_doge_solana_core::data_accounts::vk_registry::VK_REGISTRY_SEED;
+use psy_doge_solana_core::data_accounts::withdrawal_receipt::WITHDRAWAL_RECEIPT_SEED;
+use psy_doge_solana_core::instructions::doge_bridge::{
+    VerifierKeyUpdateInstructionData, DOGE_BRIDGE_INSTRUCTION_ACTIVATE_VK_UPDATE,
+    DOGE_BRIDGE_INSTRUCTION_CANCEL_VK_UPDATE, DOGE_BRIDGE_INSTRUCTION_PROPOSE_VK_UPDATE,
+};
+use psy_doge_solana_core::instructions::doge_bridge::{
+    SetWithdrawalLimitsInstructionData, DOGE_BRIDGE_INSTRUCTION_SET_WITHDRAWAL_LIMITS,
+};
+use psy_doge_solana_core::instructions::doge_bridge::{
+    DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT,
+    DOGE_BRIDGE_INSTRUCTION_MARK_WITHDRAWAL_RECEIPTS_FULFILLED,
+};
+use psy_doge_solana_core::instructions::doge_bridge::{
+    SetFeeSplitInstructionData, DOGE_BRIDGE_INSTRUCTION_DISTRIBUTE_FEES,
+    DOGE_BRIDGE_INSTRUCTION_SET_FEE_SPLIT,
+};
+use psy_doge_solana_core::instructions::doge_bridge::{
+    InitFinalizedBlockHistoryInstructionData, DOGE_BRIDGE_INSTRUCTION_INIT_FINALIZED_BLOCK_HISTORY,
+};
+use psy_doge_solana_core::instructions::doge_bridge::{
+    VerifyTxInclusionFixedData, DOGE_BRIDGE_INSTRUCTION_INIT_BLOCK_HASH_ARCHIVE,
+    DOGE_BRIDGE_INSTRUCTION_MIGRATE_STATE, DOGE_BRIDGE_INSTRUCTION_VERIFY_TX_INCLUSION,
+};
+use psy_doge_solana_core::instructions::doge_bridge::{
+    StoreCustodianWalletConfigInstructionData, DOGE_BRIDGE_INSTRUCTION_STORE_CUSTODIAN_WALLET_CONFIG,
+};
+use psy_doge_solana_core::instructions::doge_bridge::{
+    ProcessCustodianSweepInstructionData, ScheduleCustodianRotationInstructionData,
+    DOGE_BRIDGE_INSTRUCTION_CANCEL_CUSTODIAN_ROTATION, DOGE_BRIDGE_INSTRUCTION_PROCESS_CUSTODIAN_SWEEP,
+    DOGE_BRIDGE_INSTRUCTION_SCHEDULE_CUSTODIAN_ROTATION,
+};
+use psy_doge_solana_core::instructions::doge_bridge::{
+    RequestScriptWithdrawalFixedData, DOGE_BRIDGE_INSTRUCTION_REQUEST_SCRIPT_WITHDRAWAL,
+};
+use psy_doge_solana_core::instructions::doge_bridge::DOGE_BRIDGE_INSTRUCTION_CANCEL_CONFIG_UPDATE;
+use psy_doge_solana_core::instructions::doge_bridge::{
+    SetPolicyConfigInstructionData, DOGE_BRIDGE_INSTRUCTION_SET_POLICY_CONFIG,
+};
 use psy_doge_solana_core::instructions::manual_claim::{MC_MANUAL_CLAIM_TRANSACTION_DESCRIMINATOR, ManualClaimInstruction};
-use psy_bridge_core::{common_types::QHash256, crypto::zk::CompactBridgeZKProof, header::PsyBridgeHeader};
-use psy_doge_solana_core::program_state::{FinalizedBlockMintTxoInfo, PsyReturnTxOutput, PsyWithdrawalRequest};
-use psy_doge_solana_core::instructions::doge_bridge::{BlockUpdateFixedData, DOGE_BRIDGE_INSTRUCTION_BLOCK_UPDATE, DOGE_BRIDGE_INSTRUCTION_INITIALIZE, DOGE_BRIDGE_INSTRUCTION_OPERATOR_WITHDRAW_FEES, DOGE_BRIDGE_INSTRUCTION_PROCESS_MANUAL_DEPOSIT, DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP, DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP_AUTO_ADVANCE, DOGE_BRIDGE_INSTRUCTION_PROCESS_REORG_BLOCKS, DOGE_BRIDGE_INSTRUCTION_PROCESS_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_REPLAY_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_SNAPSHOT_WITHDRAWALS, InitializeBridgeInstructionData, InitializeBridgeParams, ProcessManualDepositInstructionData, ProcessReorgBlocksFixedData, ProcessWithdrawalInstructionData, RequestWithdrawalInstructionData};
+use psy_bridge_core::{common_types::QHash256, crypto::zk::CompactBridgeZKProof, custodian_config::Bridge7MultisigCustodianWalletConfig, header::PsyBridgeHeader};
+use psy_doge_solana_core::program_state::{FinalizedBlockMintTxoInfo, PsyBridgeConfig, PsyBridgePolicyConfig, PsyReturnTxOutput, PsyWithdrawalRequest};
+use psy_doge_solana_core::instructions::doge_bridge::{BlockUpdateFixedData, DOGE_BRIDGE_INSTRUCTION_BLOCK_UPDATE, DOGE_BRIDGE_INSTRUCTION_INITIALIZE, DOGE_BRIDGE_INSTRUCTION_OPERATOR_WITHDRAW_FEES, DOGE_BRIDGE_INSTRUCTION_PROCESS_MANUAL_DEPOSIT, DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP, DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP_AUTO_ADVANCE, DOGE_BRIDGE_INSTRUCTION_PROCESS_REORG_BLOCKS, DOGE_BRIDGE_INSTRUCTION_PROCESS_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_REPLAY_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWALS_BATCH, DOGE_BRIDGE_INSTRUCTION_SNAPSHOT_WITHDRAWALS, DOGE_BRIDGE_INSTRUCTION_GUARDIAN_SET_PAUSE, DOGE_BRIDGE_INSTRUCTION_SET_CONTROL_MODE, DOGE_BRIDGE_INSTRUCTION_PROPOSE_CONFIG_UPDATE, DOGE_BRIDGE_INSTRUCTION_APPLY_CONFIG_UPDATE, DOGE_BRIDGE_INSTRUCTION_NOMINATE_KEY, DOGE_BRIDGE_INSTRUCTION_ACCEPT_KEY, DOGE_BRIDGE_INSTRUCTION_ADMIN_FORCE_ROTATE_KEY, GuardianSetPauseInstructionData, RotateKeyInstructionData, ProposeConfigUpdateInstructionData, SetBridgeControlModeInstructionData, InitializeBridgeInstructionData, InitializeBridgeParams, ProcessManualDepositInstructionData, ProcessReorgBlocksFixedData, ProcessWithdrawalInstructionData, RequestWithdrawalInstructionData};
+use solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
 use solana_sdk::sysvar::clock;
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
@@ -37,12 +85,35 @@ pub fn initialize_bridge(
     fee_spender_pubkey: Pubkey,
     doge_mint: Pubkey,
     initialize_bridge_params: &InitializeBridgeParams,
+) -> Instruction {
+    // the operator doubles as the guardian and there is no admin unless given explicitly
+    initialize_bridge_with_authorities(
+        payer,
+        operator_pubkey,
+        fee_spender_pubkey,
+        operator_pubkey,
+        None,
+        doge_mint,
+        initialize_bridge_params,
+    )
+}
+
+pub fn initialize_bridge_with_authorities(
+    payer: Pubkey,
+    operator_pubkey: Pubkey,
+    fee_spender_pubkey: Pubkey,
+    guardian_pubkey: Pubkey,
+    admin_pubkey: Option<Pubkey>,
+    doge_mint: Pubkey,
+    initialize_bridge_params: &InitializeBridgeParams,
 ) -> Instruction {
     let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &DOGE_BRIDGE_PROGRAM_ID);
 
     let data_struct = InitializeBridgeInstructionData {
         operator_pubkey: operator_pubkey.to_bytes(),
         fee_spender_pubkey: fee_spender_pubkey.to_bytes(),
+        guardian_pubkey: guardian_pubkey.to_bytes(),
+        admin_pubkey: admin_pubkey.map(|k| k.to_bytes()).unwrap_or_default(),
         doge_mint: doge_mint.to_bytes(),
         bridge_header: initialize_bridge_params.bridge_header,
         start_return_txo_output: initialize_bridge_params.start_return_txo_output,
@@ -96,6 +167,8 @@ pub fn block_update(
             // Program Accounts for CPI
             AccountMeta::new_readonly(PENDING_MINT_BUFFER_BUILDER_PROGRAM_ID, false),
             AccountMeta::new_readonly(TXO_BUFFER_BUILDER_PROGRAM_ID, false),
+            AccountMeta::new_readonly(get_vk_registry_pda(&program_id), false),
+            AccountMeta::new(get_finalized_block_history_pda(&program_id), false),
         ],
         data,
     }
@@ -135,25 +208,32 @@ pub fn process_reorg_blocks(
             AccountMeta::new(payer, true),
             AccountMeta::new_readonly(PENDING_MINT_BUFFER_BUILDER_PROGRAM_ID, false),
             AccountMeta::new_readonly(TXO_BUFFER_BUILDER_PROGRAM_ID, false),
+            AccountMeta::new_readonly(get_vk_registry_pda(&program_id), false),
+            AccountMeta::new(get_finalized_block_history_pda(&program_id), false),
         ],
         data,
     }
 }
 
+/// Request a withdrawal. `receipt_nonce` keys the receipt PDA created for the request, it can be
+/// any value the user has no open receipt under, the assigned tree index is stored in the receipt.
 pub fn request_withdrawal(
     program_id: Pubkey,
+    payer: Pubkey,
     user_authority: Pubkey,
     mint: Pubkey,
     user_token_account: Pubkey,
     recipient_address: [u8; 20],
     amount_sats: u64,
     address_type: u32,
+    receipt_nonce: u64,
 ) -> Instruction {
     let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
     let request = PsyWithdrawalRequest::new(recipient_address, amount_sats, address_type);
     
     let data_struct = RequestWithdrawalInstructionData {
         request,
+        receipt_nonce,
     };
     
     let data = gen_aligned_instruction(
@@ -169,11 +249,104 @@ pub fn request_withdrawal(
             AccountMeta::new(mint, false),
             AccountMeta::new_readonly(user_authority, true),
             AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(get_withdrawal_receipt_pda(&program_id, &user_authority, receipt_nonce), false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
         ],
         data,
     }
 }
 
+/// Request several withdrawals at once, burning their total. Each request gets its own receipt
+/// PDA, keyed by consecutive nonces starting at `first_receipt_nonce`.
+pub fn request_withdrawals_batch(
+    program_id: Pubkey,
+    payer: Pubkey,
+    user_authority: Pubkey,
+    mint: Pubkey,
+    user_token_account: Pubkey,
+    requests: &[PsyWithdrawalRequest],
+    first_receipt_nonce: u64,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let batch: Vec<RequestWithdrawalInstructionData> = requests
+        .iter()
+        .zip(first_receipt_nonce..)
+        .map(|(request, receipt_nonce)| RequestWithdrawalInstructionData {
+            request: *request,
+            receipt_nonce,
+        })
+        .collect();
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWALS_BATCH,
+        bytemuck::cast_slice(&batch),
+    );
+
+    let mut accounts = vec![
+        AccountMeta::new(bridge_state, false),
+        AccountMeta::new(user_token_account, false),
+        AccountMeta::new(mint, false),
+        AccountMeta::new_readonly(user_authority, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(batch.iter().map(|entry| {
+        AccountMeta::new(
+            get_withdrawal_receipt_pda(&program_id, &user_authority, entry.receipt_nonce),
+            false,
+        )
+    }));
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Request a withdrawal to an arbitrary scriptPubKey, the queued leaf commits to its hash160.
+/// Pass the script in `script_pubkey`, or an empty slice and the generic buffer holding it in `script_buffer`.
+pub fn request_script_withdrawal(
+    program_id: Pubkey,
+    payer: Pubkey,
+    user_authority: Pubkey,
+    mint: Pubkey,
+    user_token_account: Pubkey,
+    script_pubkey: &[u8],
+    amount_sats: u64,
+    receipt_nonce: u64,
+    script_buffer: Option<Pubkey>,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let mut data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_REQUEST_SCRIPT_WITHDRAWAL,
+        bytemuck::bytes_of(&RequestScriptWithdrawalFixedData { amount_sats, receipt_nonce }),
+    );
+    data.extend_from_slice(script_pubkey);
+
+    let mut accounts = vec![
+        AccountMeta::new(bridge_state, false),
+        AccountMeta::new(user_token_account, false),
+        AccountMeta::new(mint, false),
+        AccountMeta::new_readonly(user_authority, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(get_withdrawal_receipt_pda(&program_id, &user_authority, receipt_nonce), false),
+        AccountMeta::new(payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(script_buffer) = script_buffer {
+        accounts.push(AccountMeta::new_readonly(script_buffer, false));
+    }
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
 pub fn process_withdrawal(
     program_id: Pubkey,
     payer: Pubkey,
@@ -237,6 +410,7 @@ pub fn process_withdrawal(
             AccountMeta::new_readonly(system_program::id(), false), // 9
             AccountMeta::new_readonly(wormhole_core_program_id, false), // 10
             AccountMeta::new_readonly(event_authority, false), // 11
+            AccountMeta::new_readonly(get_vk_registry_pda(&program_id), false), // 12: VK Registry, optional until it is created
         ],
         data,
     }
@@ -333,6 +507,7 @@ pub fn process_manual_deposit(
             AccountMeta::new(mint, false),
             AccountMeta::new_readonly(spl_token::id(), false),
             AccountMeta::new_readonly(manual_claim_program_id, true),
+            AccountMeta::new_readonly(get_finalized_block_history_pda(&program_id), false),
         ],
         data,
     }
@@ -383,6 +558,8 @@ pub fn manual_claim_deposit_instruction(
             AccountMeta::new_readonly(user_signer, true),
             AccountMeta::new(payer, true),
             AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_vk_registry_pda(&bridge_program_id), false),
+            AccountMeta::new_readonly(get_finalized_block_history_pda(&bridge_program_id), false),
         ],
         data,
     }
@@ -658,4 +835,682 @@ pub fn snapshot_withdrawals(
         ],
         data,
     }
-}
\ No newline at end of file
+}
+
+/// Pause the bridge for `pause_duration_secs` seconds, or lift the guardian pause when it is 0.
+pub fn guardian_set_pause(
+    program_id: Pubkey,
+    guardian: Pubkey,
+    pause_duration_secs: u32,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = GuardianSetPauseInstructionData { pause_duration_secs };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_GUARDIAN_SET_PAUSE,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new_readonly(guardian, true),
+        ],
+        data,
+    }
+}
+
+/// Set the bridge control mode bitmask (see `BRIDGE_CONTROL_MODE_DISABLE_*`), signed by the guardian.
+pub fn set_bridge_control_mode(
+    program_id: Pubkey,
+    guardian: Pubkey,
+    bridge_control_mode: u32,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = SetBridgeControlModeInstructionData { bridge_control_mode };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_SET_CONTROL_MODE,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new_readonly(guardian, true),
+        ],
+        data,
+    }
+}
+
+/// Propose new fee parameters, applicable after `delay_secs` and effective from the next block transition.
+///
+/// Signed by the settings authority (the admin if one is configured, otherwise the operator).
+pub fn propose_config_update(
+    program_id: Pubkey,
+    authority: Pubkey,
+    new_config_params: PsyBridgeConfig,
+    delay_secs: u32,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = ProposeConfigUpdateInstructionData {
+        new_config_params,
+        delay_secs,
+        _padding: 0,
+    };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_PROPOSE_CONFIG_UPDATE,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data,
+    }
+}
+
+/// Drop the pending fee parameters, signed by the settings authority or the guardian.
+pub fn cancel_config_update(program_id: Pubkey, authority: Pubkey) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data = gen_aligned_instruction(DOGE_BRIDGE_INSTRUCTION_CANCEL_CONFIG_UPDATE, &[]);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data,
+    }
+}
+
+/// Set the minimum withdrawal and deposit amounts and the withdrawal cancellation fee, signed by
+/// the admin, or the operator when no admin is configured. Unlike the fee parameters these apply
+/// immediately.
+pub fn set_policy_config(
+    program_id: Pubkey,
+    authority: Pubkey,
+    policy_config: PsyBridgePolicyConfig,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = SetPolicyConfigInstructionData { policy_config };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_SET_POLICY_CONFIG,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data,
+    }
+}
+
+/// Queue the pending fee parameters once their timelock has elapsed (permissionless).
+pub fn apply_config_update(program_id: Pubkey) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data = gen_aligned_instruction(DOGE_BRIDGE_INSTRUCTION_APPLY_CONFIG_UPDATE, &[]);
+
+    Instruction {
+        program_id,
+        accounts: vec![AccountMeta::new(bridge_state, false)],
+        data,
+    }
+}
+
+fn rotate_key_instruction(
+    program_id: Pubkey,
+    discriminator: u8,
+    signer: Pubkey,
+    key_role: u32,
+    new_pubkey: Pubkey,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = RotateKeyInstructionData {
+        new_pubkey: new_pubkey.to_bytes(),
+        key_role,
+    };
+    let data = gen_aligned_instruction(discriminator, bytemuck::bytes_of(&data_struct));
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new_readonly(signer, true),
+        ],
+        data,
+    }
+}
+
+/// Nominate a successor for `key_role` (see `BRIDGE_KEY_ROLE_*`), signed by the current key holder.
+pub fn nominate_key(
+    program_id: Pubkey,
+    current_key: Pubkey,
+    key_role: u32,
+    new_pubkey: Pubkey,
+) -> Instruction {
+    rotate_key_instruction(program_id, DOGE_BRIDGE_INSTRUCTION_NOMINATE_KEY, current_key, key_role, new_pubkey)
+}
+
+/// Accept a pending nomination for `key_role`, signed by the nominee.
+pub fn accept_key(program_id: Pubkey, nominee: Pubkey, key_role: u32) -> Instruction {
+    rotate_key_instruction(program_id, DOGE_BRIDGE_INSTRUCTION_ACCEPT_KEY, nominee, key_role, Pubkey::default())
+}
+
+/// Replace the key for `key_role` immediately, signed by the bridge admin.
+pub fn admin_force_rotate_key(
+    program_id: Pubkey,
+    admin: Pubkey,
+    key_role: u32,
+    new_pubkey: Pubkey,
+) -> Instruction {
+    rotate_key_instruction(program_id, DOGE_BRIDGE_INSTRUCTION_ADMIN_FORCE_ROTATE_KEY, admin, key_role, new_pubkey)
+}
+
+/// The verifier key registry PDA of the bridge program (may not exist until the first vk update).
+pub fn get_vk_registry_pda(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VK_REGISTRY_SEED], program_id).0
+}
+
+/// Propose a new verifier key for `vk_kind` (see `VK_KIND_*`), signed by the admin, or the operator
+/// when no admin is configured. The first proposal creates the registry account.
+pub fn propose_vk_update(
+    program_id: Pubkey,
+    authority: Pubkey,
+    payer: Pubkey,
+    vk_kind: u32,
+    new_vk: [u8; 32],
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = VerifierKeyUpdateInstructionData { new_vk, vk_kind };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_PROPOSE_VK_UPDATE,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(get_vk_registry_pda(&program_id), false),
+            // records that the registry exists when the first proposal creates it
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Record a proposed verifier key as active once its timelock has elapsed (permissionless).
+pub fn activate_vk_update(program_id: Pubkey, vk_kind: u32) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = VerifierKeyUpdateInstructionData { new_vk: [0u8; 32], vk_kind };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_ACTIVATE_VK_UPDATE,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(get_vk_registry_pda(&program_id), false),
+            AccountMeta::new_readonly(bridge_state, false),
+        ],
+        data,
+    }
+}
+
+/// Drop a pending verifier key update before it activates.
+pub fn cancel_vk_update(program_id: Pubkey, authority: Pubkey, vk_kind: u32) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = VerifierKeyUpdateInstructionData { new_vk: [0u8; 32], vk_kind };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_CANCEL_VK_UPDATE,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(get_vk_registry_pda(&program_id), false),
+            AccountMeta::new_readonly(bridge_state, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data,
+    }
+}
+
+/// Set the withdrawal circuit breaker limits (0 disables a limit), signed by the admin, or the
+/// operator when no admin is configured. `reset_window_usage` clears the volume counted so far.
+pub fn set_withdrawal_limits(
+    program_id: Pubkey,
+    authority: Pubkey,
+    limit_per_request_sats: u64,
+    limit_per_window_sats: u64,
+    limit_window_secs: u64,
+    reset_window_usage: bool,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = SetWithdrawalLimitsInstructionData {
+        limit_per_request_sats,
+        limit_per_window_sats,
+        limit_window_secs,
+        reset_window_usage: reset_window_usage as u32,
+        _padding: 0,
+    };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_SET_WITHDRAWAL_LIMITS,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data,
+    }
+}
+
+/// The receipt PDA created for the requester's withdrawal request made with `receipt_nonce`.
+pub fn get_withdrawal_receipt_pda(program_id: &Pubkey, requester: &Pubkey, receipt_nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[WITHDRAWAL_RECEIPT_SEED, requester.as_ref(), &receipt_nonce.to_le_bytes()],
+        program_id,
+    )
+    .0
+}
+
+/// Mark receipts fulfilled once `next_processed_withdrawals_index` has moved past them (permissionless).
+pub fn mark_withdrawal_receipts_fulfilled(program_id: Pubkey, withdrawal_receipts: &[Pubkey]) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let mut accounts = vec![AccountMeta::new_readonly(bridge_state, false)];
+    accounts.extend(
+        withdrawal_receipts
+            .iter()
+            .map(|receipt| AccountMeta::new(*receipt, false)),
+    );
+
+    Instruction {
+        program_id,
+        accounts,
+        data: gen_aligned_instruction(DOGE_BRIDGE_INSTRUCTION_MARK_WITHDRAWAL_RECEIPTS_FULFILLED, &[]),
+    }
+}
+
+/// Close a fulfilled or cancelled receipt, returning its rent to the requester.
+pub fn close_withdrawal_receipt(program_id: Pubkey, requester: Pubkey, receipt_nonce: u64) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(get_withdrawal_receipt_pda(&program_id, &requester, receipt_nonce), false),
+            AccountMeta::new(requester, true),
+        ],
+        data: gen_aligned_instruction(DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT, &[]),
+    }
+}
+
+/// Cancel a withdrawal request that no snapshot covers yet. The burned amount, minus the
+/// configured cancellation fee, is minted back to `user_token_account`.
+pub fn cancel_withdrawal(
+    program_id: Pubkey,
+    requester: Pubkey,
+    mint: Pubkey,
+    user_token_account: Pubkey,
+    receipt_nonce: u64,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new(get_withdrawal_receipt_pda(&program_id, &requester, receipt_nonce), false),
+            AccountMeta::new(user_token_account, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(requester, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: gen_aligned_instruction(DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL, &[]),
+    }
+}
+
+pub fn get_fee_split_pda(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[FEE_SPLIT_SEED], program_id).0
+}
+
+/// Replace the fee split table, signed by the fee spender. The first update creates the
+/// fee split account, after which fees can only be paid out through `distribute_fees`.
+pub fn set_fee_split(
+    program_id: Pubkey,
+    fee_spender: Pubkey,
+    payer: Pubkey,
+    fee_split: &FeeSplitTable,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = SetFeeSplitInstructionData { fee_split: *fee_split };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_SET_FEE_SPLIT,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new(get_fee_split_pda(&program_id), false),
+            AccountMeta::new_readonly(fee_spender, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Mint the withdrawable fees to the fee split recipients (permissionless).
+pub fn distribute_fees(program_id: Pubkey, doge_mint: Pubkey, fee_split: &FeeSplitTable) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let mut accounts = vec![
+        AccountMeta::new(bridge_state, false),
+        AccountMeta::new_readonly(get_fee_split_pda(&program_id), false),
+        AccountMeta::new(doge_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(Pubkey::new_from_array(fee_split.fee_spender_token_account), false),
+    ];
+    accounts.extend(
+        fee_split
+            .get_recipients()
+            .iter()
+            .map(|recipient| AccountMeta::new(Pubkey::new_from_array(recipient.token_account), false)),
+    );
+
+    Instruction {
+        program_id,
+        accounts,
+        data: gen_aligned_instruction(DOGE_BRIDGE_INSTRUCTION_DISTRIBUTE_FEES, &[]),
+    }
+}
+
+pub fn get_finalized_block_history_pda(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[FINALIZED_BLOCK_HISTORY_SEED], program_id).0
+}
+
+/// Number of `init_finalized_block_history` instructions needed to allocate a history of the
+/// given capacity, each one grows the account by at most `MAX_PERMITTED_DATA_INCREASE` bytes.
+pub fn init_finalized_block_history_steps(capacity: u32) -> u32 {
+    finalized_block_history_account_size(capacity).div_ceil(MAX_PERMITTED_DATA_INCREASE) as u32
+}
+
+/// Allocate the finalized block history, signed by the settings authority. Send it for each
+/// step in `0..init_finalized_block_history_steps(capacity)`, the last one enables the history.
+pub fn init_finalized_block_history(
+    program_id: Pubkey,
+    authority: Pubkey,
+    payer: Pubkey,
+    capacity: u32,
+    step: u32,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = InitFinalizedBlockHistoryInstructionData { capacity, step };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_INIT_FINALIZED_BLOCK_HISTORY,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new(get_finalized_block_history_pda(&program_id), false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Read-only check that a raw dogecoin tx is in a finalized block, the `TxInclusionResult` is set as
+/// the return data. `siblings` is the tx merkle branch followed by the block merkle tree siblings.
+/// Leave `siblings` and `tx_bytes` empty and pass a generic buffer holding them, in that order,
+/// when they don't fit in the transaction.
+pub fn verify_tx_inclusion(
+    program_id: Pubkey,
+    params: &VerifyTxInclusionFixedData,
+    siblings: &[QHash256],
+    tx_bytes: &[u8],
+    proof_buffer: Option<Pubkey>,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let mut data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_VERIFY_TX_INCLUSION,
+        bytemuck::bytes_of(params),
+    );
+    data.extend_from_slice(bytemuck::cast_slice(siblings));
+    data.extend_from_slice(tx_bytes);
+
+    let mut accounts = vec![AccountMeta::new_readonly(bridge_state, false)];
+    if let Some(proof_buffer) = proof_buffer {
+        accounts.push(AccountMeta::new_readonly(proof_buffer, false));
+    }
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn get_block_hash_archive_segment_pda(program_id: &Pubkey, segment_index: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[BLOCK_HASH_ARCHIVE_SEED, &segment_index.to_le_bytes()],
+        program_id,
+    )
+    .0
+}
+
+/// Accounts to append to `block_update` once the block hash archive is enabled: the segment
+/// the next entry goes into and the system program to pay its rent.
+/// `block_hash_archive_len` is read from the bridge state, no accounts are needed while it is 0.
+pub fn block_hash_archive_accounts(program_id: &Pubkey, block_hash_archive_len: u64) -> Vec<AccountMeta> {
+    reorg_block_hash_archive_accounts(program_id, block_hash_archive_len, 1, None)
+}
+
+/// Accounts to append to `process_reorg_blocks` once the block hash archive is enabled.
+///
+/// A reorg appends an entry for each of the `finalized_blocks_count` heights it finalizes. When that is more than
+/// one, `reorg_block_hashes_buffer` is a generic buffer holding the block merkle tree siblings of the current
+/// finalized block followed by the hashes of the heights in between, and the next segment is added when the
+/// entries don't fit in the current one.
+pub fn reorg_block_hash_archive_accounts(
+    program_id: &Pubkey,
+    block_hash_archive_len: u64,
+    finalized_blocks_count: u32,
+    reorg_block_hashes_buffer: Option<Pubkey>,
+) -> Vec<AccountMeta> {
+    if block_hash_archive_len == 0 {
+        return vec![];
+    }
+    let (segment_index, _) = block_hash_archive_position(block_hash_archive_len);
+    let mut accounts = vec![
+        AccountMeta::new(get_block_hash_archive_segment_pda(program_id, segment_index), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(reorg_block_hashes_buffer) = reorg_block_hashes_buffer {
+        accounts.push(AccountMeta::new_readonly(reorg_block_hashes_buffer, false));
+        let (last_segment_index, _) =
+            block_hash_archive_position(block_hash_archive_len + finalized_blocks_count as u64 - 1);
+        if last_segment_index != segment_index {
+            accounts.push(AccountMeta::new(get_block_hash_archive_segment_pda(program_id, last_segment_index), false));
+        }
+    }
+    accounts
+}
+
+/// Create the block hash archive starting at the current finalized block, signed by the settings authority.
+pub fn init_block_hash_archive(program_id: Pubkey, authority: Pubkey, payer: Pubkey) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new(get_block_hash_archive_segment_pda(&program_id, 0), false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: gen_aligned_instruction(DOGE_BRIDGE_INSTRUCTION_INIT_BLOCK_HASH_ARCHIVE, &[]),
+    }
+}
+
+/// Move the bridge state account forward one layout version. Permissionless, the payer covers any extra rent.
+pub fn migrate_state(program_id: Pubkey, payer: Pubkey) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: gen_aligned_instruction(DOGE_BRIDGE_INSTRUCTION_MIGRATE_STATE, &[]),
+    }
+}
+
+pub fn get_custodian_wallet_config_pda(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CUSTODIAN_WALLET_CONFIG_SEED], program_id).0
+}
+
+/// Publish the custodian wallet config behind the bridge's `custodian_wallet_config_hash`.
+/// Permissionless, the program rejects a config that does not hash to the stored hash.
+pub fn store_custodian_wallet_config(
+    program_id: Pubkey,
+    payer: Pubkey,
+    config: &Bridge7MultisigCustodianWalletConfig,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = StoreCustodianWalletConfigInstructionData { config: *config };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_STORE_CUSTODIAN_WALLET_CONFIG,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(bridge_state, false),
+            AccountMeta::new(get_custodian_wallet_config_pda(&program_id), false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Schedule a switch to a new custodian wallet config, signed by the settings authority.
+///
+/// The switch happens once the return output has been swept to the new custodian with
+/// [`process_custodian_sweep`], which is accepted after `CUSTODIAN_ROTATION_DELAY_SECS`.
+pub fn schedule_custodian_rotation(
+    program_id: Pubkey,
+    authority: Pubkey,
+    new_custodian_wallet_config_hash: QHash256,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data_struct = ScheduleCustodianRotationInstructionData {
+        new_custodian_wallet_config_hash,
+    };
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_SCHEDULE_CUSTODIAN_ROTATION,
+        bytemuck::bytes_of(&data_struct),
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data,
+    }
+}
+
+/// Cancel the scheduled custodian rotation, signed by the settings authority or the guardian.
+pub fn cancel_custodian_rotation(program_id: Pubkey, authority: Pubkey) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let data = gen_aligned_instruction(DOGE_BRIDGE_INSTRUCTION_CANCEL_CUSTODIAN_ROTATION, &[]);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data,
+    }
+}
+
+/// Process the proof of the tx sweeping the return output to the scheduled custodian.
+///
+/// Takes the same accounts as [`process_withdrawal`], the sweep tx is published over Wormhole.
+pub fn process_custodian_sweep(
+    program_id: Pubkey,
+    payer: Pubkey,
+    generic_buffer_account: Pubkey,
+    wormhole_shim_program_id: Pubkey,
+    wormhole_core_program_id: Pubkey,
+    proof: CompactBridgeZKProof,
+    new_return_output: PsyReturnTxOutput,
+    new_spent_txo_tree_root: QHash256,
+) -> Instruction {
+    let mut instruction = process_withdrawal(
+        program_id,
+        payer,
+        generic_buffer_account,
+        wormhole_shim_program_id,
+        wormhole_core_program_id,
+        proof,
+        new_return_output,
+        new_spent_txo_tree_root,
+        0,
+    );
+    let data_struct = ProcessCustodianSweepInstructionData {
+        proof,
+        new_return_output,
+        new_spent_txo_tree_root,
+    };
+    instruction.data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_PROCESS_CUSTODIAN_SWEEP,
+        bytemuck::bytes_of(&data_struct),
+    );
+    instruction
+}
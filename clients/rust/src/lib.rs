@@ -64,11 +64,12 @@ pub use config::{
 };
 pub use errors::{BridgeError, BridgeResult, ErrorCategory};
 pub use types::{
-    CompactBridgeZKProof, DepositTxOutputRecord,
+    decode_doge_withdrawal_address, withdrawal_request_from_address, BlockHashArchiveEntry, Bridge7MultisigCustodianWalletConfig, BridgeCustodianConfig,
+    CompactBridgeZKProof, DepositTxOutputRecord, FeeSplitRecipient, FeeSplitTable,
     FinalizedBlockMintTxoInfo, InitializeBridgeParams, PendingMint, ProcessMintsResult,
     PsyBridgeConfig, PsyBridgeHeader, PsyBridgeHeaderUpdate, PsyBridgeProgramState,
-    PsyBridgeStateCommitment, PsyBridgeTipStateCommitment, PsyReturnTxOutput,
-    PsyWithdrawalChainSnapshot, PsyWithdrawalRequest,
+    PsyBridgeStateCommitment, PsyBridgeTipStateCommitment, PsyCustodianRotation, PsyReturnTxOutput,
+    PsyWithdrawalChainSnapshot, PsyWithdrawalRequest, WithdrawalAddressType, WithdrawalReceipt,
 };
 
 // Monitoring and history re-exports
@@ -77,8 +78,10 @@ pub use history::{
     ProcessedWithdrawalRecord, SyncCheckpoint, SyncHandle, WithdrawalRequestRecord,
 };
 pub use monitor::{
-    BridgeEvent, BridgeMonitor, ManualDepositClaimedEvent, MonitorConfig, MonitorHandle,
-    WithdrawalProcessedEvent, WithdrawalRequestedEvent,
+    decode_bridge_events_from_logs, BlockTransitionEvent, BridgeEvent, BridgeMonitor,
+    FeesWithdrawnEvent, ManualDepositClaimedEvent, MintGroupProcessedEvent, MonitorConfig,
+    MonitorHandle, CustodianRotatedEvent, WithdrawalCancelledEvent, WithdrawalProcessedEvent, WithdrawalRequestedEvent,
+    WithdrawalScriptEvent,
 };
 pub use noop_shim_monitor::{
     NoopShimMonitor, NoopShimMonitorConfig, NoopShimMonitorHandle, NoopShimWithdrawalMessage,
@@ -1,9 +1,16 @@
 //! Bridge monitoring client for streaming events.
 //!
 //! This module provides a client for monitoring bridge events in real-time:
-//! - Withdrawal requests
+//! - Withdrawal requests and cancellations
 //! - Manually claimed deposits
 //! - Processed withdrawals
+//! - Block updates, reorgs and auto-claim mint groups
+//! - Fee withdrawals
+//!
+//! Events are decoded from the typed records the bridge program logs with
+//! `sol_log_data` (see `psy_doge_solana_core::events`), so calls made through
+//! a CPI are picked up as well. Transactions from before the program emitted
+//! events fall back to decoding the instruction data.
 //!
 //! The monitor is designed for bridge node operators who need to track
 //! all bridge activity efficiently without hitting rate limits.
@@ -15,7 +22,7 @@ use solana_client::rpc_config::RpcTransactionConfig;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
-use solana_transaction_status::UiTransactionEncoding;
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding};
 use tokio::sync::mpsc;
 use tokio::time::{interval, Duration};
 
@@ -23,11 +30,21 @@ use crate::config::RateLimitConfig;
 use crate::errors::BridgeError;
 use crate::rpc::RpcRateLimiter;
 
+use psy_doge_solana_core::events::{
+    BlockTransitionEventData, BridgeEventHeader, CustodianRotatedEventData, FeesWithdrawnEventData,
+    ManualDepositClaimedEventData, MintGroupProcessedEventData, WithdrawalCancelledEventData,
+    WithdrawalProcessedEventData, WithdrawalRequestedEventData, WithdrawalScriptEventData, BRIDGE_EVENT_KIND_BLOCK_UPDATE,
+    BRIDGE_EVENT_KIND_CUSTODIAN_ROTATED, BRIDGE_EVENT_KIND_FEES_WITHDRAWN, BRIDGE_EVENT_KIND_MANUAL_DEPOSIT_CLAIMED,
+    BRIDGE_EVENT_KIND_MINT_GROUP_PROCESSED, BRIDGE_EVENT_KIND_REORG,
+    BRIDGE_EVENT_KIND_WITHDRAWAL_CANCELLED, BRIDGE_EVENT_KIND_WITHDRAWAL_PROCESSED,
+    BRIDGE_EVENT_KIND_WITHDRAWAL_REQUESTED, BRIDGE_EVENT_KIND_WITHDRAWAL_SCRIPT,
+};
 use psy_doge_solana_core::instructions::doge_bridge::{
     DOGE_BRIDGE_INSTRUCTION_PROCESS_MANUAL_DEPOSIT, DOGE_BRIDGE_INSTRUCTION_PROCESS_WITHDRAWAL,
     DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWAL, RequestWithdrawalInstructionData,
     ProcessWithdrawalInstructionData, ProcessManualDepositInstructionData,
 };
+use psy_doge_solana_core::program_state::PsyWithdrawalRequest;
 
 /// Event types emitted by the bridge monitor.
 #[derive(Debug, Clone)]
@@ -40,6 +57,33 @@ pub enum BridgeEvent {
     ManualDepositClaimed(ManualDepositClaimedEvent),
     /// A block transition occurred (new finalized block).
     BlockTransition(BlockTransitionEvent),
+    /// A group of auto-claimed deposits was minted.
+    MintGroupProcessed(MintGroupProcessedEvent),
+    /// Fees were minted to a token account.
+    FeesWithdrawn(FeesWithdrawnEvent),
+    /// A user cancelled a withdrawal request before it was snapshotted.
+    WithdrawalCancelled(WithdrawalCancelledEvent),
+    /// The return output was swept to a new custodian and the bridge switched to its config.
+    CustodianRotated(CustodianRotatedEvent),
+    /// The scriptPubKey behind a script withdrawal, follows its `WithdrawalRequested` event.
+    WithdrawalScript(WithdrawalScriptEvent),
+}
+
+impl BridgeEvent {
+    /// Signature of the transaction the event was emitted in.
+    pub fn signature(&self) -> Signature {
+        match self {
+            BridgeEvent::WithdrawalRequested(e) => e.signature,
+            BridgeEvent::WithdrawalProcessed(e) => e.signature,
+            BridgeEvent::ManualDepositClaimed(e) => e.signature,
+            BridgeEvent::BlockTransition(e) => e.signature,
+            BridgeEvent::MintGroupProcessed(e) => e.signature,
+            BridgeEvent::FeesWithdrawn(e) => e.signature,
+            BridgeEvent::WithdrawalCancelled(e) => e.signature,
+            BridgeEvent::CustodianRotated(e) => e.signature,
+            BridgeEvent::WithdrawalScript(e) => e.signature,
+        }
+    }
 }
 
 /// Event when a user requests a withdrawal.
@@ -51,8 +95,10 @@ pub struct WithdrawalRequestedEvent {
     pub slot: u64,
     /// Block time (if available)
     pub block_time: Option<i64>,
-    /// Withdrawal amount in satoshis
+    /// Withdrawal amount in satoshis (burned from the user)
     pub amount_sats: u64,
+    /// Amount sent to the recipient after withdrawal fees (0 if decoded from instruction data)
+    pub amount_after_fees_sats: u64,
     /// Recipient Dogecoin address (20 bytes)
     pub recipient_address: [u8; 20],
     /// Address type (0 = P2PKH, 1 = P2SH)
@@ -61,6 +107,8 @@ pub struct WithdrawalRequestedEvent {
     pub user_pubkey: Pubkey,
     /// Index in the withdrawal queue
     pub withdrawal_index: u64,
+    /// Nonce keying the request's receipt PDA together with the user pubkey
+    pub receipt_nonce: u64,
 }
 
 /// Event when a withdrawal is processed (sent to Dogecoin).
@@ -72,6 +120,8 @@ pub struct WithdrawalProcessedEvent {
     pub slot: u64,
     /// Block time (if available)
     pub block_time: Option<i64>,
+    /// Sighash of the processed Dogecoin withdrawal transaction (zero if decoded from instruction data)
+    pub sighash: [u8; 32],
     /// New return output sighash
     pub new_return_output_sighash: [u8; 32],
     /// New return output index
@@ -99,6 +149,8 @@ pub struct ManualDepositClaimedEvent {
     pub combined_txo_index: u64,
     /// Deposit amount in satoshis
     pub deposit_amount_sats: u64,
+    /// Amount minted after deposit fees (0 if decoded from instruction data)
+    pub minted_amount_sats: u64,
     /// Depositor's Solana public key
     pub depositor_pubkey: [u8; 32],
     /// User who claimed (signer)
@@ -118,6 +170,299 @@ pub struct BlockTransitionEvent {
     pub block_height: u32,
     /// Whether this was a reorg
     pub is_reorg: bool,
+    /// New finalized block hash (zero if decoded from instruction data)
+    pub block_hash: [u8; 32],
+    /// New tip block height (0 if decoded from instruction data)
+    pub tip_block_height: u32,
+    /// Number of blocks finalized by this transition
+    pub finalized_blocks_count: u32,
+}
+
+/// Event when a group of auto-claimed deposits is minted.
+#[derive(Debug, Clone)]
+pub struct MintGroupProcessedEvent {
+    /// Transaction signature
+    pub signature: Signature,
+    /// Slot where transaction was confirmed
+    pub slot: u64,
+    /// Block time (if available)
+    pub block_time: Option<i64>,
+    /// Pending mint buffer the group was read from
+    pub mint_buffer: Pubkey,
+    /// Index of the group within the buffer
+    pub mint_group_index: u32,
+    /// Number of mints in the group
+    pub mints_count: u32,
+    /// Total amount minted in satoshis
+    pub total_minted_sats: u64,
+    /// Whether the buffer was unlocked after this group
+    pub buffer_unlocked: bool,
+}
+
+/// Event when fees are minted, by `operator_withdraw_fees` or `distribute_fees`.
+#[derive(Debug, Clone)]
+pub struct FeesWithdrawnEvent {
+    /// Transaction signature
+    pub signature: Signature,
+    /// Slot where transaction was confirmed
+    pub slot: u64,
+    /// Block time (if available)
+    pub block_time: Option<i64>,
+    /// Token account the fees were minted to
+    pub token_account: Pubkey,
+    /// Amount minted in satoshis
+    pub amount_sats: u64,
+    /// Total fees withdrawn over the bridge's lifetime
+    pub total_fees_withdrawn_sats: u64,
+}
+
+/// Event when a withdrawal request is cancelled.
+#[derive(Debug, Clone)]
+pub struct WithdrawalCancelledEvent {
+    /// Transaction signature
+    pub signature: Signature,
+    /// Slot where transaction was confirmed
+    pub slot: u64,
+    /// Block time (if available)
+    pub block_time: Option<i64>,
+    /// User who requested the withdrawal
+    pub user_pubkey: Pubkey,
+    /// Index of the cancelled request in the withdrawal queue
+    pub withdrawal_index: u64,
+    /// Amount minted back to the user in satoshis
+    pub refund_amount_sats: u64,
+}
+
+/// Event when the return output is swept to a new custodian.
+#[derive(Debug, Clone)]
+pub struct CustodianRotatedEvent {
+    /// Transaction signature
+    pub signature: Signature,
+    /// Slot where transaction was confirmed
+    pub slot: u64,
+    /// Block time (if available)
+    pub block_time: Option<i64>,
+    /// Custodian wallet config hash before the rotation
+    pub old_custodian_wallet_config_hash: [u8; 32],
+    /// Custodian wallet config hash the bridge now commits to
+    pub new_custodian_wallet_config_hash: [u8; 32],
+    /// Hash of the sweep transaction
+    pub sighash: [u8; 32],
+    /// Output index of the new return output
+    pub new_return_output_index: u64,
+    /// Amount held by the new return output in satoshis
+    pub new_return_output_amount: u64,
+    /// New spent TXO tree root
+    pub new_spent_txo_tree_root: [u8; 32],
+}
+
+/// Event carrying the output script of a script withdrawal.
+#[derive(Debug, Clone)]
+pub struct WithdrawalScriptEvent {
+    /// Transaction signature
+    pub signature: Signature,
+    /// Slot where transaction was confirmed
+    pub slot: u64,
+    /// Block time (if available)
+    pub block_time: Option<i64>,
+    /// Index of the request in the withdrawal queue
+    pub withdrawal_index: u64,
+    /// The scriptPubKey, its hash160 is the request's recipient_address
+    pub script_pubkey: Vec<u8>,
+}
+
+/// Decode the bridge events logged by `program_id` in a transaction's log messages.
+///
+/// Only `Program data:` records logged while `program_id` is the innermost running
+/// program are decoded, so events from CPI calls into the bridge are included and
+/// look-alike records logged by other programs are ignored.
+pub fn decode_bridge_events_from_logs(
+    program_id: &Pubkey,
+    signature: &Signature,
+    slot: u64,
+    block_time: Option<i64>,
+    log_messages: &[String],
+) -> Vec<BridgeEvent> {
+    use base64::Engine;
+
+    let program_id_str = program_id.to_string();
+    let mut invoke_stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in log_messages {
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if invoke_stack.last() != Some(&program_id_str.as_str()) {
+                continue;
+            }
+            let fields: Option<Vec<Vec<u8>>> = data
+                .split(' ')
+                .map(|field| base64::engine::general_purpose::STANDARD.decode(field).ok())
+                .collect();
+            if let Some(fields) = fields {
+                if let Some(event) = decode_bridge_event(signature, slot, block_time, &fields) {
+                    events.push(event);
+                }
+            }
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut parts = rest.split(' ');
+            let (Some(id), Some(action)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            match action {
+                "invoke" => invoke_stack.push(id),
+                "success" | "failed:" => {
+                    invoke_stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events
+}
+
+/// Read an event record, accepting records with fields appended by newer event versions.
+fn read_event_data<T: bytemuck::Pod>(data: &[u8]) -> Option<T> {
+    let size = std::mem::size_of::<T>();
+    if data.len() < size {
+        return None;
+    }
+    Some(bytemuck::pod_read_unaligned(&data[..size]))
+}
+
+/// Decode one `sol_log_data` record (header field + event field) into a bridge event.
+fn decode_bridge_event(
+    signature: &Signature,
+    slot: u64,
+    block_time: Option<i64>,
+    fields: &[Vec<u8>],
+) -> Option<BridgeEvent> {
+    let [header, data] = fields else {
+        return None;
+    };
+    let header = BridgeEventHeader::from_log_field(header)?;
+    let signature = *signature;
+
+    match header.kind {
+        BRIDGE_EVENT_KIND_BLOCK_UPDATE | BRIDGE_EVENT_KIND_REORG => {
+            let e: BlockTransitionEventData = read_event_data(data)?;
+            Some(BridgeEvent::BlockTransition(BlockTransitionEvent {
+                signature,
+                slot,
+                block_time,
+                block_height: e.finalized_block_height,
+                is_reorg: header.kind == BRIDGE_EVENT_KIND_REORG,
+                block_hash: e.finalized_block_hash,
+                tip_block_height: e.tip_block_height,
+                finalized_blocks_count: e.finalized_blocks_count,
+            }))
+        }
+        BRIDGE_EVENT_KIND_MINT_GROUP_PROCESSED => {
+            let e: MintGroupProcessedEventData = read_event_data(data)?;
+            Some(BridgeEvent::MintGroupProcessed(MintGroupProcessedEvent {
+                signature,
+                slot,
+                block_time,
+                mint_buffer: Pubkey::new_from_array(e.mint_buffer),
+                mint_group_index: e.mint_group_index,
+                mints_count: e.mints_count,
+                total_minted_sats: e.total_minted_sats,
+                buffer_unlocked: e.buffer_unlocked != 0,
+            }))
+        }
+        BRIDGE_EVENT_KIND_WITHDRAWAL_REQUESTED => {
+            let e: WithdrawalRequestedEventData = read_event_data(data)?;
+            Some(BridgeEvent::WithdrawalRequested(WithdrawalRequestedEvent {
+                signature,
+                slot,
+                block_time,
+                amount_sats: e.amount_burned_sats,
+                amount_after_fees_sats: e.request.amount_sats,
+                recipient_address: e.request.recipient_address,
+                address_type: e.request.address_type,
+                user_pubkey: Pubkey::new_from_array(e.requester),
+                withdrawal_index: e.withdrawal_index,
+                receipt_nonce: e.receipt_nonce,
+            }))
+        }
+        BRIDGE_EVENT_KIND_WITHDRAWAL_PROCESSED => {
+            let e: WithdrawalProcessedEventData = read_event_data(data)?;
+            Some(BridgeEvent::WithdrawalProcessed(WithdrawalProcessedEvent {
+                signature,
+                slot,
+                block_time,
+                sighash: e.sighash,
+                new_return_output_sighash: e.new_return_output.sighash,
+                new_return_output_index: e.new_return_output.output_index,
+                new_return_output_amount: e.new_return_output.amount_sats,
+                new_spent_txo_tree_root: e.new_spent_txo_tree_root,
+                new_next_processed_withdrawals_index: e.new_next_processed_withdrawals_index,
+            }))
+        }
+        BRIDGE_EVENT_KIND_MANUAL_DEPOSIT_CLAIMED => {
+            let e: ManualDepositClaimedEventData = read_event_data(data)?;
+            Some(BridgeEvent::ManualDepositClaimed(ManualDepositClaimedEvent {
+                signature,
+                slot,
+                block_time,
+                tx_hash: e.tx_hash,
+                combined_txo_index: e.combined_txo_index,
+                deposit_amount_sats: e.deposit_amount_sats,
+                minted_amount_sats: e.minted_amount_sats,
+                depositor_pubkey: e.depositor,
+                // the manual claim program only claims for the depositor
+                claimer_pubkey: Pubkey::new_from_array(e.depositor),
+            }))
+        }
+        BRIDGE_EVENT_KIND_FEES_WITHDRAWN => {
+            let e: FeesWithdrawnEventData = read_event_data(data)?;
+            Some(BridgeEvent::FeesWithdrawn(FeesWithdrawnEvent {
+                signature,
+                slot,
+                block_time,
+                token_account: Pubkey::new_from_array(e.token_account),
+                amount_sats: e.amount_sats,
+                total_fees_withdrawn_sats: e.total_fees_withdrawn_sats,
+            }))
+        }
+        BRIDGE_EVENT_KIND_WITHDRAWAL_CANCELLED => {
+            let e: WithdrawalCancelledEventData = read_event_data(data)?;
+            Some(BridgeEvent::WithdrawalCancelled(WithdrawalCancelledEvent {
+                signature,
+                slot,
+                block_time,
+                user_pubkey: Pubkey::new_from_array(e.requester),
+                withdrawal_index: e.withdrawal_index,
+                refund_amount_sats: e.refund_amount_sats,
+            }))
+        }
+        BRIDGE_EVENT_KIND_CUSTODIAN_ROTATED => {
+            let e: CustodianRotatedEventData = read_event_data(data)?;
+            Some(BridgeEvent::CustodianRotated(CustodianRotatedEvent {
+                signature,
+                slot,
+                block_time,
+                old_custodian_wallet_config_hash: e.old_custodian_wallet_config_hash,
+                new_custodian_wallet_config_hash: e.new_custodian_wallet_config_hash,
+                sighash: e.sighash,
+                new_return_output_index: e.new_return_output.output_index,
+                new_return_output_amount: e.new_return_output.amount_sats,
+                new_spent_txo_tree_root: e.new_spent_txo_tree_root,
+            }))
+        }
+        BRIDGE_EVENT_KIND_WITHDRAWAL_SCRIPT => {
+            let e: WithdrawalScriptEventData = read_event_data(data)?;
+            Some(BridgeEvent::WithdrawalScript(WithdrawalScriptEvent {
+                signature,
+                slot,
+                block_time,
+                withdrawal_index: e.withdrawal_index,
+                script_pubkey: e.get_script().to_vec(),
+            }))
+        }
+        // kinds added by newer program versions are skipped
+        _ => None,
+    }
 }
 
 /// Configuration for the bridge monitor.
@@ -306,9 +651,9 @@ impl BridgeMonitor {
 
             for sig_info in &signatures {
                 tracing::info!("fetching tx for signature: {}", sig_info.signature);
-                if let Some(event) = self.parse_transaction(&sig_info.signature).await? {
-                    events.push(event);
-                }
+                // signatures are newest-first, reversed below, so keep each tx's events in reverse too
+                let tx_events = self.parse_transaction(&sig_info.signature).await?;
+                events.extend(tx_events.into_iter().rev());
             }
 
             // Update pagination cursor to oldest sig in this batch to fetch older txs next
@@ -387,7 +732,7 @@ impl BridgeMonitor {
         // Process in reverse (oldest first) for chronological order
         for sig_info in signatures.iter().rev() {
             if let Ok(sig) = sig_info.signature.parse::<Signature>() {
-                if let Some(event) = Self::parse_transaction_static(rpc, rate_limiter, &config.program_id, &sig).await? {
+                for event in Self::parse_transaction_static(rpc, rate_limiter, &config.program_id, &sig).await? {
                     if sender.send(event).await.is_err() {
                         // Receiver dropped, stop
                         return Ok(());
@@ -400,7 +745,7 @@ impl BridgeMonitor {
     }
 
     /// Parse a transaction to extract bridge events.
-    async fn parse_transaction(&self, signature_str: &str) -> Result<Option<BridgeEvent>, BridgeError> {
+    async fn parse_transaction(&self, signature_str: &str) -> Result<Vec<BridgeEvent>, BridgeError> {
         let sig = signature_str.parse::<Signature>().map_err(|e| {
             BridgeError::InvalidInput(format!("Invalid signature: {}", e))
         })?;
@@ -414,7 +759,7 @@ impl BridgeMonitor {
         rate_limiter: &RpcRateLimiter,
         program_id: &Pubkey,
         signature: &Signature,
-    ) -> Result<Option<BridgeEvent>, BridgeError> {
+    ) -> Result<Vec<BridgeEvent>, BridgeError> {
         let _guard = rate_limiter.acquire().await?;
 
         let tx = rpc
@@ -431,7 +776,21 @@ impl BridgeMonitor {
         let slot = tx.slot;
         let block_time = tx.block_time;
 
-        // Extract the transaction data
+        if let Some(meta) = &tx.transaction.meta {
+            // a failed transaction can still carry the events logged before it failed
+            if meta.err.is_some() {
+                return Ok(vec![]);
+            }
+            if let OptionSerializer::Some(log_messages) = &meta.log_messages {
+                let events =
+                    decode_bridge_events_from_logs(program_id, signature, slot, block_time, log_messages);
+                if !events.is_empty() {
+                    return Ok(events);
+                }
+            }
+        }
+
+        // Fall back to the instruction data for transactions from before the program emitted events
         let transaction = match tx.transaction.transaction {
             solana_transaction_status::EncodedTransaction::Binary(data, _) => {
                 use base64::Engine;
@@ -440,7 +799,7 @@ impl BridgeMonitor {
                 bincode::deserialize::<solana_sdk::transaction::VersionedTransaction>(&bytes)
                     .map_err(|e| BridgeError::InvalidInput(format!("Failed to deserialize tx: {}", e)))?
             }
-            _ => return Ok(None),
+            _ => return Ok(vec![]),
         };
 
         // Find instructions to this program
@@ -479,7 +838,7 @@ impl BridgeMonitor {
                         &ix.accounts,
                         account_keys,
                     ) {
-                        return Ok(Some(BridgeEvent::WithdrawalRequested(event)));
+                        return Ok(vec![BridgeEvent::WithdrawalRequested(event)]);
                     }
                 }
                 DOGE_BRIDGE_INSTRUCTION_PROCESS_WITHDRAWAL => {
@@ -489,7 +848,7 @@ impl BridgeMonitor {
                         block_time,
                         ix_data,
                     ) {
-                        return Ok(Some(BridgeEvent::WithdrawalProcessed(event)));
+                        return Ok(vec![BridgeEvent::WithdrawalProcessed(event)]);
                     }
                 }
                 DOGE_BRIDGE_INSTRUCTION_PROCESS_MANUAL_DEPOSIT => {
@@ -501,14 +860,14 @@ impl BridgeMonitor {
                         &ix.accounts,
                         account_keys,
                     ) {
-                        return Ok(Some(BridgeEvent::ManualDepositClaimed(event)));
+                        return Ok(vec![BridgeEvent::ManualDepositClaimed(event)]);
                     }
                 }
                 _ => {}
             }
         }
 
-        Ok(None)
+        Ok(vec![])
     }
 
     /// Parse a withdrawal request instruction.
@@ -520,13 +879,18 @@ impl BridgeMonitor {
         accounts: &[u8],
         account_keys: &[Pubkey],
     ) -> Option<WithdrawalRequestedEvent> {
-        // Skip 8-byte alignment header
-        if ix_data.len() < 8 + std::mem::size_of::<RequestWithdrawalInstructionData>() {
+        // Skip 8-byte alignment header, only the leading request is read since requests made
+        // before receipts were keyed by a nonce carry no trailing receipt_nonce
+        if ix_data.len() < 8 + std::mem::size_of::<PsyWithdrawalRequest>() {
             return None;
         }
 
-        let data: &RequestWithdrawalInstructionData =
-            bytemuck::from_bytes(&ix_data[8..8 + std::mem::size_of::<RequestWithdrawalInstructionData>()]);
+        let request: &PsyWithdrawalRequest =
+            bytemuck::from_bytes(&ix_data[8..8 + std::mem::size_of::<PsyWithdrawalRequest>()]);
+        let receipt_nonce = ix_data
+            .get(8..8 + std::mem::size_of::<RequestWithdrawalInstructionData>())
+            .map(|data| bytemuck::from_bytes::<RequestWithdrawalInstructionData>(data).receipt_nonce)
+            .unwrap_or(0);
 
         // The user signer is typically the first account
         let user_pubkey = if !accounts.is_empty() && (accounts[0] as usize) < account_keys.len() {
@@ -539,11 +903,13 @@ impl BridgeMonitor {
             signature: *signature,
             slot,
             block_time,
-            amount_sats: data.request.amount_sats,
-            recipient_address: data.request.recipient_address,
-            address_type: data.request.address_type,
+            amount_sats: request.amount_sats,
+            amount_after_fees_sats: 0,
+            recipient_address: request.recipient_address,
+            address_type: request.address_type,
             user_pubkey,
             withdrawal_index: 0, // Would need to read from state
+            receipt_nonce,
         })
     }
 
@@ -566,6 +932,7 @@ impl BridgeMonitor {
             signature: *signature,
             slot,
             block_time,
+            sighash: [0u8; 32],
             new_return_output_sighash: data.new_return_output.sighash,
             new_return_output_index: data.new_return_output.output_index,
             new_return_output_amount: data.new_return_output.amount_sats,
@@ -605,6 +972,7 @@ impl BridgeMonitor {
             tx_hash: data.tx_hash,
             combined_txo_index: data.combined_txo_index,
             deposit_amount_sats: data.deposit_amount_sats,
+            minted_amount_sats: 0,
             depositor_pubkey: data.depositor_solana_public_key,
             claimer_pubkey,
         })
@@ -677,10 +1045,12 @@ mod tests {
             slot: 100,
             block_time: Some(12345),
             amount_sats: 1_000_000,
+            amount_after_fees_sats: 989_000,
             recipient_address: [0u8; 20],
             address_type: 0,
             user_pubkey: Pubkey::new_unique(),
             withdrawal_index: 5,
+            receipt_nonce: 0,
         });
 
         match event {
@@ -691,4 +1061,54 @@ mod tests {
             _ => panic!("Wrong event type"),
         }
     }
+
+    #[test]
+    fn test_decode_bridge_events_from_logs() {
+        use base64::Engine;
+        let program_id = Pubkey::new_unique();
+        let caller_id = Pubkey::new_unique();
+        let requester = Pubkey::new_unique();
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+
+        let header = BridgeEventHeader::new(BRIDGE_EVENT_KIND_WITHDRAWAL_REQUESTED);
+        let data = WithdrawalRequestedEventData {
+            requester: requester.to_bytes(),
+            request: PsyWithdrawalRequest::new([7u8; 20], 98_000, 0),
+            withdrawal_index: 42,
+            amount_burned_sats: 100_000,
+            receipt_nonce: 3,
+        };
+        let data_log = format!(
+            "Program data: {} {}",
+            encode(bytemuck::bytes_of(&header)),
+            encode(bytemuck::bytes_of(&data))
+        );
+
+        // the bridge is invoked through a CPI, the caller logs a look-alike record itself
+        let logs = vec![
+            format!("Program {} invoke [1]", caller_id),
+            data_log.clone(),
+            format!("Program {} invoke [2]", program_id),
+            "Program log: Instruction: RequestWithdrawal".to_string(),
+            data_log,
+            format!("Program {} consumed 12000 of 200000 compute units", program_id),
+            format!("Program {} success", program_id),
+            format!("Program {} success", caller_id),
+        ];
+
+        let events =
+            decode_bridge_events_from_logs(&program_id, &Signature::default(), 100, None, &logs);
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            BridgeEvent::WithdrawalRequested(e) => {
+                assert_eq!(e.user_pubkey, requester);
+                assert_eq!(e.withdrawal_index, 42);
+                assert_eq!(e.receipt_nonce, 3);
+                assert_eq!(e.amount_sats, 100_000);
+                assert_eq!(e.amount_after_fees_sats, 98_000);
+                assert_eq!(e.recipient_address, [7u8; 20]);
+            }
+            _ => panic!("Wrong event type"),
+        }
+    }
 }
@@ -6,21 +6,31 @@
 // Re-exports from psy-bridge-core
 pub use psy_bridge_core::{
     crypto::zk::CompactBridgeZKProof,
+    custodian_config::{Bridge7MultisigCustodianWalletConfig, BridgeCustodianConfig},
+    dogecoin::{
+        address::{DogeAddress, DogeAddressType},
+        network::DogeNetwork,
+    },
     header::{PsyBridgeHeader, PsyBridgeHeaderUpdate, PsyBridgeStateCommitment, PsyBridgeTipStateCommitment},
 };
 
 // Re-exports from psy-doge-solana-core
 pub use psy_doge_solana_core::{
+    data_accounts::block_hash_archive::BlockHashArchiveEntry,
+    data_accounts::fee_split::{FeeSplitRecipient, FeeSplitTable},
     data_accounts::pending_mint::PendingMint,
+    data_accounts::withdrawal_receipt::WithdrawalReceipt,
     instructions::doge_bridge::InitializeBridgeParams,
     program_state::{
-        FinalizedBlockMintTxoInfo, PsyBridgeConfig, PsyBridgeProgramState,
-        PsyReturnTxOutput, PsyWithdrawalChainSnapshot, PsyWithdrawalRequest,
+        FinalizedBlockMintTxoInfo, PsyBridgeConfig, PsyBridgeProgramState, PsyCustodianRotation,
+        PsyReturnTxOutput, PsyWithdrawalChainSnapshot, PsyWithdrawalRequest, WithdrawalAddressType,
     },
 };
 
 use solana_sdk::signature::Signature;
 
+use crate::errors::BridgeError;
+
 /// Record for a deposit transaction output.
 ///
 /// Used when querying manual deposits from the bridge state.
@@ -116,6 +126,24 @@ impl TransactionResult {
     }
 }
 
+/// Decode a Base58Check Dogecoin address into its address type and 20 byte hash.
+///
+/// Mainnet, testnet and regtest P2PKH and P2SH addresses are accepted.
+pub fn decode_doge_withdrawal_address(address: &str) -> Result<(WithdrawalAddressType, [u8; 20]), BridgeError> {
+    let address = parse_doge_address(address)?;
+    Ok((address.address_type.into(), address.hash))
+}
+
+/// Build a withdrawal request paying `amount_sats` (before fees) to a Base58Check Dogecoin address.
+pub fn withdrawal_request_from_address(address: &str, amount_sats: u64) -> Result<PsyWithdrawalRequest, BridgeError> {
+    Ok(PsyWithdrawalRequest::from_doge_address(&parse_doge_address(address)?, amount_sats))
+}
+
+fn parse_doge_address(address: &str) -> Result<DogeAddress, BridgeError> {
+    DogeAddress::parse(address)
+        .map_err(|e| BridgeError::InvalidInput(format!("invalid dogecoin address {}: {}", address, e)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,4 +169,25 @@ mod tests {
         assert_eq!(record.amount_sats, 1_000_000);
         assert_eq!(record.block_height, 100);
     }
+
+    #[test]
+    fn test_withdrawal_request_from_address() {
+        let hash: [u8; 20] = std::array::from_fn(|i| i as u8 + 1);
+        let request = withdrawal_request_from_address("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H1", 1_000_000).unwrap();
+        assert_eq!(request, PsyWithdrawalRequest::new(hash, 1_000_000, 0));
+
+        for (address, address_type) in [
+            ("9rXbkMyi1S6thykRoXAZcY8fwUKYsy6cXE", WithdrawalAddressType::P2SH),
+            ("nUHVMF6vcrGd8RSK2hUZjwuGDNmPeNoBRb", WithdrawalAddressType::P2PKH),
+            ("2MsLZ5FqqYpjM1Q1W4X81zMVZTF9gdbhVwd", WithdrawalAddressType::P2SH),
+            ("mfcHP2WMCVLsVZA8yrovmhMgxNFW9r98xw", WithdrawalAddressType::P2PKH),
+        ] {
+            assert_eq!(decode_doge_withdrawal_address(address).unwrap(), (address_type, hash));
+        }
+
+        // bitcoin version byte, broken checksum, not base58
+        assert!(decode_doge_withdrawal_address("16L5yRNPTuciSgXGHqYwn9N6NeoKqopAu").is_err());
+        assert!(decode_doge_withdrawal_address("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H2").is_err());
+        assert!(decode_doge_withdrawal_address("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H0").is_err());
+    }
 }
@@ -2,66 +2,24 @@
 //!
 //! Run with:
 //! ```bash
-//! cargo run --example withdraw_to_doge -- <RPC_URL> <USER_KEYPAIR_PATH> <DOGE_ADDRESS> <AMOUNT_SATS>
+//! cargo run --example withdraw_to_doge -- <RPC_URL> <USER_KEYPAIR_PATH> <DOGE_ADDRESS> <AMOUNT_SATS> [mainnet|testnet|regtest]
 //! ```
 //!
-//! The Dogecoin address should be a P2PKH address (starts with 'D' on mainnet).
+//! The Dogecoin address can be a P2PKH (starts with 'D' on mainnet) or P2SH address.
+//! Addresses from a different network than the one given (mainnet by default) are rejected.
 
-use doge_bridge_user_client::UserClient;
+use doge_bridge_user_client::{
+    parse_doge_address, DogeNetwork, PsyWithdrawalRequest, UserClient, UserClientConfigBuilder,
+};
 use solana_sdk::{signature::read_keypair_file, signer::Signer};
 use std::env;
 
-/// Decode a Dogecoin P2PKH address to get the 20-byte pubkey hash.
-///
-/// Dogecoin P2PKH addresses use Base58Check encoding with version byte 0x1e (mainnet).
-fn decode_doge_address(address: &str) -> Result<[u8; 20], String> {
-    // Decode from base58
-    let decoded = bs58::decode(address)
-        .into_vec()
-        .map_err(|e| format!("Invalid base58: {}", e))?;
-
-    if decoded.len() != 25 {
-        return Err(format!(
-            "Invalid address length: expected 25 bytes, got {}",
-            decoded.len()
-        ));
-    }
-
-    // Verify checksum (last 4 bytes)
-    let payload = &decoded[..21];
-    let checksum = &decoded[21..];
-
-    use sha2::{Digest, Sha256};
-    let hash1 = Sha256::digest(payload);
-    let hash2 = Sha256::digest(&hash1);
-    let expected_checksum = &hash2[..4];
-
-    if checksum != expected_checksum {
-        return Err("Invalid checksum".to_string());
-    }
-
-    // Check version byte (0x1e for mainnet P2PKH, 0x71 for testnet)
-    let version = decoded[0];
-    if version != 0x1e && version != 0x71 {
-        return Err(format!(
-            "Unsupported address version: 0x{:02x} (expected 0x1e for mainnet or 0x71 for testnet)",
-            version
-        ));
-    }
-
-    // Extract the 20-byte pubkey hash
-    let mut pubkey_hash = [0u8; 20];
-    pubkey_hash.copy_from_slice(&decoded[1..21]);
-
-    Ok(pubkey_hash)
-}
-
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
     if args.len() < 5 {
         eprintln!(
-            "Usage: {} <RPC_URL> <USER_KEYPAIR_PATH> <DOGE_ADDRESS> <AMOUNT_SATS>",
+            "Usage: {} <RPC_URL> <USER_KEYPAIR_PATH> <DOGE_ADDRESS> <AMOUNT_SATS> [mainnet|testnet|regtest]",
             args[0]
         );
         eprintln!(
@@ -77,18 +35,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let amount_sats: u64 = args[4]
         .parse()
         .map_err(|_| "Invalid amount: must be a positive integer")?;
+    let network = match args.get(5).map(String::as_str) {
+        None | Some("mainnet") => DogeNetwork::Mainnet,
+        Some("testnet") => DogeNetwork::Testnet,
+        Some("regtest") => DogeNetwork::Regtest,
+        Some(other) => return Err(format!("Unknown network: {}", other).into()),
+    };
 
     // Load the user keypair
     let user = read_keypair_file(keypair_path)
         .map_err(|e| format!("Failed to read keypair from {}: {}", keypair_path, e))?;
 
     // Decode the Dogecoin address
-    let recipient_hash = decode_doge_address(doge_address)?;
-    println!("Dogecoin address: {}", doge_address);
-    println!("Pubkey hash (hex): {}", hex::encode(recipient_hash));
+    let address = parse_doge_address(doge_address, network)?;
+    let request = PsyWithdrawalRequest::from_doge_address(&address, amount_sats);
+    println!("Dogecoin address: {} ({:?} {:?})", address, network, address.address_type);
+    println!(
+        "Address hash (hex): {} (address type {})",
+        hex::encode(request.recipient_address),
+        request.address_type
+    );
 
     println!("Creating UserClient connected to: {}", rpc_url);
-    let client = UserClient::new(rpc_url)?;
+    let config = UserClientConfigBuilder::new()
+        .rpc_url(rpc_url)
+        .doge_network(network)
+        .build()?;
+    let client = UserClient::with_config(config)?;
 
     println!("User Solana address: {}", user.pubkey());
     println!(
@@ -116,14 +89,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Requesting withdrawal to Dogecoin...");
     println!("Note: The bridge operator will process this withdrawal and send DOGE to your address.");
 
-    // Address type 0 = P2PKH
+    // any nonce without an open receipt works, the current time keeps repeated runs apart
+    let receipt_nonce = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+
     match client
-        .request_withdrawal(&user, recipient_hash, amount_sats, 0)
+        .request_withdrawal_to_address(&user, doge_address, amount_sats, receipt_nonce)
         .await
     {
         Ok(signature) => {
             println!("Withdrawal request submitted successfully!");
             println!("  Transaction signature: {}", signature);
+            println!("  Receipt nonce: {}", receipt_nonce);
 
             // Show updated balance
             let new_balance = client.get_balance(&user.pubkey()).await?;
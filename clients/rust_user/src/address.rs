@@ -0,0 +1,64 @@
+//! Dogecoin address helpers for withdrawal requests.
+
+use psy_bridge_core::dogecoin::{address::DogeAddress, network::DogeNetwork};
+use psy_doge_solana_core::program_state::{PsyWithdrawalRequest, WithdrawalAddressType};
+
+use crate::errors::{UserClientError, UserClientResult};
+
+/// Parse a Base58Check Dogecoin address, rejecting addresses that belong to another network.
+///
+/// Testnet and regtest P2SH addresses share a version byte, so either network accepts them.
+pub fn parse_doge_address(address: &str, network: DogeNetwork) -> UserClientResult<DogeAddress> {
+    DogeAddress::parse_for_network(address, network)
+        .map_err(|e| UserClientError::InvalidInput(format!("Invalid {:?} Dogecoin address {}: {}", network, address, e)))
+}
+
+/// Decode a Base58Check Dogecoin address into its address type and 20-byte hash.
+///
+/// Mainnet, testnet and regtest P2PKH and P2SH addresses are accepted.
+pub fn decode_doge_address(address: &str) -> UserClientResult<(WithdrawalAddressType, [u8; 20])> {
+    let address = parse_any_doge_address(address)?;
+    Ok((address.address_type.into(), address.hash))
+}
+
+/// Build a withdrawal request paying `amount_sats` (before fees) to a Base58Check Dogecoin address.
+pub fn withdrawal_request_from_address(address: &str, amount_sats: u64) -> UserClientResult<PsyWithdrawalRequest> {
+    Ok(PsyWithdrawalRequest::from_doge_address(&parse_any_doge_address(address)?, amount_sats))
+}
+
+fn parse_any_doge_address(address: &str) -> UserClientResult<DogeAddress> {
+    DogeAddress::parse(address)
+        .map_err(|e| UserClientError::InvalidInput(format!("Invalid Dogecoin address {}: {}", address, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_doge_address() {
+        let hash: [u8; 20] = std::array::from_fn(|i| i as u8 + 1);
+        assert_eq!(
+            decode_doge_address("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H1").unwrap(),
+            (WithdrawalAddressType::P2PKH, hash)
+        );
+        assert_eq!(
+            withdrawal_request_from_address("9rXbkMyi1S6thykRoXAZcY8fwUKYsy6cXE", 5_000).unwrap(),
+            PsyWithdrawalRequest::new(hash, 5_000, 1)
+        );
+        assert!(decode_doge_address("16L5yRNPTuciSgXGHqYwn9N6NeoKqopAu").is_err());
+        assert!(decode_doge_address("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H2").is_err());
+    }
+
+    #[test]
+    fn test_parse_doge_address_checks_network() {
+        let address = parse_doge_address("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H1", DogeNetwork::Mainnet).unwrap();
+        assert_eq!(
+            PsyWithdrawalRequest::from_doge_address(&address, 5_000),
+            withdrawal_request_from_address("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H1", 5_000).unwrap()
+        );
+        assert!(parse_doge_address("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H1", DogeNetwork::Testnet).is_err());
+        assert!(parse_doge_address("nUHVMF6vcrGd8RSK2hUZjwuGDNmPeNoBRb", DogeNetwork::Mainnet).is_err());
+        assert!(parse_doge_address("2MsLZ5FqqYpjM1Q1W4X81zMVZTF9gdbhVwd", DogeNetwork::Regtest).is_ok());
+    }
+}
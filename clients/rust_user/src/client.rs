@@ -4,7 +4,12 @@
 
 use std::sync::Arc;
 
-use psy_doge_solana_core::program_state::BridgeProgramStateWithDogeMint;
+use psy_bridge_core::dogecoin::network::DogeNetwork;
+use psy_doge_solana_core::{
+    constants::{MAX_WITHDRAWAL_REQUESTS_PER_BATCH, MAX_WITHDRAWAL_SCRIPT_SIZE},
+    data_accounts::withdrawal_receipt::WithdrawalReceipt,
+    program_state::{decode_bridge_state_account, PsyWithdrawalRequest},
+};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
@@ -21,6 +26,7 @@ use spl_associated_token_account::{
 use spl_token::instruction as token_instruction;
 
 use crate::{
+    address::parse_doge_address,
     config::{UserClientConfig, UserClientConfigBuilder},
     errors::{UserClientError, UserClientResult},
     instructions,
@@ -88,6 +94,11 @@ impl UserClient {
         self.config.bridge_state_pda
     }
 
+    /// Get the Dogecoin network withdrawal addresses are checked against.
+    pub fn doge_network(&self) -> DogeNetwork {
+        self.config.doge_network
+    }
+
     /// Get the RPC client for advanced operations.
     pub fn rpc(&self) -> &RpcClient {
         &self.rpc
@@ -113,7 +124,12 @@ impl UserClient {
                 address: self.config.bridge_state_pda.to_string(),
             })?;
 
-        let bridge_state: &BridgeProgramStateWithDogeMint = bytemuck::from_bytes(&account.data);
+        let bridge_state = decode_bridge_state_account(&account.data).map_err(|err| {
+            UserClientError::InvalidAccountData {
+                address: self.config.bridge_state_pda.to_string(),
+                message: err.to_string(),
+            }
+        })?;
         let mint = Pubkey::new_from_array(bridge_state.doge_mint);
 
         // Cache the result
@@ -276,6 +292,7 @@ impl UserClient {
     /// * `recipient_address` - 20-byte Dogecoin address (P2PKH hash160)
     /// * `amount_sats` - Amount to withdraw in satoshis
     /// * `address_type` - Address type (0 for P2PKH)
+    /// * `receipt_nonce` - Keys the request's receipt, must not be used by another open receipt of the user
     ///
     /// # Returns
     /// The transaction signature.
@@ -285,6 +302,7 @@ impl UserClient {
         recipient_address: [u8; 20],
         amount_sats: u64,
         address_type: u32,
+        receipt_nonce: u64,
     ) -> UserClientResult<Signature> {
         let doge_mint = self.get_doge_mint().await?;
         let user_token_account = get_associated_token_address(&user.pubkey(), &doge_mint);
@@ -306,6 +324,238 @@ impl UserClient {
             recipient_address,
             amount_sats,
             address_type,
+            receipt_nonce,
+        );
+
+        self.send_and_confirm(&[ix], user, &[]).await
+    }
+
+    /// Request a withdrawal to a Base58Check Dogecoin address.
+    ///
+    /// Same as [`UserClient::request_withdrawal`], with the recipient hash and
+    /// address type taken from the address.
+    ///
+    /// # Arguments
+    /// * `user` - The keypair of the user requesting the withdrawal
+    /// * `address` - P2PKH or P2SH Dogecoin address, rejected unless it belongs to the configured `doge_network`
+    /// * `amount_sats` - Amount to withdraw in satoshis
+    /// * `receipt_nonce` - Keys the request's receipt, must not be used by another open receipt of the user
+    ///
+    /// # Returns
+    /// The transaction signature.
+    pub async fn request_withdrawal_to_address(
+        &self,
+        user: &Keypair,
+        address: &str,
+        amount_sats: u64,
+        receipt_nonce: u64,
+    ) -> UserClientResult<Signature> {
+        let address = parse_doge_address(address, self.config.doge_network)?;
+        let request = PsyWithdrawalRequest::from_doge_address(&address, amount_sats);
+        self.request_withdrawal(user, request.recipient_address, amount_sats, request.address_type, receipt_nonce)
+            .await
+    }
+
+    /// Request several withdrawals in a single transaction.
+    ///
+    /// The total of the requested amounts is burned once, each request is charged
+    /// its own withdrawal fee and gets its own receipt.
+    ///
+    /// # Arguments
+    /// * `user` - The keypair of the user requesting the withdrawals
+    /// * `requests` - Up to `MAX_WITHDRAWAL_REQUESTS_PER_BATCH` requests
+    /// * `first_receipt_nonce` - The receipts are keyed by consecutive nonces starting here
+    ///
+    /// # Returns
+    /// The transaction signature.
+    pub async fn request_withdrawals_batch(
+        &self,
+        user: &Keypair,
+        requests: &[PsyWithdrawalRequest],
+        first_receipt_nonce: u64,
+    ) -> UserClientResult<Signature> {
+        if requests.is_empty() || requests.len() > MAX_WITHDRAWAL_REQUESTS_PER_BATCH {
+            return Err(UserClientError::InvalidInput(format!(
+                "withdrawal batch must hold 1 to {} requests, got {}",
+                MAX_WITHDRAWAL_REQUESTS_PER_BATCH,
+                requests.len()
+            )));
+        }
+        let doge_mint = self.get_doge_mint().await?;
+        let user_token_account = get_associated_token_address(&user.pubkey(), &doge_mint);
+
+        // Check balance
+        let total_sats = requests
+            .iter()
+            .try_fold(0u64, |total, request| total.checked_add(request.amount_sats))
+            .ok_or_else(|| UserClientError::InvalidInput("withdrawal batch total overflows".to_string()))?;
+        let balance = self.get_balance(&user.pubkey()).await?;
+        if balance < total_sats {
+            return Err(UserClientError::InsufficientBalance {
+                required: total_sats,
+                available: balance,
+            });
+        }
+
+        let ix = instructions::request_withdrawals_batch(
+            self.config.program_id,
+            user.pubkey(),
+            doge_mint,
+            user_token_account,
+            requests,
+            first_receipt_nonce,
+        );
+
+        self.send_and_confirm(&[ix], user, &[]).await
+    }
+
+    /// Request a withdrawal to an arbitrary Dogecoin scriptPubKey.
+    ///
+    /// Use this when the recipient needs more than a plain address, e.g. an exchange
+    /// that credits deposits by a memo/tag embedded in the output script.
+    ///
+    /// # Arguments
+    /// * `user` - The keypair of the user requesting the withdrawal
+    /// * `script_pubkey` - The output script, at most `MAX_WITHDRAWAL_SCRIPT_SIZE` bytes
+    /// * `amount_sats` - Amount to withdraw in satoshis
+    /// * `receipt_nonce` - Keys the request's receipt, must not be used by another open receipt of the user
+    ///
+    /// # Returns
+    /// The transaction signature.
+    pub async fn request_script_withdrawal(
+        &self,
+        user: &Keypair,
+        script_pubkey: &[u8],
+        amount_sats: u64,
+        receipt_nonce: u64,
+    ) -> UserClientResult<Signature> {
+        if script_pubkey.is_empty() || script_pubkey.len() > MAX_WITHDRAWAL_SCRIPT_SIZE {
+            return Err(UserClientError::InvalidInput(format!(
+                "withdrawal script must be 1 to {} bytes, got {}",
+                MAX_WITHDRAWAL_SCRIPT_SIZE,
+                script_pubkey.len()
+            )));
+        }
+        let doge_mint = self.get_doge_mint().await?;
+        let user_token_account = get_associated_token_address(&user.pubkey(), &doge_mint);
+
+        // Check balance
+        let balance = self.get_balance(&user.pubkey()).await?;
+        if balance < amount_sats {
+            return Err(UserClientError::InsufficientBalance {
+                required: amount_sats,
+                available: balance,
+            });
+        }
+
+        let ix = instructions::request_script_withdrawal(
+            self.config.program_id,
+            user.pubkey(),
+            doge_mint,
+            user_token_account,
+            script_pubkey,
+            amount_sats,
+            receipt_nonce,
+        );
+
+        self.send_and_confirm(&[ix], user, &[]).await
+    }
+
+    /// Get the index the next withdrawal request will be assigned.
+    pub async fn get_next_withdrawal_index(&self) -> UserClientResult<u64> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(&self.config.bridge_state_pda, CommitmentConfig::confirmed())
+            .await?
+            .value
+            .ok_or_else(|| UserClientError::AccountNotFound {
+                address: self.config.bridge_state_pda.to_string(),
+            })?;
+
+        let bridge_state = decode_bridge_state_account(&account.data).map_err(|err| {
+            UserClientError::InvalidAccountData {
+                address: self.config.bridge_state_pda.to_string(),
+                message: err.to_string(),
+            }
+        })?;
+        Ok(bridge_state.core_state.requested_withdrawals_tree.next_index)
+    }
+
+    /// Get the receipt of a withdrawal request.
+    ///
+    /// # Arguments
+    /// * `requester` - The user that requested the withdrawal
+    /// * `receipt_nonce` - The nonce the request was made with
+    ///
+    /// # Returns
+    /// The receipt, or `None` if it does not exist or has already been closed.
+    pub async fn get_withdrawal_receipt(
+        &self,
+        requester: &Pubkey,
+        receipt_nonce: u64,
+    ) -> UserClientResult<Option<WithdrawalReceipt>> {
+        let receipt_pda =
+            instructions::get_withdrawal_receipt_pda(&self.config.program_id, requester, receipt_nonce);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&receipt_pda, CommitmentConfig::confirmed())
+            .await?
+            .value;
+
+        Ok(account.and_then(|account| {
+            bytemuck::try_from_bytes::<WithdrawalReceipt>(&account.data)
+                .ok()
+                .copied()
+        }))
+    }
+
+    /// Cancel a withdrawal request that has not been snapshotted yet.
+    ///
+    /// The burned amount, minus the bridge's cancellation fee, is minted back
+    /// to the user's associated token account.
+    ///
+    /// # Arguments
+    /// * `user` - The keypair of the user that requested the withdrawal
+    /// * `receipt_nonce` - The nonce the request was made with
+    ///
+    /// # Returns
+    /// The transaction signature.
+    pub async fn cancel_withdrawal(
+        &self,
+        user: &Keypair,
+        receipt_nonce: u64,
+    ) -> UserClientResult<Signature> {
+        let doge_mint = self.get_doge_mint().await?;
+        let user_token_account = get_associated_token_address(&user.pubkey(), &doge_mint);
+
+        let ix = instructions::cancel_withdrawal(
+            self.config.program_id,
+            user.pubkey(),
+            doge_mint,
+            user_token_account,
+            receipt_nonce,
+        );
+
+        self.send_and_confirm(&[ix], user, &[]).await
+    }
+
+    /// Close a fulfilled or cancelled withdrawal receipt to reclaim its rent.
+    ///
+    /// # Arguments
+    /// * `user` - The keypair of the user that requested the withdrawal
+    /// * `receipt_nonce` - The nonce the request was made with
+    ///
+    /// # Returns
+    /// The transaction signature.
+    pub async fn close_withdrawal_receipt(
+        &self,
+        user: &Keypair,
+        receipt_nonce: u64,
+    ) -> UserClientResult<Signature> {
+        let ix = instructions::close_withdrawal_receipt(
+            self.config.program_id,
+            user.pubkey(),
+            receipt_nonce,
         );
 
         self.send_and_confirm(&[ix], user, &[]).await
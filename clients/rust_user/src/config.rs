@@ -1,5 +1,6 @@
 //! Configuration for the user client.
 
+use psy_bridge_core::dogecoin::network::DogeNetwork;
 use solana_sdk::pubkey::Pubkey;
 
 /// Default bridge program ID
@@ -14,6 +15,9 @@ pub struct UserClientConfig {
     pub program_id: Pubkey,
     /// Bridge state PDA (derived from program_id if not provided)
     pub bridge_state_pda: Pubkey,
+    /// Dogecoin network the bridge custodies DOGE on (mainnet if not provided),
+    /// withdrawal addresses of other networks are rejected
+    pub doge_network: DogeNetwork,
 }
 
 /// Builder for UserClientConfig.
@@ -21,6 +25,7 @@ pub struct UserClientConfigBuilder {
     rpc_url: Option<String>,
     program_id: Option<Pubkey>,
     bridge_state_pda: Option<Pubkey>,
+    doge_network: Option<DogeNetwork>,
 }
 
 impl UserClientConfigBuilder {
@@ -30,6 +35,7 @@ impl UserClientConfigBuilder {
             rpc_url: None,
             program_id: None,
             bridge_state_pda: None,
+            doge_network: None,
         }
     }
 
@@ -51,6 +57,12 @@ impl UserClientConfigBuilder {
         self
     }
 
+    /// Set the Dogecoin network the bridge custodies DOGE on.
+    pub fn doge_network(mut self, network: DogeNetwork) -> Self {
+        self.doge_network = Some(network);
+        self
+    }
+
     /// Build the configuration.
     pub fn build(self) -> Result<UserClientConfig, String> {
         let rpc_url = self.rpc_url.ok_or("RPC URL is required")?;
@@ -67,6 +79,7 @@ impl UserClientConfigBuilder {
             rpc_url,
             program_id,
             bridge_state_pda,
+            doge_network: self.doge_network.unwrap_or(DogeNetwork::Mainnet),
         })
     }
 }
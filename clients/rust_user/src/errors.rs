@@ -20,6 +20,10 @@ pub enum UserClientError {
     #[error("Account not found: {address}")]
     AccountNotFound { address: String },
 
+    /// Account data could not be decoded
+    #[error("Invalid account data for {address}: {message}")]
+    InvalidAccountData { address: String, message: String },
+
     /// Configuration error
     #[error("Configuration error: {message}")]
     InvalidConfig { message: String },
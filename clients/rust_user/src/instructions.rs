@@ -1,12 +1,19 @@
 //! Instruction builders for user operations.
 
 use psy_doge_solana_core::{
-    instructions::doge_bridge::{RequestWithdrawalInstructionData, DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWAL},
+    data_accounts::withdrawal_receipt::WITHDRAWAL_RECEIPT_SEED,
+    instructions::doge_bridge::{
+        RequestScriptWithdrawalFixedData, RequestWithdrawalInstructionData,
+        DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT,
+        DOGE_BRIDGE_INSTRUCTION_REQUEST_SCRIPT_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWAL,
+        DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWALS_BATCH,
+    },
     program_state::PsyWithdrawalRequest,
 };
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
+    system_program,
 };
 
 /// Generate aligned instruction data with the discriminator.
@@ -16,7 +23,19 @@ fn gen_aligned_instruction(instruction_discriminator: u8, data_struct_bytes: &[u
     data
 }
 
+/// Derive the receipt PDA of the requester's withdrawal request made with `receipt_nonce`.
+pub fn get_withdrawal_receipt_pda(program_id: &Pubkey, requester: &Pubkey, receipt_nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[WITHDRAWAL_RECEIPT_SEED, requester.as_ref(), &receipt_nonce.to_le_bytes()],
+        program_id,
+    )
+    .0
+}
+
 /// Build a request_withdrawal instruction.
+///
+/// The user pays the rent of the receipt keyed by `receipt_nonce`, which can be any
+/// value the user has no open receipt under.
 pub fn request_withdrawal(
     program_id: Pubkey,
     user_authority: Pubkey,
@@ -25,12 +44,14 @@ pub fn request_withdrawal(
     recipient_address: [u8; 20],
     amount_sats: u64,
     address_type: u32,
+    receipt_nonce: u64,
 ) -> Instruction {
     let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
     let request = PsyWithdrawalRequest::new(recipient_address, amount_sats, address_type);
 
     let data_struct = RequestWithdrawalInstructionData {
         request,
+        receipt_nonce,
     };
 
     let data = gen_aligned_instruction(
@@ -46,7 +67,136 @@ pub fn request_withdrawal(
             AccountMeta::new(mint, false),
             AccountMeta::new_readonly(user_authority, true),
             AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(get_withdrawal_receipt_pda(&program_id, &user_authority, receipt_nonce), false),
+            AccountMeta::new(user_authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
         ],
         data,
     }
 }
+
+/// Build a request_withdrawals_batch instruction.
+///
+/// The total of the requests is burned once. The receipts are keyed by consecutive
+/// nonces starting at `first_receipt_nonce`, the user pays the rent of every receipt.
+pub fn request_withdrawals_batch(
+    program_id: Pubkey,
+    user_authority: Pubkey,
+    mint: Pubkey,
+    user_token_account: Pubkey,
+    requests: &[PsyWithdrawalRequest],
+    first_receipt_nonce: u64,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let batch: Vec<RequestWithdrawalInstructionData> = requests
+        .iter()
+        .zip(first_receipt_nonce..)
+        .map(|(request, receipt_nonce)| RequestWithdrawalInstructionData {
+            request: *request,
+            receipt_nonce,
+        })
+        .collect();
+    let data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWALS_BATCH,
+        bytemuck::cast_slice(&batch),
+    );
+
+    let mut accounts = vec![
+        AccountMeta::new(bridge_state, false),
+        AccountMeta::new(user_token_account, false),
+        AccountMeta::new(mint, false),
+        AccountMeta::new_readonly(user_authority, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(user_authority, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(batch.iter().map(|entry| {
+        AccountMeta::new(
+            get_withdrawal_receipt_pda(&program_id, &user_authority, entry.receipt_nonce),
+            false,
+        )
+    }));
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Build a request_script_withdrawal instruction.
+///
+/// The script is passed in the instruction data, the queued request commits to its hash160.
+/// The user pays the rent of the receipt.
+pub fn request_script_withdrawal(
+    program_id: Pubkey,
+    user_authority: Pubkey,
+    mint: Pubkey,
+    user_token_account: Pubkey,
+    script_pubkey: &[u8],
+    amount_sats: u64,
+    receipt_nonce: u64,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    let mut data = gen_aligned_instruction(
+        DOGE_BRIDGE_INSTRUCTION_REQUEST_SCRIPT_WITHDRAWAL,
+        bytemuck::bytes_of(&RequestScriptWithdrawalFixedData { amount_sats, receipt_nonce }),
+    );
+    data.extend_from_slice(script_pubkey);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new(user_token_account, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(user_authority, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(get_withdrawal_receipt_pda(&program_id, &user_authority, receipt_nonce), false),
+            AccountMeta::new(user_authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Build a close_withdrawal_receipt instruction, returning the receipt's rent to the requester.
+pub fn close_withdrawal_receipt(program_id: Pubkey, requester: Pubkey, receipt_nonce: u64) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(get_withdrawal_receipt_pda(&program_id, &requester, receipt_nonce), false),
+            AccountMeta::new(requester, true),
+        ],
+        data: gen_aligned_instruction(DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT, &[]),
+    }
+}
+
+/// Build a cancel_withdrawal instruction.
+///
+/// Only requests not yet covered by a withdrawal snapshot can be cancelled. The burned
+/// amount, minus the bridge's cancellation fee, is minted back to `user_token_account`.
+pub fn cancel_withdrawal(
+    program_id: Pubkey,
+    user_authority: Pubkey,
+    mint: Pubkey,
+    user_token_account: Pubkey,
+    receipt_nonce: u64,
+) -> Instruction {
+    let (bridge_state, _) = Pubkey::find_program_address(&[b"bridge_state"], &program_id);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_state, false),
+            AccountMeta::new(get_withdrawal_receipt_pda(&program_id, &user_authority, receipt_nonce), false),
+            AccountMeta::new(user_token_account, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(user_authority, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: gen_aligned_instruction(DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL, &[]),
+    }
+}
@@ -28,12 +28,14 @@
 //! }
 //! ```
 
+mod address;
 mod client;
 mod config;
 mod errors;
 mod instructions;
 mod manual_claim_client;
 
+pub use address::{decode_doge_address, parse_doge_address, withdrawal_request_from_address};
 pub use client::UserClient;
 pub use config::{UserClientConfig, UserClientConfigBuilder};
 pub use errors::{UserClientError, UserClientResult};
@@ -47,3 +49,9 @@ pub use solana_sdk::{
     pubkey::Pubkey,
     signature::{Keypair, Signature},
 };
+pub use psy_doge_solana_core::data_accounts::withdrawal_receipt::WithdrawalReceipt;
+pub use psy_doge_solana_core::program_state::{PsyWithdrawalRequest, WithdrawalAddressType};
+pub use psy_bridge_core::dogecoin::{
+    address::{DogeAddress, DogeAddressType},
+    network::DogeNetwork,
+};
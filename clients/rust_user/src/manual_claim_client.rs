@@ -8,10 +8,12 @@
 use std::str::FromStr;
 use std::sync::Arc;
 
+use psy_doge_solana_core::data_accounts::finalized_block_history::FINALIZED_BLOCK_HISTORY_SEED;
+use psy_doge_solana_core::data_accounts::vk_registry::VK_REGISTRY_SEED;
 use psy_doge_solana_core::instructions::manual_claim::{
     ManualClaimInstruction, MC_MANUAL_CLAIM_TRANSACTION_DESCRIMINATOR,
 };
-use psy_doge_solana_core::program_state::BridgeProgramStateWithDogeMint;
+use psy_doge_solana_core::program_state::decode_bridge_state_account;
 use psy_doge_solana_core::user_manual_deposit_manager::UserManualDepositManagerProgramState;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
@@ -205,7 +207,12 @@ impl ManualClaimClient {
                 address: self.config.bridge_state_pda.to_string(),
             })?;
 
-        let bridge_state: &BridgeProgramStateWithDogeMint = bytemuck::from_bytes(&account.data);
+        let bridge_state = decode_bridge_state_account(&account.data).map_err(|err| {
+            UserClientError::InvalidAccountData {
+                address: self.config.bridge_state_pda.to_string(),
+                message: err.to_string(),
+            }
+        })?;
         let mint = Pubkey::new_from_array(bridge_state.doge_mint);
 
         // Cache the result
@@ -234,6 +241,16 @@ impl ManualClaimClient {
         self.derive_claim_pda(user).0
     }
 
+    /// Get the verifier key registry PDA of the bridge program
+    pub fn get_vk_registry_pda(&self) -> Pubkey {
+        Pubkey::find_program_address(&[VK_REGISTRY_SEED], &self.config.bridge_program_id).0
+    }
+
+    /// Get the finalized block history PDA of the bridge program
+    pub fn get_finalized_block_history_pda(&self) -> Pubkey {
+        Pubkey::find_program_address(&[FINALIZED_BLOCK_HISTORY_SEED], &self.config.bridge_program_id).0
+    }
+
     /// Check if a user's manual claim PDA account exists
     pub async fn claim_account_exists(&self, user: &Pubkey) -> UserClientResult<bool> {
         let (pda, _) = self.derive_claim_pda(user);
@@ -329,6 +346,8 @@ impl ManualClaimClient {
                 AccountMeta::new_readonly(user, true),           // user (signer)
                 AccountMeta::new(payer, true),                   // payer (signer, writable)
                 AccountMeta::new_readonly(system_program::id(), false), // system_program
+                AccountMeta::new_readonly(self.get_vk_registry_pda(), false), // vk_registry (may not exist yet)
+                AccountMeta::new_readonly(self.get_finalized_block_history_pda(), false), // finalized_block_history (may not exist yet)
             ],
             data,
         }
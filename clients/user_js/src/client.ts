@@ -22,9 +22,19 @@ import {
   getAccount,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
-import { UserClientConfig, UserClientConfigBuilder, DEFAULT_BRIDGE_PROGRAM_ID } from "./config";
+import {
+  UserClientConfig,
+  UserClientConfigBuilder,
+  DEFAULT_BRIDGE_PROGRAM_ID,
+  BRIDGE_STATE_NEXT_WITHDRAWAL_INDEX_OFFSET,
+  getBridgeStateOffsets,
+} from "./config";
 import { UserClientError } from "./errors";
-import { requestWithdrawal as requestWithdrawalInstruction } from "./instructions";
+import {
+  requestWithdrawal as requestWithdrawalInstruction,
+  closeWithdrawalReceipt as closeWithdrawalReceiptInstruction,
+  cancelWithdrawal as cancelWithdrawalInstruction,
+} from "./instructions";
 
 /**
  * Client for end-users to interact with the Doge bridge on Solana.
@@ -48,7 +58,8 @@ import { requestWithdrawal as requestWithdrawalInstruction } from "./instruction
  *   userKeypair,
  *   dogeAddressBytes,
  *   100000000n, // 1 DOGE
- *   0 // P2PKH
+ *   0, // P2PKH
+ *   0n // receipt nonce
  * );
  * ```
  */
@@ -111,10 +122,7 @@ export class UserClient {
       throw UserClientError.accountNotFound(this.config.bridgeStatePda.toString());
     }
 
-    // DOGE mint is at a specific offset in the bridge state
-    // Based on the Rust code, it's after header + config + operator + fee_spender
-    // For simplicity, we read from a known offset or from the end
-    const mintOffset = account.data.length - 32;
+    const mintOffset = getBridgeStateOffsets(account.data).dogeMintOffset;
     const mintBytes = account.data.slice(mintOffset, mintOffset + 32);
     const mint = new PublicKey(mintBytes);
 
@@ -253,13 +261,15 @@ export class UserClient {
    * @param recipientAddress - 20-byte Dogecoin address (P2PKH hash160)
    * @param amountSats - Amount to withdraw in satoshis
    * @param addressType - Address type (0 for P2PKH)
+   * @param receiptNonce - Keys the request's receipt, must not be used by another open receipt of the user
    * @returns The transaction signature.
    */
   async requestWithdrawal(
     user: Keypair,
     recipientAddress: Uint8Array,
     amountSats: bigint,
-    addressType: number = 0
+    addressType: number,
+    receiptNonce: bigint
   ): Promise<TransactionSignature> {
     if (recipientAddress.length !== 20) {
       throw UserClientError.invalidInput("Recipient address must be 20 bytes");
@@ -281,12 +291,62 @@ export class UserClient {
       userTokenAccount,
       recipientAddress,
       amountSats,
-      addressType
+      addressType,
+      receiptNonce
     );
 
     return this.sendAndConfirm([ix], user);
   }
 
+  /**
+   * Get the index the next withdrawal request will be assigned.
+   */
+  async getNextWithdrawalIndex(): Promise<bigint> {
+    const account = await this.connection.getAccountInfo(this.config.bridgeStatePda);
+    if (!account) {
+      throw UserClientError.accountNotFound(this.config.bridgeStatePda.toString());
+    }
+    const { coreOffset } = getBridgeStateOffsets(account.data);
+    const view = new DataView(account.data.buffer, account.data.byteOffset);
+    return view.getBigUint64(coreOffset + BRIDGE_STATE_NEXT_WITHDRAWAL_INDEX_OFFSET, true);
+  }
+
+  /**
+   * Cancel a withdrawal request that has not been snapshotted yet.
+   *
+   * The burned amount, minus the bridge's cancellation fee, is minted back
+   * to the user's associated token account.
+   *
+   * @param user - The keypair of the user that requested the withdrawal
+   * @param receiptNonce - The nonce the request was made with
+   * @returns The transaction signature.
+   */
+  async cancelWithdrawal(user: Keypair, receiptNonce: bigint): Promise<TransactionSignature> {
+    const dogeMint = await this.getDogeMint();
+    const userTokenAccount = await getAssociatedTokenAddress(dogeMint, user.publicKey);
+
+    const ix = cancelWithdrawalInstruction(
+      this.config.programId,
+      user.publicKey,
+      dogeMint,
+      userTokenAccount,
+      receiptNonce
+    );
+    return this.sendAndConfirm([ix], user);
+  }
+
+  /**
+   * Close a fulfilled or cancelled withdrawal receipt to reclaim its rent.
+   *
+   * @param user - The keypair of the user that requested the withdrawal
+   * @param receiptNonce - The nonce the request was made with
+   * @returns The transaction signature.
+   */
+  async closeWithdrawalReceipt(user: Keypair, receiptNonce: bigint): Promise<TransactionSignature> {
+    const ix = closeWithdrawalReceiptInstruction(this.config.programId, user.publicKey, receiptNonce);
+    return this.sendAndConfirm([ix], user);
+  }
+
   /**
    * Set the close authority of a token account to null.
    *
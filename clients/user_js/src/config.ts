@@ -10,6 +10,30 @@ export const DEFAULT_BRIDGE_PROGRAM_ID = new PublicKey("DBjo5tqf2uwt4sg9JznSk9SB
 /** Bridge state seed */
 export const BRIDGE_STATE_SEED = "bridge_state";
 
+/** Withdrawal receipt seed */
+export const WITHDRAWAL_RECEIPT_SEED = "withdrawal_receipt";
+
+/** Bridge state layout version header, accounts created before versioning have none */
+export const BRIDGE_STATE_VERSION_MAGIC = "PSYS";
+export const BRIDGE_STATE_VERSION_HEADER_SIZE = 8;
+export const BRIDGE_STATE_RESERVED_SIZE = 512;
+
+/** Offset of the next withdrawal index (requested_withdrawals_tree.next_index) from the start of the core state */
+export const BRIDGE_STATE_NEXT_WITHDRAWAL_INDEX_OFFSET = 4880;
+
+/**
+ * Offsets of the core state and the DOGE mint in a bridge state account of any layout version.
+ */
+export function getBridgeStateOffsets(data: Uint8Array): { coreOffset: number; dogeMintOffset: number } {
+  if (new TextDecoder().decode(data.subarray(0, 4)) !== BRIDGE_STATE_VERSION_MAGIC) {
+    return { coreOffset: 0, dogeMintOffset: data.length - 32 };
+  }
+  return {
+    coreOffset: BRIDGE_STATE_VERSION_HEADER_SIZE,
+    dogeMintOffset: data.length - BRIDGE_STATE_RESERVED_SIZE - 32,
+  };
+}
+
 /**
  * Get the bridge state PDA for a program ID.
  */
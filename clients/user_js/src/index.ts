@@ -31,7 +31,8 @@
  *   userKeypair,
  *   dogeAddressBytes, // 20-byte hash160
  *   100000000n,       // 1 DOGE
- *   0                 // P2PKH address type
+ *   0,                // P2PKH address type
+ *   0n                // receipt nonce, any value without an open receipt
  * );
  * ```
  */
@@ -45,14 +46,22 @@ export {
   UserClientConfigBuilder,
   DEFAULT_BRIDGE_PROGRAM_ID,
   BRIDGE_STATE_SEED,
+  WITHDRAWAL_RECEIPT_SEED,
   getBridgeStatePda,
+  getBridgeStateOffsets,
 } from "./config";
 
 // Errors
 export { UserClientError } from "./errors";
 
 // Instructions
-export { requestWithdrawal, getBridgeStatePda as getBridgeStatePdaFromInstructions } from "./instructions";
+export {
+  requestWithdrawal,
+  closeWithdrawalReceipt,
+  cancelWithdrawal,
+  getWithdrawalReceiptPda,
+  getBridgeStatePda as getBridgeStatePdaFromInstructions,
+} from "./instructions";
 
 // Manual Claim Client
 export {
@@ -63,6 +72,8 @@ export {
   ParsedManualClaim,
   DEFAULT_MANUAL_CLAIM_PROGRAM_ID,
   MANUAL_CLAIM_SEED,
+  VK_REGISTRY_SEED,
+  FINALIZED_BLOCK_HISTORY_SEED,
   MC_MANUAL_CLAIM_TRANSACTION_DISCRIMINATOR,
   MANUAL_CLAIM_INSTRUCTION_SIZE,
   createEmptyManualClaimInstruction,
@@ -2,11 +2,13 @@
  * Instruction builders for user operations.
  */
 
-import { PublicKey, TransactionInstruction } from "@solana/web3.js";
+import { PublicKey, SystemProgram, TransactionInstruction } from "@solana/web3.js";
 import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
-import { BRIDGE_STATE_SEED } from "./config";
+import { BRIDGE_STATE_SEED, WITHDRAWAL_RECEIPT_SEED } from "./config";
 
 const DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWAL = 2;
+const DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT = 23;
+const DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL = 24;
 
 /**
  * Generate aligned instruction data with the discriminator.
@@ -28,6 +30,22 @@ export function getBridgeStatePda(programId: PublicKey): [PublicKey, number] {
   );
 }
 
+/**
+ * Get the receipt PDA of the requester's withdrawal request made with `receiptNonce`.
+ */
+export function getWithdrawalReceiptPda(
+  programId: PublicKey,
+  requester: PublicKey,
+  receiptNonce: bigint
+): [PublicKey, number] {
+  const nonceBytes = new Uint8Array(8);
+  new DataView(nonceBytes.buffer).setBigUint64(0, receiptNonce, true);
+  return PublicKey.findProgramAddressSync(
+    [new TextEncoder().encode(WITHDRAWAL_RECEIPT_SEED), requester.toBuffer(), nonceBytes],
+    programId
+  );
+}
+
 /**
  * Build a request_withdrawal instruction.
  *
@@ -38,6 +56,7 @@ export function getBridgeStatePda(programId: PublicKey): [PublicKey, number] {
  * @param recipientAddress - 20-byte Dogecoin address (P2PKH hash160)
  * @param amountSats - Amount to withdraw in satoshis
  * @param addressType - Address type (0 for P2PKH)
+ * @param receiptNonce - Keys the receipt the user pays rent for, must not be used by another open receipt of the user
  */
 export function requestWithdrawal(
   programId: PublicKey,
@@ -46,7 +65,8 @@ export function requestWithdrawal(
   userTokenAccount: PublicKey,
   recipientAddress: Uint8Array,
   amountSats: bigint,
-  addressType: number
+  addressType: number,
+  receiptNonce: bigint
 ): TransactionInstruction {
   const [bridgeState] = getBridgeStatePda(programId);
 
@@ -55,7 +75,8 @@ export function requestWithdrawal(
   //   - amount_sats: u64 (8 bytes) at offset 0
   //   - address_type: u32 (4 bytes) at offset 8
   //   - recipient_address: [u8; 20] at offset 12
-  const dataSize = 32;
+  // receipt_nonce: u64 (8 bytes) at offset 32
+  const dataSize = 40;
   const instructionData = new Uint8Array(dataSize);
   const view = new DataView(instructionData.buffer);
 
@@ -63,6 +84,7 @@ export function requestWithdrawal(
   view.setBigUint64(0, amountSats, true);
   view.setUint32(8, addressType, true);
   instructionData.set(recipientAddress, 12);
+  view.setBigUint64(32, receiptNonce, true);
 
   const data = genAlignedInstruction(DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWAL, instructionData);
 
@@ -73,6 +95,69 @@ export function requestWithdrawal(
       { pubkey: mint, isSigner: false, isWritable: true },
       { pubkey: userAuthority, isSigner: true, isWritable: false },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: getWithdrawalReceiptPda(programId, userAuthority, receiptNonce)[0], isSigner: false, isWritable: true },
+      { pubkey: userAuthority, isSigner: true, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    programId,
+    data: Buffer.from(data),
+  });
+}
+
+/**
+ * Build a close_withdrawal_receipt instruction.
+ *
+ * @param programId - The bridge program ID
+ * @param requester - The user that requested the withdrawal (signer, receives the rent)
+ * @param receiptNonce - The nonce the request was made with
+ */
+export function closeWithdrawalReceipt(
+  programId: PublicKey,
+  requester: PublicKey,
+  receiptNonce: bigint
+): TransactionInstruction {
+  const data = genAlignedInstruction(DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT, new Uint8Array(0));
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: getWithdrawalReceiptPda(programId, requester, receiptNonce)[0], isSigner: false, isWritable: true },
+      { pubkey: requester, isSigner: true, isWritable: true },
+    ],
+    programId,
+    data: Buffer.from(data),
+  });
+}
+
+/**
+ * Build a cancel_withdrawal instruction.
+ *
+ * Only requests not yet covered by a withdrawal snapshot can be cancelled. The burned
+ * amount, minus the bridge's cancellation fee, is minted back to the token account.
+ *
+ * @param programId - The bridge program ID
+ * @param userAuthority - The user that requested the withdrawal (signer)
+ * @param mint - The DOGE token mint
+ * @param userTokenAccount - The token account receiving the refund
+ * @param receiptNonce - The nonce the request was made with
+ */
+export function cancelWithdrawal(
+  programId: PublicKey,
+  userAuthority: PublicKey,
+  mint: PublicKey,
+  userTokenAccount: PublicKey,
+  receiptNonce: bigint
+): TransactionInstruction {
+  const [bridgeState] = getBridgeStatePda(programId);
+  const data = genAlignedInstruction(DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL, new Uint8Array(0));
+
+  return new TransactionInstruction({
+    keys: [
+      { pubkey: bridgeState, isSigner: false, isWritable: true },
+      { pubkey: getWithdrawalReceiptPda(programId, userAuthority, receiptNonce)[0], isSigner: false, isWritable: true },
+      { pubkey: userTokenAccount, isSigner: false, isWritable: true },
+      { pubkey: mint, isSigner: false, isWritable: true },
+      { pubkey: userAuthority, isSigner: true, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
     ],
     programId,
     data: Buffer.from(data),
@@ -24,7 +24,7 @@ import {
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { UserClientError } from "./errors";
-import { DEFAULT_BRIDGE_PROGRAM_ID, BRIDGE_STATE_SEED } from "./config";
+import { DEFAULT_BRIDGE_PROGRAM_ID, BRIDGE_STATE_SEED, getBridgeStateOffsets } from "./config";
 
 /** Default manual-claim program ID */
 export const DEFAULT_MANUAL_CLAIM_PROGRAM_ID = new PublicKey("MCdYbqiK3uj36tohbMjsh3Ssg8iRSJmSHToNxW8TWWE");
@@ -32,6 +32,12 @@ export const DEFAULT_MANUAL_CLAIM_PROGRAM_ID = new PublicKey("MCdYbqiK3uj36tohbM
 /** Manual claim PDA seed */
 export const MANUAL_CLAIM_SEED = "manual-claim";
 
+/** Verifier key registry PDA seed (owned by the bridge program) */
+export const VK_REGISTRY_SEED = "vk_registry";
+
+/** Finalized block history PDA seed (owned by the bridge program) */
+export const FINALIZED_BLOCK_HISTORY_SEED = "finalized_block_history";
+
 /** Manual claim instruction discriminator */
 export const MC_MANUAL_CLAIM_TRANSACTION_DISCRIMINATOR = 0;
 
@@ -228,8 +234,7 @@ export class ManualClaimClient {
       throw UserClientError.accountNotFound(this.config.bridgeStatePda.toString());
     }
 
-    // DOGE mint is at the end of the bridge state
-    const mintOffset = account.data.length - 32;
+    const mintOffset = getBridgeStateOffsets(account.data).dogeMintOffset;
     const mintBytes = account.data.slice(mintOffset, mintOffset + 32);
     const mint = new PublicKey(mintBytes);
 
@@ -260,6 +265,26 @@ export class ManualClaimClient {
     return this.deriveClaimPda(user)[0];
   }
 
+  /**
+   * Get the verifier key registry PDA of the bridge program
+   */
+  getVkRegistryPda(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [new TextEncoder().encode(VK_REGISTRY_SEED)],
+      this.config.bridgeProgramId
+    )[0];
+  }
+
+  /**
+   * Get the finalized block history PDA of the bridge program
+   */
+  getFinalizedBlockHistoryPda(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [new TextEncoder().encode(FINALIZED_BLOCK_HISTORY_SEED)],
+      this.config.bridgeProgramId
+    )[0];
+  }
+
   /**
    * Check if a user's manual claim PDA account exists
    */
@@ -344,6 +369,8 @@ export class ManualClaimClient {
         { pubkey: user, isSigner: true, isWritable: false },                        // user (signer)
         { pubkey: payer, isSigner: true, isWritable: true },                        // payer (signer, writable)
         { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },    // system_program
+        { pubkey: this.getVkRegistryPda(), isSigner: false, isWritable: false },    // vk_registry (may not exist yet)
+        { pubkey: this.getFinalizedBlockHistoryPda(), isSigner: false, isWritable: false }, // finalized_block_history (may not exist yet)
       ],
       programId: this.config.manualClaimProgramId,
       data: Buffer.from(data),
@@ -15,7 +15,7 @@ compile_error!("You must enable exactly one hashing backend: 'sp1', 'sha2', or '
 #[cfg(all(feature = "sha2", not(feature = "solprogram")))]
 use sha2::{Digest, Sha256};
 #[cfg(feature = "sp1")]
-use sha2_v0_10_9::Sha256;
+use sha2_v0_10_9::{Digest, Sha256};
 
 use crate::common_types::QHash256;
 
@@ -1,5 +1,29 @@
-//use crate::{common_types::{QHash160, QHash256}, crypto::hash::sha256_impl::hash_impl_sha256_bytes};
-/*
+use crate::{
+    common_types::{QHash160, QHash256},
+    crypto::hash::sha256_impl::hash_impl_sha256_bytes,
+};
+
+pub const DOGE_NETWORK_TYPE_MAINNET: u32 = 0;
+pub const DOGE_NETWORK_TYPE_TESTNET: u32 = 1;
+pub const DOGE_NETWORK_TYPE_REGTEST: u32 = 2;
+
+pub fn doge_p2pkh_address_version(network_type: u32) -> Option<u8> {
+    match network_type {
+        DOGE_NETWORK_TYPE_MAINNET => Some(0x1e),
+        DOGE_NETWORK_TYPE_TESTNET => Some(0x71),
+        DOGE_NETWORK_TYPE_REGTEST => Some(0x6f),
+        _ => None,
+    }
+}
+
+pub fn doge_p2sh_address_version(network_type: u32) -> Option<u8> {
+    match network_type {
+        DOGE_NETWORK_TYPE_MAINNET => Some(0x16),
+        DOGE_NETWORK_TYPE_TESTNET | DOGE_NETWORK_TYPE_REGTEST => Some(0xc4),
+        _ => None,
+    }
+}
+
 #[cfg_attr(feature = "serialize_serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serialize_borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
 #[cfg_attr(feature = "serialize_speedy", derive(speedy::Readable, speedy::Writable))]
@@ -12,8 +36,9 @@ pub struct BridgeCustodianConfig {
 }
 
 impl BridgeCustodianConfig {
+    #[cfg(feature = "serialize_bytemuck")]
     pub fn get_wallet_config_hash(&self) -> QHash256 {
-        hash_impl_sha256_bytes(&bytemuck::bytes_of(self))
+        hash_impl_sha256_bytes(bytemuck::bytes_of(self))
     }
     pub fn new_basic(wallet_address_hash: QHash160, network_type: u32) -> Self {
         Self {
@@ -23,9 +48,6 @@ impl BridgeCustodianConfig {
     }
 }
 
-
-
-
 #[cfg_attr(feature = "serialize_serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serialize_borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
 #[cfg_attr(feature = "serialize_speedy", derive(speedy::Readable, speedy::Writable))]
@@ -37,10 +59,13 @@ pub struct Bridge7MultisigCustodianWalletConfig {
     pub signer_public_keys_y_parity: u32,
     pub network_type: u32,
 }
+const _ASSERT_SIZE_BRIDGE_7_MULTISIG_CUSTODIAN_WALLET_CONFIG: () =
+    assert!(std::mem::size_of::<Bridge7MultisigCustodianWalletConfig>() == 232);
 
 impl Bridge7MultisigCustodianWalletConfig {
+    #[cfg(feature = "serialize_bytemuck")]
     pub fn get_wallet_config_hash(&self) -> QHash256 {
-        hash_impl_sha256_bytes(&bytemuck::bytes_of(self))
+        hash_impl_sha256_bytes(bytemuck::bytes_of(self))
     }
     pub fn new_basic(signer_public_keys: [[u8; 32]; 7], signer_public_keys_y_parity: u32, network_type: u32) -> Self {
         Self {
@@ -79,4 +104,22 @@ impl Bridge7MultisigCustodianWalletConfig {
     }
 }
 
-*/
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_public_keys_round_trip() {
+        let mut compressed_keys = [[0u8; 33]; 7];
+        for (i, key) in compressed_keys.iter_mut().enumerate() {
+            key[0] = if i % 2 == 0 { 0x02 } else { 0x03 };
+            key[1..].fill(i as u8 + 1);
+        }
+        let config = Bridge7MultisigCustodianWalletConfig::from_compressed_public_keys(
+            std::array::from_fn(|i| &compressed_keys[i]),
+            DOGE_NETWORK_TYPE_MAINNET,
+        );
+        assert_eq!(config.signer_public_keys_y_parity, 0b0101010);
+        assert_eq!(config.to_compressed_public_keys(), compressed_keys);
+    }
+}
@@ -0,0 +1,207 @@
+use core::{fmt, str::FromStr};
+
+use crate::{
+    common_types::QHash160,
+    crypto::{hash::sha256::btc_hash256_bytes, secp256k1::signature::CompressedPublicKey},
+    dogecoin::{
+        network::DogeNetwork,
+        tx::{get_p2pkh_script_pubkey, get_p2sh_script_pubkey},
+    },
+    error::{DogeBridgeError, QDogeResult},
+};
+
+// version byte, hash160 and a 4 byte checksum
+const DOGE_ADDRESS_DECODED_SIZE: usize = 25;
+
+#[derive(PartialEq, Clone, Copy, Debug, Eq, Hash)]
+pub enum DogeAddressType {
+    P2PKH,
+    P2SH,
+}
+
+impl DogeAddressType {
+    pub fn address_version(&self, network: DogeNetwork) -> u8 {
+        match self {
+            DogeAddressType::P2PKH => network.p2pkh_address_version(),
+            DogeAddressType::P2SH => network.p2sh_address_version(),
+        }
+    }
+}
+
+// a base58check p2pkh or p2sh address
+#[derive(PartialEq, Clone, Copy, Debug, Eq, Hash)]
+pub struct DogeAddress {
+    pub network: DogeNetwork,
+    pub address_type: DogeAddressType,
+    pub hash: QHash160,
+}
+
+impl DogeAddress {
+    pub fn new(network: DogeNetwork, address_type: DogeAddressType, hash: QHash160) -> Self {
+        Self {
+            network,
+            address_type,
+            hash,
+        }
+    }
+
+    pub fn version(&self) -> u8 {
+        self.address_type.address_version(self.network)
+    }
+
+    // testnet and regtest p2sh addresses share a version byte, those are parsed as testnet addresses.
+    // use parse_for_network when the network is known.
+    pub fn parse(address: &str) -> QDogeResult<Self> {
+        let decoded = bs58::decode(address)
+            .into_vec()
+            .map_err(|_| DogeBridgeError::InvalidDogeAddressBase58)?;
+        if decoded.len() != DOGE_ADDRESS_DECODED_SIZE {
+            return Err(DogeBridgeError::InvalidDogeAddressLength);
+        }
+        let (payload, checksum) = decoded.split_at(DOGE_ADDRESS_DECODED_SIZE - 4);
+        if btc_hash256_bytes(payload)[..4] != *checksum {
+            return Err(DogeBridgeError::InvalidDogeAddressChecksum);
+        }
+        let (network, address_type) = DogeNetwork::ALL
+            .into_iter()
+            .find_map(|network| {
+                [DogeAddressType::P2PKH, DogeAddressType::P2SH]
+                    .into_iter()
+                    .find(|address_type| address_type.address_version(network) == payload[0])
+                    .map(|address_type| (network, address_type))
+            })
+            .ok_or(DogeBridgeError::UnknownDogeAddressVersion)?;
+        let mut hash = [0u8; 20];
+        hash.copy_from_slice(&payload[1..]);
+        Ok(Self::new(network, address_type, hash))
+    }
+
+    // parses an address and fails with DogeAddressNetworkMismatch unless its version byte belongs to network
+    pub fn parse_for_network(address: &str, network: DogeNetwork) -> QDogeResult<Self> {
+        let parsed = Self::parse(address)?;
+        if !parsed.is_valid_for_network(network) {
+            return Err(DogeBridgeError::DogeAddressNetworkMismatch);
+        }
+        Ok(Self::new(network, parsed.address_type, parsed.hash))
+    }
+
+    pub fn is_valid_for_network(&self, network: DogeNetwork) -> bool {
+        self.address_type.address_version(network) == self.version()
+    }
+
+    pub fn to_script_pubkey(&self) -> Vec<u8> {
+        match self.address_type {
+            DogeAddressType::P2PKH => get_p2pkh_script_pubkey(&self.hash),
+            DogeAddressType::P2SH => get_p2sh_script_pubkey(&self.hash),
+        }
+    }
+}
+
+// to_p2pkh_address stays the bare hash160, it is also built for the programs where there is no DogeAddress
+impl CompressedPublicKey {
+    pub fn to_p2pkh_doge_address(&self, network: DogeNetwork) -> DogeAddress {
+        DogeAddress::new(network, DogeAddressType::P2PKH, self.to_p2pkh_address())
+    }
+}
+
+impl fmt::Display for DogeAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut data = [0u8; DOGE_ADDRESS_DECODED_SIZE];
+        data[0] = self.version();
+        data[1..21].copy_from_slice(&self.hash);
+        let checksum = btc_hash256_bytes(&data[..21]);
+        data[21..].copy_from_slice(&checksum[..4]);
+        f.write_str(&bs58::encode(data).into_string())
+    }
+}
+
+impl FromStr for DogeAddress {
+    type Err = DogeBridgeError;
+
+    fn from_str(address: &str) -> QDogeResult<Self> {
+        Self::parse(address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let hash: QHash160 = core::array::from_fn(|i| i as u8 + 1);
+        for (address, network, address_type) in [
+            ("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H1", DogeNetwork::Mainnet, DogeAddressType::P2PKH),
+            ("9rXbkMyi1S6thykRoXAZcY8fwUKYsy6cXE", DogeNetwork::Mainnet, DogeAddressType::P2SH),
+            ("nUHVMF6vcrGd8RSK2hUZjwuGDNmPeNoBRb", DogeNetwork::Testnet, DogeAddressType::P2PKH),
+            ("2MsLZ5FqqYpjM1Q1W4X81zMVZTF9gdbhVwd", DogeNetwork::Testnet, DogeAddressType::P2SH),
+            ("mfcHP2WMCVLsVZA8yrovmhMgxNFW9r98xw", DogeNetwork::Regtest, DogeAddressType::P2PKH),
+        ] {
+            let parsed = DogeAddress::parse(address).unwrap();
+            assert_eq!(parsed, DogeAddress::new(network, address_type, hash));
+            assert_eq!(parsed.to_string(), address);
+            assert_eq!(address.parse::<DogeAddress>(), Ok(parsed));
+        }
+
+        let zero_hash = DogeAddress::new(DogeNetwork::Mainnet, DogeAddressType::P2PKH, [0u8; 20]);
+        assert_eq!(zero_hash.to_string(), "D596YFweJQuHY1BbjazZYmAbt8jJPbKehC");
+        assert_eq!(
+            zero_hash.to_script_pubkey(),
+            [&[0x76, 0xa9, 0x14][..], &[0u8; 20], &[0x88, 0xac]].concat()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // bitcoin's version byte, the leading zero byte decodes to a '1'
+        assert_eq!(
+            DogeAddress::parse("1111111111111111111114oLvT2"),
+            Err(DogeBridgeError::UnknownDogeAddressVersion)
+        );
+        assert_eq!(
+            DogeAddress::parse("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H2"),
+            Err(DogeBridgeError::InvalidDogeAddressChecksum)
+        );
+        assert_eq!(
+            DogeAddress::parse("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H0"),
+            Err(DogeBridgeError::InvalidDogeAddressBase58)
+        );
+        assert_eq!(
+            DogeAddress::parse("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H"),
+            Err(DogeBridgeError::InvalidDogeAddressLength)
+        );
+        assert_eq!(DogeAddress::parse(""), Err(DogeBridgeError::InvalidDogeAddressLength));
+    }
+
+    #[test]
+    fn test_parse_for_network() {
+        let testnet_p2sh = "2MsLZ5FqqYpjM1Q1W4X81zMVZTF9gdbhVwd";
+        let regtest = DogeAddress::parse_for_network(testnet_p2sh, DogeNetwork::Regtest).unwrap();
+        assert_eq!(regtest.network, DogeNetwork::Regtest);
+        assert_eq!(regtest.to_string(), testnet_p2sh);
+        assert_eq!(
+            DogeAddress::parse_for_network(testnet_p2sh, DogeNetwork::Mainnet),
+            Err(DogeBridgeError::DogeAddressNetworkMismatch)
+        );
+        assert_eq!(
+            DogeAddress::parse_for_network("nUHVMF6vcrGd8RSK2hUZjwuGDNmPeNoBRb", DogeNetwork::Regtest),
+            Err(DogeBridgeError::DogeAddressNetworkMismatch)
+        );
+        assert!(DogeAddress::parse_for_network("D5ERdEN1gsouFSs7zsq7VYJxyWP6dP28H1", DogeNetwork::Mainnet).is_ok());
+    }
+
+    #[test]
+    fn test_public_key_to_p2pkh_address() {
+        // the secp256k1 generator point
+        let public_key = CompressedPublicKey(hex_literal::hex!(
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        ));
+        let address = public_key.to_p2pkh_doge_address(DogeNetwork::Mainnet);
+        assert_eq!(address.hash, hex_literal::hex!("751e76e8199196d454941c45d1b3a323f1433bd6"));
+        assert_eq!(address.to_string(), "DFpN6QqFfUm3gKNaxN6tNcab1FArL9cZLE");
+        assert_eq!(
+            public_key.to_p2pkh_doge_address(DogeNetwork::Testnet).to_string(),
+            "nesRpRaAbTDmZHwmzBkLd2AtF7Z9L9z5S2"
+        );
+    }
+}
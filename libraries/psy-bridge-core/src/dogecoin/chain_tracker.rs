@@ -0,0 +1,471 @@
+use std::collections::HashMap;
+
+use crate::{
+    common_types::QHash256,
+    crypto::hash::{
+        merkle::fixed_append_tree::{get_changed_next_siblings_for_revert, FixedMerkleAppendTree},
+        sha256_impl::hash_impl_sha256_compute_merkle_root,
+    },
+    dogecoin::{
+        difficulty::{add_block_proof, check_next_work_required, DogeU256},
+        header::{DogeAuxPowBlockHeader, DogeBlockHeader, DogePowParams},
+    },
+    error::{DogeBridgeError, QDogeResult},
+    header::PsyBridgeTipStateCommitment,
+    txo_constants::TXO_TREE_INDEX_BITS_BLOCK_NUM_LENGTH,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrackedDogeBlock {
+    pub height: u32,
+    pub hash: QHash256,
+    pub header: DogeBlockHeader,
+    // cumulative work of the chain ending at this block
+    pub chain_work: DogeU256,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeaderChainUpdate {
+    // the block extended the best chain
+    Extended,
+    // the block made a fork heavier than the best chain, the new best chain branches off at fork_height
+    Reorg { fork_height: u32 },
+    // the block was stored on a fork that has no more work than the best chain
+    SideChain,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct BestChainEntry {
+    hash: QHash256,
+    // the block tree right after this block was appended, kept so rollbacks can be checked against it
+    block_tree: FixedMerkleAppendTree,
+}
+
+// the root of the bridge's block merkle tree, whose leaf at each height is that block's hash
+pub fn get_block_merkle_tree_root(block_tree: &FixedMerkleAppendTree) -> QHash256 {
+    let proof = block_tree.get_partial_merkle_proof_fixed_for_current_index();
+    hash_impl_sha256_compute_merkle_root(
+        &proof.value,
+        proof.index,
+        &proof.siblings[..TXO_TREE_INDEX_BITS_BLOCK_NUM_LENGTH],
+    )
+}
+
+// follows the dogecoin header chain above the bridge's finalized block. Every fork that branches off at or above the
+// finalized block is kept, the heaviest one is the best chain and the block merkle tree always covers it. Once the best
+// chain is more than max_blocks above the finalized block, the finalized block moves up and forks below it are dropped.
+#[derive(Clone, Debug)]
+pub struct HeaderChainTracker {
+    params: DogePowParams,
+    max_blocks: u32,
+    finalized: TrackedDogeBlock,
+    // best_chain[0] is the finalized block
+    best_chain: Vec<BestChainEntry>,
+    // every tracked block above the finalized block, on any fork
+    blocks: HashMap<QHash256, TrackedDogeBlock>,
+    block_tree: FixedMerkleAppendTree,
+}
+
+impl HeaderChainTracker {
+    // finalized_block_tree must hold the hashes of every block up to and including the finalized block
+    pub fn new(
+        params: DogePowParams,
+        max_blocks: u32,
+        finalized_height: u32,
+        finalized_header: DogeBlockHeader,
+        finalized_chain_work: DogeU256,
+        finalized_block_tree: FixedMerkleAppendTree,
+    ) -> QDogeResult<Self> {
+        if finalized_block_tree.get_next_index() != finalized_height as u64 + 1 {
+            return Err(DogeBridgeError::BlockTipSyncMismatch);
+        }
+        let finalized = TrackedDogeBlock {
+            height: finalized_height,
+            hash: finalized_header.get_hash(),
+            header: finalized_header,
+            chain_work: finalized_chain_work,
+        };
+        Ok(Self {
+            params,
+            max_blocks,
+            finalized,
+            best_chain: vec![BestChainEntry {
+                hash: finalized.hash,
+                block_tree: finalized_block_tree,
+            }],
+            blocks: HashMap::new(),
+            block_tree: finalized_block_tree,
+        })
+    }
+
+    pub fn finalized(&self) -> &TrackedDogeBlock {
+        &self.finalized
+    }
+
+    pub fn best_tip(&self) -> &TrackedDogeBlock {
+        // the last best chain entry is always tracked
+        self.get_block(&self.best_chain[self.best_chain.len() - 1].hash).unwrap()
+    }
+
+    pub fn get_block(&self, hash: &QHash256) -> Option<&TrackedDogeBlock> {
+        if *hash == self.finalized.hash {
+            Some(&self.finalized)
+        } else {
+            self.blocks.get(hash)
+        }
+    }
+
+    fn get_best_chain_entry(&self, height: u32) -> QDogeResult<&BestChainEntry> {
+        if height < self.finalized.height {
+            return Err(DogeBridgeError::BlockNotInCache);
+        }
+        self.best_chain
+            .get((height - self.finalized.height) as usize)
+            .ok_or(DogeBridgeError::BlockNotInCache)
+    }
+
+    // the best chain's block at height, which can't be below the finalized block
+    pub fn get_block_at_height(&self, height: u32) -> QDogeResult<&TrackedDogeBlock> {
+        let hash = self.get_best_chain_entry(height)?.hash;
+        Ok(self.get_block(&hash).unwrap())
+    }
+
+    pub fn is_on_best_chain(&self, block: &TrackedDogeBlock) -> bool {
+        self.get_best_chain_entry(block.height)
+            .map(|entry| entry.hash == block.hash)
+            .unwrap_or(false)
+    }
+
+    pub fn get_block_tree(&self) -> &FixedMerkleAppendTree {
+        &self.block_tree
+    }
+
+    pub fn get_block_merkle_tree_root(&self) -> QHash256 {
+        get_block_merkle_tree_root(&self.block_tree)
+    }
+
+    // the block merkle tree root as of the best chain's block at height
+    pub fn get_block_merkle_tree_root_at_height(&self, height: u32) -> QDogeResult<QHash256> {
+        Ok(get_block_merkle_tree_root(&self.get_best_chain_entry(height)?.block_tree))
+    }
+
+    pub fn get_tip_state_commitment(&self) -> PsyBridgeTipStateCommitment {
+        let tip = self.best_tip();
+        PsyBridgeTipStateCommitment {
+            block_hash: tip.hash,
+            block_merkle_tree_root: self.get_block_merkle_tree_root(),
+            block_time: tip.header.timestamp,
+            block_height: tip.height,
+        }
+    }
+
+    // checks the header's proof of work and difficulty, stores it and switches the best chain if its fork is now the heaviest
+    pub fn append(&mut self, header: &DogeAuxPowBlockHeader) -> QDogeResult<HeaderChainUpdate> {
+        let hash = header.get_hash();
+        if self.get_block(&hash).is_some() {
+            return Err(DogeBridgeError::InsertBlockAlreadyInCache);
+        }
+        let parent = *self
+            .get_block(&header.header.prev_block_hash)
+            .ok_or(DogeBridgeError::InvalidParentBlockHash)?;
+
+        header.check_proof_of_work(&self.params)?;
+        // the finalized block's parent isn't tracked, so the difficulty of its children is taken as is
+        if parent.hash != self.finalized.hash {
+            let grandparent = self.get_block(&parent.header.prev_block_hash).unwrap();
+            check_next_work_required(
+                parent.height,
+                parent.header.bits,
+                parent.header.timestamp,
+                grandparent.header.timestamp,
+                header.header.timestamp,
+                header.header.bits,
+                &self.params,
+            )?;
+        }
+
+        let block = TrackedDogeBlock {
+            height: parent.height + 1,
+            hash,
+            header: header.header,
+            chain_work: add_block_proof(&parent.chain_work, header.header.bits),
+        };
+        self.blocks.insert(hash, block);
+
+        let tip = *self.best_tip();
+        let update = if parent.hash == tip.hash {
+            self.push_best_chain(hash);
+            HeaderChainUpdate::Extended
+        } else if block.chain_work > tip.chain_work {
+            // walk back to where the fork meets the best chain
+            let mut branch = Vec::new();
+            let mut cursor = block;
+            while !self.is_on_best_chain(&cursor) {
+                branch.push(cursor.hash);
+                cursor = *self.get_block(&cursor.header.prev_block_hash).unwrap();
+            }
+            self.truncate_best_chain(cursor.height)?;
+            for hash in branch.into_iter().rev() {
+                self.push_best_chain(hash);
+            }
+            HeaderChainUpdate::Reorg {
+                fork_height: cursor.height,
+            }
+        } else {
+            HeaderChainUpdate::SideChain
+        };
+
+        self.advance_finalized();
+        Ok(update)
+    }
+
+    // drops every block above height, the best chain's block at height becomes the tip even if a fork has more work
+    pub fn rollback_to(&mut self, height: u32) -> QDogeResult<()> {
+        if height < self.finalized.height {
+            return Err(DogeBridgeError::AttemptedToModifiyFinalizedBlock);
+        }
+        if height > self.best_tip().height {
+            return Err(DogeBridgeError::BlockNotInCache);
+        }
+        self.truncate_best_chain(height)?;
+        self.blocks.retain(|_, block| block.height <= height);
+        Ok(())
+    }
+
+    fn push_best_chain(&mut self, hash: QHash256) {
+        self.block_tree.append(hash);
+        self.best_chain.push(BestChainEntry {
+            hash,
+            block_tree: self.block_tree,
+        });
+    }
+
+    fn truncate_best_chain(&mut self, height: u32) -> QDogeResult<()> {
+        let target = self.get_best_chain_entry(height)?.block_tree;
+        if target.get_next_index() < self.block_tree.get_next_index() {
+            let changed_left_next_siblings = get_changed_next_siblings_for_revert(
+                self.block_tree.get_next_index(),
+                target.get_next_index(),
+                &target.next_siblings,
+            )?;
+            self.block_tree
+                .revert_to_next_index(target.get_next_index(), &changed_left_next_siblings)?;
+        }
+        if self.block_tree.get_next_index() != target.get_next_index() {
+            return Err(DogeBridgeError::RollbackBlockTreeIndexMismatch);
+        }
+        if self.block_tree.get_root() != target.get_root() {
+            return Err(DogeBridgeError::RollbackBlockTreeRootMismatch);
+        }
+        self.best_chain.truncate((height - self.finalized.height) as usize + 1);
+        Ok(())
+    }
+
+    // moves the finalized block up to max_blocks below the best tip and forgets every fork that doesn't descend from it
+    fn advance_finalized(&mut self) {
+        let blocks_above_finalized = self.best_chain.len() as u32 - 1;
+        if blocks_above_finalized <= self.max_blocks {
+            return;
+        }
+        let finalized_index = (blocks_above_finalized - self.max_blocks) as usize;
+        self.finalized = self.blocks.remove(&self.best_chain[finalized_index].hash).unwrap();
+        self.best_chain.drain(..finalized_index);
+
+        // a block is kept if its parent was kept, so blocks are visited parents first
+        let mut blocks: Vec<TrackedDogeBlock> = self.blocks.drain().map(|(_, block)| block).collect();
+        blocks.sort_by_key(|block| block.height);
+        for block in blocks {
+            if block.header.prev_block_hash == self.finalized.hash || self.blocks.contains_key(&block.header.prev_block_hash) {
+                self.blocks.insert(block.hash, block);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        custodian_config::DOGE_NETWORK_TYPE_REGTEST,
+        dogecoin::{difficulty::get_block_proof, header::DOGE_AUXPOW_CHAIN_ID},
+    };
+
+    // every header meets this target and adds one unit of work
+    const EASY_BITS: u32 = 0x2100_ffff;
+
+    fn test_params() -> DogePowParams {
+        DogePowParams {
+            pow_limit: [0xff; 32],
+            ..DogePowParams::for_network(DOGE_NETWORK_TYPE_REGTEST).unwrap()
+        }
+    }
+
+    fn finalized_header() -> DogeBlockHeader {
+        DogeBlockHeader {
+            version: (DOGE_AUXPOW_CHAIN_ID << 16) | 4,
+            prev_block_hash: [0u8; 32],
+            merkle_root: [0u8; 32],
+            timestamp: 1_700_000_000,
+            bits: EASY_BITS,
+            nonce: 0,
+        }
+    }
+
+    // fork tells siblings apart
+    fn child(parent: &DogeBlockHeader, fork: u8) -> DogeAuxPowBlockHeader {
+        DogeAuxPowBlockHeader {
+            header: DogeBlockHeader {
+                prev_block_hash: parent.get_hash(),
+                merkle_root: [fork; 32],
+                timestamp: parent.timestamp + 60,
+                ..*parent
+            },
+            aux_pow: None,
+        }
+    }
+
+    fn chain(parent: &DogeBlockHeader, fork: u8, length: usize) -> Vec<DogeAuxPowBlockHeader> {
+        let mut headers: Vec<DogeAuxPowBlockHeader> = Vec::new();
+        for _ in 0..length {
+            let next = child(headers.last().map(|h| &h.header).unwrap_or(parent), fork);
+            headers.push(next);
+        }
+        headers
+    }
+
+    fn new_tracker(max_blocks: u32) -> HeaderChainTracker {
+        let mut block_tree = FixedMerkleAppendTree::new_empty();
+        block_tree.append(finalized_header().get_hash());
+        HeaderChainTracker::new(
+            test_params(),
+            max_blocks,
+            0,
+            finalized_header(),
+            get_block_proof(EASY_BITS),
+            block_tree,
+        )
+        .unwrap()
+    }
+
+    // the root of a block tree rebuilt from scratch with the tracker's best chain
+    fn expected_block_tree_root(tracker: &HeaderChainTracker, base: &[QHash256]) -> QHash256 {
+        let mut block_tree = FixedMerkleAppendTree::new_empty();
+        for hash in base {
+            block_tree.append(*hash);
+        }
+        for height in tracker.finalized().height..=tracker.best_tip().height {
+            block_tree.append(tracker.get_block_at_height(height).unwrap().hash);
+        }
+        get_block_merkle_tree_root(&block_tree)
+    }
+
+    #[test]
+    fn test_append_and_reorg_to_heavier_fork() {
+        let mut tracker = new_tracker(10);
+        let a = chain(&finalized_header(), 1, 4);
+        let b = chain(&finalized_header(), 2, 3);
+
+        assert_eq!(tracker.append(&a[0]), Ok(HeaderChainUpdate::Extended));
+        assert_eq!(tracker.append(&a[1]), Ok(HeaderChainUpdate::Extended));
+        assert_eq!(tracker.get_block_merkle_tree_root(), expected_block_tree_root(&tracker, &[]));
+        let root_at_a1 = tracker.get_block_merkle_tree_root_at_height(1).unwrap();
+
+        // equal work keeps the first seen chain
+        assert_eq!(tracker.append(&b[0]), Ok(HeaderChainUpdate::SideChain));
+        assert_eq!(tracker.append(&b[1]), Ok(HeaderChainUpdate::SideChain));
+        assert_eq!(tracker.best_tip().hash, a[1].get_hash());
+
+        assert_eq!(tracker.append(&b[2]), Ok(HeaderChainUpdate::Reorg { fork_height: 0 }));
+        assert_eq!(tracker.best_tip().hash, b[2].get_hash());
+        assert_eq!(tracker.best_tip().height, 3);
+        assert_eq!(tracker.best_tip().chain_work, DogeU256::from_u64(4));
+        assert_eq!(tracker.get_block_merkle_tree_root(), expected_block_tree_root(&tracker, &[]));
+        assert_ne!(tracker.get_block_merkle_tree_root_at_height(1).unwrap(), root_at_a1);
+
+        assert_eq!(tracker.append(&a[2]), Ok(HeaderChainUpdate::SideChain));
+        assert_eq!(tracker.append(&a[3]), Ok(HeaderChainUpdate::Reorg { fork_height: 0 }));
+        assert_eq!(tracker.get_block_at_height(1).unwrap().hash, a[0].get_hash());
+        assert_eq!(tracker.get_block_merkle_tree_root_at_height(1), Ok(root_at_a1));
+        assert_eq!(tracker.get_block_merkle_tree_root(), expected_block_tree_root(&tracker, &[]));
+
+        let tip_state = tracker.get_tip_state_commitment();
+        assert_eq!(tip_state.block_hash, a[3].get_hash());
+        assert_eq!(tip_state.block_height, 4);
+        assert_eq!(tip_state.block_time, a[3].header.timestamp);
+    }
+
+    #[test]
+    fn test_rollback_to() {
+        let mut tracker = new_tracker(10);
+        let a = chain(&finalized_header(), 1, 4);
+        for header in a.iter() {
+            tracker.append(header).unwrap();
+        }
+        let root_at_a2 = tracker.get_block_merkle_tree_root_at_height(2).unwrap();
+
+        assert_eq!(tracker.rollback_to(2), Ok(()));
+        assert_eq!(tracker.best_tip().hash, a[1].get_hash());
+        assert_eq!(tracker.get_block_merkle_tree_root(), root_at_a2);
+        assert_eq!(tracker.get_block_merkle_tree_root(), expected_block_tree_root(&tracker, &[]));
+        assert!(tracker.get_block(&a[2].get_hash()).is_none());
+
+        // the dropped blocks can be appended again
+        assert_eq!(tracker.append(&a[2]), Ok(HeaderChainUpdate::Extended));
+
+        assert_eq!(tracker.rollback_to(4), Err(DogeBridgeError::BlockNotInCache));
+        assert_eq!(tracker.rollback_to(0), Ok(()));
+        assert_eq!(tracker.best_tip().hash, finalized_header().get_hash());
+        assert_eq!(tracker.get_block_tree().get_next_index(), 1);
+    }
+
+    #[test]
+    fn test_finalized_block_advances_and_prunes_forks() {
+        let mut tracker = new_tracker(2);
+        let a = chain(&finalized_header(), 1, 5);
+        tracker.append(&a[0]).unwrap();
+        // a fork off the first block, dropped once a[0] is finalized
+        let b = child(&a[0].header, 2);
+        tracker.append(&a[1]).unwrap();
+        tracker.append(&b).unwrap();
+        for header in a[2..].iter() {
+            tracker.append(header).unwrap();
+        }
+
+        assert_eq!(tracker.finalized().hash, a[2].get_hash());
+        assert_eq!(tracker.finalized().height, 3);
+        assert_eq!(tracker.best_tip().height, 5);
+        assert!(tracker.get_block(&b.get_hash()).is_none());
+        assert_eq!(tracker.get_block_at_height(2), Err(DogeBridgeError::BlockNotInCache));
+        assert_eq!(tracker.get_block_at_height(6), Err(DogeBridgeError::BlockNotInCache));
+        let finalized_base = [finalized_header().get_hash(), a[0].get_hash(), a[1].get_hash()];
+        assert_eq!(tracker.get_block_merkle_tree_root(), expected_block_tree_root(&tracker, &finalized_base));
+
+        assert_eq!(tracker.rollback_to(2), Err(DogeBridgeError::AttemptedToModifiyFinalizedBlock));
+        assert_eq!(tracker.append(&child(&a[1].header, 3)), Err(DogeBridgeError::InvalidParentBlockHash));
+        assert_eq!(tracker.append(&a[4]), Err(DogeBridgeError::InsertBlockAlreadyInCache));
+        assert_eq!(tracker.append(&a[2]), Err(DogeBridgeError::InsertBlockAlreadyInCache));
+    }
+
+    #[test]
+    fn test_append_checks_header() {
+        let mut tracker = new_tracker(10);
+        let a = chain(&finalized_header(), 1, 2);
+        tracker.append(&a[0]).unwrap();
+
+        // regtest doesn't retarget, so the bits have to match the parent's
+        let mut wrong_bits = a[1].clone();
+        wrong_bits.header.bits = 0x2100_fffe;
+        assert_eq!(tracker.append(&wrong_bits), Err(DogeBridgeError::DifficutlyBitsMismatch));
+
+        let mut aux_pow_missing = a[1].clone();
+        aux_pow_missing.header.version |= crate::dogecoin::header::DOGE_AUXPOW_VERSION_FLAG;
+        assert_eq!(tracker.append(&aux_pow_missing), Err(DogeBridgeError::AuxPowMissing));
+
+        let mut block_tree = FixedMerkleAppendTree::new_empty();
+        block_tree.append([1u8; 32]);
+        assert_eq!(
+            HeaderChainTracker::new(test_params(), 10, 5, finalized_header(), DogeU256::ZERO, block_tree).err(),
+            Some(DogeBridgeError::BlockTipSyncMismatch)
+        );
+    }
+}
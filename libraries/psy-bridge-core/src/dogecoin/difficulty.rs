@@ -0,0 +1,395 @@
+use core::cmp::Ordering;
+
+use crate::{
+    common_types::QHash256,
+    dogecoin::header::DogePowParams,
+    error::{DogeBridgeError, QDogeResult},
+};
+
+// digishield retargets every block towards one block per minute
+pub const DOGE_DIGISHIELD_TARGET_TIMESPAN: i64 = 60;
+pub const DOGE_DIGISHIELD_TARGET_SPACING: i64 = 60;
+// testnet and regtest only allow digishield min difficulty blocks after this height
+pub const DOGE_DIGISHIELD_MIN_DIFFICULTY_HEIGHT: u32 = 157_500;
+
+// the subset of arith_uint256 the difficulty rules need, stored as little endian u64 limbs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct DogeU256(pub [u64; 4]);
+
+impl DogeU256 {
+    pub const ZERO: Self = Self([0; 4]);
+
+    pub fn from_u64(value: u64) -> Self {
+        Self([value, 0, 0, 0])
+    }
+
+    pub fn from_le_bytes(bytes: &QHash256) -> Self {
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        Self(limbs)
+    }
+
+    pub fn to_le_bytes(&self) -> QHash256 {
+        let mut bytes = [0u8; 32];
+        for (chunk, limb) in bytes.chunks_mut(8).zip(self.0) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    pub fn low_u64(&self) -> u64 {
+        self.0[0]
+    }
+
+    // the position of the highest set bit plus one
+    pub fn bits(&self) -> u32 {
+        for i in (0..4).rev() {
+            if self.0[i] != 0 {
+                return 64 * i as u32 + 64 - self.0[i].leading_zeros();
+            }
+        }
+        0
+    }
+
+    fn bit(&self, index: u32) -> bool {
+        (self.0[(index / 64) as usize] >> (index % 64)) & 1 == 1
+    }
+
+    pub fn shl(&self, shift: u32) -> Self {
+        let mut result = Self::ZERO;
+        let limb_shift = (shift / 64) as usize;
+        let bit_shift = shift % 64;
+        for i in limb_shift..4 {
+            result.0[i] = self.0[i - limb_shift] << bit_shift;
+            if bit_shift != 0 && i > limb_shift {
+                result.0[i] |= self.0[i - limb_shift - 1] >> (64 - bit_shift);
+            }
+        }
+        result
+    }
+
+    pub fn shr(&self, shift: u32) -> Self {
+        let mut result = Self::ZERO;
+        let limb_shift = (shift / 64) as usize;
+        let bit_shift = shift % 64;
+        for i in 0..4usize.saturating_sub(limb_shift) {
+            result.0[i] = self.0[i + limb_shift] >> bit_shift;
+            if bit_shift != 0 && i + limb_shift < 3 {
+                result.0[i] |= self.0[i + limb_shift + 1] << (64 - bit_shift);
+            }
+        }
+        result
+    }
+
+    pub fn not(&self) -> Self {
+        Self(self.0.map(|limb| !limb))
+    }
+
+    pub fn overflowing_add(&self, other: &Self) -> (Self, bool) {
+        let mut result = Self::ZERO;
+        let mut carry = false;
+        for i in 0..4 {
+            let (sum, carry_a) = self.0[i].overflowing_add(other.0[i]);
+            let (sum, carry_b) = sum.overflowing_add(carry as u64);
+            result.0[i] = sum;
+            carry = carry_a || carry_b;
+        }
+        (result, carry)
+    }
+
+    pub fn saturating_add(&self, other: &Self) -> Self {
+        match self.overflowing_add(other) {
+            (_, true) => Self([u64::MAX; 4]),
+            (sum, false) => sum,
+        }
+    }
+
+    pub fn wrapping_sub(&self, other: &Self) -> Self {
+        let mut result = Self::ZERO;
+        let mut borrow = false;
+        for i in 0..4 {
+            let (diff, borrow_a) = self.0[i].overflowing_sub(other.0[i]);
+            let (diff, borrow_b) = diff.overflowing_sub(borrow as u64);
+            result.0[i] = diff;
+            borrow = borrow_a || borrow_b;
+        }
+        result
+    }
+
+    // wraps like arith_uint256's operator*=
+    pub fn wrapping_mul_u64(&self, multiplier: u64) -> Self {
+        let mut result = Self::ZERO;
+        let mut carry = 0u128;
+        for i in 0..4 {
+            let product = self.0[i] as u128 * multiplier as u128 + carry;
+            result.0[i] = product as u64;
+            carry = product >> 64;
+        }
+        result
+    }
+
+    pub fn checked_div(&self, divisor: &Self) -> Option<Self> {
+        if divisor.is_zero() {
+            return None;
+        }
+        let mut quotient = Self::ZERO;
+        let mut remainder = Self::ZERO;
+        for i in (0..self.bits()).rev() {
+            // the remainder is below the divisor, so the bit shifted out only matters for divisors above 2^255
+            let shifted_out = remainder.bit(255);
+            remainder = remainder.shl(1);
+            remainder.0[0] |= self.bit(i) as u64;
+            if shifted_out || remainder >= *divisor {
+                remainder = remainder.wrapping_sub(divisor);
+                quotient.0[(i / 64) as usize] |= 1 << (i % 64);
+            }
+        }
+        Some(quotient)
+    }
+
+    // arith_uint256::SetCompact, returns the value along with its negative and overflow flags
+    pub fn from_compact(bits: u32) -> (Self, bool, bool) {
+        let size = bits >> 24;
+        let mut word = bits & 0x007f_ffff;
+        let value = if size <= 3 {
+            word >>= 8 * (3 - size);
+            Self::from_u64(word as u64)
+        } else {
+            Self::from_u64(word as u64).shl(8 * (size - 3))
+        };
+        let is_negative = word != 0 && (bits & 0x0080_0000) != 0;
+        let is_overflow = word != 0 && (size > 34 || (word > 0xff && size > 33) || (word > 0xffff && size > 32));
+        (value, is_negative, is_overflow)
+    }
+
+    // arith_uint256::GetCompact for a non-negative value
+    pub fn to_compact(&self) -> u32 {
+        let mut size = self.bits().div_ceil(8);
+        let mut compact = if size <= 3 {
+            (self.low_u64() << (8 * (3 - size))) as u32
+        } else {
+            self.shr(8 * (size - 3)).low_u64() as u32
+        };
+        // the 0x00800000 bit is the sign, move the mantissa down a byte instead of setting it
+        if compact & 0x0080_0000 != 0 {
+            compact >>= 8;
+            size += 1;
+        }
+        compact | (size << 24)
+    }
+}
+
+impl Ord for DogeU256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl PartialOrd for DogeU256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub fn get_pow_limit_bits(params: &DogePowParams) -> u32 {
+    DogeU256::from_le_bytes(&params.pow_limit).to_compact()
+}
+
+// CalculateDogecoinNextWorkRequired with digishield, first_block_time is the time of the block before last
+pub fn calculate_digishield_next_work_required(
+    last_bits: u32,
+    last_block_time: u32,
+    first_block_time: u32,
+    params: &DogePowParams,
+) -> u32 {
+    let actual_timespan = last_block_time as i64 - first_block_time as i64;
+
+    // amplitude filter, only an eighth of the deviation from the target timespan is applied
+    let modulated_timespan = DOGE_DIGISHIELD_TARGET_TIMESPAN + (actual_timespan - DOGE_DIGISHIELD_TARGET_TIMESPAN) / 8;
+    let modulated_timespan = modulated_timespan.clamp(
+        DOGE_DIGISHIELD_TARGET_TIMESPAN - DOGE_DIGISHIELD_TARGET_TIMESPAN / 4,
+        DOGE_DIGISHIELD_TARGET_TIMESPAN + DOGE_DIGISHIELD_TARGET_TIMESPAN / 2,
+    );
+
+    let (last_target, _, _) = DogeU256::from_compact(last_bits);
+    let new_target = last_target
+        .wrapping_mul_u64(modulated_timespan as u64)
+        .checked_div(&DogeU256::from_u64(DOGE_DIGISHIELD_TARGET_TIMESPAN as u64))
+        .unwrap();
+    new_target.min(DogeU256::from_le_bytes(&params.pow_limit)).to_compact()
+}
+
+// GetNextWorkRequired for the block after last. Only covers the digishield era, which retargets every block and so only
+// needs the last two blocks; the bridge starts long after digishield activated at height 145,000.
+pub fn get_next_work_required(
+    last_height: u32,
+    last_bits: u32,
+    last_block_time: u32,
+    prev_block_time: u32,
+    new_block_time: u32,
+    params: &DogePowParams,
+) -> u32 {
+    // testnet and regtest allow a min difficulty block once no block has been found for twice the target spacing
+    if params.allow_min_difficulty_blocks
+        && last_height >= DOGE_DIGISHIELD_MIN_DIFFICULTY_HEIGHT
+        && new_block_time as i64 > last_block_time as i64 + DOGE_DIGISHIELD_TARGET_SPACING * 2
+    {
+        return get_pow_limit_bits(params);
+    }
+    if params.no_retargeting {
+        return last_bits;
+    }
+    calculate_digishield_next_work_required(last_bits, last_block_time, prev_block_time, params)
+}
+
+pub fn check_next_work_required(
+    last_height: u32,
+    last_bits: u32,
+    last_block_time: u32,
+    prev_block_time: u32,
+    new_block_time: u32,
+    new_bits: u32,
+    params: &DogePowParams,
+) -> QDogeResult<()> {
+    let expected_bits = get_next_work_required(
+        last_height,
+        last_bits,
+        last_block_time,
+        prev_block_time,
+        new_block_time,
+        params,
+    );
+    if new_bits != expected_bits {
+        return Err(DogeBridgeError::DifficutlyBitsMismatch);
+    }
+    Ok(())
+}
+
+// GetBlockProof: the expected number of hashes needed to find a block meeting bits, zero if bits is not a valid target
+pub fn get_block_proof(bits: u32) -> DogeU256 {
+    let (target, is_negative, is_overflow) = DogeU256::from_compact(bits);
+    if is_negative || is_overflow || target.is_zero() {
+        return DogeU256::ZERO;
+    }
+    // 2^256 / (target + 1) computed as ~target / (target + 1) + 1 so it fits in 256 bits
+    let (target_plus_one, _) = target.overflowing_add(&DogeU256::from_u64(1));
+    match target.not().checked_div(&target_plus_one) {
+        Some(proof) => proof.overflowing_add(&DogeU256::from_u64(1)).0,
+        None => DogeU256::from_u64(1),
+    }
+}
+
+// the cumulative work of a chain after appending a block with the given bits
+pub fn add_block_proof(chain_work: &DogeU256, bits: u32) -> DogeU256 {
+    chain_work.saturating_add(&get_block_proof(bits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custodian_config::{DOGE_NETWORK_TYPE_MAINNET, DOGE_NETWORK_TYPE_REGTEST, DOGE_NETWORK_TYPE_TESTNET};
+
+    fn mainnet() -> DogePowParams {
+        DogePowParams::for_network(DOGE_NETWORK_TYPE_MAINNET).unwrap()
+    }
+
+    #[test]
+    fn test_compact_round_trip() {
+        // (bits, value, is_negative, is_overflow, re-encoded bits) from arith_uint256's tests
+        let vectors: [(u32, u64, bool, bool, u32); 9] = [
+            (0x0000_0000, 0, false, false, 0),
+            (0x0012_3456, 0, false, false, 0),
+            (0x0100_3456, 0, false, false, 0),
+            (0x0112_3456, 0x12, false, false, 0x0112_0000),
+            (0x0212_3456, 0x1234, false, false, 0x0212_3400),
+            (0x0312_3456, 0x12_3456, false, false, 0x0312_3456),
+            (0x0412_3456, 0x1234_5600, false, false, 0x0412_3456),
+            (0x0500_9234, 0x9234_0000, false, false, 0x0500_9234),
+            (0x01fe_dcba, 0x7e, true, false, 0x017e_0000),
+        ];
+        for (bits, value, is_negative, is_overflow, encoded) in vectors {
+            let decoded = DogeU256::from_compact(bits);
+            assert_eq!(decoded, (DogeU256::from_u64(value), is_negative, is_overflow));
+            assert_eq!(decoded.0.to_compact(), encoded);
+        }
+
+        let (value, _, _) = DogeU256::from_compact(0x2012_3456);
+        assert_eq!(value, DogeU256::from_u64(0x12_3456).shl(29 * 8));
+        assert_eq!(value.to_compact(), 0x2012_3456);
+        assert!(DogeU256::from_compact(0xff12_3456).2);
+
+        assert_eq!(get_pow_limit_bits(&mainnet()), 0x1e0f_ffff);
+        let regtest = DogePowParams::for_network(DOGE_NETWORK_TYPE_REGTEST).unwrap();
+        assert_eq!(get_pow_limit_bits(&regtest), 0x207f_ffff);
+    }
+
+    #[test]
+    fn test_digishield_retarget() {
+        // (last bits, last block time, first block time, expected bits) from dogecoin's digishield tests
+        let vectors = [
+            (0x1b49_9dfd, 1395094679, 1395094427, 0x1b67_1062),
+            (0x1b34_39cd, 1395101360, 1395100835, 0x1b4e_56b3),
+            (0x1b44_6f21, 1395380447, 1395380517, 0x1b33_5358),
+            (0x1b67_1062, 1395094727, 1395094679, 0x1b65_58a4),
+        ];
+        for (last_bits, last_block_time, first_block_time, expected_bits) in vectors {
+            assert_eq!(
+                calculate_digishield_next_work_required(last_bits, last_block_time, first_block_time, &mainnet()),
+                expected_bits
+            );
+            assert_eq!(
+                check_next_work_required(200_000, last_bits, last_block_time, first_block_time, last_block_time + 60, expected_bits, &mainnet()),
+                Ok(())
+            );
+            assert_eq!(
+                check_next_work_required(200_000, last_bits, last_block_time, first_block_time, last_block_time + 60, expected_bits + 1, &mainnet()),
+                Err(DogeBridgeError::DifficutlyBitsMismatch)
+            );
+        }
+
+        // the new target never exceeds the pow limit
+        assert_eq!(
+            calculate_digishield_next_work_required(0x1e0f_ffff, 1_000_000, 0, &mainnet()),
+            0x1e0f_ffff
+        );
+    }
+
+    #[test]
+    fn test_min_difficulty_blocks() {
+        let testnet = DogePowParams::for_network(DOGE_NETWORK_TYPE_TESTNET).unwrap();
+        let last_height = DOGE_DIGISHIELD_MIN_DIFFICULTY_HEIGHT;
+        let (last_bits, last_block_time, prev_block_time) = (0x1b49_9dfd, 1_600_000_000, 1_599_999_940);
+        let retargeted = calculate_digishield_next_work_required(last_bits, last_block_time, prev_block_time, &testnet);
+
+        // only a block more than two target spacings after its parent may drop to the pow limit
+        let late = last_block_time + 121;
+        assert_eq!(get_next_work_required(last_height, last_bits, last_block_time, prev_block_time, late, &testnet), 0x1e0f_ffff);
+        assert_eq!(get_next_work_required(last_height, last_bits, last_block_time, prev_block_time, late - 1, &testnet), retargeted);
+        assert_eq!(get_next_work_required(last_height - 1, last_bits, last_block_time, prev_block_time, late, &testnet), retargeted);
+        assert_eq!(get_next_work_required(last_height, last_bits, last_block_time, prev_block_time, late, &mainnet()), retargeted);
+
+        // regtest keeps its difficulty
+        let regtest = DogePowParams::for_network(DOGE_NETWORK_TYPE_REGTEST).unwrap();
+        assert_eq!(get_next_work_required(1, 0x207f_ffff, last_block_time, 0, last_block_time + 1, &regtest), 0x207f_ffff);
+    }
+
+    #[test]
+    fn test_chain_work() {
+        // dogecoin's genesis block
+        assert_eq!(get_block_proof(0x1e0f_fff0), DogeU256::from_u64(0x10_0010));
+        assert_eq!(get_block_proof(0x207f_ffff), DogeU256::from_u64(2));
+        assert_eq!(get_block_proof(0x0492_3456), DogeU256::ZERO);
+        assert_eq!(get_block_proof(0), DogeU256::ZERO);
+
+        let chain_work = add_block_proof(&add_block_proof(&DogeU256::ZERO, 0x1e0f_fff0), 0x1b49_9dfd);
+        assert!(chain_work > get_block_proof(0x1b49_9dfd));
+        assert_eq!(DogeU256::from_le_bytes(&chain_work.to_le_bytes()), chain_work);
+        assert_eq!(DogeU256([u64::MAX; 4]).saturating_add(&chain_work), DogeU256([u64::MAX; 4]));
+    }
+}
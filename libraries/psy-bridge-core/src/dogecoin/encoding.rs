@@ -0,0 +1,181 @@
+use crate::{
+    common_types::QHash256,
+    error::{DogeBridgeError, QDogeResult},
+};
+
+// same bound dogecoind puts on a compact size when deserializing
+pub const DOGE_MAX_COMPACT_SIZE: u64 = 0x0200_0000;
+
+// reads dogecoin's wire encoding, every read fails with DeserializationError once the data runs out
+#[derive(Clone, Copy, Debug)]
+pub struct DogeByteReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> DogeByteReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.offset
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> QDogeResult<&'a [u8]> {
+        if len > self.remaining() {
+            return Err(DogeBridgeError::DeserializationError);
+        }
+        let bytes = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    pub fn read_array<const N: usize>(&mut self) -> QDogeResult<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    pub fn read_u8(&mut self) -> QDogeResult<u8> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    pub fn read_u16_le(&mut self) -> QDogeResult<u16> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u32_le(&mut self) -> QDogeResult<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u64_le(&mut self) -> QDogeResult<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_hash(&mut self) -> QDogeResult<QHash256> {
+        self.read_array()
+    }
+
+    // rejects non-canonical encodings like dogecoind's ReadCompactSize
+    pub fn read_compact_size(&mut self) -> QDogeResult<u64> {
+        let size = match self.read_u8()? {
+            0xfd => {
+                let size = self.read_u16_le()? as u64;
+                if size < 0xfd {
+                    return Err(DogeBridgeError::DeserializationError);
+                }
+                size
+            }
+            0xfe => {
+                let size = self.read_u32_le()? as u64;
+                if size <= 0xffff {
+                    return Err(DogeBridgeError::DeserializationError);
+                }
+                size
+            }
+            0xff => {
+                let size = self.read_u64_le()?;
+                if size <= 0xffff_ffff {
+                    return Err(DogeBridgeError::DeserializationError);
+                }
+                size
+            }
+            size => size as u64,
+        };
+        if size > DOGE_MAX_COMPACT_SIZE {
+            return Err(DogeBridgeError::DeserializationError);
+        }
+        Ok(size)
+    }
+
+    // a compact size prefixed byte string
+    pub fn read_var_bytes(&mut self) -> QDogeResult<&'a [u8]> {
+        let len = self.read_compact_size()? as usize;
+        self.read_bytes(len)
+    }
+
+    // a compact size prefixed list of hashes
+    pub fn read_hash_list(&mut self) -> QDogeResult<Vec<QHash256>> {
+        let count = self.read_compact_size()? as usize;
+        // checked before allocating so a bogus count can't reserve more than the data holds
+        if count > self.remaining() / 32 {
+            return Err(DogeBridgeError::DeserializationError);
+        }
+        (0..count).map(|_| self.read_hash()).collect()
+    }
+}
+
+pub fn write_compact_size(out: &mut Vec<u8>, size: u64) {
+    if size < 0xfd {
+        out.push(size as u8);
+    } else if size <= 0xffff {
+        out.push(0xfd);
+        out.extend_from_slice(&(size as u16).to_le_bytes());
+    } else if size <= 0xffff_ffff {
+        out.push(0xfe);
+        out.extend_from_slice(&(size as u32).to_le_bytes());
+    } else {
+        out.push(0xff);
+        out.extend_from_slice(&size.to_le_bytes());
+    }
+}
+
+pub fn write_var_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_compact_size(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+pub fn write_hash_list(out: &mut Vec<u8>, hashes: &[QHash256]) {
+    write_compact_size(out, hashes.len() as u64);
+    for hash in hashes {
+        out.extend_from_slice(hash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_size_round_trip() {
+        for size in [0u64, 0xfc, 0xfd, 0xffff, 0x1_0000, DOGE_MAX_COMPACT_SIZE] {
+            let mut bytes = Vec::new();
+            write_compact_size(&mut bytes, size);
+            let mut reader = DogeByteReader::new(&bytes);
+            assert_eq!(reader.read_compact_size(), Ok(size));
+            assert!(reader.is_empty());
+        }
+
+        // non-canonical, oversized and truncated encodings
+        for bytes in [&[0xfd, 0xfc, 0x00][..], &[0xfe, 0xff, 0xff, 0x00, 0x00], &[0xfe, 0x01, 0x00, 0x00, 0x02], &[0xfd, 0x00]] {
+            assert_eq!(
+                DogeByteReader::new(bytes).read_compact_size(),
+                Err(DogeBridgeError::DeserializationError)
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_list_count_is_bounded_by_data() {
+        let mut bytes = Vec::new();
+        write_compact_size(&mut bytes, 3);
+        bytes.extend_from_slice(&[7u8; 64]);
+        assert_eq!(
+            DogeByteReader::new(&bytes).read_hash_list(),
+            Err(DogeBridgeError::DeserializationError)
+        );
+
+        let mut bytes = Vec::new();
+        write_hash_list(&mut bytes, &[[1u8; 32], [2u8; 32]]);
+        assert_eq!(DogeByteReader::new(&bytes).read_hash_list(), Ok(vec![[1u8; 32], [2u8; 32]]));
+    }
+}
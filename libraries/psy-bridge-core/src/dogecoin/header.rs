@@ -0,0 +1,647 @@
+use crate::{
+    common_types::QHash256,
+    crypto::hash::{sha256::btc_hash256_bytes, sha256_impl::hash_impl_btc_hash256_two_to_one_bytes},
+    dogecoin::{
+        difficulty::DogeU256,
+        encoding::{write_hash_list, DogeByteReader},
+        network::DogeNetwork,
+        scrypt::hash_impl_doge_scrypt_bytes,
+    },
+    error::{DogeBridgeError, QDogeResult},
+};
+
+pub const DOGE_BLOCK_HEADER_SIZE: usize = 80;
+
+// merge mined headers set this version bit and carry an AuxPoW after the 80 byte header
+pub const DOGE_AUXPOW_VERSION_FLAG: u32 = 1 << 8;
+pub const DOGE_AUXPOW_CHAIN_ID_SHIFT: u32 = 16;
+pub const DOGE_AUXPOW_CHAIN_ID: u32 = 0x0062;
+pub const DOGE_AUXPOW_MAX_CHAIN_MERKLE_BRANCH_LENGTH: usize = 30;
+
+// marks the chain merkle root in the parent coinbase script
+pub const MERGED_MINING_HEADER: [u8; 4] = [0xfa, 0xbe, b'm', b'm'];
+// without MERGED_MINING_HEADER the chain merkle root has to start within the first 20 bytes of the script
+pub const DOGE_AUXPOW_LEGACY_CHAIN_MERKLE_ROOT_MAX_OFFSET: usize = 20;
+
+// consensus parameters the proof of work checks depend on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DogePowParams {
+    // largest allowed target, little endian like the pow hash
+    pub pow_limit: QHash256,
+    pub aux_pow_chain_id: u32,
+    // testnet accepts headers with any chain id
+    pub strict_chain_id: bool,
+    pub allow_min_difficulty_blocks: bool,
+    pub no_retargeting: bool,
+}
+
+impl DogePowParams {
+    pub fn for_network(network_type: u32) -> Option<Self> {
+        DogeNetwork::from_network_type(network_type).map(Self::for_doge_network)
+    }
+
+    pub fn for_doge_network(network: DogeNetwork) -> Self {
+        // 0x00000fff...ff on mainnet and testnet, 0x7fff...ff on regtest
+        let mut pow_limit = [0xffu8; 32];
+        match network {
+            DogeNetwork::Mainnet | DogeNetwork::Testnet => {
+                pow_limit[29] = 0x0f;
+                pow_limit[30] = 0;
+                pow_limit[31] = 0;
+            }
+            DogeNetwork::Regtest => pow_limit[31] = 0x7f,
+        }
+        Self {
+            pow_limit,
+            aux_pow_chain_id: DOGE_AUXPOW_CHAIN_ID,
+            strict_chain_id: network != DogeNetwork::Testnet,
+            allow_min_difficulty_blocks: network != DogeNetwork::Mainnet,
+            no_retargeting: network == DogeNetwork::Regtest,
+        }
+    }
+}
+
+// decodes nBits into a little endian target, None if it is negative, zero or overflows
+pub fn compact_bits_to_target(bits: u32) -> Option<QHash256> {
+    let (target, is_negative, is_overflow) = DogeU256::from_compact(bits);
+    if is_negative || is_overflow || target.is_zero() {
+        return None;
+    }
+    Some(target.to_le_bytes())
+}
+
+// compares two little endian 256 bit numbers
+pub fn le_u256_lte(a: &QHash256, b: &QHash256) -> bool {
+    a.iter().rev().le(b.iter().rev())
+}
+
+// CheckProofOfWork: the target must be valid and within the pow limit, and the hash must not exceed it
+pub fn check_proof_of_work(pow_hash: &QHash256, bits: u32, params: &DogePowParams) -> bool {
+    match compact_bits_to_target(bits) {
+        Some(target) => le_u256_lte(&target, &params.pow_limit) && le_u256_lte(pow_hash, &target),
+        None => false,
+    }
+}
+
+#[cfg_attr(feature = "serialize_serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize_borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "serialize_speedy", derive(speedy::Readable, speedy::Writable))]
+#[cfg_attr(feature = "serialize_bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+#[derive(PartialEq, Clone, Debug, Eq, Ord, PartialOrd, Copy, Hash, Default)]
+#[repr(C)]
+pub struct DogeBlockHeader {
+    pub version: u32,
+    pub prev_block_hash: QHash256,
+    pub merkle_root: QHash256,
+    pub timestamp: u32,
+    pub bits: u32,
+    pub nonce: u32,
+}
+
+impl DogeBlockHeader {
+    pub fn from_bytes(bytes: &[u8; DOGE_BLOCK_HEADER_SIZE]) -> Self {
+        let mut reader = DogeByteReader::new(bytes);
+        // the reader can't run out of data on an 80 byte header
+        Self::read_from(&mut reader).unwrap()
+    }
+
+    pub fn read_from(reader: &mut DogeByteReader) -> QDogeResult<Self> {
+        Ok(Self {
+            version: reader.read_u32_le()?,
+            prev_block_hash: reader.read_hash()?,
+            merkle_root: reader.read_hash()?,
+            timestamp: reader.read_u32_le()?,
+            bits: reader.read_u32_le()?,
+            nonce: reader.read_u32_le()?,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; DOGE_BLOCK_HEADER_SIZE] {
+        let mut bytes = [0u8; DOGE_BLOCK_HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&self.version.to_le_bytes());
+        bytes[4..36].copy_from_slice(&self.prev_block_hash);
+        bytes[36..68].copy_from_slice(&self.merkle_root);
+        bytes[68..72].copy_from_slice(&self.timestamp.to_le_bytes());
+        bytes[72..76].copy_from_slice(&self.bits.to_le_bytes());
+        bytes[76..80].copy_from_slice(&self.nonce.to_le_bytes());
+        bytes
+    }
+
+    // the block hash (sha256d), in internal byte order
+    pub fn get_hash(&self) -> QHash256 {
+        btc_hash256_bytes(&self.to_bytes())
+    }
+
+    // the scrypt hash checked against the target
+    pub fn get_pow_hash(&self) -> QHash256 {
+        hash_impl_doge_scrypt_bytes(&self.to_bytes())
+    }
+
+    pub fn is_aux_pow(&self) -> bool {
+        self.version & DOGE_AUXPOW_VERSION_FLAG != 0
+    }
+
+    pub fn get_chain_id(&self) -> u32 {
+        self.version >> DOGE_AUXPOW_CHAIN_ID_SHIFT
+    }
+
+    pub fn get_base_version(&self) -> u32 {
+        self.version & (DOGE_AUXPOW_VERSION_FLAG - 1)
+    }
+
+    // blocks from before merge mining was enabled carry no chain id
+    pub fn is_legacy(&self) -> bool {
+        self.version == 1 || self.version == 2
+    }
+}
+
+// a merkle branch in the order dogecoind serializes it, bit i of side_mask is set if the node at level i is a right child
+#[derive(PartialEq, Clone, Debug, Eq, Hash, Default)]
+pub struct DogeMerkleBranch {
+    pub hashes: Vec<QHash256>,
+    pub side_mask: u32,
+}
+
+impl DogeMerkleBranch {
+    pub fn read_from(reader: &mut DogeByteReader) -> QDogeResult<Self> {
+        Ok(Self {
+            hashes: reader.read_hash_list()?,
+            side_mask: reader.read_u32_le()?,
+        })
+    }
+
+    pub fn write_to(&self, out: &mut Vec<u8>) {
+        write_hash_list(out, &self.hashes);
+        out.extend_from_slice(&self.side_mask.to_le_bytes());
+    }
+
+    pub fn compute_root(&self, leaf: &QHash256) -> QHash256 {
+        let mut hash = *leaf;
+        let mut side_mask = self.side_mask;
+        for sibling in self.hashes.iter() {
+            hash = if side_mask & 1 == 1 {
+                hash_impl_btc_hash256_two_to_one_bytes(sibling, &hash)
+            } else {
+                hash_impl_btc_hash256_two_to_one_bytes(&hash, sibling)
+            };
+            side_mask >>= 1;
+        }
+        hash
+    }
+}
+
+// the slot of the chain merkle tree a chain has to use, derived from the nonce in the parent coinbase
+pub fn get_expected_aux_pow_chain_index(nonce: u32, chain_id: u32, merkle_height: usize) -> u32 {
+    let mut rand = nonce.wrapping_mul(1103515245).wrapping_add(12345);
+    rand = rand.wrapping_add(chain_id);
+    rand = rand.wrapping_mul(1103515245).wrapping_add(12345);
+    rand % (1u32 << merkle_height)
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+// walks a legacy transaction, returning its length and the script of its first input
+fn read_legacy_tx_first_input_script<'a>(reader: &mut DogeByteReader<'a>) -> QDogeResult<(usize, Option<&'a [u8]>)> {
+    let start = reader.offset();
+    reader.read_u32_le()?;
+    let mut first_input_script = None;
+    for i in 0..reader.read_compact_size()? {
+        reader.read_bytes(36)?;
+        let script = reader.read_var_bytes()?;
+        if i == 0 {
+            first_input_script = Some(script);
+        }
+        reader.read_u32_le()?;
+    }
+    for _ in 0..reader.read_compact_size()? {
+        reader.read_u64_le()?;
+        reader.read_var_bytes()?;
+    }
+    reader.read_u32_le()?;
+    Ok((reader.offset() - start, first_input_script))
+}
+
+// the proof that a parent chain block committed to a merge mined dogecoin block
+#[derive(PartialEq, Clone, Debug, Eq, Hash, Default)]
+pub struct DogeAuxPow {
+    // the parent block's coinbase transaction, legacy serialization
+    pub coinbase_tx: Vec<u8>,
+    // hashBlock of the coinbase merkle tx, not used by consensus
+    pub parent_hash: QHash256,
+    // proves coinbase_tx is in the parent block, it has to be the first transaction
+    pub coinbase_branch: DogeMerkleBranch,
+    // proves our block hash is in the chain merkle tree whose root is in the coinbase script
+    pub blockchain_branch: DogeMerkleBranch,
+    pub parent_block: DogeBlockHeader,
+}
+
+impl DogeAuxPow {
+    pub fn read_from(reader: &mut DogeByteReader) -> QDogeResult<Self> {
+        let mut tx_reader = *reader;
+        let (tx_len, _) = read_legacy_tx_first_input_script(&mut tx_reader)?;
+        let coinbase_tx = reader.read_bytes(tx_len)?.to_vec();
+        Ok(Self {
+            coinbase_tx,
+            parent_hash: reader.read_hash()?,
+            coinbase_branch: DogeMerkleBranch::read_from(reader)?,
+            blockchain_branch: DogeMerkleBranch::read_from(reader)?,
+            parent_block: DogeBlockHeader::read_from(reader)?,
+        })
+    }
+
+    pub fn write_to(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.coinbase_tx);
+        out.extend_from_slice(&self.parent_hash);
+        self.coinbase_branch.write_to(out);
+        self.blockchain_branch.write_to(out);
+        out.extend_from_slice(&self.parent_block.to_bytes());
+    }
+
+    pub fn get_coinbase_script(&self) -> QDogeResult<&[u8]> {
+        let mut reader = DogeByteReader::new(&self.coinbase_tx);
+        let (tx_len, first_input_script) = read_legacy_tx_first_input_script(&mut reader)?;
+        if tx_len != self.coinbase_tx.len() {
+            return Err(DogeBridgeError::DeserializationError);
+        }
+        first_input_script.ok_or(DogeBridgeError::AuxPowCoinbaseNoInputs)
+    }
+
+    // the parent chain (litecoin) is scrypt mined as well
+    pub fn get_parent_block_pow_hash(&self) -> QHash256 {
+        self.parent_block.get_pow_hash()
+    }
+
+    // CAuxPow::check, does not check the parent block's proof of work
+    pub fn check(&self, aux_block_hash: &QHash256, chain_id: u32, params: &DogePowParams) -> QDogeResult<()> {
+        if self.coinbase_branch.side_mask != 0 {
+            return Err(DogeBridgeError::AuxPowCoinBaseBranchSideMaskNonZero);
+        }
+        if params.strict_chain_id && self.parent_block.get_chain_id() == chain_id {
+            return Err(DogeBridgeError::AuxPowParentHasOurChainId);
+        }
+        let merkle_height = self.blockchain_branch.hashes.len();
+        if merkle_height > DOGE_AUXPOW_MAX_CHAIN_MERKLE_BRANCH_LENGTH {
+            return Err(DogeBridgeError::AuxPowChainMerkleBranchTooLong);
+        }
+
+        let coinbase_hash = btc_hash256_bytes(&self.coinbase_tx);
+        if self.coinbase_branch.compute_root(&coinbase_hash) != self.parent_block.merkle_root {
+            return Err(DogeBridgeError::IncorrectAuxPowMerkleRoot);
+        }
+
+        // the script holds the chain merkle root in display (reversed) byte order
+        let mut chain_merkle_root = self.blockchain_branch.compute_root(aux_block_hash);
+        chain_merkle_root.reverse();
+
+        let script = self.get_coinbase_script()?;
+        let root_position = find_subslice(script, &chain_merkle_root)
+            .ok_or(DogeBridgeError::AuxPowCoinbaseMissingChainMerkleRoot)?;
+        match find_subslice(script, &MERGED_MINING_HEADER) {
+            Some(header_position) => {
+                // only one chain merkle root may be committed to
+                if find_subslice(&script[header_position + 1..], &MERGED_MINING_HEADER).is_some() {
+                    return Err(DogeBridgeError::MergedMiningHeaderFoundTwiceInCoinbase);
+                }
+                if header_position + MERGED_MINING_HEADER.len() != root_position {
+                    return Err(DogeBridgeError::MergedMiningHeaderNotFoundAtCoinbaseScriptStart);
+                }
+            }
+            None => {
+                if root_position > DOGE_AUXPOW_LEGACY_CHAIN_MERKLE_ROOT_MAX_OFFSET {
+                    return Err(DogeBridgeError::AuxPowChainMerkleRootTooLateInCoinbaseInputScript);
+                }
+            }
+        }
+
+        // the root is followed by the chain merkle tree size and the nonce that picks our slot in it
+        let tree_params = &script[root_position + chain_merkle_root.len()..];
+        if tree_params.len() < 8 {
+            return Err(DogeBridgeError::AuxPowCoinbaseTransactionInputScriptTooShort);
+        }
+        let tree_size = u32::from_le_bytes(tree_params[0..4].try_into().unwrap());
+        if tree_size != 1u32 << merkle_height {
+            return Err(DogeBridgeError::AuxPowCoinbaseScriptInvalidNSize);
+        }
+        let nonce = u32::from_le_bytes(tree_params[4..8].try_into().unwrap());
+        if self.blockchain_branch.side_mask != get_expected_aux_pow_chain_index(nonce, chain_id, merkle_height) {
+            return Err(DogeBridgeError::AuxPowCoinbaseScriptInvalidSideMask);
+        }
+        Ok(())
+    }
+}
+
+// a header as dogecoind serializes it, followed by an AuxPoW iff the version has DOGE_AUXPOW_VERSION_FLAG set
+#[derive(PartialEq, Clone, Debug, Eq, Hash, Default)]
+pub struct DogeAuxPowBlockHeader {
+    pub header: DogeBlockHeader,
+    pub aux_pow: Option<DogeAuxPow>,
+}
+
+impl DogeAuxPowBlockHeader {
+    pub fn read_from(reader: &mut DogeByteReader) -> QDogeResult<Self> {
+        let header = DogeBlockHeader::read_from(reader)?;
+        let aux_pow = if header.is_aux_pow() {
+            Some(DogeAuxPow::read_from(reader)?)
+        } else {
+            None
+        };
+        Ok(Self { header, aux_pow })
+    }
+
+    // the bytes must hold exactly one header
+    pub fn from_bytes(bytes: &[u8]) -> QDogeResult<Self> {
+        let mut reader = DogeByteReader::new(bytes);
+        let header = Self::read_from(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(DogeBridgeError::DeserializationError);
+        }
+        Ok(header)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header.to_bytes().to_vec();
+        if let Some(aux_pow) = &self.aux_pow {
+            aux_pow.write_to(&mut bytes);
+        }
+        bytes
+    }
+
+    pub fn get_hash(&self) -> QHash256 {
+        self.header.get_hash()
+    }
+
+    // CheckAuxPowProofOfWork
+    pub fn check_proof_of_work(&self, params: &DogePowParams) -> QDogeResult<()> {
+        let header = &self.header;
+        if !header.is_legacy() && params.strict_chain_id && header.get_chain_id() != params.aux_pow_chain_id {
+            return Err(DogeBridgeError::AuxPowChainIdMismatch);
+        }
+        match &self.aux_pow {
+            None => {
+                if header.is_aux_pow() {
+                    return Err(DogeBridgeError::AuxPowMissing);
+                }
+                if !check_proof_of_work(&header.get_pow_hash(), header.bits, params) {
+                    return Err(DogeBridgeError::StandardPoWCheckFailed);
+                }
+            }
+            Some(aux_pow) => {
+                if !header.is_aux_pow() {
+                    return Err(DogeBridgeError::AuxPowNotExpected);
+                }
+                aux_pow.check(&header.get_hash(), header.get_chain_id(), params)?;
+                if !check_proof_of_work(&aux_pow.get_parent_block_pow_hash(), header.bits, params) {
+                    return Err(DogeBridgeError::AuxPowParentBlockPoWCheckFailed);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{custodian_config::DOGE_NETWORK_TYPE_MAINNET, dogecoin::encoding::write_var_bytes};
+
+    const GENESIS_HEADER: [u8; 80] = hex_literal::hex!(
+        "01000000000000000000000000000000000000000000000000000000000000000000000069"
+        "6ad20e2dd4365c7459b4a4a5af743d5e92c6da3229e6532cd605f6533f2a5b24a6a152f0ff0f1e67860100"
+    );
+
+    // any hash meets the target of these params, so the tests don't have to grind nonces
+    fn easy_params() -> DogePowParams {
+        DogePowParams {
+            pow_limit: [0xff; 32],
+            ..DogePowParams::for_network(DOGE_NETWORK_TYPE_MAINNET).unwrap()
+        }
+    }
+    const EASY_BITS: u32 = 0x2100_ffff;
+
+    fn coinbase_tx_with_script(script: &[u8]) -> Vec<u8> {
+        let mut tx = 1u32.to_le_bytes().to_vec();
+        tx.push(1);
+        tx.extend_from_slice(&[0u8; 32]);
+        tx.extend_from_slice(&u32::MAX.to_le_bytes());
+        write_var_bytes(&mut tx, script);
+        tx.extend_from_slice(&u32::MAX.to_le_bytes());
+        tx.push(1);
+        tx.extend_from_slice(&5_000_000_000u64.to_le_bytes());
+        write_var_bytes(&mut tx, &[0x51]);
+        tx.extend_from_slice(&0u32.to_le_bytes());
+        tx
+    }
+
+    fn coinbase_script(chain_merkle_root: &QHash256, merkle_height: usize, nonce: u32) -> Vec<u8> {
+        let mut root = *chain_merkle_root;
+        root.reverse();
+        let mut script = vec![0x03, 0x01, 0x02, 0x03];
+        script.extend_from_slice(&MERGED_MINING_HEADER);
+        script.extend_from_slice(&root);
+        script.extend_from_slice(&(1u32 << merkle_height).to_le_bytes());
+        script.extend_from_slice(&nonce.to_le_bytes());
+        script
+    }
+
+    // a merge mined header whose parent coinbase commits to a chain merkle tree of the given height
+    fn aux_pow_header(merkle_height: usize, bits: u32, script_for_root: impl Fn(&QHash256) -> Vec<u8>) -> DogeAuxPowBlockHeader {
+        let header = DogeBlockHeader {
+            version: (DOGE_AUXPOW_CHAIN_ID << DOGE_AUXPOW_CHAIN_ID_SHIFT) | DOGE_AUXPOW_VERSION_FLAG | 4,
+            prev_block_hash: [1u8; 32],
+            merkle_root: [2u8; 32],
+            timestamp: 1_700_000_000,
+            bits,
+            nonce: 0,
+        };
+        let nonce = 7;
+        let blockchain_branch = DogeMerkleBranch {
+            hashes: (0..merkle_height).map(|i| [i as u8 + 10; 32]).collect(),
+            side_mask: get_expected_aux_pow_chain_index(nonce, DOGE_AUXPOW_CHAIN_ID, merkle_height),
+        };
+        let coinbase_tx = coinbase_tx_with_script(&script_for_root(&blockchain_branch.compute_root(&header.get_hash())));
+        let coinbase_branch = DogeMerkleBranch {
+            hashes: vec![[3u8; 32], [4u8; 32]],
+            side_mask: 0,
+        };
+        let parent_block = DogeBlockHeader {
+            version: 0x2000_0000,
+            prev_block_hash: [5u8; 32],
+            merkle_root: coinbase_branch.compute_root(&btc_hash256_bytes(&coinbase_tx)),
+            timestamp: 1_700_000_000,
+            bits: EASY_BITS,
+            nonce: 0,
+        };
+        DogeAuxPowBlockHeader {
+            header,
+            aux_pow: Some(DogeAuxPow {
+                coinbase_tx,
+                parent_hash: [0u8; 32],
+                coinbase_branch,
+                blockchain_branch,
+                parent_block,
+            }),
+        }
+    }
+
+    fn valid_aux_pow_header(merkle_height: usize) -> DogeAuxPowBlockHeader {
+        aux_pow_header(merkle_height, EASY_BITS, |root| coinbase_script(root, merkle_height, 7))
+    }
+
+    #[test]
+    fn test_genesis_header() {
+        let header = DogeBlockHeader::from_bytes(&GENESIS_HEADER);
+        assert_eq!(header.to_bytes(), GENESIS_HEADER);
+        assert_eq!(header.bits, 0x1e0f_fff0);
+        assert!(header.is_legacy());
+        assert!(!header.is_aux_pow());
+
+        let mut display_hash = header.get_hash();
+        display_hash.reverse();
+        assert_eq!(
+            display_hash,
+            hex_literal::hex!("1a91e3dace36e2be3bf030a65679fe821aa1d6ef92e7c9902eb318182c355691")
+        );
+
+        let mainnet = DogePowParams::for_network(DOGE_NETWORK_TYPE_MAINNET).unwrap();
+        let parsed = DogeAuxPowBlockHeader::from_bytes(&GENESIS_HEADER).unwrap();
+        assert_eq!(parsed.aux_pow, None);
+        assert_eq!(parsed.check_proof_of_work(&mainnet), Ok(()));
+
+        let mut bad_nonce = parsed.clone();
+        bad_nonce.header.nonce += 1;
+        assert_eq!(
+            bad_nonce.check_proof_of_work(&mainnet),
+            Err(DogeBridgeError::StandardPoWCheckFailed)
+        );
+    }
+
+    #[test]
+    fn test_compact_bits_to_target() {
+        let mut expected = [0u8; 32];
+        expected[27..30].copy_from_slice(&[0xf0, 0xff, 0x0f]);
+        assert_eq!(compact_bits_to_target(0x1e0f_fff0), Some(expected));
+        let mut expected = [0u8; 32];
+        expected[0] = 0x12;
+        assert_eq!(compact_bits_to_target(0x0112_3456), Some(expected));
+        // negative, zero and overflowing targets
+        assert_eq!(compact_bits_to_target(0x04923456), None);
+        assert_eq!(compact_bits_to_target(0x0000_0000), None);
+        assert_eq!(compact_bits_to_target(0xff12_3456), None);
+
+        // a valid target above the pow limit
+        let mainnet = DogePowParams::for_network(DOGE_NETWORK_TYPE_MAINNET).unwrap();
+        assert!(!check_proof_of_work(&[0u8; 32], 0x1f0f_ffff, &mainnet));
+        assert!(check_proof_of_work(&[0u8; 32], 0x1e0f_ffff, &mainnet));
+    }
+
+    #[test]
+    fn test_aux_pow_header_round_trip_and_check() {
+        for merkle_height in [0, 1, 3] {
+            let header = valid_aux_pow_header(merkle_height);
+            let bytes = header.to_bytes();
+            assert_eq!(DogeAuxPowBlockHeader::from_bytes(&bytes), Ok(header.clone()));
+            assert_eq!(header.check_proof_of_work(&easy_params()), Ok(()));
+
+            // trailing or missing bytes
+            let mut extended = bytes.clone();
+            extended.push(0);
+            assert!(DogeAuxPowBlockHeader::from_bytes(&extended).is_err());
+            assert!(DogeAuxPowBlockHeader::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        }
+
+        // the parent block's scrypt hash has to meet our target
+        let hard = aux_pow_header(1, 0x1e0f_fff0, |root| coinbase_script(root, 1, 7));
+        assert_eq!(
+            hard.check_proof_of_work(&easy_params()),
+            Err(DogeBridgeError::AuxPowParentBlockPoWCheckFailed)
+        );
+    }
+
+    #[test]
+    fn test_aux_pow_header_flag_and_chain_id() {
+        let header = valid_aux_pow_header(1);
+
+        let mut missing = header.clone();
+        missing.aux_pow = None;
+        assert_eq!(missing.check_proof_of_work(&easy_params()), Err(DogeBridgeError::AuxPowMissing));
+
+        let mut unexpected = header.clone();
+        unexpected.header.version &= !DOGE_AUXPOW_VERSION_FLAG;
+        assert_eq!(unexpected.check_proof_of_work(&easy_params()), Err(DogeBridgeError::AuxPowNotExpected));
+
+        let mut wrong_chain = header.clone();
+        wrong_chain.header.version = (0x0063 << DOGE_AUXPOW_CHAIN_ID_SHIFT) | DOGE_AUXPOW_VERSION_FLAG | 4;
+        assert_eq!(wrong_chain.check_proof_of_work(&easy_params()), Err(DogeBridgeError::AuxPowChainIdMismatch));
+
+        let mut parent_has_our_chain_id = header.clone();
+        parent_has_our_chain_id.aux_pow.as_mut().unwrap().parent_block.version = DOGE_AUXPOW_CHAIN_ID << DOGE_AUXPOW_CHAIN_ID_SHIFT;
+        assert_eq!(
+            parent_has_our_chain_id.check_proof_of_work(&easy_params()),
+            Err(DogeBridgeError::AuxPowParentHasOurChainId)
+        );
+        // testnet doesn't enforce chain ids
+        let relaxed = DogePowParams { strict_chain_id: false, ..easy_params() };
+        assert_eq!(parent_has_our_chain_id.check_proof_of_work(&relaxed), Ok(()));
+    }
+
+    #[test]
+    fn test_aux_pow_check_errors() {
+        let check = |header: &DogeAuxPowBlockHeader| header.check_proof_of_work(&easy_params());
+
+        let mut header = valid_aux_pow_header(1);
+        header.aux_pow.as_mut().unwrap().coinbase_branch.side_mask = 1;
+        assert_eq!(check(&header), Err(DogeBridgeError::AuxPowCoinBaseBranchSideMaskNonZero));
+
+        let mut header = valid_aux_pow_header(1);
+        header.aux_pow.as_mut().unwrap().coinbase_branch.hashes[0][0] ^= 1;
+        assert_eq!(check(&header), Err(DogeBridgeError::IncorrectAuxPowMerkleRoot));
+
+        let mut header = valid_aux_pow_header(1);
+        header.aux_pow.as_mut().unwrap().blockchain_branch.side_mask ^= 1;
+        assert_eq!(check(&header), Err(DogeBridgeError::AuxPowCoinbaseMissingChainMerkleRoot));
+
+        let header = valid_aux_pow_header(DOGE_AUXPOW_MAX_CHAIN_MERKLE_BRANCH_LENGTH + 1);
+        assert_eq!(check(&header), Err(DogeBridgeError::AuxPowChainMerkleBranchTooLong));
+
+        let header = aux_pow_header(1, EASY_BITS, |root| {
+            let mut script = MERGED_MINING_HEADER.to_vec();
+            script.extend_from_slice(&coinbase_script(root, 1, 7));
+            script
+        });
+        assert_eq!(check(&header), Err(DogeBridgeError::MergedMiningHeaderFoundTwiceInCoinbase));
+
+        let header = aux_pow_header(1, EASY_BITS, |root| {
+            let mut script = coinbase_script(root, 1, 7);
+            script.insert(8, 0);
+            script
+        });
+        assert_eq!(check(&header), Err(DogeBridgeError::MergedMiningHeaderNotFoundAtCoinbaseScriptStart));
+
+        // legacy commitments without the merged mining header must start within the first 20 bytes
+        let legacy_script = |padding: usize| {
+            move |root: &QHash256| {
+                let mut script = vec![0u8; padding];
+                script.extend_from_slice(&coinbase_script(root, 1, 7)[8..]);
+                script
+            }
+        };
+        assert_eq!(check(&aux_pow_header(1, EASY_BITS, legacy_script(20))), Ok(()));
+        assert_eq!(
+            check(&aux_pow_header(1, EASY_BITS, legacy_script(21))),
+            Err(DogeBridgeError::AuxPowChainMerkleRootTooLateInCoinbaseInputScript)
+        );
+
+        let header = aux_pow_header(1, EASY_BITS, |root| coinbase_script(root, 1, 7)[..44].to_vec());
+        assert_eq!(check(&header), Err(DogeBridgeError::AuxPowCoinbaseTransactionInputScriptTooShort));
+
+        let header = aux_pow_header(1, EASY_BITS, |root| coinbase_script(root, 2, 7));
+        assert_eq!(check(&header), Err(DogeBridgeError::AuxPowCoinbaseScriptInvalidNSize));
+
+        // the nonce in the script picks a different slot than the one our branch proves
+        let header = aux_pow_header(3, EASY_BITS, |root| coinbase_script(root, 3, 8));
+        assert_eq!(check(&header), Err(DogeBridgeError::AuxPowCoinbaseScriptInvalidSideMask));
+
+    }
+}
@@ -0,0 +1,10 @@
+pub mod address;
+// off-chain only, the tracker keeps its blocks in a HashMap
+#[cfg(feature = "std")]
+pub mod chain_tracker;
+pub mod difficulty;
+pub mod encoding;
+pub mod header;
+pub mod network;
+pub mod scrypt;
+pub mod tx;
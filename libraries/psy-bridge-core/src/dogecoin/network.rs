@@ -0,0 +1,71 @@
+use crate::{
+    custodian_config::{DOGE_NETWORK_TYPE_MAINNET, DOGE_NETWORK_TYPE_REGTEST, DOGE_NETWORK_TYPE_TESTNET},
+    dogecoin::header::DogePowParams,
+};
+
+// typed counterpart of the DOGE_NETWORK_TYPE_* values stored in the custodian config
+#[derive(PartialEq, Clone, Copy, Debug, Eq, Hash)]
+pub enum DogeNetwork {
+    Mainnet,
+    Testnet,
+    Regtest,
+}
+
+impl DogeNetwork {
+    pub const ALL: [DogeNetwork; 3] = [DogeNetwork::Mainnet, DogeNetwork::Testnet, DogeNetwork::Regtest];
+
+    pub fn from_network_type(network_type: u32) -> Option<Self> {
+        match network_type {
+            DOGE_NETWORK_TYPE_MAINNET => Some(DogeNetwork::Mainnet),
+            DOGE_NETWORK_TYPE_TESTNET => Some(DogeNetwork::Testnet),
+            DOGE_NETWORK_TYPE_REGTEST => Some(DogeNetwork::Regtest),
+            _ => None,
+        }
+    }
+
+    pub fn network_type(&self) -> u32 {
+        match self {
+            DogeNetwork::Mainnet => DOGE_NETWORK_TYPE_MAINNET,
+            DogeNetwork::Testnet => DOGE_NETWORK_TYPE_TESTNET,
+            DogeNetwork::Regtest => DOGE_NETWORK_TYPE_REGTEST,
+        }
+    }
+
+    pub fn p2pkh_address_version(&self) -> u8 {
+        match self {
+            DogeNetwork::Mainnet => 0x1e,
+            DogeNetwork::Testnet => 0x71,
+            DogeNetwork::Regtest => 0x6f,
+        }
+    }
+
+    // testnet and regtest share this version byte
+    pub fn p2sh_address_version(&self) -> u8 {
+        match self {
+            DogeNetwork::Mainnet => 0x16,
+            DogeNetwork::Testnet | DogeNetwork::Regtest => 0xc4,
+        }
+    }
+
+    pub fn pow_params(&self) -> DogePowParams {
+        DogePowParams::for_doge_network(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custodian_config::{doge_p2pkh_address_version, doge_p2sh_address_version};
+
+    #[test]
+    fn test_network_parameters_match_network_type() {
+        for network in DogeNetwork::ALL {
+            assert_eq!(DogeNetwork::from_network_type(network.network_type()), Some(network));
+            // the on-chain code only has the network type
+            assert_eq!(doge_p2pkh_address_version(network.network_type()), Some(network.p2pkh_address_version()));
+            assert_eq!(doge_p2sh_address_version(network.network_type()), Some(network.p2sh_address_version()));
+            assert_eq!(DogePowParams::for_network(network.network_type()), Some(network.pow_params()));
+        }
+        assert_eq!(DogeNetwork::from_network_type(3), None);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{
+    common_types::QHash256,
+    crypto::hash::sha256_impl::{hash_impl_sha256_bytes, hashv_impl_sha256_bytes},
+};
+
+// dogecoin's proof of work hash is scrypt(N = 1024, r = 1, p = 1) with the 80 byte header as both password and salt.
+// everything is built on hash_impl_sha256 so the sp1 guest uses its sha256 precompile.
+const SCRYPT_N: usize = 1024;
+const SCRYPT_BLOCK_WORDS: usize = 32;
+
+struct HmacSha256 {
+    inner_pad: [u8; 64],
+    outer_pad: [u8; 64],
+}
+
+impl HmacSha256 {
+    fn new(key: &[u8]) -> Self {
+        let mut key_block = [0u8; 64];
+        if key.len() > 64 {
+            key_block[..32].copy_from_slice(&hash_impl_sha256_bytes(key));
+        } else {
+            key_block[..key.len()].copy_from_slice(key);
+        }
+        let mut inner_pad = [0x36u8; 64];
+        let mut outer_pad = [0x5cu8; 64];
+        for i in 0..64 {
+            inner_pad[i] ^= key_block[i];
+            outer_pad[i] ^= key_block[i];
+        }
+        Self { inner_pad, outer_pad }
+    }
+
+    fn mac(&self, message: &[u8], block_index: u32) -> QHash256 {
+        let inner = hashv_impl_sha256_bytes(&[&self.inner_pad, message, &block_index.to_be_bytes()]);
+        hashv_impl_sha256_bytes(&[&self.outer_pad, &inner])
+    }
+}
+
+#[inline(always)]
+fn salsa_quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+    x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+    x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+    x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+}
+
+fn salsa20_8(block: &mut [u32; 16]) {
+    let mut x = *block;
+    for _ in 0..4 {
+        salsa_quarter_round(&mut x, 0, 4, 8, 12);
+        salsa_quarter_round(&mut x, 5, 9, 13, 1);
+        salsa_quarter_round(&mut x, 10, 14, 2, 6);
+        salsa_quarter_round(&mut x, 15, 3, 7, 11);
+        salsa_quarter_round(&mut x, 0, 1, 2, 3);
+        salsa_quarter_round(&mut x, 5, 6, 7, 4);
+        salsa_quarter_round(&mut x, 10, 11, 8, 9);
+        salsa_quarter_round(&mut x, 15, 12, 13, 14);
+    }
+    for (word, mixed) in block.iter_mut().zip(x) {
+        *word = word.wrapping_add(mixed);
+    }
+}
+
+// BlockMix with r = 1, the output halves need no reordering
+fn block_mix(block: &mut [u32; SCRYPT_BLOCK_WORDS]) {
+    let mut x = [0u32; 16];
+    x.copy_from_slice(&block[16..]);
+    for half in 0..2 {
+        for (word, input) in x.iter_mut().zip(&block[half * 16..half * 16 + 16]) {
+            *word ^= input;
+        }
+        salsa20_8(&mut x);
+        block[half * 16..half * 16 + 16].copy_from_slice(&x);
+    }
+}
+
+pub fn hash_impl_doge_scrypt_bytes(input: &[u8]) -> QHash256 {
+    let hmac = HmacSha256::new(input);
+
+    let mut block = [0u32; SCRYPT_BLOCK_WORDS];
+    for (i, chunk) in block.chunks_mut(8).enumerate() {
+        let derived = hmac.mac(input, i as u32 + 1);
+        for (word, bytes) in chunk.iter_mut().zip(derived.chunks(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+    }
+
+    let mut scratchpad = vec![[0u32; SCRYPT_BLOCK_WORDS]; SCRYPT_N];
+    for entry in scratchpad.iter_mut() {
+        *entry = block;
+        block_mix(&mut block);
+    }
+    for _ in 0..SCRYPT_N {
+        let entry = &scratchpad[block[16] as usize & (SCRYPT_N - 1)];
+        for (word, mixed) in block.iter_mut().zip(entry) {
+            *word ^= mixed;
+        }
+        block_mix(&mut block);
+    }
+
+    let mut mixed_bytes = [0u8; SCRYPT_BLOCK_WORDS * 4];
+    for (bytes, word) in mixed_bytes.chunks_mut(4).zip(block) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    hmac.mac(&mixed_bytes, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrypt_dogecoin_genesis() {
+        let header = hex_literal::hex!(
+            "01000000000000000000000000000000000000000000000000000000000000000000000069"
+            "6ad20e2dd4365c7459b4a4a5af743d5e92c6da3229e6532cd605f6533f2a5b24a6a152f0ff0f1e67860100"
+        );
+        assert_eq!(
+            hash_impl_doge_scrypt_bytes(&header),
+            hex_literal::hex!("48b41053487d4159cfaacf3adad783cf2f2dedea1413250cca74783f6f020000")
+        );
+    }
+}
@@ -0,0 +1,558 @@
+use crate::{
+    common_types::{QHash160, QHash256},
+    crypto::hash::sha256::btc_hash256_bytes,
+    dogecoin::encoding::{write_compact_size, write_var_bytes, DogeByteReader},
+    error::{DogeBridgeError, QDogeResult},
+};
+
+pub const DOGE_SIGHASH_ALL: u32 = 0x01;
+pub const DOGE_SIGHASH_NONE: u32 = 0x02;
+pub const DOGE_SIGHASH_SINGLE: u32 = 0x03;
+pub const DOGE_SIGHASH_ANYONECANPAY: u32 = 0x80;
+
+const OP_DUP: u8 = 0x76;
+const OP_HASH160: u8 = 0xa9;
+const OP_EQUAL: u8 = 0x87;
+const OP_EQUALVERIFY: u8 = 0x88;
+const OP_CHECKSIG: u8 = 0xac;
+const OP_CODESEPARATOR: u8 = 0xab;
+const OP_PUSHDATA1: u8 = 0x4c;
+const OP_PUSHDATA2: u8 = 0x4d;
+const OP_PUSHDATA4: u8 = 0x4e;
+
+// the hash dogecoind signs when a SIGHASH_SINGLE input has no output at its index
+pub const DOGE_SIGHASH_SINGLE_WITHOUT_OUTPUT_HASH: QHash256 = {
+    let mut hash = [0u8; 32];
+    hash[0] = 1;
+    hash
+};
+
+pub fn get_p2pkh_script_pubkey(public_key_hash: &QHash160) -> Vec<u8> {
+    [&[OP_DUP, OP_HASH160, 20][..], public_key_hash, &[OP_EQUALVERIFY, OP_CHECKSIG]].concat()
+}
+
+pub fn get_p2sh_script_pubkey(script_hash: &QHash160) -> Vec<u8> {
+    [&[OP_HASH160, 20][..], script_hash, &[OP_EQUAL]].concat()
+}
+
+#[derive(PartialEq, Clone, Copy, Debug, Eq, Hash, Default)]
+pub struct DogeOutPoint {
+    pub txid: QHash256,
+    pub vout: u32,
+}
+
+#[derive(PartialEq, Clone, Debug, Eq, Hash, Default)]
+pub struct DogeTxIn {
+    pub previous_output: DogeOutPoint,
+    pub script_sig: Vec<u8>,
+    pub sequence: u32,
+}
+
+#[derive(PartialEq, Clone, Debug, Eq, Hash, Default)]
+pub struct DogeTxOut {
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+// a transaction in dogecoin's (pre-segwit) wire format
+#[derive(PartialEq, Clone, Debug, Eq, Hash, Default)]
+pub struct DogeTransaction {
+    pub version: u32,
+    pub inputs: Vec<DogeTxIn>,
+    pub outputs: Vec<DogeTxOut>,
+    pub lock_time: u32,
+}
+
+fn write_out_point(out: &mut Vec<u8>, out_point: &DogeOutPoint) {
+    out.extend_from_slice(&out_point.txid);
+    out.extend_from_slice(&out_point.vout.to_le_bytes());
+}
+
+fn write_tx_out(out: &mut Vec<u8>, tx_out: &DogeTxOut) {
+    out.extend_from_slice(&tx_out.value.to_le_bytes());
+    write_var_bytes(out, &tx_out.script_pubkey);
+}
+
+// the script code as CTransactionSignatureSerializer writes it, with every OP_CODESEPARATOR removed.
+// if a push runs past the end of the script the rest is copied as is.
+fn write_script_code(out: &mut Vec<u8>, script_code: &[u8]) {
+    let mut stripped = Vec::with_capacity(script_code.len());
+    let mut position = 0;
+    while position < script_code.len() {
+        let opcode = script_code[position];
+        let push_len = match opcode {
+            0x01..=0x4b => Some((1, opcode as usize)),
+            OP_PUSHDATA1 => script_code.get(position + 1).map(|len| (2, *len as usize)),
+            OP_PUSHDATA2 => script_code
+                .get(position + 1..position + 3)
+                .map(|len| (3, u16::from_le_bytes([len[0], len[1]]) as usize)),
+            OP_PUSHDATA4 => script_code
+                .get(position + 1..position + 5)
+                .map(|len| (5, u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize)),
+            _ => Some((1, 0)),
+        };
+        let op_len = match push_len {
+            Some((prefix_len, data_len)) if position + prefix_len + data_len <= script_code.len() => prefix_len + data_len,
+            _ => {
+                stripped.extend_from_slice(&script_code[position..]);
+                break;
+            }
+        };
+        if opcode != OP_CODESEPARATOR {
+            stripped.extend_from_slice(&script_code[position..position + op_len]);
+        }
+        position += op_len;
+    }
+    write_var_bytes(out, &stripped);
+}
+
+impl DogeTransaction {
+    pub fn read_from(reader: &mut DogeByteReader) -> QDogeResult<Self> {
+        let version = reader.read_u32_le()?;
+        let input_count = reader.read_compact_size()? as usize;
+        // every input takes at least 41 bytes, so a bogus count can't reserve more than the data holds
+        if input_count > reader.remaining() / 41 {
+            return Err(DogeBridgeError::DeserializationError);
+        }
+        let mut inputs = Vec::with_capacity(input_count);
+        for _ in 0..input_count {
+            inputs.push(DogeTxIn {
+                previous_output: DogeOutPoint {
+                    txid: reader.read_hash()?,
+                    vout: reader.read_u32_le()?,
+                },
+                script_sig: reader.read_var_bytes()?.to_vec(),
+                sequence: reader.read_u32_le()?,
+            });
+        }
+        let output_count = reader.read_compact_size()? as usize;
+        if output_count > reader.remaining() / 9 {
+            return Err(DogeBridgeError::DeserializationError);
+        }
+        let mut outputs = Vec::with_capacity(output_count);
+        for _ in 0..output_count {
+            outputs.push(DogeTxOut {
+                value: reader.read_u64_le()?,
+                script_pubkey: reader.read_var_bytes()?.to_vec(),
+            });
+        }
+        Ok(Self {
+            version,
+            inputs,
+            outputs,
+            lock_time: reader.read_u32_le()?,
+        })
+    }
+
+    // the bytes must hold exactly one transaction
+    pub fn from_bytes(bytes: &[u8]) -> QDogeResult<Self> {
+        let mut reader = DogeByteReader::new(bytes);
+        let tx = Self::read_from(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(DogeBridgeError::DeserializationError);
+        }
+        Ok(tx)
+    }
+
+    pub fn write_to(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.version.to_le_bytes());
+        write_compact_size(out, self.inputs.len() as u64);
+        for input in self.inputs.iter() {
+            write_out_point(out, &input.previous_output);
+            write_var_bytes(out, &input.script_sig);
+            out.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+        write_compact_size(out, self.outputs.len() as u64);
+        for output in self.outputs.iter() {
+            write_tx_out(out, output);
+        }
+        out.extend_from_slice(&self.lock_time.to_le_bytes());
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write_to(&mut bytes);
+        bytes
+    }
+
+    // the txid in internal byte order, reverse it for the hex shown by explorers
+    pub fn get_txid(&self) -> QHash256 {
+        btc_hash256_bytes(&self.to_bytes())
+    }
+
+    // the data a legacy signature for input_index commits to. For a P2PKH input script_code is the spent output's
+    // script_pubkey, for a P2SH input it is the redeem script.
+    pub fn get_legacy_sighash_preimage(&self, input_index: usize, script_code: &[u8], sighash_type: u32) -> QDogeResult<Vec<u8>> {
+        if input_index >= self.inputs.len() {
+            return Err(DogeBridgeError::DogeTxInputIndexOutOfRange);
+        }
+        let base_type = sighash_type & 0x1f;
+        let anyone_can_pay = sighash_type & DOGE_SIGHASH_ANYONECANPAY != 0;
+        if base_type == DOGE_SIGHASH_SINGLE && input_index >= self.outputs.len() {
+            return Err(DogeBridgeError::DogeTxSighashSingleWithoutOutput);
+        }
+
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(&self.version.to_le_bytes());
+
+        // ANYONECANPAY only commits to the input being signed
+        let signed_inputs = if anyone_can_pay { input_index..input_index + 1 } else { 0..self.inputs.len() };
+        write_compact_size(&mut preimage, signed_inputs.len() as u64);
+        for i in signed_inputs {
+            let input = &self.inputs[i];
+            write_out_point(&mut preimage, &input.previous_output);
+            if i == input_index {
+                write_script_code(&mut preimage, script_code);
+            } else {
+                write_compact_size(&mut preimage, 0);
+            }
+            // NONE and SINGLE let the other inputs update their sequence
+            let sequence = if i != input_index && (base_type == DOGE_SIGHASH_NONE || base_type == DOGE_SIGHASH_SINGLE) {
+                0
+            } else {
+                input.sequence
+            };
+            preimage.extend_from_slice(&sequence.to_le_bytes());
+        }
+
+        match base_type {
+            DOGE_SIGHASH_NONE => write_compact_size(&mut preimage, 0),
+            DOGE_SIGHASH_SINGLE => {
+                // outputs before ours are blanked to value -1 with an empty script
+                write_compact_size(&mut preimage, input_index as u64 + 1);
+                for _ in 0..input_index {
+                    write_tx_out(&mut preimage, &DogeTxOut { value: u64::MAX, script_pubkey: Vec::new() });
+                }
+                write_tx_out(&mut preimage, &self.outputs[input_index]);
+            }
+            _ => {
+                write_compact_size(&mut preimage, self.outputs.len() as u64);
+                for output in self.outputs.iter() {
+                    write_tx_out(&mut preimage, output);
+                }
+            }
+        }
+
+        preimage.extend_from_slice(&self.lock_time.to_le_bytes());
+        preimage.extend_from_slice(&sighash_type.to_le_bytes());
+        Ok(preimage)
+    }
+
+    // the digest a legacy ecdsa signature for input_index signs
+    pub fn get_legacy_sighash(&self, input_index: usize, script_code: &[u8], sighash_type: u32) -> QDogeResult<QHash256> {
+        match self.get_legacy_sighash_preimage(input_index, script_code, sighash_type) {
+            Ok(preimage) => Ok(btc_hash256_bytes(&preimage)),
+            Err(DogeBridgeError::DogeTxSighashSingleWithoutOutput) => Ok(DOGE_SIGHASH_SINGLE_WITHOUT_OUTPUT_HASH),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the coinbase of dogecoin's genesis block, its txid is the genesis block's merkle root
+    const DOGE_GENESIS_COINBASE_TX: [u8; 143] = hex_literal::hex!(
+        "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff1004ffff001d0104084e696e746f6e"
+        "646fffffffff010058850c020000004341040184710fa689ad5023690c80f3a49c8f13f8d45b8c857fbcbc8bc4a8e4d3eb4b10f4d4604fa0"
+        "8dce601aaf0f470216fe1b51850b4acf21b179c45070ac7b03a9ac00000000"
+    );
+
+    // bitcoin's f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16 from block 170, a pay-to-pubkey spend.
+    // it is not a dogecoin transaction, but dogecoin kept bitcoin's legacy format and sighash, so a signature someone
+    // else produced is an independent check of our sighash.
+    const BTC_PAY_TO_PUBKEY_SPEND_TX: [u8; 275] = hex_literal::hex!(
+        "0100000001c997a5e56e104102fa209c6a852dd90660a20b2d9c352423edce25857fcd3704000000004847304402204e45e16932b8af5149"
+        "61a1d3a1a25fdf3f4f7732e9d624c6c61548ab5fb8cd410220181522ec8eca07de4860a4acdd12909d831cc56cbbac4622082221a8768d1d"
+        "0901ffffffff0200ca9a3b00000000434104ae1a62fe09c5f51b13905f07f06b99a2f7159b2225f374cd378d71302fa28414e7aab37397f5"
+        "54a7df5f142c21c1b7303b8a0626f1baded5c72a704f7e6cd84cac00286bee0000000043410411db93e1dcdb8a016b49840f8c53bc1eb68a"
+        "382e97b1482ecad7b148a6909a5cb2e0eaddfb84ccf9744464f82e160bfa9b8b64f9d4c03f999b8643f656b412a3ac00000000"
+    );
+
+    fn display_hash(mut hash: QHash256) -> QHash256 {
+        hash.reverse();
+        hash
+    }
+
+    // the data pushed by a push-only script sig, OP_0 pushes nothing
+    #[cfg(all(feature = "std", feature = "secp256k1"))]
+    fn read_pushes(script: &[u8]) -> Vec<Vec<u8>> {
+        let mut reader = DogeByteReader::new(script);
+        let mut pushes = Vec::new();
+        while !reader.is_empty() {
+            let len = match reader.read_u8().unwrap() {
+                OP_PUSHDATA1 => reader.read_u8().unwrap() as usize,
+                opcode @ 0x00..=0x4b => opcode as usize,
+                opcode => panic!("unexpected opcode {opcode:#x} in script sig"),
+            };
+            pushes.push(reader.read_bytes(len).unwrap().to_vec());
+        }
+        pushes
+    }
+
+    // checks a der signature with its trailing sighash type byte the way the script interpreter does
+    #[cfg(feature = "secp256k1")]
+    fn verify_script_signature(public_key: &[u8], signature: &[u8], sighash: QHash256) {
+        use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+        let verifying_key = VerifyingKey::from_sec1_bytes(public_key).unwrap();
+        let signature = Signature::from_der(&signature[..signature.len() - 1]).unwrap();
+        let signature = signature.normalize_s().unwrap_or(signature);
+        verifying_key.verify_prehash(&sighash, &signature).unwrap();
+    }
+
+    // spends `input_count` previous outputs with empty script sigs for the caller to sign
+    #[cfg(all(feature = "std", feature = "secp256k1"))]
+    fn unsigned_spend(input_count: u8) -> DogeTransaction {
+        DogeTransaction {
+            version: 1,
+            inputs: (0..input_count)
+                .map(|i| DogeTxIn {
+                    previous_output: DogeOutPoint { txid: [0x40 + i; 32], vout: i as u32 },
+                    script_sig: Vec::new(),
+                    sequence: 0xffff_ffff,
+                })
+                .collect(),
+            outputs: vec![DogeTxOut { value: 42 * 100_000_000, script_pubkey: get_p2pkh_script_pubkey(&[0x33; 20]) }],
+            lock_time: 0,
+        }
+    }
+
+    // two inputs and three outputs so every sighash type commits to something different
+    fn test_tx() -> DogeTransaction {
+        DogeTransaction {
+            version: 1,
+            inputs: (0..2u8)
+                .map(|i| DogeTxIn {
+                    previous_output: DogeOutPoint { txid: [i + 1; 32], vout: i as u32 },
+                    script_sig: vec![i; 3],
+                    sequence: 0xffff_fffe - i as u32,
+                })
+                .collect(),
+            outputs: (0..3u8)
+                .map(|i| DogeTxOut {
+                    value: 100_000_000 * (i as u64 + 1),
+                    script_pubkey: get_p2pkh_script_pubkey(&[i; 20]),
+                })
+                .collect(),
+            lock_time: 5_000_000,
+        }
+    }
+
+    #[test]
+    fn test_transactions_round_trip() {
+        let coinbase = DogeTransaction::from_bytes(&DOGE_GENESIS_COINBASE_TX).unwrap();
+        assert_eq!(coinbase.to_bytes(), DOGE_GENESIS_COINBASE_TX);
+        assert_eq!(coinbase.inputs.len(), 1);
+        assert_eq!(coinbase.inputs[0].script_sig[8..], *b"Nintondo");
+        assert_eq!(coinbase.outputs[0].value, 88 * 100_000_000);
+        assert_eq!(
+            display_hash(coinbase.get_txid()),
+            hex_literal::hex!("5b2a3f53f605d62c53e62932dac6925e3d74afa5a4b459745c36d42d0ed26a69")
+        );
+
+        let spend = DogeTransaction::from_bytes(&BTC_PAY_TO_PUBKEY_SPEND_TX).unwrap();
+        assert_eq!(spend.to_bytes(), BTC_PAY_TO_PUBKEY_SPEND_TX);
+        assert_eq!(spend.outputs.iter().map(|o| o.value).collect::<Vec<_>>(), vec![1_000_000_000, 4_000_000_000]);
+        assert_eq!(
+            display_hash(spend.get_txid()),
+            hex_literal::hex!("f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16")
+        );
+
+        // truncated or trailing data
+        assert!(DogeTransaction::from_bytes(&BTC_PAY_TO_PUBKEY_SPEND_TX[..274]).is_err());
+        assert!(DogeTransaction::from_bytes(&[&BTC_PAY_TO_PUBKEY_SPEND_TX[..], &[0]].concat()).is_err());
+    }
+
+    #[test]
+    fn test_legacy_sighash_all_matches_pay_to_pubkey_signature() {
+        let spend = DogeTransaction::from_bytes(&BTC_PAY_TO_PUBKEY_SPEND_TX).unwrap();
+        // the spent output paid to the same key as the change output
+        let script_code = spend.outputs[1].script_pubkey.clone();
+        let sighash = spend.get_legacy_sighash(0, &script_code, DOGE_SIGHASH_ALL).unwrap();
+        assert_eq!(sighash, hex_literal::hex!("7a05c6145f10101e9d6325494245adf1297d80f8f38d4d576d57cdba220bcb19"));
+        assert_eq!(
+            spend.get_legacy_sighash(1, &script_code, DOGE_SIGHASH_ALL),
+            Err(DogeBridgeError::DogeTxInputIndexOutOfRange)
+        );
+
+        // <pubkey> OP_CHECKSIG is spent by a single signature push
+        #[cfg(feature = "secp256k1")]
+        {
+            let signature = &spend.inputs[0].script_sig[1..];
+            assert_eq!(spend.inputs[0].script_sig[0] as usize, signature.len());
+            assert_eq!(*signature.last().unwrap() as u32, DOGE_SIGHASH_ALL);
+            verify_script_signature(&script_code[1..66], signature, sighash);
+        }
+    }
+
+    #[cfg(all(feature = "std", feature = "secp256k1"))]
+    #[test]
+    fn test_p2pkh_spend_signature_matches_sighash() {
+        use crate::crypto::{
+            hash::ripemd160_impl::hash_impl_btc_hash160_bytes,
+            secp256k1::{memory_wallet::MemorySecp256K1Wallet, Secp256K1WalletProvider},
+        };
+
+        let mut wallet = MemorySecp256K1Wallet::new();
+        let public_key = wallet.add_private_key([0x21; 32]).unwrap();
+        let spent_script = get_p2pkh_script_pubkey(&public_key.to_p2pkh_address());
+
+        let mut tx = unsigned_spend(1);
+        let sighash = tx.get_legacy_sighash(0, &spent_script, DOGE_SIGHASH_ALL).unwrap();
+        tx.inputs[0].script_sig = wallet.sign(&public_key, sighash).unwrap().to_btc_script();
+
+        let bytes = tx.to_bytes();
+        let spend = DogeTransaction::from_bytes(&bytes).unwrap();
+        assert_eq!(spend, tx);
+        assert_eq!(spend.to_bytes(), bytes);
+        assert_eq!(spend.get_txid(), btc_hash256_bytes(&bytes));
+
+        // <signature> <pubkey>, where the pubkey hashes to the one the spent output pays to
+        let pushes = read_pushes(&spend.inputs[0].script_sig);
+        assert_eq!(pushes.len(), 2);
+        assert_eq!(get_p2pkh_script_pubkey(&hash_impl_btc_hash160_bytes(&pushes[1])), spent_script);
+        let hash_type = *pushes[0].last().unwrap() as u32;
+        assert_eq!(hash_type, DOGE_SIGHASH_ALL);
+        verify_script_signature(&pushes[1], &pushes[0], spend.get_legacy_sighash(0, &spent_script, hash_type).unwrap());
+    }
+
+    #[cfg(all(feature = "std", feature = "secp256k1"))]
+    #[test]
+    fn test_p2sh_multisig_spend_signatures_match_sighash() {
+        use crate::crypto::{
+            hash::ripemd160_impl::hash_impl_btc_hash160_bytes,
+            secp256k1::{memory_wallet::MemorySecp256K1Wallet, Secp256K1WalletProvider},
+        };
+
+        let mut wallet = MemorySecp256K1Wallet::new();
+        let public_keys = [0x31u8, 0x32, 0x33].map(|key| wallet.add_private_key([key; 32]).unwrap());
+        // OP_2 <pubkey> <pubkey> <pubkey> OP_3 OP_CHECKMULTISIG
+        let redeem_script = [
+            vec![0x52],
+            public_keys.iter().flat_map(|public_key| [&[0x21][..], &public_key.0].concat()).collect(),
+            vec![0x53, 0xae],
+        ]
+        .concat();
+        let spent_script = get_p2sh_script_pubkey(&hash_impl_btc_hash160_bytes(&redeem_script));
+
+        // two inputs locked by the same script still sign different hashes
+        let mut tx = unsigned_spend(2);
+        let sighashes: Vec<QHash256> =
+            (0..2).map(|i| tx.get_legacy_sighash(i, &redeem_script, DOGE_SIGHASH_ALL).unwrap()).collect();
+        assert_ne!(sighashes[0], sighashes[1]);
+        for (input, sighash) in tx.inputs.iter_mut().zip(&sighashes) {
+            // OP_0 <signature> <signature> OP_PUSHDATA1 <redeem script>, signed by the first and last key
+            let mut script_sig = vec![0x00];
+            for public_key in [&public_keys[0], &public_keys[2]] {
+                let signature = wallet.sign(public_key, *sighash).unwrap().to_btc_script();
+                script_sig.extend_from_slice(&signature[..signature.len() - 34]);
+            }
+            script_sig.extend_from_slice(&[OP_PUSHDATA1, redeem_script.len() as u8]);
+            script_sig.extend_from_slice(&redeem_script);
+            input.script_sig = script_sig;
+        }
+
+        let bytes = tx.to_bytes();
+        let spend = DogeTransaction::from_bytes(&bytes).unwrap();
+        assert_eq!(spend, tx);
+        assert_eq!(spend.to_bytes(), bytes);
+        assert_eq!(spend.get_txid(), btc_hash256_bytes(&bytes));
+
+        for (i, input) in spend.inputs.iter().enumerate() {
+            let pushes = read_pushes(&input.script_sig);
+            assert_eq!(pushes.len(), 4);
+            assert!(pushes[0].is_empty());
+            let script_code = &pushes[3];
+            assert_eq!(get_p2sh_script_pubkey(&hash_impl_btc_hash160_bytes(script_code)), spent_script);
+            for (signature, key_index) in pushes[1..3].iter().zip([0, 2]) {
+                let hash_type = *signature.last().unwrap() as u32;
+                assert_eq!(hash_type, DOGE_SIGHASH_ALL);
+                let public_key = &script_code[2 + 34 * key_index..35 + 34 * key_index];
+                verify_script_signature(public_key, signature, spend.get_legacy_sighash(i, script_code, hash_type).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_legacy_sighash_types() {
+        let tx = test_tx();
+        let redeem_script = [&[0x51, 0x21][..], &[2u8; 33], &[0x51, 0xae]].concat();
+
+        // ALL signs a copy of the transaction with only our input's script set to the script code
+        let mut expected = tx.clone();
+        expected.inputs[0].script_sig.clear();
+        expected.inputs[1].script_sig = redeem_script.clone();
+        let expected_preimage = [expected.to_bytes(), DOGE_SIGHASH_ALL.to_le_bytes().to_vec()].concat();
+        assert_eq!(tx.get_legacy_sighash_preimage(1, &redeem_script, DOGE_SIGHASH_ALL), Ok(expected_preimage.clone()));
+        assert_eq!(tx.get_legacy_sighash(1, &redeem_script, DOGE_SIGHASH_ALL), Ok(btc_hash256_bytes(&expected_preimage)));
+
+        // NONE drops the outputs and the other inputs' sequences
+        let mut expected_none = expected.clone();
+        expected_none.outputs.clear();
+        expected_none.inputs[0].sequence = 0;
+        assert_eq!(
+            tx.get_legacy_sighash_preimage(1, &redeem_script, DOGE_SIGHASH_NONE),
+            Ok([expected_none.to_bytes(), DOGE_SIGHASH_NONE.to_le_bytes().to_vec()].concat())
+        );
+
+        // SINGLE keeps the output at our index and blanks the ones before it
+        let mut expected_single = expected.clone();
+        expected_single.inputs[0].sequence = 0;
+        expected_single.outputs.truncate(2);
+        expected_single.outputs[0] = DogeTxOut { value: u64::MAX, script_pubkey: Vec::new() };
+        assert_eq!(
+            tx.get_legacy_sighash_preimage(1, &redeem_script, DOGE_SIGHASH_SINGLE),
+            Ok([expected_single.to_bytes(), DOGE_SIGHASH_SINGLE.to_le_bytes().to_vec()].concat())
+        );
+
+        // ANYONECANPAY only keeps our input
+        let mut expected_anyone_can_pay = expected.clone();
+        expected_anyone_can_pay.inputs.remove(0);
+        let sighash_type = DOGE_SIGHASH_ALL | DOGE_SIGHASH_ANYONECANPAY;
+        assert_eq!(
+            tx.get_legacy_sighash_preimage(1, &redeem_script, sighash_type),
+            Ok([expected_anyone_can_pay.to_bytes(), sighash_type.to_le_bytes().to_vec()].concat())
+        );
+
+        let sighashes = [DOGE_SIGHASH_ALL, DOGE_SIGHASH_NONE, DOGE_SIGHASH_SINGLE, sighash_type]
+            .map(|sighash_type| tx.get_legacy_sighash(1, &redeem_script, sighash_type).unwrap());
+        for i in 0..sighashes.len() {
+            for j in i + 1..sighashes.len() {
+                assert_ne!(sighashes[i], sighashes[j]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_legacy_sighash_single_without_output() {
+        let mut tx = test_tx();
+        tx.outputs.truncate(1);
+        let script_code = get_p2pkh_script_pubkey(&[7u8; 20]);
+        assert_eq!(
+            tx.get_legacy_sighash_preimage(1, &script_code, DOGE_SIGHASH_SINGLE),
+            Err(DogeBridgeError::DogeTxSighashSingleWithoutOutput)
+        );
+        assert_eq!(
+            tx.get_legacy_sighash(1, &script_code, DOGE_SIGHASH_SINGLE),
+            Ok(DOGE_SIGHASH_SINGLE_WITHOUT_OUTPUT_HASH)
+        );
+        assert!(tx.get_legacy_sighash(0, &script_code, DOGE_SIGHASH_SINGLE).unwrap() != DOGE_SIGHASH_SINGLE_WITHOUT_OUTPUT_HASH);
+    }
+
+    #[test]
+    fn test_script_code_drops_code_separators() {
+        let tx = test_tx();
+        let p2pkh = get_p2pkh_script_pubkey(&[9u8; 20]);
+        assert_eq!(p2pkh.len(), 25);
+        assert_eq!(get_p2sh_script_pubkey(&[9u8; 20]).len(), 23);
+
+        // a code separator inside pushed data is kept, the opcode itself is dropped
+        let with_separators = [&[OP_CODESEPARATOR, 0x01, OP_CODESEPARATOR][..], &p2pkh, &[OP_CODESEPARATOR]].concat();
+        let without_separators = [&[0x01, OP_CODESEPARATOR][..], &p2pkh].concat();
+        assert_eq!(
+            tx.get_legacy_sighash(0, &with_separators, DOGE_SIGHASH_ALL),
+            tx.get_legacy_sighash(0, &without_separators, DOGE_SIGHASH_ALL)
+        );
+    }
+}
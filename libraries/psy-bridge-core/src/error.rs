@@ -302,6 +302,161 @@ pub enum DogeBridgeError {
 
     #[error("Invalid Doge transaction hash")]
     InvalidDogeTxHash = 952,
+
+    #[error("Bridge is paused")]
+    BridgePaused = 953,
+    #[error("Signer is not the bridge guardian")]
+    UnauthorizedGuardian = 954,
+    #[error("Operation is disabled by the bridge control mode")]
+    OperationDisabled = 955,
+    #[error("Bridge control mode contains unknown flags")]
+    InvalidBridgeControlMode = 956,
+
+    #[error("No pending bridge config update")]
+    NoPendingConfigUpdate = 957,
+    #[error("Bridge config update timelock has not elapsed")]
+    ConfigUpdateTimelockNotElapsed = 958,
+    #[error("Invalid bridge config parameters")]
+    InvalidBridgeConfig = 959,
+    #[error("Bridge config update delay is shorter than the minimum")]
+    ConfigUpdateDelayTooShort = 960,
+
+    #[error("Signer is not allowed to rotate this key")]
+    UnauthorizedKeyRotation = 961,
+    #[error("No pending key rotation for this signer")]
+    NoPendingKeyRotation = 962,
+    #[error("Invalid key role")]
+    InvalidKeyRole = 963,
+    #[error("Bridge admin key is not configured")]
+    AdminNotConfigured = 964,
+
+    #[error("Invalid verifier key kind")]
+    InvalidVerifierKeyKind = 965,
+    #[error("Signer is not allowed to update verifier keys")]
+    UnauthorizedVerifierKeyUpdate = 966,
+    #[error("No pending verifier key update")]
+    NoPendingVerifierKeyUpdate = 967,
+    #[error("Verifier key update timelock has not elapsed")]
+    VerifierKeyUpdateTimelockNotElapsed = 968,
+    #[error("Invalid verifier key registry account")]
+    InvalidVerifierKeyRegistry = 969,
+
+    #[error("Withdrawal exceeds the per-request limit")]
+    WithdrawalExceedsPerRequestLimit = 970,
+    #[error("Withdrawal volume limit for the current window exceeded")]
+    WithdrawalWindowLimitExceeded = 971,
+    #[error("Signer is not allowed to update withdrawal limits")]
+    UnauthorizedWithdrawalLimitUpdate = 972,
+    #[error("Invalid withdrawal limits")]
+    InvalidWithdrawalLimits = 973,
+
+    #[error("Withdrawal amount after fees is below the minimum withdrawal")]
+    WithdrawalBelowMinimum = 974,
+    #[error("Deposit amount is below the minimum deposit")]
+    DepositBelowMinimum = 975,
+
+    #[error("Withdrawal has not been processed yet")]
+    WithdrawalNotYetProcessed = 976,
+    #[error("Withdrawal receipt must be fulfilled or cancelled before it can be closed")]
+    WithdrawalReceiptNotFulfilled = 977,
+    #[error("Only the requester can close a withdrawal receipt")]
+    UnauthorizedWithdrawalReceiptClose = 978,
+
+    #[error("Withdrawal request is already covered by a withdrawal snapshot")]
+    WithdrawalAlreadySnapshotted = 979,
+    #[error("Withdrawal request is not pending")]
+    WithdrawalNotCancellable = 980,
+    #[error("Only the requester can cancel a withdrawal request")]
+    UnauthorizedWithdrawalCancel = 981,
+    #[error("Withdrawal fee to refund has already been withdrawn")]
+    WithdrawalFeeAlreadyWithdrawn = 982,
+
+    #[error("Invalid fee split recipients or shares")]
+    InvalidFeeSplit = 983,
+    #[error("Only the fee spender can update the fee split")]
+    UnauthorizedFeeSplitUpdate = 984,
+    #[error("Fees are distributed through the fee split")]
+    FeesDistributedByFeeSplit = 985,
+
+    #[error("Invalid finalized block history capacity")]
+    InvalidFinalizedBlockHistoryCapacity = 986,
+    #[error("Unauthorized finalized block history update")]
+    UnauthorizedFinalizedBlockHistoryUpdate = 987,
+    #[error("Finalized block history already enabled")]
+    FinalizedBlockHistoryAlreadyEnabled = 988,
+    #[error("Invalid finalized block history account")]
+    InvalidFinalizedBlockHistoryAccount = 989,
+
+    #[error("Block is not finalized yet")]
+    TxInclusionBlockNotFinalized = 990,
+    #[error("Block header is not in the finalized block merkle tree")]
+    InvalidBlockInclusionProof = 991,
+    #[error("Transaction is not in the block merkle root")]
+    InvalidTxInclusionProof = 992,
+    #[error("Transaction does not have enough confirmations")]
+    InsufficientTxConfirmations = 993,
+
+    #[error("Only the settings authority can enable the block hash archive")]
+    UnauthorizedBlockHashArchiveUpdate = 994,
+    #[error("Block hash archive is already enabled")]
+    BlockHashArchiveAlreadyEnabled = 995,
+    #[error("Invalid block hash archive account")]
+    InvalidBlockHashArchiveAccount = 996,
+
+    #[error("Bridge state already uses the current layout version")]
+    BridgeStateVersionUpToDate = 997,
+    #[error("Unsupported bridge state layout version")]
+    UnsupportedBridgeStateVersion = 998,
+
+    #[error("Custodian wallet config does not match the custodian wallet config hash")]
+    CustodianWalletConfigHashMismatch = 999,
+
+    #[error("A custodian rotation is already scheduled")]
+    CustodianRotationAlreadyScheduled = 1000,
+    #[error("No custodian rotation is scheduled")]
+    NoCustodianRotationScheduled = 1001,
+    #[error("Custodian rotation timelock has not elapsed")]
+    CustodianRotationTimelockNotElapsed = 1002,
+    #[error("New custodian wallet config hash must be non-zero and differ from the current one")]
+    InvalidCustodianRotation = 1003,
+    #[error("Signer is not allowed to change the custodian rotation")]
+    UnauthorizedCustodianRotation = 1004,
+
+    #[error("Withdrawal batch is empty or exceeds the maximum batch size")]
+    InvalidWithdrawalBatchSize = 1005,
+
+    #[error("Unknown withdrawal address type")]
+    InvalidWithdrawalAddressType = 1006,
+
+    #[error("Withdrawal script is empty or exceeds the maximum script size")]
+    InvalidWithdrawalScript = 1007,
+    #[error("Script withdrawals must be requested together with their script")]
+    WithdrawalScriptRequired = 1008,
+
+    #[error("Transaction input index is out of range")]
+    DogeTxInputIndexOutOfRange = 1009,
+    #[error("SIGHASH_SINGLE input has no output at the same index")]
+    DogeTxSighashSingleWithoutOutput = 1010,
+
+    #[error("Dogecoin address contains a non base58 character")]
+    InvalidDogeAddressBase58 = 1011,
+    #[error("Dogecoin address checksum does not match")]
+    InvalidDogeAddressChecksum = 1012,
+    #[error("Dogecoin address payload is not 21 bytes")]
+    InvalidDogeAddressLength = 1013,
+    #[error("Dogecoin address version byte is not a known P2PKH or P2SH version")]
+    UnknownDogeAddressVersion = 1014,
+    #[error("Dogecoin address belongs to a different network")]
+    DogeAddressNetworkMismatch = 1015,
+
+    #[error("Signer is not allowed to update the bridge config")]
+    UnauthorizedConfigUpdate = 1016,
+
+    #[error("Transaction is empty or 64 bytes long and can't be told apart from a merkle node")]
+    InvalidTxInclusionTxSize = 1017,
+
+    #[error("Reorg block hashes do not lead from the previous to the new finalized block merkle tree root")]
+    InvalidReorgBlockHashes = 1018,
 }
 #[cfg(feature = "solprogram")]
 impl solana_program_error::ToStr for DogeBridgeError {
@@ -434,6 +589,115 @@ impl solana_program_error::ToStr for DogeBridgeError {
             DogeBridgeError::InvalidTxoBufferPDA => "Invalid txo buffer PDA",
             DogeBridgeError::CannotUnlockAfterAutoAdvance => "Cannot unlock pending mint buffer after auto advancing pending mint state",
             DogeBridgeError::InvalidDogeTxHash => "Invalid Doge transaction hash",
+
+            // Pause controls
+            DogeBridgeError::BridgePaused => "Bridge is paused",
+            DogeBridgeError::UnauthorizedGuardian => "Signer is not the bridge guardian",
+            DogeBridgeError::OperationDisabled => "Operation is disabled by the bridge control mode",
+            DogeBridgeError::InvalidBridgeControlMode => "Bridge control mode contains unknown flags",
+
+            // Config updates
+            DogeBridgeError::NoPendingConfigUpdate => "No pending bridge config update",
+            DogeBridgeError::ConfigUpdateTimelockNotElapsed => "Bridge config update timelock has not elapsed",
+            DogeBridgeError::InvalidBridgeConfig => "Invalid bridge config parameters",
+            DogeBridgeError::ConfigUpdateDelayTooShort => "Bridge config update delay is shorter than the minimum",
+
+            // Key rotation
+            DogeBridgeError::UnauthorizedKeyRotation => "Signer is not allowed to rotate this key",
+            DogeBridgeError::NoPendingKeyRotation => "No pending key rotation for this signer",
+            DogeBridgeError::InvalidKeyRole => "Invalid key role",
+            DogeBridgeError::AdminNotConfigured => "Bridge admin key is not configured",
+
+            // Verifier key registry
+            DogeBridgeError::InvalidVerifierKeyKind => "Invalid verifier key kind",
+            DogeBridgeError::UnauthorizedVerifierKeyUpdate => "Signer is not allowed to update verifier keys",
+            DogeBridgeError::NoPendingVerifierKeyUpdate => "No pending verifier key update",
+            DogeBridgeError::VerifierKeyUpdateTimelockNotElapsed => "Verifier key update timelock has not elapsed",
+            DogeBridgeError::InvalidVerifierKeyRegistry => "Invalid verifier key registry account",
+
+            // Withdrawal limits
+            DogeBridgeError::WithdrawalExceedsPerRequestLimit => "Withdrawal exceeds the per-request limit",
+            DogeBridgeError::WithdrawalWindowLimitExceeded => "Withdrawal volume limit for the current window exceeded",
+            DogeBridgeError::UnauthorizedWithdrawalLimitUpdate => "Signer is not allowed to update withdrawal limits",
+            DogeBridgeError::InvalidWithdrawalLimits => "Invalid withdrawal limits",
+
+            // Minimum amounts
+            DogeBridgeError::WithdrawalBelowMinimum => "Withdrawal amount after fees is below the minimum withdrawal",
+            DogeBridgeError::DepositBelowMinimum => "Deposit amount is below the minimum deposit",
+
+            // Withdrawal receipts
+            DogeBridgeError::WithdrawalNotYetProcessed => "Withdrawal has not been processed yet",
+            DogeBridgeError::WithdrawalReceiptNotFulfilled => "Withdrawal receipt must be fulfilled or cancelled before it can be closed",
+            DogeBridgeError::UnauthorizedWithdrawalReceiptClose => "Only the requester can close a withdrawal receipt",
+
+            // Withdrawal cancellation
+            DogeBridgeError::WithdrawalAlreadySnapshotted => "Withdrawal request is already covered by a withdrawal snapshot",
+            DogeBridgeError::WithdrawalNotCancellable => "Withdrawal request is not pending",
+            DogeBridgeError::UnauthorizedWithdrawalCancel => "Only the requester can cancel a withdrawal request",
+            DogeBridgeError::WithdrawalFeeAlreadyWithdrawn => "Withdrawal fee to refund has already been withdrawn",
+
+            // Fee split
+            DogeBridgeError::InvalidFeeSplit => "Invalid fee split recipients or shares",
+            DogeBridgeError::UnauthorizedFeeSplitUpdate => "Only the fee spender can update the fee split",
+            DogeBridgeError::FeesDistributedByFeeSplit => "Fees are distributed through the fee split",
+
+            // Finalized block history
+            DogeBridgeError::InvalidFinalizedBlockHistoryCapacity => "Invalid finalized block history capacity",
+            DogeBridgeError::UnauthorizedFinalizedBlockHistoryUpdate => "Unauthorized finalized block history update",
+            DogeBridgeError::FinalizedBlockHistoryAlreadyEnabled => "Finalized block history already enabled",
+            DogeBridgeError::InvalidFinalizedBlockHistoryAccount => "Invalid finalized block history account",
+
+            // Tx inclusion oracle
+            DogeBridgeError::TxInclusionBlockNotFinalized => "Block is not finalized yet",
+            DogeBridgeError::InvalidBlockInclusionProof => "Block header is not in the finalized block merkle tree",
+            DogeBridgeError::InvalidTxInclusionProof => "Transaction is not in the block merkle root",
+            DogeBridgeError::InsufficientTxConfirmations => "Transaction does not have enough confirmations",
+
+            // Block hash archive
+            DogeBridgeError::UnauthorizedBlockHashArchiveUpdate => "Only the settings authority can enable the block hash archive",
+            DogeBridgeError::BlockHashArchiveAlreadyEnabled => "Block hash archive is already enabled",
+            DogeBridgeError::InvalidBlockHashArchiveAccount => "Invalid block hash archive account",
+
+            // State migration
+            DogeBridgeError::BridgeStateVersionUpToDate => "Bridge state already uses the current layout version",
+            DogeBridgeError::UnsupportedBridgeStateVersion => "Unsupported bridge state layout version",
+
+            // Custodian wallet config
+            DogeBridgeError::CustodianWalletConfigHashMismatch => "Custodian wallet config does not match the custodian wallet config hash",
+
+            // Custodian rotation
+            DogeBridgeError::CustodianRotationAlreadyScheduled => "A custodian rotation is already scheduled",
+            DogeBridgeError::NoCustodianRotationScheduled => "No custodian rotation is scheduled",
+            DogeBridgeError::CustodianRotationTimelockNotElapsed => "Custodian rotation timelock has not elapsed",
+            DogeBridgeError::InvalidCustodianRotation => "New custodian wallet config hash must be non-zero and differ from the current one",
+            DogeBridgeError::UnauthorizedCustodianRotation => "Signer is not allowed to change the custodian rotation",
+
+            // Withdrawal batches
+            DogeBridgeError::InvalidWithdrawalBatchSize => "Withdrawal batch is empty or exceeds the maximum batch size",
+
+            // Withdrawal address types
+            DogeBridgeError::InvalidWithdrawalAddressType => "Unknown withdrawal address type",
+
+            // Script withdrawals
+            DogeBridgeError::InvalidWithdrawalScript => "Withdrawal script is empty or exceeds the maximum script size",
+            DogeBridgeError::WithdrawalScriptRequired => "Script withdrawals must be requested together with their script",
+
+            // Dogecoin transactions
+            DogeBridgeError::DogeTxInputIndexOutOfRange => "Transaction input index is out of range",
+            DogeBridgeError::DogeTxSighashSingleWithoutOutput => "SIGHASH_SINGLE input has no output at the same index",
+
+            // Dogecoin addresses
+            DogeBridgeError::InvalidDogeAddressBase58 => "Dogecoin address contains a non base58 character",
+            DogeBridgeError::InvalidDogeAddressChecksum => "Dogecoin address checksum does not match",
+            DogeBridgeError::InvalidDogeAddressLength => "Dogecoin address payload is not 21 bytes",
+            DogeBridgeError::UnknownDogeAddressVersion => "Dogecoin address version byte is not a known P2PKH or P2SH version",
+            DogeBridgeError::DogeAddressNetworkMismatch => "Dogecoin address belongs to a different network",
+            // Bridge config updates
+            DogeBridgeError::UnauthorizedConfigUpdate => "Signer is not allowed to update the bridge config",
+
+            DogeBridgeError::InvalidTxInclusionTxSize => "Transaction is empty or 64 bytes long and can't be told apart from a merkle node",
+
+            DogeBridgeError::InvalidReorgBlockHashes => "Reorg block hashes do not lead from the previous to the new finalized block merkle tree root",
         }
     }
 }
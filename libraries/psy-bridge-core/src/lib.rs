@@ -5,6 +5,8 @@ pub mod error;
 pub mod serde_arrays;
 pub mod header;
 pub mod custodian_config;
+#[cfg(any(feature = "std", feature = "sp1"))]
+pub mod dogecoin;
 use macro_rules_attribute::attribute_alias;
 
 // Define the alias for the entire block of attributes.
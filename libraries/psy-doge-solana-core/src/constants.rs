@@ -1 +1,72 @@
-pub const PSY_DOGE_BRIDGE_MAX_REORG_BLOCKS: usize = 10;
\ No newline at end of file
+pub const PSY_DOGE_BRIDGE_MAX_REORG_BLOCKS: usize = 10;
+
+// bridge_control_mode bits, a set bit disables the operation (0 = everything enabled)
+pub const BRIDGE_CONTROL_MODE_DISABLE_AUTO_MINTS: u32 = 1 << 0;
+pub const BRIDGE_CONTROL_MODE_DISABLE_MANUAL_CLAIMS: u32 = 1 << 1;
+pub const BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_REQUESTS: u32 = 1 << 2;
+pub const BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_PROCESSING: u32 = 1 << 3;
+pub const BRIDGE_CONTROL_MODE_DISABLE_FEE_WITHDRAWALS: u32 = 1 << 4;
+pub const BRIDGE_CONTROL_MODE_ALL_FLAGS: u32 = BRIDGE_CONTROL_MODE_DISABLE_AUTO_MINTS
+    | BRIDGE_CONTROL_MODE_DISABLE_MANUAL_CLAIMS
+    | BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_REQUESTS
+    | BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_PROCESSING
+    | BRIDGE_CONTROL_MODE_DISABLE_FEE_WITHDRAWALS;
+
+// pending_config_status values
+pub const PENDING_CONFIG_STATUS_NONE: u32 = 0;
+pub const PENDING_CONFIG_STATUS_PROPOSED: u32 = 1;
+// the pending config replaces config_params after the next successful block transition
+pub const PENDING_CONFIG_STATUS_QUEUED: u32 = 2;
+pub const MIN_CONFIG_UPDATE_DELAY_SECS: u32 = 60 * 60 * 24; // 1 day
+
+// a scheduled custodian rotation can only be swept after this delay, giving the guardian time to cancel it
+pub const CUSTODIAN_ROTATION_DELAY_SECS: u32 = 60 * 60 * 24 * 2; // 2 days
+
+// key roles for the nominate/accept rotation flow
+pub const BRIDGE_KEY_ROLE_OPERATOR: u32 = 0;
+pub const BRIDGE_KEY_ROLE_FEE_SPENDER: u32 = 1;
+pub const BRIDGE_KEY_ROLE_GUARDIAN: u32 = 2;
+
+// proof kinds tracked by the verifier key registry
+pub const VK_KIND_SINGLE_BLOCK_UPDATE: u32 = 0;
+pub const VK_KIND_BLOCK_REORG: u32 = 1;
+pub const VK_KIND_WITHDRAWAL: u32 = 2;
+pub const VK_KIND_MANUAL_CLAIM: u32 = 3;
+pub const VK_REGISTRY_KIND_COUNT: usize = 4;
+pub const VK_UPDATE_DELAY_SECS: u32 = 60 * 60 * 24 * 2; // 2 days
+// how long proofs made with the replaced vk are still accepted after an update activates
+pub const VK_ROLLOVER_WINDOW_SECS: u32 = 60 * 60 * 24; // 1 day
+
+// address_type of a tombstone leaf in requested_withdrawals_tree. its amount field holds the index of the
+// cancelled request, which has already been refunded on Solana. the withdrawal circuit must:
+// - collect the tombstones among all leaves below the snapshot's next_requested_withdrawals_tree_index, not
+//   only the ones it pays out, a batch that stops early can still be followed by the tombstone of a request in it
+// - build no output for a tombstone or for a request named by one, but count both in new_next_processed_withdrawals_index
+// get_payable_withdrawal_requests is the reference for which requests that leaves to pay out.
+// a circuit without this rejects the unknown address type, so the withdrawal vk has to be rotated to one that
+// handles tombstones before cancel_withdrawal is used, otherwise withdrawals stall at the first tombstone
+pub const WITHDRAWAL_ADDRESS_TYPE_TOMBSTONE: u32 = 0xFFFF_FFFF;
+
+// bounded by the compute budget and the transaction size, every request in a batch needs its own receipt account
+pub const MAX_WITHDRAWAL_REQUESTS_PER_BATCH: usize = 8;
+
+// a script withdrawal's leaf commits to hash160 of its scriptPubKey, the script is bounded so the withdrawal
+// circuit can build the output and a request still fits in the instruction data
+pub const MAX_WITHDRAWAL_SCRIPT_SIZE: usize = 256;
+
+// fee split table, shares are in basis points of the withdrawable fees
+pub const FEE_SPLIT_MAX_RECIPIENTS: usize = 8;
+pub const FEE_SPLIT_TOTAL_BPS: u64 = 10_000;
+
+// optional finalized block history ring buffer, extends the 8 recent_finalized_blocks searched by manual claims
+pub const FINALIZED_BLOCK_HISTORY_MIN_CAPACITY: u32 = 16;
+pub const FINALIZED_BLOCK_HISTORY_MAX_CAPACITY: u32 = 4096;
+
+// the block merkle tree is indexed by block height, its leaves are the block hashes
+pub const DOGE_BLOCK_MERKLE_TREE_HEIGHT: usize = psy_bridge_core::txo_constants::TXO_TREE_INDEX_BITS_BLOCK_NUM_LENGTH;
+// enough for any block at the tx limit of the txo tree
+pub const DOGE_TX_MERKLE_BRANCH_MAX_LENGTH: usize = psy_bridge_core::txo_constants::TXO_TREE_INDEX_BITS_TX_NUM_LENGTH;
+pub const DOGE_BLOCK_HEADER_SIZE: usize = 80;
+
+// block hash archive segments are capped well below the 10MiB account limit, a full segment is continued in the next one
+pub const BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY: u32 = 65_536;
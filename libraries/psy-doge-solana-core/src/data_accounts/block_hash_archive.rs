@@ -0,0 +1,168 @@
+use psy_bridge_core::{
+    common_types::QHash256,
+    error::{DogeBridgeError, QDogeResult},
+    header::PsyBridgeStateCommitment,
+};
+
+use crate::constants::BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY;
+
+pub const BLOCK_HASH_ARCHIVE_SEED: &[u8] = b"block_hash_archive";
+
+// each segment is the header followed by len entries, it is grown by one entry per finalized block
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct BlockHashArchiveSegmentHeader {
+    pub segment_index: u32,
+    pub len: u32,
+    pub first_block_height: u32,
+    pub _padding: u32, // keep 8-byte alignment
+}
+pub const BLOCK_HASH_ARCHIVE_SEGMENT_HEADER_SIZE: usize =
+    std::mem::size_of::<BlockHashArchiveSegmentHeader>();
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct BlockHashArchiveEntry {
+    // Offset 0
+    pub block_hash: QHash256,
+    // Offset 32
+    pub block_merkle_tree_root: QHash256,
+    // Offset 64
+    pub block_height: u32,
+    // Offset 68
+    pub _padding: u32, // keep 8-byte alignment
+}
+pub const BLOCK_HASH_ARCHIVE_ENTRY_SIZE: usize = std::mem::size_of::<BlockHashArchiveEntry>();
+const _ASSERT_SIZE_BLOCK_HASH_ARCHIVE_ENTRY: () = assert!(BLOCK_HASH_ARCHIVE_ENTRY_SIZE == 72);
+
+pub fn block_hash_archive_segment_size(len: u32) -> usize {
+    BLOCK_HASH_ARCHIVE_SEGMENT_HEADER_SIZE + len as usize * BLOCK_HASH_ARCHIVE_ENTRY_SIZE
+}
+
+// (segment index, index in segment) of the entry at archive_index
+pub fn block_hash_archive_position(archive_index: u64) -> (u32, u32) {
+    let capacity = BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY as u64;
+    ((archive_index / capacity) as u32, (archive_index % capacity) as u32)
+}
+
+impl BlockHashArchiveEntry {
+    pub fn from_commitment(commitment: &PsyBridgeStateCommitment) -> Self {
+        Self {
+            block_hash: commitment.block_hash,
+            block_merkle_tree_root: commitment.block_merkle_tree_root,
+            block_height: commitment.block_height,
+            _padding: 0,
+        }
+    }
+}
+
+pub struct BlockHashArchiveSegment;
+
+impl BlockHashArchiveSegment {
+    // the account must already be grown to hold the new entries, a zeroed header means a new segment
+    pub fn append_to_account_data(
+        data: &mut [u8],
+        segment_index: u32,
+        new_entries: &[BlockHashArchiveEntry],
+    ) -> QDogeResult<()> {
+        if data.len() < BLOCK_HASH_ARCHIVE_SEGMENT_HEADER_SIZE || new_entries.is_empty() {
+            return Err(DogeBridgeError::InvalidBlockHashArchiveAccount);
+        }
+        let (header_data, entries_data) = data.split_at_mut(BLOCK_HASH_ARCHIVE_SEGMENT_HEADER_SIZE);
+        let header = bytemuck::try_from_bytes_mut::<BlockHashArchiveSegmentHeader>(header_data)
+            .map_err(|_| DogeBridgeError::InvalidBlockHashArchiveAccount)?;
+        let entries = bytemuck::try_cast_slice_mut::<u8, BlockHashArchiveEntry>(entries_data)
+            .map_err(|_| DogeBridgeError::InvalidBlockHashArchiveAccount)?;
+        let new_len = header.len as usize + new_entries.len();
+        if entries.len() != new_len || new_len > BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY as usize {
+            return Err(DogeBridgeError::InvalidBlockHashArchiveAccount);
+        }
+        if header.len == 0 {
+            header.segment_index = segment_index;
+            header.first_block_height = new_entries[0].block_height;
+        } else if header.segment_index != segment_index {
+            return Err(DogeBridgeError::InvalidBlockHashArchiveAccount);
+        }
+        for entry in new_entries {
+            let len = header.len as usize;
+            // heights only move forward, which keeps every segment sorted for lookups
+            if len != 0 && entries[len - 1].block_height >= entry.block_height {
+                return Err(DogeBridgeError::InvalidBlockHashArchiveAccount);
+            }
+            entries[len] = *entry;
+            header.len += 1;
+        }
+        Ok(())
+    }
+
+    pub fn read_account_data(
+        data: &[u8],
+    ) -> Option<(&BlockHashArchiveSegmentHeader, &[BlockHashArchiveEntry])> {
+        if data.len() < BLOCK_HASH_ARCHIVE_SEGMENT_HEADER_SIZE {
+            return None;
+        }
+        let header = bytemuck::try_from_bytes::<BlockHashArchiveSegmentHeader>(
+            &data[..BLOCK_HASH_ARCHIVE_SEGMENT_HEADER_SIZE],
+        )
+        .ok()?;
+        let entries_end = block_hash_archive_segment_size(header.len);
+        if data.len() < entries_end {
+            return None;
+        }
+        let entries = bytemuck::try_cast_slice::<u8, BlockHashArchiveEntry>(
+            &data[BLOCK_HASH_ARCHIVE_SEGMENT_HEADER_SIZE..entries_end],
+        )
+        .ok()?;
+        Some((header, entries))
+    }
+
+    pub fn find_in_account_data(data: &[u8], block_height: u32) -> Option<BlockHashArchiveEntry> {
+        let (_, entries) = Self::read_account_data(data)?;
+        entries
+            .binary_search_by_key(&block_height, |entry| entry.block_height)
+            .ok()
+            .map(|i| entries[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(height: u32) -> BlockHashArchiveEntry {
+        BlockHashArchiveEntry {
+            block_hash: [height as u8; 32],
+            block_merkle_tree_root: [height as u8 + 100; 32],
+            block_height: height,
+            _padding: 0,
+        }
+    }
+
+    #[test]
+    fn test_archive_segment_appends_and_looks_up_heights() {
+        let mut data = vec![0u8; block_hash_archive_segment_size(1)];
+        BlockHashArchiveSegment::append_to_account_data(&mut data, 3, &[entry(10)]).unwrap();
+        // a reorg appends every height it finalizes at once
+        data.resize(data.len() + 3 * BLOCK_HASH_ARCHIVE_ENTRY_SIZE, 0);
+        BlockHashArchiveSegment::append_to_account_data(&mut data, 3, &[entry(11), entry(12), entry(13)]).unwrap();
+        data.resize(data.len() + BLOCK_HASH_ARCHIVE_ENTRY_SIZE, 0);
+        BlockHashArchiveSegment::append_to_account_data(&mut data, 3, &[entry(15)]).unwrap();
+
+        let (header, entries) = BlockHashArchiveSegment::read_account_data(&data).unwrap();
+        assert_eq!((header.segment_index, header.len, header.first_block_height), (3, 5, 10));
+        assert_eq!(entries[4], entry(15));
+        assert_eq!(BlockHashArchiveSegment::find_in_account_data(&data, 12), Some(entry(12)));
+        assert_eq!(BlockHashArchiveSegment::find_in_account_data(&data, 14), None);
+
+        // not grown first, or going backwards
+        assert_eq!(
+            BlockHashArchiveSegment::append_to_account_data(&mut data, 3, &[entry(16)]),
+            Err(DogeBridgeError::InvalidBlockHashArchiveAccount)
+        );
+        data.resize(data.len() + 2 * BLOCK_HASH_ARCHIVE_ENTRY_SIZE, 0);
+        assert_eq!(
+            BlockHashArchiveSegment::append_to_account_data(&mut data, 3, &[entry(16), entry(16)]),
+            Err(DogeBridgeError::InvalidBlockHashArchiveAccount)
+        );
+
+        assert_eq!(block_hash_archive_position(BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY as u64 + 5), (1, 5));
+    }
+}
@@ -0,0 +1,7 @@
+use psy_bridge_core::custodian_config::Bridge7MultisigCustodianWalletConfig;
+
+pub const CUSTODIAN_WALLET_CONFIG_SEED: &[u8] = b"custodian_wallet_config";
+
+// the account holds the preimage of the bridge's custodian_wallet_config_hash as it was last stored
+pub const CUSTODIAN_WALLET_CONFIG_ACCOUNT_SIZE: usize =
+    std::mem::size_of::<Bridge7MultisigCustodianWalletConfig>();
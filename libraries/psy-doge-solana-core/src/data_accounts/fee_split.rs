@@ -0,0 +1,127 @@
+use psy_bridge_core::error::{DogeBridgeError, QDogeResult};
+
+use crate::constants::{FEE_SPLIT_MAX_RECIPIENTS, FEE_SPLIT_TOTAL_BPS};
+
+pub const FEE_SPLIT_SEED: &[u8] = b"fee_split";
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct FeeSplitRecipient {
+    // Offset 0
+    pub token_account: [u8; 32],
+    // Offset 32
+    pub share_bps: u64,
+}
+const _ASSERT_SIZE_FEE_SPLIT_RECIPIENT: () = assert!(std::mem::size_of::<FeeSplitRecipient>() == 40);
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct FeeSplitTable {
+    // receives whatever the recipients' shares leave over, including rounding dust
+    pub fee_spender_token_account: [u8; 32],
+    pub recipients: [FeeSplitRecipient; FEE_SPLIT_MAX_RECIPIENTS],
+    pub recipient_count: u64,
+}
+pub const FEE_SPLIT_TABLE_SIZE: usize = std::mem::size_of::<FeeSplitTable>();
+
+// amounts to mint for one distribution, recipient_amounts follows the table's recipient order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeSplitAmounts {
+    pub fee_spender_amount: u64,
+    pub recipient_amounts: [u64; FEE_SPLIT_MAX_RECIPIENTS],
+}
+
+impl FeeSplitTable {
+    pub fn get_recipients(&self) -> &[FeeSplitRecipient] {
+        &self.recipients[..(self.recipient_count as usize).min(FEE_SPLIT_MAX_RECIPIENTS)]
+    }
+
+    pub fn validate(&self) -> QDogeResult<()> {
+        if self.recipient_count as usize > FEE_SPLIT_MAX_RECIPIENTS
+            || self.fee_spender_token_account == [0u8; 32]
+        {
+            return Err(DogeBridgeError::InvalidFeeSplit);
+        }
+        let mut total_bps = 0u64;
+        for (i, recipient) in self.get_recipients().iter().enumerate() {
+            if recipient.share_bps == 0 || recipient.token_account == [0u8; 32] {
+                return Err(DogeBridgeError::InvalidFeeSplit);
+            }
+            // a token account can only appear once so the mint accounts map 1:1 onto the table
+            if self.recipients[..i]
+                .iter()
+                .any(|r| r.token_account == recipient.token_account)
+            {
+                return Err(DogeBridgeError::InvalidFeeSplit);
+            }
+            total_bps += recipient.share_bps;
+        }
+        if total_bps > FEE_SPLIT_TOTAL_BPS {
+            return Err(DogeBridgeError::InvalidFeeSplit);
+        }
+        Ok(())
+    }
+
+    pub fn split_fees(&self, total_fees_sats: u64) -> FeeSplitAmounts {
+        let mut recipient_amounts = [0u64; FEE_SPLIT_MAX_RECIPIENTS];
+        let mut distributed_sats = 0u64;
+        for (i, recipient) in self.get_recipients().iter().enumerate() {
+            let amount = (total_fees_sats as u128 * recipient.share_bps as u128
+                / FEE_SPLIT_TOTAL_BPS as u128) as u64;
+            recipient_amounts[i] = amount;
+            distributed_sats += amount;
+        }
+        FeeSplitAmounts {
+            fee_spender_amount: total_fees_sats - distributed_sats,
+            recipient_amounts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(shares: &[([u8; 32], u64)]) -> FeeSplitTable {
+        let mut table = FeeSplitTable {
+            fee_spender_token_account: [9u8; 32],
+            recipient_count: shares.len() as u64,
+            ..Default::default()
+        };
+        for (i, (token_account, share_bps)) in shares.iter().enumerate() {
+            table.recipients[i] = FeeSplitRecipient {
+                token_account: *token_account,
+                share_bps: *share_bps,
+            };
+        }
+        table
+    }
+
+    #[test]
+    fn test_split_fees_gives_remainder_to_fee_spender() {
+        let table = table(&[([1u8; 32], 3000), ([2u8; 32], 3333)]);
+        table.validate().unwrap();
+
+        let amounts = table.split_fees(10_001);
+        assert_eq!(amounts.recipient_amounts[0], 3000);
+        assert_eq!(amounts.recipient_amounts[1], 3333);
+        assert_eq!(amounts.fee_spender_amount, 10_001 - 3000 - 3333);
+    }
+
+    #[test]
+    fn test_invalid_fee_split_is_rejected() {
+        assert_eq!(
+            table(&[([1u8; 32], 6000), ([2u8; 32], 4001)]).validate(),
+            Err(DogeBridgeError::InvalidFeeSplit)
+        );
+        assert_eq!(
+            table(&[([1u8; 32], 1000), ([1u8; 32], 1000)]).validate(),
+            Err(DogeBridgeError::InvalidFeeSplit)
+        );
+        assert_eq!(
+            table(&[([1u8; 32], 0)]).validate(),
+            Err(DogeBridgeError::InvalidFeeSplit)
+        );
+        let mut too_many = table(&[]);
+        too_many.recipient_count = FEE_SPLIT_MAX_RECIPIENTS as u64 + 1;
+        assert_eq!(too_many.validate(), Err(DogeBridgeError::InvalidFeeSplit));
+    }
+}
@@ -0,0 +1,176 @@
+use psy_bridge_core::{
+    common_types::QHash256,
+    error::{DogeBridgeError, QDogeResult},
+    header::PsyBridgeStateCommitment,
+};
+
+pub const FINALIZED_BLOCK_HISTORY_SEED: &[u8] = b"finalized_block_history";
+
+// the account is the header followed by `capacity` entries, a capacity of 0 means it is still being allocated
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct FinalizedBlockHistoryHeader {
+    pub capacity: u32,
+    // number of entries written, saturates at capacity
+    pub len: u32,
+    // slot the next finalized block is written to
+    pub next_index: u32,
+    pub _padding: u32, // keep 8-byte alignment
+}
+pub const FINALIZED_BLOCK_HISTORY_HEADER_SIZE: usize =
+    std::mem::size_of::<FinalizedBlockHistoryHeader>();
+
+// the parts of a finalized state commitment a manual claim is checked against
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct FinalizedBlockHistoryEntry {
+    // Offset 0
+    pub auto_claimed_deposits_tree_root: QHash256,
+    // Offset 32
+    pub block_merkle_tree_root: QHash256,
+    // Offset 64
+    pub block_height: u32,
+    // Offset 68
+    pub _padding: u32, // keep 8-byte alignment
+}
+pub const FINALIZED_BLOCK_HISTORY_ENTRY_SIZE: usize =
+    std::mem::size_of::<FinalizedBlockHistoryEntry>();
+const _ASSERT_SIZE_FINALIZED_BLOCK_HISTORY_ENTRY: () =
+    assert!(FINALIZED_BLOCK_HISTORY_ENTRY_SIZE == 72);
+
+pub fn finalized_block_history_account_size(capacity: u32) -> usize {
+    FINALIZED_BLOCK_HISTORY_HEADER_SIZE + capacity as usize * FINALIZED_BLOCK_HISTORY_ENTRY_SIZE
+}
+
+impl FinalizedBlockHistoryEntry {
+    pub fn from_commitment(commitment: &PsyBridgeStateCommitment) -> Self {
+        Self {
+            auto_claimed_deposits_tree_root: commitment.auto_claimed_deposits_tree_root,
+            block_merkle_tree_root: commitment.block_merkle_tree_root,
+            block_height: commitment.block_height,
+            _padding: 0,
+        }
+    }
+}
+
+pub struct FinalizedBlockHistory<'a> {
+    pub header: &'a mut FinalizedBlockHistoryHeader,
+    pub entries: &'a mut [FinalizedBlockHistoryEntry],
+}
+
+impl<'a> FinalizedBlockHistory<'a> {
+    // formats a freshly allocated account, seed_blocks are appended oldest first
+    pub fn initialize_account_data(
+        data: &'a mut [u8],
+        capacity: u32,
+        seed_blocks: &[PsyBridgeStateCommitment],
+    ) -> QDogeResult<Self> {
+        if capacity == 0 || data.len() != finalized_block_history_account_size(capacity) {
+            return Err(DogeBridgeError::InvalidFinalizedBlockHistoryAccount);
+        }
+        data.fill(0);
+        let mut history = Self::from_account_data_unchecked(data)?;
+        history.header.capacity = capacity;
+        for block in seed_blocks {
+            history.append(block);
+        }
+        Ok(history)
+    }
+
+    pub fn from_account_data(data: &'a mut [u8], capacity: u32) -> QDogeResult<Self> {
+        let history = Self::from_account_data_unchecked(data)?;
+        if capacity == 0 || history.header.capacity != capacity {
+            return Err(DogeBridgeError::InvalidFinalizedBlockHistoryAccount);
+        }
+        Ok(history)
+    }
+
+    fn from_account_data_unchecked(data: &'a mut [u8]) -> QDogeResult<Self> {
+        if data.len() < FINALIZED_BLOCK_HISTORY_HEADER_SIZE {
+            return Err(DogeBridgeError::InvalidFinalizedBlockHistoryAccount);
+        }
+        let (header_data, entries_data) = data.split_at_mut(FINALIZED_BLOCK_HISTORY_HEADER_SIZE);
+        let header = bytemuck::try_from_bytes_mut::<FinalizedBlockHistoryHeader>(header_data)
+            .map_err(|_| DogeBridgeError::InvalidFinalizedBlockHistoryAccount)?;
+        let entries = bytemuck::try_cast_slice_mut::<u8, FinalizedBlockHistoryEntry>(entries_data)
+            .map_err(|_| DogeBridgeError::InvalidFinalizedBlockHistoryAccount)?;
+        if header.capacity as usize > entries.len() {
+            return Err(DogeBridgeError::InvalidFinalizedBlockHistoryAccount);
+        }
+        Ok(Self { header, entries })
+    }
+
+    pub fn append(&mut self, block: &PsyBridgeStateCommitment) {
+        let capacity = self.header.capacity;
+        self.entries[self.header.next_index as usize] = FinalizedBlockHistoryEntry::from_commitment(block);
+        self.header.next_index = (self.header.next_index + 1) % capacity;
+        self.header.len = (self.header.len + 1).min(capacity);
+    }
+
+    // an account that is empty or still being allocated holds no history
+    pub fn find_in_account_data(
+        data: &[u8],
+        auto_claimed_deposits_tree_root: &QHash256,
+    ) -> Option<(u32, QHash256)> {
+        if data.len() < FINALIZED_BLOCK_HISTORY_HEADER_SIZE {
+            return None;
+        }
+        let header = bytemuck::try_from_bytes::<FinalizedBlockHistoryHeader>(
+            &data[..FINALIZED_BLOCK_HISTORY_HEADER_SIZE],
+        )
+        .ok()?;
+        if header.capacity == 0 || data.len() < finalized_block_history_account_size(header.capacity) {
+            return None;
+        }
+        let entries = bytemuck::try_cast_slice::<u8, FinalizedBlockHistoryEntry>(
+            &data[FINALIZED_BLOCK_HISTORY_HEADER_SIZE..finalized_block_history_account_size(header.capacity)],
+        )
+        .ok()?;
+        // newest first, the same root can only repeat across blocks without auto claimed deposits
+        let capacity = header.capacity as usize;
+        (1..=header.len as usize)
+            .map(|back| &entries[(header.next_index as usize + capacity - back) % capacity])
+            .find(|entry| entry.auto_claimed_deposits_tree_root == *auto_claimed_deposits_tree_root)
+            .map(|entry| (entry.block_height, entry.block_merkle_tree_root))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(height: u32) -> PsyBridgeStateCommitment {
+        PsyBridgeStateCommitment {
+            auto_claimed_deposits_tree_root: [height as u8; 32],
+            block_merkle_tree_root: [height as u8 + 100; 32],
+            block_height: height,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_history_keeps_the_last_capacity_blocks() {
+        let mut data = vec![0u8; finalized_block_history_account_size(4)];
+        let mut history =
+            FinalizedBlockHistory::initialize_account_data(&mut data, 4, &[block(1), block(2)]).unwrap();
+        for height in 3..=6 {
+            history.append(&block(height));
+        }
+        assert_eq!(history.header.len, 4);
+
+        assert_eq!(FinalizedBlockHistory::find_in_account_data(&data, &[2u8; 32]), None);
+        assert_eq!(
+            FinalizedBlockHistory::find_in_account_data(&data, &[3u8; 32]),
+            Some((3, [103u8; 32]))
+        );
+        assert_eq!(
+            FinalizedBlockHistory::find_in_account_data(&data, &[6u8; 32]),
+            Some((6, [106u8; 32]))
+        );
+
+        // an account that is still being allocated is ignored
+        assert_eq!(FinalizedBlockHistory::find_in_account_data(&[0u8; 64], &[0u8; 32]), None);
+        assert_eq!(
+            FinalizedBlockHistory::from_account_data(&mut data, 8).err(),
+            Some(DogeBridgeError::InvalidFinalizedBlockHistoryAccount)
+        );
+    }
+}
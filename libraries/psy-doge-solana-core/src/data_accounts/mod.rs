@@ -1 +1,7 @@
-pub mod pending_mint;
\ No newline at end of file
+pub mod block_hash_archive;
+pub mod custodian_config;
+pub mod fee_split;
+pub mod finalized_block_history;
+pub mod pending_mint;
+pub mod vk_registry;
+pub mod withdrawal_receipt;
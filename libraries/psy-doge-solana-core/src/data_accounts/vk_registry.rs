@@ -0,0 +1,286 @@
+use psy_bridge_core::{
+    crypto::zk::CompactBridgeZKVerifierKey,
+    error::{DogeBridgeError, QDogeResult},
+};
+
+use crate::constants::{VK_REGISTRY_KIND_COUNT, VK_ROLLOVER_WINDOW_SECS, VK_UPDATE_DELAY_SECS};
+
+pub const VK_REGISTRY_SEED: &[u8] = b"vk_registry";
+
+// version 0 means the program's built-in verifier key is in use
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct VerifierKeyEntry {
+    // Offset 0
+    pub current_vk: CompactBridgeZKVerifierKey,
+    // Offset 32
+    pub previous_vk: CompactBridgeZKVerifierKey,
+    // Offset 64
+    pub pending_vk: CompactBridgeZKVerifierKey,
+    // Offset 96, proofs for previous_vk are accepted until this time
+    pub previous_vk_expires_at_secs: u64,
+    // Offset 104, 0 = no pending update
+    pub pending_vk_activates_at_secs: u64,
+    // Offset 112
+    pub current_version: u32,
+    // Offset 116
+    pub previous_version: u32,
+    // Offset 120
+    pub pending_version: u32,
+    // Offset 124
+    pub _padding: u32, // keep 8-byte alignment
+}
+const _ASSERT_SIZE_VK_ENTRY: () = assert!(std::mem::size_of::<VerifierKeyEntry>() == 128);
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct VerifierKeyRegistry {
+    pub entries: [VerifierKeyEntry; VK_REGISTRY_KIND_COUNT],
+}
+pub const VK_REGISTRY_SIZE: usize = std::mem::size_of::<VerifierKeyRegistry>();
+
+// the keys a proof of a given kind may be verified against right now
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AcceptedVerifierKeys {
+    pub current: CompactBridgeZKVerifierKey,
+    pub previous: Option<CompactBridgeZKVerifierKey>,
+}
+
+impl AcceptedVerifierKeys {
+    pub fn builtin(vk: &CompactBridgeZKVerifierKey) -> Self {
+        Self {
+            current: *vk,
+            previous: None,
+        }
+    }
+
+    // runs a proof check against the current key and, during a rollover window, the previous one.
+    // the core state transitions verify the proof before mutating state, so a zkp failure can be retried.
+    pub fn verify_with<T>(&self, mut run: impl FnMut(&[u8]) -> QDogeResult<T>) -> QDogeResult<T> {
+        match (run(&self.current), self.previous) {
+            (
+                Err(DogeBridgeError::BridgeZKPError | DogeBridgeError::InvalidBridgeInputZKP),
+                Some(previous),
+            ) => run(&previous),
+            (res, _) => res,
+        }
+    }
+}
+
+impl VerifierKeyEntry {
+    fn resolve_vk(
+        vk: &CompactBridgeZKVerifierKey,
+        version: u32,
+        builtin_vk: &CompactBridgeZKVerifierKey,
+    ) -> CompactBridgeZKVerifierKey {
+        if version == 0 {
+            *builtin_vk
+        } else {
+            *vk
+        }
+    }
+
+    fn has_pending_update(&self) -> bool {
+        self.pending_vk_activates_at_secs != 0
+    }
+
+    // folds a pending update whose timelock has elapsed into the current key
+    fn settle(&mut self, now_secs: u32) {
+        if !self.has_pending_update() || (now_secs as u64) < self.pending_vk_activates_at_secs {
+            return;
+        }
+        self.previous_vk = self.current_vk;
+        self.previous_version = self.current_version;
+        self.previous_vk_expires_at_secs =
+            self.pending_vk_activates_at_secs + VK_ROLLOVER_WINDOW_SECS as u64;
+        self.current_vk = self.pending_vk;
+        self.current_version = self.pending_version;
+        self.pending_vk = [0u8; 32];
+        self.pending_version = 0;
+        self.pending_vk_activates_at_secs = 0;
+    }
+
+    pub fn get_accepted_vks(
+        &self,
+        builtin_vk: &CompactBridgeZKVerifierKey,
+        now_secs: u32,
+    ) -> AcceptedVerifierKeys {
+        // read-only callers (e.g. manual claims) see an elapsed update as active without writing it back
+        let mut entry = *self;
+        entry.settle(now_secs);
+
+        let current = Self::resolve_vk(&entry.current_vk, entry.current_version, builtin_vk);
+        let previous = if entry.current_version != 0
+            && (now_secs as u64) < entry.previous_vk_expires_at_secs
+        {
+            Some(Self::resolve_vk(
+                &entry.previous_vk,
+                entry.previous_version,
+                builtin_vk,
+            ))
+        } else {
+            None
+        };
+        AcceptedVerifierKeys { current, previous }
+    }
+}
+
+impl VerifierKeyRegistry {
+    pub fn get_entry(&self, vk_kind: u32) -> QDogeResult<&VerifierKeyEntry> {
+        self.entries
+            .get(vk_kind as usize)
+            .ok_or(DogeBridgeError::InvalidVerifierKeyKind)
+    }
+
+    fn get_entry_mut(&mut self, vk_kind: u32) -> QDogeResult<&mut VerifierKeyEntry> {
+        self.entries
+            .get_mut(vk_kind as usize)
+            .ok_or(DogeBridgeError::InvalidVerifierKeyKind)
+    }
+
+    pub fn get_accepted_vks(
+        &self,
+        vk_kind: u32,
+        builtin_vk: &CompactBridgeZKVerifierKey,
+        now_secs: u32,
+    ) -> QDogeResult<AcceptedVerifierKeys> {
+        Ok(self.get_entry(vk_kind)?.get_accepted_vks(builtin_vk, now_secs))
+    }
+
+    // an empty account means the registry was never created and the built-in key applies
+    pub fn get_accepted_vks_from_account_data(
+        data: &[u8],
+        vk_kind: u32,
+        builtin_vk: &CompactBridgeZKVerifierKey,
+        now_secs: u32,
+    ) -> QDogeResult<AcceptedVerifierKeys> {
+        if data.is_empty() {
+            return Ok(AcceptedVerifierKeys::builtin(builtin_vk));
+        }
+        let registry: &VerifierKeyRegistry = bytemuck::try_from_bytes(data)
+            .map_err(|_| DogeBridgeError::InvalidVerifierKeyRegistry)?;
+        registry.get_accepted_vks(vk_kind, builtin_vk, now_secs)
+    }
+
+    // queues a new vk which becomes active once VK_UPDATE_DELAY_SECS has elapsed.
+    // proposing again before activation replaces the pending key and restarts the timelock.
+    pub fn run_propose_vk_update(
+        &mut self,
+        vk_kind: u32,
+        new_vk: &CompactBridgeZKVerifierKey,
+        now_secs: u32,
+    ) -> QDogeResult<()> {
+        let entry = self.get_entry_mut(vk_kind)?;
+        entry.settle(now_secs);
+        entry.pending_vk = *new_vk;
+        entry.pending_version = entry.current_version + 1;
+        entry.pending_vk_activates_at_secs = now_secs as u64 + VK_UPDATE_DELAY_SECS as u64;
+        Ok(())
+    }
+
+    // writes an elapsed pending update back to the registry so the new version is visible on-chain
+    pub fn run_activate_vk_update(&mut self, vk_kind: u32, now_secs: u32) -> QDogeResult<()> {
+        let entry = self.get_entry_mut(vk_kind)?;
+        if !entry.has_pending_update() {
+            return Err(DogeBridgeError::NoPendingVerifierKeyUpdate);
+        }
+        if (now_secs as u64) < entry.pending_vk_activates_at_secs {
+            return Err(DogeBridgeError::VerifierKeyUpdateTimelockNotElapsed);
+        }
+        entry.settle(now_secs);
+        Ok(())
+    }
+
+    pub fn run_cancel_vk_update(&mut self, vk_kind: u32) -> QDogeResult<()> {
+        let entry = self.get_entry_mut(vk_kind)?;
+        if !entry.has_pending_update() {
+            return Err(DogeBridgeError::NoPendingVerifierKeyUpdate);
+        }
+        entry.pending_vk = [0u8; 32];
+        entry.pending_version = 0;
+        entry.pending_vk_activates_at_secs = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::VK_KIND_WITHDRAWAL;
+
+    #[test]
+    fn test_vk_update_timelock_and_rollover() {
+        let builtin = [1u8; 32];
+        let new_vk = [2u8; 32];
+        let mut registry = VerifierKeyRegistry::default();
+
+        assert_eq!(
+            registry.get_accepted_vks(VK_KIND_WITHDRAWAL, &builtin, 100).unwrap(),
+            AcceptedVerifierKeys::builtin(&builtin)
+        );
+
+        registry
+            .run_propose_vk_update(VK_KIND_WITHDRAWAL, &new_vk, 100)
+            .unwrap();
+        let activates_at = 100 + VK_UPDATE_DELAY_SECS;
+        assert_eq!(
+            registry.run_activate_vk_update(VK_KIND_WITHDRAWAL, activates_at - 1),
+            Err(DogeBridgeError::VerifierKeyUpdateTimelockNotElapsed)
+        );
+        assert_eq!(
+            registry
+                .get_accepted_vks(VK_KIND_WITHDRAWAL, &builtin, activates_at - 1)
+                .unwrap(),
+            AcceptedVerifierKeys::builtin(&builtin)
+        );
+
+        // both keys are accepted during the rollover window, even before activation is written back
+        let during_rollover = registry
+            .get_accepted_vks(VK_KIND_WITHDRAWAL, &builtin, activates_at)
+            .unwrap();
+        assert_eq!(during_rollover.current, new_vk);
+        assert_eq!(during_rollover.previous, Some(builtin));
+
+        registry
+            .run_activate_vk_update(VK_KIND_WITHDRAWAL, activates_at)
+            .unwrap();
+        assert_eq!(registry.entries[VK_KIND_WITHDRAWAL as usize].current_version, 1);
+
+        let after_rollover = registry
+            .get_accepted_vks(
+                VK_KIND_WITHDRAWAL,
+                &builtin,
+                activates_at + VK_ROLLOVER_WINDOW_SECS,
+            )
+            .unwrap();
+        assert_eq!(after_rollover, AcceptedVerifierKeys::builtin(&new_vk));
+    }
+
+    #[test]
+    fn test_verify_with_falls_back_to_previous_vk() {
+        let accepted = AcceptedVerifierKeys {
+            current: [2u8; 32],
+            previous: Some([1u8; 32]),
+        };
+        let res = accepted.verify_with(|vk| {
+            if vk == [1u8; 32] {
+                Ok(())
+            } else {
+                Err(DogeBridgeError::BridgeZKPError)
+            }
+        });
+        assert_eq!(res, Ok(()));
+
+        // errors other than a failed proof are not retried
+        let res: QDogeResult<()> =
+            accepted.verify_with(|_| Err(DogeBridgeError::InvalidZKProofSize));
+        assert_eq!(res, Err(DogeBridgeError::InvalidZKProofSize));
+    }
+
+    #[test]
+    fn test_unknown_vk_kind_is_rejected() {
+        let registry = VerifierKeyRegistry::default();
+        assert_eq!(
+            registry.get_accepted_vks(VK_REGISTRY_KIND_COUNT as u32, &[0u8; 32], 0),
+            Err(DogeBridgeError::InvalidVerifierKeyKind)
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use psy_bridge_core::error::{DogeBridgeError, QDogeResult};
+
+use crate::program_state::PsyWithdrawalRequest;
+
+// PDA seeds: [WITHDRAWAL_RECEIPT_SEED, requester, receipt_nonce (u64 le)]
+// the nonce is picked by the requester, so the receipt address doesn't depend on the tree index other
+// requests may take first. the assigned index is stored in the receipt.
+pub const WITHDRAWAL_RECEIPT_SEED: &[u8] = b"withdrawal_receipt";
+
+pub const WITHDRAWAL_RECEIPT_STATUS_PENDING: u32 = 0;
+pub const WITHDRAWAL_RECEIPT_STATUS_FULFILLED: u32 = 1;
+pub const WITHDRAWAL_RECEIPT_STATUS_CANCELLED: u32 = 2;
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct WithdrawalReceipt {
+    // Offset 0
+    pub requester: [u8; 32],
+    // Offset 32, the request as it was appended to requested_withdrawals_tree (amount after fees)
+    pub request: PsyWithdrawalRequest,
+    // Offset 64, index of the request's leaf in requested_withdrawals_tree
+    pub withdrawal_index: u64,
+    // Offset 72
+    pub amount_burned_sats: u64,
+    // Offset 80
+    pub requested_at_slot: u64,
+    // Offset 88, 0 = not fulfilled yet
+    pub fulfilled_at_slot: u64,
+    // Offset 96, 0 = not cancelled
+    pub cancelled_at_slot: u64,
+    // Offset 104
+    pub status: u32,
+    // Offset 108
+    pub _padding: u32, // keep 8-byte alignment
+    // Offset 112
+    pub receipt_nonce: u64,
+}
+pub const WITHDRAWAL_RECEIPT_SIZE: usize = std::mem::size_of::<WithdrawalReceipt>();
+const _ASSERT_SIZE_WITHDRAWAL_RECEIPT: () = assert!(WITHDRAWAL_RECEIPT_SIZE == 120);
+
+impl WithdrawalReceipt {
+    pub fn new(
+        requester: [u8; 32],
+        receipt_nonce: u64,
+        request: PsyWithdrawalRequest,
+        withdrawal_index: u64,
+        amount_burned_sats: u64,
+        requested_at_slot: u64,
+    ) -> Self {
+        Self {
+            requester,
+            request,
+            withdrawal_index,
+            amount_burned_sats,
+            requested_at_slot,
+            fulfilled_at_slot: 0,
+            cancelled_at_slot: 0,
+            status: WITHDRAWAL_RECEIPT_STATUS_PENDING,
+            _padding: 0,
+            receipt_nonce,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.status == WITHDRAWAL_RECEIPT_STATUS_PENDING
+    }
+
+    pub fn is_fulfilled(&self) -> bool {
+        self.status == WITHDRAWAL_RECEIPT_STATUS_FULFILLED
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.status == WITHDRAWAL_RECEIPT_STATUS_CANCELLED
+    }
+
+    // marking an already fulfilled or cancelled receipt is a no-op so cranks can submit overlapping batches
+    pub fn run_mark_fulfilled(
+        &mut self,
+        next_processed_withdrawals_index: u64,
+        current_slot: u64,
+    ) -> QDogeResult<()> {
+        if !self.is_pending() {
+            return Ok(());
+        }
+        if self.withdrawal_index >= next_processed_withdrawals_index {
+            return Err(DogeBridgeError::WithdrawalNotYetProcessed);
+        }
+        self.status = WITHDRAWAL_RECEIPT_STATUS_FULFILLED;
+        self.fulfilled_at_slot = current_slot;
+        Ok(())
+    }
+
+    pub fn mark_cancelled(&mut self, current_slot: u64) {
+        self.status = WITHDRAWAL_RECEIPT_STATUS_CANCELLED;
+        self.cancelled_at_slot = current_slot;
+    }
+
+    pub fn ensure_can_close(&self, signer_pubkey: &[u8; 32]) -> QDogeResult<()> {
+        if &self.requester != signer_pubkey {
+            return Err(DogeBridgeError::UnauthorizedWithdrawalReceiptClose);
+        }
+        if self.is_pending() {
+            return Err(DogeBridgeError::WithdrawalReceiptNotFulfilled);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_receipt_fulfilled_after_processing() {
+        let request = PsyWithdrawalRequest::new([3u8; 20], 98_000, 0);
+        let mut receipt = WithdrawalReceipt::new([1u8; 32], 7, request, 5, 100_000, 10);
+
+        assert_eq!(
+            receipt.run_mark_fulfilled(5, 20),
+            Err(DogeBridgeError::WithdrawalNotYetProcessed)
+        );
+        assert_eq!(
+            receipt.ensure_can_close(&[1u8; 32]),
+            Err(DogeBridgeError::WithdrawalReceiptNotFulfilled)
+        );
+
+        receipt.run_mark_fulfilled(6, 20).unwrap();
+        assert!(receipt.is_fulfilled());
+        assert_eq!(receipt.fulfilled_at_slot, 20);
+
+        // marking again keeps the original slot
+        receipt.run_mark_fulfilled(7, 30).unwrap();
+        assert_eq!(receipt.fulfilled_at_slot, 20);
+    }
+
+    #[test]
+    fn test_only_requester_can_close_receipt() {
+        let request = PsyWithdrawalRequest::new([3u8; 20], 98_000, 0);
+        let mut receipt = WithdrawalReceipt::new([1u8; 32], 7, request, 0, 100_000, 10);
+        receipt.run_mark_fulfilled(1, 20).unwrap();
+
+        assert_eq!(
+            receipt.ensure_can_close(&[2u8; 32]),
+            Err(DogeBridgeError::UnauthorizedWithdrawalReceiptClose)
+        );
+        assert_eq!(receipt.ensure_can_close(&[1u8; 32]), Ok(()));
+    }
+}
@@ -0,0 +1,184 @@
+use psy_bridge_core::common_types::QHash256;
+
+use crate::constants::MAX_WITHDRAWAL_SCRIPT_SIZE;
+use crate::program_state::{PsyReturnTxOutput, PsyWithdrawalRequest};
+
+// Every event is logged with sol_log_data as two fields: a BridgeEventHeader and the event record.
+// New fields are only ever appended to a record (bumping BRIDGE_EVENT_VERSION), so a decoder can
+// read the prefix it knows from any newer version.
+pub const BRIDGE_EVENT_MAGIC: [u8; 4] = *b"PSYB";
+pub const BRIDGE_EVENT_VERSION: u8 = 1;
+
+pub const BRIDGE_EVENT_KIND_BLOCK_UPDATE: u8 = 1;
+pub const BRIDGE_EVENT_KIND_REORG: u8 = 2;
+pub const BRIDGE_EVENT_KIND_MINT_GROUP_PROCESSED: u8 = 3;
+pub const BRIDGE_EVENT_KIND_WITHDRAWAL_REQUESTED: u8 = 4;
+pub const BRIDGE_EVENT_KIND_WITHDRAWAL_PROCESSED: u8 = 5;
+pub const BRIDGE_EVENT_KIND_MANUAL_DEPOSIT_CLAIMED: u8 = 6;
+pub const BRIDGE_EVENT_KIND_FEES_WITHDRAWN: u8 = 7;
+pub const BRIDGE_EVENT_KIND_WITHDRAWAL_CANCELLED: u8 = 8;
+pub const BRIDGE_EVENT_KIND_CUSTODIAN_ROTATED: u8 = 9;
+pub const BRIDGE_EVENT_KIND_WITHDRAWAL_SCRIPT: u8 = 10;
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct BridgeEventHeader {
+    pub magic: [u8; 4],
+    pub version: u8,
+    pub kind: u8,
+    pub _padding: [u8; 2],
+}
+pub const BRIDGE_EVENT_HEADER_SIZE: usize = std::mem::size_of::<BridgeEventHeader>();
+const _ASSERT_SIZE_BRIDGE_EVENT_HEADER: () = assert!(BRIDGE_EVENT_HEADER_SIZE == 8);
+
+impl BridgeEventHeader {
+    pub fn new(kind: u8) -> Self {
+        Self {
+            magic: BRIDGE_EVENT_MAGIC,
+            version: BRIDGE_EVENT_VERSION,
+            kind,
+            _padding: [0u8; 2],
+        }
+    }
+
+    pub fn from_log_field(field: &[u8]) -> Option<Self> {
+        if field.len() != BRIDGE_EVENT_HEADER_SIZE || field[0..4] != BRIDGE_EVENT_MAGIC {
+            return None;
+        }
+        Some(Self {
+            magic: BRIDGE_EVENT_MAGIC,
+            version: field[4],
+            kind: field[5],
+            _padding: [0u8; 2],
+        })
+    }
+}
+
+// emitted for BRIDGE_EVENT_KIND_BLOCK_UPDATE and BRIDGE_EVENT_KIND_REORG
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct BlockTransitionEventData {
+    pub finalized_block_hash: QHash256,
+    pub finalized_block_height: u32,
+    pub tip_block_height: u32,
+    pub auto_claimed_deposits_next_index: u32,
+    // 1 for a block update, 1 + the extra finalized blocks for a reorg
+    pub finalized_blocks_count: u32,
+}
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct MintGroupProcessedEventData {
+    pub mint_buffer: [u8; 32],
+    pub total_minted_sats: u64,
+    pub mint_group_index: u32,
+    pub mints_count: u32,
+    // 1 if this was the last group of the buffer and the buffer was unlocked
+    pub buffer_unlocked: u32,
+    pub _padding: u32, // keep 8-byte alignment
+}
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct WithdrawalRequestedEventData {
+    pub requester: [u8; 32],
+    // the request as appended to requested_withdrawals_tree (amount after fees)
+    pub request: PsyWithdrawalRequest,
+    pub withdrawal_index: u64,
+    pub amount_burned_sats: u64,
+    // keys the receipt PDA together with the requester
+    pub receipt_nonce: u64,
+}
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct WithdrawalProcessedEventData {
+    pub sighash: QHash256,
+    pub new_return_output: PsyReturnTxOutput,
+    pub new_spent_txo_tree_root: QHash256,
+    pub new_next_processed_withdrawals_index: u64,
+}
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct ManualDepositClaimedEventData {
+    pub tx_hash: QHash256,
+    pub depositor: [u8; 32],
+    pub recipient_token_account: [u8; 32],
+    pub combined_txo_index: u64,
+    pub deposit_amount_sats: u64,
+    pub minted_amount_sats: u64,
+}
+
+// emitted once per token account fees are minted to, by operator_withdraw_fees and distribute_fees
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct FeesWithdrawnEventData {
+    pub token_account: [u8; 32],
+    pub amount_sats: u64,
+    pub total_fees_withdrawn_sats: u64,
+}
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct WithdrawalCancelledEventData {
+    pub requester: [u8; 32],
+    pub withdrawal_index: u64,
+    pub refund_amount_sats: u64,
+}
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct CustodianRotatedEventData {
+    pub old_custodian_wallet_config_hash: QHash256,
+    pub new_custodian_wallet_config_hash: QHash256,
+    // hash of the sweep tx, published over wormhole like a withdrawal tx
+    pub sighash: QHash256,
+    pub new_return_output: PsyReturnTxOutput,
+    pub new_spent_txo_tree_root: QHash256,
+}
+
+// emitted right after the WITHDRAWAL_REQUESTED event of a script withdrawal, so the withdrawal builder can
+// recover the scriptPubKey behind the request's script hash
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeReprC)]
+pub struct WithdrawalScriptEventData {
+    pub withdrawal_index: u64,
+    pub script_len: u32,
+    pub _padding: u32, // keep 8-byte alignment
+    #[cfg_attr(feature = "serialize_serde", serde(with = "psy_bridge_core::serde_arrays::serde_arrays"))]
+    pub script: [u8; MAX_WITHDRAWAL_SCRIPT_SIZE],
+}
+impl Default for WithdrawalScriptEventData {
+    fn default() -> Self {
+        Self {
+            withdrawal_index: 0,
+            script_len: 0,
+            _padding: 0,
+            script: [0u8; MAX_WITHDRAWAL_SCRIPT_SIZE],
+        }
+    }
+}
+impl WithdrawalScriptEventData {
+    // the caller has already checked script_pubkey.len() <= MAX_WITHDRAWAL_SCRIPT_SIZE
+    pub fn new(withdrawal_index: u64, script_pubkey: &[u8]) -> Self {
+        let mut data = Self {
+            withdrawal_index,
+            script_len: script_pubkey.len() as u32,
+            ..Default::default()
+        };
+        data.script[..script_pubkey.len()].copy_from_slice(script_pubkey);
+        data
+    }
+
+    pub fn get_script(&self) -> &[u8] {
+        &self.script[..(self.script_len as usize).min(MAX_WITHDRAWAL_SCRIPT_SIZE)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_header_round_trip() {
+        let header = BridgeEventHeader::new(BRIDGE_EVENT_KIND_WITHDRAWAL_REQUESTED);
+        let bytes = bytemuck::bytes_of(&header);
+        assert_eq!(BridgeEventHeader::from_log_field(bytes), Some(header));
+
+        assert_eq!(BridgeEventHeader::from_log_field(&bytes[..7]), None);
+        let mut foreign = bytes.to_vec();
+        foreign[0] = b'X';
+        assert_eq!(BridgeEventHeader::from_log_field(&foreign), None);
+    }
+}
@@ -1,6 +1,9 @@
 use psy_bridge_core::{common_types::QHash256, header::PsyBridgeHeader};
+use psy_bridge_core::custodian_config::Bridge7MultisigCustodianWalletConfig;
 use psy_bridge_core::crypto::zk::CompactBridgeZKProof;
-use crate::program_state::{FinalizedBlockMintTxoInfo, PsyBridgeConfig, PsyReturnTxOutput, PsyWithdrawalRequest};
+use crate::constants::DOGE_BLOCK_HEADER_SIZE;
+use crate::data_accounts::fee_split::FeeSplitTable;
+use crate::program_state::{FinalizedBlockMintTxoInfo, PsyBridgeConfig, PsyBridgePolicyConfig, PsyReturnTxOutput, PsyWithdrawalRequest};
 
 // Instruction Discriminators
 pub const DOGE_BRIDGE_INSTRUCTION_INITIALIZE: u8 = 0;
@@ -14,6 +17,35 @@ pub const DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP: u8 = 7;
 pub const DOGE_BRIDGE_INSTRUCTION_PROCESS_REORG_BLOCKS: u8 = 8;
 pub const DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP_AUTO_ADVANCE: u8 = 9;
 pub const DOGE_BRIDGE_INSTRUCTION_SNAPSHOT_WITHDRAWALS: u8 = 10;
+pub const DOGE_BRIDGE_INSTRUCTION_GUARDIAN_SET_PAUSE: u8 = 11;
+pub const DOGE_BRIDGE_INSTRUCTION_SET_CONTROL_MODE: u8 = 12;
+pub const DOGE_BRIDGE_INSTRUCTION_PROPOSE_CONFIG_UPDATE: u8 = 13;
+pub const DOGE_BRIDGE_INSTRUCTION_APPLY_CONFIG_UPDATE: u8 = 14;
+pub const DOGE_BRIDGE_INSTRUCTION_NOMINATE_KEY: u8 = 15;
+pub const DOGE_BRIDGE_INSTRUCTION_ACCEPT_KEY: u8 = 16;
+pub const DOGE_BRIDGE_INSTRUCTION_ADMIN_FORCE_ROTATE_KEY: u8 = 17;
+pub const DOGE_BRIDGE_INSTRUCTION_PROPOSE_VK_UPDATE: u8 = 18;
+pub const DOGE_BRIDGE_INSTRUCTION_ACTIVATE_VK_UPDATE: u8 = 19;
+pub const DOGE_BRIDGE_INSTRUCTION_CANCEL_VK_UPDATE: u8 = 20;
+pub const DOGE_BRIDGE_INSTRUCTION_SET_WITHDRAWAL_LIMITS: u8 = 21;
+pub const DOGE_BRIDGE_INSTRUCTION_MARK_WITHDRAWAL_RECEIPTS_FULFILLED: u8 = 22;
+pub const DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT: u8 = 23;
+pub const DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL: u8 = 24;
+pub const DOGE_BRIDGE_INSTRUCTION_SET_FEE_SPLIT: u8 = 25;
+pub const DOGE_BRIDGE_INSTRUCTION_DISTRIBUTE_FEES: u8 = 26;
+pub const DOGE_BRIDGE_INSTRUCTION_INIT_FINALIZED_BLOCK_HISTORY: u8 = 27;
+pub const DOGE_BRIDGE_INSTRUCTION_VERIFY_TX_INCLUSION: u8 = 28;
+pub const DOGE_BRIDGE_INSTRUCTION_INIT_BLOCK_HASH_ARCHIVE: u8 = 29;
+pub const DOGE_BRIDGE_INSTRUCTION_MIGRATE_STATE: u8 = 30;
+pub const DOGE_BRIDGE_INSTRUCTION_STORE_CUSTODIAN_WALLET_CONFIG: u8 = 31;
+pub const DOGE_BRIDGE_INSTRUCTION_SCHEDULE_CUSTODIAN_ROTATION: u8 = 32;
+pub const DOGE_BRIDGE_INSTRUCTION_CANCEL_CUSTODIAN_ROTATION: u8 = 33;
+pub const DOGE_BRIDGE_INSTRUCTION_PROCESS_CUSTODIAN_SWEEP: u8 = 34;
+// followed by 1..=MAX_WITHDRAWAL_REQUESTS_PER_BATCH RequestWithdrawalInstructionData, the receipt accounts follow the system program
+pub const DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWALS_BATCH: u8 = 35;
+pub const DOGE_BRIDGE_INSTRUCTION_REQUEST_SCRIPT_WITHDRAWAL: u8 = 36;
+pub const DOGE_BRIDGE_INSTRUCTION_CANCEL_CONFIG_UPDATE: u8 = 37;
+pub const DOGE_BRIDGE_INSTRUCTION_SET_POLICY_CONFIG: u8 = 38;
 
 #[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
 pub struct InitializeBridgeParams {
@@ -27,6 +59,8 @@ pub struct InitializeBridgeParams {
 pub struct InitializeBridgeInstructionData {
     pub operator_pubkey: [u8; 32],
     pub fee_spender_pubkey: [u8; 32],
+    pub guardian_pubkey: [u8; 32],
+    pub admin_pubkey: [u8; 32],
     pub doge_mint: [u8; 32],
     pub bridge_header: PsyBridgeHeader,
     pub start_return_txo_output: PsyReturnTxOutput,
@@ -61,6 +95,17 @@ impl Default for BlockUpdateFixedData {
 #[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
 pub struct RequestWithdrawalInstructionData {
     pub request: PsyWithdrawalRequest,
+    // any value the requester hasn't used for a receipt that still exists, keys the receipt PDA
+    pub receipt_nonce: u64,
+}
+
+// Request Script Withdrawal (Fixed Data Part)
+// Followed by the 1..=MAX_WITHDRAWAL_SCRIPT_SIZE byte scriptPubKey.
+// The script is read from a generic buffer account instead when one is passed after the system program.
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct RequestScriptWithdrawalFixedData {
+    pub amount_sats: u64,
+    pub receipt_nonce: u64,
 }
 
 #[macro_rules_attribute::apply(crate::DeriveCopySerializeReprC)]
@@ -92,6 +137,143 @@ pub struct ProcessManualDepositInstructionData {
     pub deposit_amount_sats: u64,
 }
 
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct GuardianSetPauseInstructionData {
+    // 0 = unpause
+    pub pause_duration_secs: u32,
+}
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct SetBridgeControlModeInstructionData {
+    // bitmask of BRIDGE_CONTROL_MODE_DISABLE_* flags
+    pub bridge_control_mode: u32,
+}
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct ProposeConfigUpdateInstructionData {
+    pub new_config_params: PsyBridgeConfig,
+    pub delay_secs: u32,
+    pub _padding: u32, // keep 8-byte alignment
+}
+
+// signed by the settings authority, takes effect immediately
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct SetPolicyConfigInstructionData {
+    pub policy_config: PsyBridgePolicyConfig,
+}
+
+// used by nominate and admin force rotate, accept ignores new_pubkey
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct RotateKeyInstructionData {
+    pub new_pubkey: [u8; 32],
+    // one of the BRIDGE_KEY_ROLE_* constants
+    pub key_role: u32,
+}
+
+// a limit of 0 disables that check
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct SetWithdrawalLimitsInstructionData {
+    pub limit_per_request_sats: u64,
+    pub limit_per_window_sats: u64,
+    pub limit_window_secs: u64,
+    // non-zero clears the usage counted against the current window
+    pub reset_window_usage: u32,
+    pub _padding: u32, // keep 8-byte alignment
+}
+
+// replaces the whole fee split table, signed by the fee spender
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct SetFeeSplitInstructionData {
+    pub fee_split: FeeSplitTable,
+}
+
+// permissionless, the config is only stored if it hashes to the bridge's custodian_wallet_config_hash
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct StoreCustodianWalletConfigInstructionData {
+    pub config: Bridge7MultisigCustodianWalletConfig,
+}
+
+// signed by the settings authority, the sweep can be processed once CUSTODIAN_ROTATION_DELAY_SECS has passed
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct ScheduleCustodianRotationInstructionData {
+    pub new_custodian_wallet_config_hash: QHash256,
+}
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeReprC)]
+pub struct ProcessCustodianSweepInstructionData {
+    #[cfg_attr(feature = "serialize_serde", serde(with = "psy_bridge_core::serde_arrays::serde_arrays"))]
+    pub proof: CompactBridgeZKProof,
+    pub new_return_output: PsyReturnTxOutput,
+    pub new_spent_txo_tree_root: QHash256,
+}
+impl Default for ProcessCustodianSweepInstructionData {
+    fn default() -> Self {
+        Self {
+            proof: [0u8; 256],
+            new_return_output: PsyReturnTxOutput::default(),
+            new_spent_txo_tree_root: [0u8; 32],
+        }
+    }
+}
+
+// signed by the settings authority, sent once per allocation step until the history account reaches its full size
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct InitFinalizedBlockHistoryInstructionData {
+    pub capacity: u32,
+    // 0-based, must match the number of steps already applied so a replayed step fails
+    pub step: u32,
+}
+
+// Verify Tx Inclusion (Fixed Data Part)
+// Followed by tx_siblings_count tx merkle branch hashes, DOGE_BLOCK_MERKLE_TREE_HEIGHT block merkle tree siblings,
+// then the tx_size byte raw transaction. The txid is computed from the raw transaction, so a 64 byte inner node
+// of the tx merkle tree can't be passed off as a transaction at a shorter branch.
+// The siblings and the transaction are read from a generic buffer account instead when one is passed after the bridge state.
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeReprC)]
+pub struct VerifyTxInclusionFixedData {
+    #[cfg_attr(feature = "serialize_serde", serde(with = "psy_bridge_core::serde_arrays::serde_arrays"))]
+    pub block_header: [u8; DOGE_BLOCK_HEADER_SIZE],
+    pub block_height: u32,
+    pub tx_index: u32,
+    pub tx_siblings_count: u32,
+    // 0 to only check inclusion
+    pub min_confirmations: u32,
+    pub tx_size: u32,
+}
+impl Default for VerifyTxInclusionFixedData {
+    fn default() -> Self {
+        Self {
+            block_header: [0u8; DOGE_BLOCK_HEADER_SIZE],
+            block_height: 0,
+            tx_index: 0,
+            tx_siblings_count: 0,
+            min_confirmations: 0,
+            tx_size: 0,
+        }
+    }
+}
+
+// set as the return data of a successful verify tx inclusion instruction
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct TxInclusionResult {
+    pub block_hash: QHash256,
+    // hash256 of the verified raw transaction, internal byte order
+    pub txid: QHash256,
+    pub block_height: u32,
+    // 1 when the block is the bridge's tip
+    pub confirmations: u32,
+    pub finalized_block_height: u32,
+    pub tip_block_height: u32,
+}
+
+// used by the vk update instructions, activate and cancel ignore new_vk
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct VerifierKeyUpdateInstructionData {
+    pub new_vk: [u8; 32],
+    // one of the VK_KIND_* constants
+    pub vk_kind: u32,
+}
+
 // Process Reorg Blocks (Fixed Data Part)
 // Followed by dynamic array of FinalizedBlockMintTxoInfo
 #[macro_rules_attribute::apply(crate::DeriveCopySerializeReprC)]
@@ -9,6 +9,7 @@ pub mod user_manual_deposit_manager;
 pub mod fake_zkp;
 pub mod programs;
 pub mod instructions;
+pub mod events;
 
 use macro_rules_attribute::attribute_alias;
 
@@ -0,0 +1,138 @@
+use psy_bridge_core::error::{DogeBridgeError, QDogeResult};
+
+use crate::{
+    constants::{BRIDGE_KEY_ROLE_FEE_SPENDER, BRIDGE_KEY_ROLE_GUARDIAN, BRIDGE_KEY_ROLE_OPERATOR},
+    program_state::{PsyBridgeAccessControlHeader, PsyBridgeProgramState},
+};
+
+const EMPTY_PUBKEY: [u8; 32] = [0u8; 32];
+
+impl PsyBridgeAccessControlHeader {
+    // returns (current key, pending key) for a role
+    fn get_role_keys_mut(&mut self, key_role: u32) -> QDogeResult<(&mut [u8; 32], &mut [u8; 32])> {
+        match key_role {
+            BRIDGE_KEY_ROLE_OPERATOR => Ok((&mut self.operator_pubkey, &mut self.pending_operator_pubkey)),
+            BRIDGE_KEY_ROLE_FEE_SPENDER => Ok((&mut self.fee_spender_pubkey, &mut self.pending_fee_spender_pubkey)),
+            BRIDGE_KEY_ROLE_GUARDIAN => Ok((&mut self.guardian_pubkey, &mut self.pending_guardian_pubkey)),
+            _ => Err(DogeBridgeError::InvalidKeyRole),
+        }
+    }
+    pub fn has_admin(&self) -> bool {
+        self.admin_pubkey != EMPTY_PUBKEY
+    }
+    // risk-increasing settings are signed by the admin when one is configured, otherwise by the operator
+    pub fn get_settings_authority(&self) -> &[u8; 32] {
+        if self.has_admin() {
+            &self.admin_pubkey
+        } else {
+            &self.operator_pubkey
+        }
+    }
+    pub fn ensure_vk_update_authority(&self, signer_pubkey: &[u8; 32]) -> QDogeResult<()> {
+        if self.get_settings_authority() != signer_pubkey {
+            return Err(DogeBridgeError::UnauthorizedVerifierKeyUpdate);
+        }
+        Ok(())
+    }
+}
+
+impl PsyBridgeProgramState {
+    // step 1: the current key holder nominates a successor, nominating all zeros cancels
+    pub fn run_nominate_key(
+        &mut self,
+        signer_pubkey: &[u8; 32],
+        key_role: u32,
+        new_pubkey: &[u8; 32],
+    ) -> QDogeResult<()> {
+        let (current, pending) = self.access_control.get_role_keys_mut(key_role)?;
+        if current != signer_pubkey {
+            return Err(DogeBridgeError::UnauthorizedKeyRotation);
+        }
+        *pending = *new_pubkey;
+        Ok(())
+    }
+
+    // step 2: the nominee signs to take over the role
+    pub fn run_accept_key(&mut self, signer_pubkey: &[u8; 32], key_role: u32) -> QDogeResult<()> {
+        let (current, pending) = self.access_control.get_role_keys_mut(key_role)?;
+        if *pending == EMPTY_PUBKEY || pending != signer_pubkey {
+            return Err(DogeBridgeError::NoPendingKeyRotation);
+        }
+        *current = *pending;
+        *pending = EMPTY_PUBKEY;
+        Ok(())
+    }
+
+    // recovery path for a lost or compromised key
+    pub fn run_admin_force_rotate_key(
+        &mut self,
+        signer_pubkey: &[u8; 32],
+        key_role: u32,
+        new_pubkey: &[u8; 32],
+    ) -> QDogeResult<()> {
+        if !self.access_control.has_admin() {
+            return Err(DogeBridgeError::AdminNotConfigured);
+        }
+        if self.access_control.admin_pubkey != *signer_pubkey {
+            return Err(DogeBridgeError::UnauthorizedKeyRotation);
+        }
+        if *new_pubkey == EMPTY_PUBKEY {
+            return Err(DogeBridgeError::InvalidAccountKey);
+        }
+        let (current, pending) = self.access_control.get_role_keys_mut(key_role)?;
+        *current = *new_pubkey;
+        *pending = EMPTY_PUBKEY;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_step_operator_rotation() {
+        let mut state = PsyBridgeProgramState::default();
+        state.access_control.operator_pubkey = [1u8; 32];
+
+        assert_eq!(
+            state.run_nominate_key(&[2u8; 32], BRIDGE_KEY_ROLE_OPERATOR, &[3u8; 32]),
+            Err(DogeBridgeError::UnauthorizedKeyRotation)
+        );
+        state
+            .run_nominate_key(&[1u8; 32], BRIDGE_KEY_ROLE_OPERATOR, &[3u8; 32])
+            .unwrap();
+        assert_eq!(
+            state.run_accept_key(&[4u8; 32], BRIDGE_KEY_ROLE_OPERATOR),
+            Err(DogeBridgeError::NoPendingKeyRotation)
+        );
+        state.run_accept_key(&[3u8; 32], BRIDGE_KEY_ROLE_OPERATOR).unwrap();
+
+        assert_eq!(state.access_control.operator_pubkey, [3u8; 32]);
+        assert_eq!(state.access_control.pending_operator_pubkey, EMPTY_PUBKEY);
+    }
+
+    #[test]
+    fn test_admin_force_rotation() {
+        let mut state = PsyBridgeProgramState::default();
+        state.access_control.fee_spender_pubkey = [1u8; 32];
+
+        assert_eq!(
+            state.run_admin_force_rotate_key(&[0u8; 32], BRIDGE_KEY_ROLE_FEE_SPENDER, &[2u8; 32]),
+            Err(DogeBridgeError::AdminNotConfigured)
+        );
+        state.access_control.admin_pubkey = [9u8; 32];
+        assert_eq!(
+            state.run_admin_force_rotate_key(&[8u8; 32], BRIDGE_KEY_ROLE_FEE_SPENDER, &[2u8; 32]),
+            Err(DogeBridgeError::UnauthorizedKeyRotation)
+        );
+        state
+            .run_admin_force_rotate_key(&[9u8; 32], BRIDGE_KEY_ROLE_FEE_SPENDER, &[2u8; 32])
+            .unwrap();
+        assert_eq!(state.access_control.fee_spender_pubkey, [2u8; 32]);
+        assert_eq!(
+            state.run_admin_force_rotate_key(&[9u8; 32], 7, &[2u8; 32]),
+            Err(DogeBridgeError::InvalidKeyRole)
+        );
+    }
+}
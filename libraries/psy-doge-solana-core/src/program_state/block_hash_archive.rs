@@ -0,0 +1,216 @@
+use psy_bridge_core::{
+    common_types::QHash256,
+    crypto::hash::{merkle::append::update_siblings_append_merkle_tree, sha256::SHA256_ZERO_HASHES},
+    error::{DogeBridgeError, QDogeResult},
+    header::PsyBridgeStateCommitment,
+};
+
+use crate::{
+    constants::DOGE_BLOCK_MERKLE_TREE_HEIGHT,
+    data_accounts::block_hash_archive::{
+        block_hash_archive_position, BlockHashArchiveEntry, BlockHashArchiveSegment,
+    },
+    program_state::PsyBridgeProgramState,
+};
+
+impl PsyBridgeProgramState {
+    pub fn has_block_hash_archive(&self) -> bool {
+        self.block_hash_archive_len != 0
+    }
+
+    // (segment index, index in segment) the next finalized block is written to
+    pub fn get_block_hash_archive_next_position(&self) -> (u32, u32) {
+        block_hash_archive_position(self.block_hash_archive_len)
+    }
+
+    pub fn ensure_can_enable_block_hash_archive(&self, signer_pubkey: &[u8; 32]) -> QDogeResult<()> {
+        if self.access_control.get_settings_authority() != signer_pubkey {
+            return Err(DogeBridgeError::UnauthorizedBlockHashArchiveUpdate);
+        }
+        if self.has_block_hash_archive() {
+            return Err(DogeBridgeError::BlockHashArchiveAlreadyEnabled);
+        }
+        Ok(())
+    }
+
+    // the archive starts at the current finalized block, segment_data is the freshly created first segment
+    pub fn run_enable_block_hash_archive(
+        &mut self,
+        signer_pubkey: &[u8; 32],
+        segment_data: &mut [u8],
+    ) -> QDogeResult<()> {
+        self.ensure_can_enable_block_hash_archive(signer_pubkey)?;
+        self.record_block_hash_archive(segment_data)
+    }
+
+    // appends the current finalized state, the archive's first entry
+    pub fn record_block_hash_archive(&mut self, segment_data: &mut [u8]) -> QDogeResult<()> {
+        let entry = BlockHashArchiveEntry::from_commitment(&self.bridge_header.finalized_state);
+        self.record_block_hash_archive_entries(segment_data, &[entry])
+    }
+
+    // appends entries to the segment the archive currently ends in, called after every successful block transition.
+    // A reorg's entries can be split over two segments, each part is recorded separately.
+    pub fn record_block_hash_archive_entries(
+        &mut self,
+        segment_data: &mut [u8],
+        entries: &[BlockHashArchiveEntry],
+    ) -> QDogeResult<()> {
+        let (segment_index, _) = self.get_block_hash_archive_next_position();
+        BlockHashArchiveSegment::append_to_account_data(segment_data, segment_index, entries)?;
+        self.block_hash_archive_len += entries.len() as u64;
+        Ok(())
+    }
+
+    // the entries of every height finalized since previous_finalized_state, oldest first.
+    // A reorg can finalize several heights, the proof only commits to the new finalized block though. reorg_block_hashes
+    // are the block merkle tree siblings of the previous finalized block followed by the hashes of the heights in
+    // between, they are accepted if appending them to the previous block merkle tree gives the new finalized root.
+    pub fn get_finalized_block_hash_archive_entries(
+        &self,
+        previous_finalized_state: &PsyBridgeStateCommitment,
+        reorg_block_hashes: &[QHash256],
+    ) -> QDogeResult<Vec<BlockHashArchiveEntry>> {
+        let finalized_state = &self.bridge_header.finalized_state;
+        if finalized_state.block_height <= previous_finalized_state.block_height {
+            return Err(DogeBridgeError::InvalidReorgBlockHashes);
+        }
+        let skipped_count = (finalized_state.block_height - previous_finalized_state.block_height - 1) as usize;
+        if skipped_count == 0 {
+            return Ok(vec![BlockHashArchiveEntry::from_commitment(finalized_state)]);
+        }
+        if reorg_block_hashes.len() != DOGE_BLOCK_MERKLE_TREE_HEIGHT + skipped_count {
+            return Err(DogeBridgeError::InvalidReorgBlockHashes);
+        }
+        let (siblings, block_hashes) = reorg_block_hashes.split_at(DOGE_BLOCK_MERKLE_TREE_HEIGHT);
+
+        // the tree is append only, only the left siblings of the previous finalized block are used
+        let previous_height = previous_finalized_state.block_height as u64;
+        let mut next_siblings: Vec<QHash256> = (0..DOGE_BLOCK_MERKLE_TREE_HEIGHT)
+            .map(|i| if (previous_height >> i) & 1 == 1 { siblings[i] } else { SHA256_ZERO_HASHES[i] })
+            .collect();
+        let previous_root =
+            update_siblings_append_merkle_tree(&mut next_siblings, previous_finalized_state.block_hash, previous_height);
+        if previous_root != previous_finalized_state.block_merkle_tree_root {
+            return Err(DogeBridgeError::InvalidReorgBlockHashes);
+        }
+
+        let mut entries = Vec::with_capacity(skipped_count + 1);
+        for (i, block_hash) in block_hashes.iter().enumerate() {
+            let block_height = previous_finalized_state.block_height + 1 + i as u32;
+            let block_merkle_tree_root =
+                update_siblings_append_merkle_tree(&mut next_siblings, *block_hash, block_height as u64);
+            entries.push(BlockHashArchiveEntry {
+                block_hash: *block_hash,
+                block_merkle_tree_root,
+                block_height,
+                _padding: 0,
+            });
+        }
+        let root = update_siblings_append_merkle_tree(
+            &mut next_siblings,
+            finalized_state.block_hash,
+            finalized_state.block_height as u64,
+        );
+        if root != finalized_state.block_merkle_tree_root {
+            return Err(DogeBridgeError::InvalidReorgBlockHashes);
+        }
+        entries.push(BlockHashArchiveEntry::from_commitment(finalized_state));
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_accounts::block_hash_archive::{
+        block_hash_archive_segment_size, BLOCK_HASH_ARCHIVE_ENTRY_SIZE,
+    };
+
+    #[test]
+    fn test_block_hash_archive_records_finalized_blocks() {
+        let mut state = PsyBridgeProgramState::default();
+        state.access_control.operator_pubkey = [1u8; 32];
+        state.bridge_header.finalized_state.block_height = 100;
+        state.bridge_header.finalized_state.block_hash = [100u8; 32];
+
+        let mut segment_data = vec![0u8; block_hash_archive_segment_size(1)];
+        assert_eq!(
+            state.run_enable_block_hash_archive(&[2u8; 32], &mut segment_data),
+            Err(DogeBridgeError::UnauthorizedBlockHashArchiveUpdate)
+        );
+        state.run_enable_block_hash_archive(&[1u8; 32], &mut segment_data).unwrap();
+        assert_eq!(
+            state.ensure_can_enable_block_hash_archive(&[1u8; 32]),
+            Err(DogeBridgeError::BlockHashArchiveAlreadyEnabled)
+        );
+
+        state.bridge_header.finalized_state.block_height = 101;
+        state.bridge_header.finalized_state.block_hash = [101u8; 32];
+        assert_eq!(state.get_block_hash_archive_next_position(), (0, 1));
+        segment_data.resize(segment_data.len() + BLOCK_HASH_ARCHIVE_ENTRY_SIZE, 0);
+        state.record_block_hash_archive(&mut segment_data).unwrap();
+        assert_eq!(state.block_hash_archive_len, 2);
+
+        let entry = BlockHashArchiveSegment::find_in_account_data(&segment_data, 100).unwrap();
+        assert_eq!(entry.block_hash, [100u8; 32]);
+        let entry = BlockHashArchiveSegment::find_in_account_data(&segment_data, 101).unwrap();
+        assert_eq!(entry.block_hash, [101u8; 32]);
+    }
+
+    #[test]
+    fn test_reorg_block_hash_archive_entries_cover_every_finalized_height() {
+        let block_hashes: Vec<QHash256> = (0..10u8).map(|i| [i + 1; 32]).collect();
+        let mut next_siblings: Vec<QHash256> = SHA256_ZERO_HASHES[..DOGE_BLOCK_MERKLE_TREE_HEIGHT].to_vec();
+        let mut previous_siblings = Vec::new();
+        let mut roots = Vec::new();
+        for (height, block_hash) in block_hashes.iter().enumerate() {
+            if height == 5 {
+                previous_siblings = (0..DOGE_BLOCK_MERKLE_TREE_HEIGHT)
+                    .map(|i| if (height >> i) & 1 == 1 { next_siblings[i] } else { SHA256_ZERO_HASHES[i] })
+                    .collect();
+            }
+            roots.push(update_siblings_append_merkle_tree(&mut next_siblings, *block_hash, height as u64));
+        }
+        let commitment = |height: usize| PsyBridgeStateCommitment {
+            block_hash: block_hashes[height],
+            block_merkle_tree_root: roots[height],
+            block_height: height as u32,
+            ..Default::default()
+        };
+
+        let mut state = PsyBridgeProgramState::default();
+        state.bridge_header.finalized_state = commitment(9);
+        let mut reorg_block_hashes = previous_siblings.clone();
+        reorg_block_hashes.extend_from_slice(&block_hashes[6..9]);
+        let entries = state
+            .get_finalized_block_hash_archive_entries(&commitment(5), &reorg_block_hashes)
+            .unwrap();
+        assert_eq!(
+            entries,
+            (6..10).map(|height| BlockHashArchiveEntry::from_commitment(&commitment(height))).collect::<Vec<_>>()
+        );
+
+        // a single finalized height needs no hashes
+        assert_eq!(
+            state.get_finalized_block_hash_archive_entries(&commitment(8), &[]),
+            Ok(vec![BlockHashArchiveEntry::from_commitment(&commitment(9))])
+        );
+        // missing or wrong hashes
+        assert_eq!(
+            state.get_finalized_block_hash_archive_entries(&commitment(5), &previous_siblings),
+            Err(DogeBridgeError::InvalidReorgBlockHashes)
+        );
+        reorg_block_hashes[DOGE_BLOCK_MERKLE_TREE_HEIGHT + 1] = [0u8; 32];
+        assert_eq!(
+            state.get_finalized_block_hash_archive_entries(&commitment(5), &reorg_block_hashes),
+            Err(DogeBridgeError::InvalidReorgBlockHashes)
+        );
+        reorg_block_hashes[DOGE_BLOCK_MERKLE_TREE_HEIGHT + 1] = block_hashes[7];
+        reorg_block_hashes[0] = [0u8; 32];
+        assert_eq!(
+            state.get_finalized_block_hash_archive_entries(&commitment(5), &reorg_block_hashes),
+            Err(DogeBridgeError::InvalidReorgBlockHashes)
+        );
+    }
+}
@@ -244,6 +244,7 @@ impl PsyBridgeProgramState {
         self.recent_finalized_blocks[self.next_recent_finalized_block_index as usize] =
             new_header.finalized_state;
         self.next_recent_finalized_block_index = (self.next_recent_finalized_block_index + 1) % 8;
+        self.apply_queued_config_update();
         Ok(())
     }
 
@@ -329,6 +330,7 @@ impl PsyBridgeProgramState {
         if first_non_empty_in_backlog.is_none() {
             // no new pending mints to process
             self.bridge_header = new_header.clone();
+            self.apply_queued_config_update();
             return Ok(());
         }
 
@@ -382,6 +384,7 @@ impl PsyBridgeProgramState {
         )?;
 
         self.bridge_header = new_header.clone();
+        self.apply_queued_config_update();
         Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,244 @@
+use psy_bridge_core::error::{DogeBridgeError, QDogeResult};
+
+use crate::{
+    constants::{
+        MIN_CONFIG_UPDATE_DELAY_SECS, PENDING_CONFIG_STATUS_NONE, PENDING_CONFIG_STATUS_PROPOSED,
+        PENDING_CONFIG_STATUS_QUEUED,
+    },
+    program_state::{PsyBridgeConfig, PsyBridgePolicyConfig, PsyBridgeProgramState},
+};
+
+impl PsyBridgeConfig {
+    pub fn is_valid(&self) -> bool {
+        self.deposit_fee_rate_denominator != 0
+            && self.withdrawal_fee_rate_denominator != 0
+            && self.deposit_fee_rate_numerator <= self.deposit_fee_rate_denominator
+            && self.withdrawal_fee_rate_numerator <= self.withdrawal_fee_rate_denominator
+    }
+}
+
+impl PsyBridgeProgramState {
+    pub fn run_propose_config_update(
+        &mut self,
+        signer_pubkey: &[u8; 32],
+        new_config: &PsyBridgeConfig,
+        delay_secs: u32,
+        current_unix_timestamp_secs: u32,
+    ) -> QDogeResult<()> {
+        if self.access_control.get_settings_authority() != signer_pubkey {
+            return Err(DogeBridgeError::UnauthorizedConfigUpdate);
+        }
+        if !new_config.is_valid() {
+            return Err(DogeBridgeError::InvalidBridgeConfig);
+        }
+        if delay_secs < MIN_CONFIG_UPDATE_DELAY_SECS {
+            return Err(DogeBridgeError::ConfigUpdateDelayTooShort);
+        }
+        // a new proposal replaces any pending one and restarts the timelock
+        self.pending_config_params = *new_config;
+        self.pending_config_apply_after_secs =
+            current_unix_timestamp_secs as u64 + delay_secs as u64;
+        self.pending_config_status = PENDING_CONFIG_STATUS_PROPOSED;
+        self.pending_config_proposed_at_secs = current_unix_timestamp_secs;
+        Ok(())
+    }
+
+    // queues the pending config once the timelock has elapsed, it takes effect after the next block transition
+    pub fn run_apply_config_update(&mut self, current_unix_timestamp_secs: u32) -> QDogeResult<()> {
+        if self.pending_config_status != PENDING_CONFIG_STATUS_PROPOSED {
+            return Err(DogeBridgeError::NoPendingConfigUpdate);
+        }
+        if (current_unix_timestamp_secs as u64) < self.pending_config_apply_after_secs {
+            return Err(DogeBridgeError::ConfigUpdateTimelockNotElapsed);
+        }
+        self.pending_config_status = PENDING_CONFIG_STATUS_QUEUED;
+        Ok(())
+    }
+
+    // the guardian can veto a proposal as well, a queued update can still be dropped before the next block transition
+    pub fn run_cancel_config_update(&mut self, signer_pubkey: &[u8; 32]) -> QDogeResult<()> {
+        if self.access_control.get_settings_authority() != signer_pubkey
+            && self.access_control.guardian_pubkey != *signer_pubkey
+        {
+            return Err(DogeBridgeError::UnauthorizedConfigUpdate);
+        }
+        if self.pending_config_status == PENDING_CONFIG_STATUS_NONE {
+            return Err(DogeBridgeError::NoPendingConfigUpdate);
+        }
+        self.clear_pending_config_update();
+        Ok(())
+    }
+
+    // called after a block transition proof has been verified against the current config hash
+    pub fn apply_queued_config_update(&mut self) {
+        if self.pending_config_status != PENDING_CONFIG_STATUS_QUEUED {
+            return;
+        }
+        self.config_params = self.pending_config_params;
+        self.clear_pending_config_update();
+    }
+
+    // the policy isn't part of the proven config, so it applies immediately
+    pub fn run_set_policy_config(
+        &mut self,
+        signer_pubkey: &[u8; 32],
+        policy_config: &PsyBridgePolicyConfig,
+    ) -> QDogeResult<()> {
+        if self.access_control.get_settings_authority() != signer_pubkey {
+            return Err(DogeBridgeError::UnauthorizedConfigUpdate);
+        }
+        self.policy_config = *policy_config;
+        Ok(())
+    }
+
+    fn clear_pending_config_update(&mut self) {
+        self.pending_config_params = PsyBridgeConfig::default();
+        self.pending_config_apply_after_secs = 0;
+        self.pending_config_status = PENDING_CONFIG_STATUS_NONE;
+        self.pending_config_proposed_at_secs = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPERATOR: [u8; 32] = [1u8; 32];
+    const GUARDIAN: [u8; 32] = [2u8; 32];
+    const ADMIN: [u8; 32] = [3u8; 32];
+
+    fn test_state() -> PsyBridgeProgramState {
+        let mut state = PsyBridgeProgramState::default();
+        state.access_control.operator_pubkey = OPERATOR;
+        state.access_control.guardian_pubkey = GUARDIAN;
+        state
+    }
+
+    fn test_config(deposit_flat_fee_sats: u64) -> PsyBridgeConfig {
+        PsyBridgeConfig {
+            deposit_fee_rate_numerator: 1,
+            deposit_fee_rate_denominator: 100,
+            withdrawal_fee_rate_numerator: 1,
+            withdrawal_fee_rate_denominator: 100,
+            deposit_flat_fee_sats,
+            withdrawal_flat_fee_sats: 1000,
+        }
+    }
+
+    #[test]
+    fn test_config_update_applies_after_timelock_and_transition() {
+        let mut state = test_state();
+        state.config_params = test_config(1000);
+        let new_config = test_config(2000);
+
+        state
+            .run_propose_config_update(&OPERATOR, &new_config, MIN_CONFIG_UPDATE_DELAY_SECS, 1_000)
+            .unwrap();
+        assert_eq!(
+            state.run_apply_config_update(1_000 + MIN_CONFIG_UPDATE_DELAY_SECS - 1),
+            Err(DogeBridgeError::ConfigUpdateTimelockNotElapsed)
+        );
+        state
+            .run_apply_config_update(1_000 + MIN_CONFIG_UPDATE_DELAY_SECS)
+            .unwrap();
+
+        // still using the old config until the next block transition
+        assert_eq!(state.config_params, test_config(1000));
+        state.apply_queued_config_update();
+        assert_eq!(state.config_params, new_config);
+        assert_eq!(state.pending_config_status, PENDING_CONFIG_STATUS_NONE);
+    }
+
+    #[test]
+    fn test_config_update_rejects_invalid_proposals() {
+        let mut state = test_state();
+        let mut bad_config = test_config(1000);
+        bad_config.withdrawal_fee_rate_denominator = 0;
+
+        assert_eq!(
+            state.run_propose_config_update(&OPERATOR, &bad_config, MIN_CONFIG_UPDATE_DELAY_SECS, 0),
+            Err(DogeBridgeError::InvalidBridgeConfig)
+        );
+        assert_eq!(
+            state.run_propose_config_update(
+                &OPERATOR,
+                &test_config(1000),
+                MIN_CONFIG_UPDATE_DELAY_SECS - 1,
+                0
+            ),
+            Err(DogeBridgeError::ConfigUpdateDelayTooShort)
+        );
+        assert_eq!(
+            state.run_apply_config_update(u32::MAX),
+            Err(DogeBridgeError::NoPendingConfigUpdate)
+        );
+    }
+
+    #[test]
+    fn test_config_update_signed_by_settings_authority() {
+        let mut state = test_state();
+
+        // the guardian can only veto
+        assert_eq!(
+            state.run_propose_config_update(&GUARDIAN, &test_config(2000), MIN_CONFIG_UPDATE_DELAY_SECS, 0),
+            Err(DogeBridgeError::UnauthorizedConfigUpdate)
+        );
+        state
+            .run_propose_config_update(&OPERATOR, &test_config(2000), MIN_CONFIG_UPDATE_DELAY_SECS, 0)
+            .unwrap();
+        assert_eq!(
+            state.run_cancel_config_update(&ADMIN),
+            Err(DogeBridgeError::UnauthorizedConfigUpdate)
+        );
+        state.run_cancel_config_update(&GUARDIAN).unwrap();
+        assert_eq!(state.pending_config_status, PENDING_CONFIG_STATUS_NONE);
+        assert_eq!(
+            state.run_cancel_config_update(&OPERATOR),
+            Err(DogeBridgeError::NoPendingConfigUpdate)
+        );
+
+        // once an admin is configured the operator can no longer propose
+        state.access_control.admin_pubkey = ADMIN;
+        assert_eq!(
+            state.run_propose_config_update(&OPERATOR, &test_config(2000), MIN_CONFIG_UPDATE_DELAY_SECS, 0),
+            Err(DogeBridgeError::UnauthorizedConfigUpdate)
+        );
+        state
+            .run_propose_config_update(&ADMIN, &test_config(2000), MIN_CONFIG_UPDATE_DELAY_SECS, 0)
+            .unwrap();
+        state.run_apply_config_update(MIN_CONFIG_UPDATE_DELAY_SECS).unwrap();
+        // a queued update can still be dropped before the next block transition
+        state.run_cancel_config_update(&ADMIN).unwrap();
+        state.apply_queued_config_update();
+        assert_eq!(state.config_params, PsyBridgeConfig::default());
+    }
+
+    #[test]
+    fn test_policy_config_set_by_settings_authority_only() {
+        let mut state = test_state();
+        state.config_params = test_config(1000);
+        let config_hash = state.config_params.get_hash();
+        let policy = PsyBridgePolicyConfig {
+            min_withdrawal_sats: 1_000_000,
+            min_deposit_sats: 500_000,
+            withdrawal_cancellation_fee_sats: 100_000,
+        };
+
+        assert_eq!(
+            state.run_set_policy_config(&GUARDIAN, &policy),
+            Err(DogeBridgeError::UnauthorizedConfigUpdate)
+        );
+        state.run_set_policy_config(&OPERATOR, &policy).unwrap();
+        assert_eq!(state.policy_config, policy);
+        // the proven config is untouched
+        assert_eq!(state.config_params.get_hash(), config_hash);
+
+        state.access_control.admin_pubkey = ADMIN;
+        assert_eq!(
+            state.run_set_policy_config(&OPERATOR, &PsyBridgePolicyConfig::default()),
+            Err(DogeBridgeError::UnauthorizedConfigUpdate)
+        );
+        state.run_set_policy_config(&ADMIN, &PsyBridgePolicyConfig::default()).unwrap();
+        assert_eq!(state.policy_config, PsyBridgePolicyConfig::default());
+    }
+}
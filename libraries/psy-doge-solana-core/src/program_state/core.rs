@@ -8,7 +8,7 @@ use psy_bridge_core::{
     }, error::{DogeBridgeError, QDogeResult}, header::{PsyBridgeHeader, PsyBridgeStateCommitment}, txo_constants::{TXO_MERKLE_INDEX_TOTAL_BITS, get_txo_block_number_tx_number_output_index_from_combined_index}
 };
 
-use crate::{ instructions::doge_bridge::InitializeBridgeInstructionData, program_state::{FinalizedBlockMintTxoManager, PsyReturnTxOutput, PsyWithdrawalChainSnapshot, PsyWithdrawalRequest}, public_inputs::get_withdrawal_proof_public_inputs, utils::{deposit_leaf::hash_deposit_leaf, fees::{FeeResult, calcuate_deposit_fee, calcuate_withdrawal_fee}}};
+use crate::{ constants::{BRIDGE_CONTROL_MODE_ALL_FLAGS, PENDING_CONFIG_STATUS_NONE}, data_accounts::finalized_block_history::FinalizedBlockHistory, instructions::doge_bridge::InitializeBridgeInstructionData, program_state::{BridgeStateVersionHeader, FinalizedBlockMintTxoManager, PsyCustodianRotation, PsyReturnTxOutput, PsyWithdrawalChainSnapshot, PsyWithdrawalRequest, WithdrawalAddressType}, public_inputs::get_withdrawal_proof_public_inputs, utils::{deposit_leaf::hash_deposit_leaf, fees::{FeeResult, calcuate_deposit_fee, calcuate_withdrawal_fee}}};
 
 const INVALID_BLOCK_HEIGHT: u32 = 0xFFFFFFFF;
 const MIN_WAIT_TIME_REPLAY_WITHDRAWAL_SECS: u32 = 60; // 1 minute
@@ -17,6 +17,13 @@ const MIN_WAIT_TIME_REPLAY_WITHDRAWAL_SECS: u32 = 60; // 1 minute
 pub struct PsyBridgeAccessControlHeader {
     pub operator_pubkey: [u8; 32],
     pub fee_spender_pubkey: [u8; 32],
+    pub guardian_pubkey: [u8; 32],
+    // all zeros = no admin, otherwise allowed to force a key rotation
+    pub admin_pubkey: [u8; 32],
+    // nominated successors, all zeros = none
+    pub pending_operator_pubkey: [u8; 32],
+    pub pending_fee_spender_pubkey: [u8; 32],
+    pub pending_guardian_pubkey: [u8; 32],
 }
 #[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
 pub struct PsyBridgeConfig {
@@ -32,16 +39,43 @@ impl PsyBridgeConfig {
         hash_impl_sha256_bytes(bytemuck::bytes_of(self))
     }
 }
+// limits only enforced by the program, unlike PsyBridgeConfig they are not part of the proven config hash
+// and can be changed by the settings authority without a timelock
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct PsyBridgePolicyConfig {
+    // 0 = no minimum, compared against the amount sent after fees
+    pub min_withdrawal_sats: u64,
+    // 0 = no minimum, compared against the amount deposited before fees
+    pub min_deposit_sats: u64,
+    // flat fee kept when a withdrawal request is cancelled, 0 = the full burned amount is refunded
+    pub withdrawal_cancellation_fee_sats: u64,
+}
 
 
 
+// the bridge state account, see state_version.rs for the older layouts migrate_state converts from
 #[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
 pub struct BridgeProgramStateWithDogeMint {
+    pub version_header: BridgeStateVersionHeader,
     pub core_state: PsyBridgeProgramState,
     pub doge_mint: [u8; 32],
+    // taken from the reserved space, all zeros = no rotation scheduled
+    pub custodian_rotation: PsyCustodianRotation,
+    // taken from the reserved space, 0 = the verifier key registry hasn't been created and the built-in keys
+    // apply, otherwise proofs must be checked against the registry so leaving it out can't bring back a replaced key
+    pub vk_registry_created_at_secs: u64,
+    pub _vk_registry_padding: [u64; 3],
+    // zeroed space later fields can be taken from without a version bump, as long as all zeros is their
+    // empty value. Nothing writes it: initialize fills a freshly created (zeroed) account and
+    // migrate_state zero fills the account before copying the older fields in.
+    pub _reserved: [[u8; 32]; 13],
 }
 impl BridgeProgramStateWithDogeMint {
     pub const SIZE: usize = std::mem::size_of::<BridgeProgramStateWithDogeMint>();
+
+    pub fn has_vk_registry(&self) -> bool {
+        self.vk_registry_created_at_secs != 0
+    }
 }
 
 #[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
@@ -63,13 +97,34 @@ pub struct PsyBridgeProgramState {
     pub next_recent_finalized_block_index: u32,
     pub last_processed_withdrawals_at_ms: u64,
     pub total_requested_withdrawals_sats: u64,
+    // withdrawal circuit breaker, a limit of 0 disables that check
+    pub withdrawal_limit_per_request_sats: u64,
+    pub withdrawal_limit_per_window_sats: u64,
+    pub withdrawal_limit_window_secs: u64,
+    // burned sats counted against the window limit while one is set, decays linearly over withdrawal_limit_window_secs
+    pub withdrawal_window_used_sats: u64,
+    pub withdrawal_window_updated_at_secs: u64,
     pub total_fees_withdrawn_sats: u64,
+    // 0 = no fee split table, otherwise fees can only be paid out through distribute_fees
+    pub fee_split_updated_at_secs: u64,
+    // 0 = no finalized block history account, otherwise every block update must append to it
+    pub finalized_block_history_capacity: u64,
+    // number of blocks in the block hash archive, 0 = no archive, otherwise every block update must append to it
+    pub block_hash_archive_len: u64,
     pub total_manual_deposit_fees_sats: u64,
     pub total_withdrawal_fees_sats: u64,
     pub last_received_block_at_ms: u64,
     pub last_replayed_withdrawal_at_ms: u64,
+    // emergency pause set by the guardian, independent of the header's paused_until_secs
+    pub guardian_paused_until_secs: u64,
+
+    pub pending_config_params: PsyBridgeConfig,
+    pub pending_config_apply_after_secs: u64,
+    pub pending_config_status: u32,
+    pub pending_config_proposed_at_secs: u32,
 
     pub config_params: PsyBridgeConfig,
+    pub policy_config: PsyBridgePolicyConfig,
 
     pub access_control: PsyBridgeAccessControlHeader,
 }
@@ -92,15 +147,34 @@ impl PsyBridgeProgramState {
         self.next_recent_finalized_block_index = 0;
         self.last_processed_withdrawals_at_ms = 0;
         self.total_requested_withdrawals_sats = 0;
+        self.withdrawal_limit_per_request_sats = 0;
+        self.withdrawal_limit_per_window_sats = 0;
+        self.withdrawal_limit_window_secs = 0;
+        self.withdrawal_window_used_sats = 0;
+        self.withdrawal_window_updated_at_secs = 0;
         self.total_fees_withdrawn_sats = 0;
+        self.fee_split_updated_at_secs = 0;
+        self.finalized_block_history_capacity = 0;
+        self.block_hash_archive_len = 0;
         self.total_manual_deposit_fees_sats = 0;
         self.total_withdrawal_fees_sats = 0;
         self.last_received_block_at_ms = 0;
         self.last_replayed_withdrawal_at_ms = 0;
+        self.guardian_paused_until_secs = 0;
+        self.pending_config_params = PsyBridgeConfig::default();
+        self.pending_config_apply_after_secs = 0;
+        self.pending_config_status = PENDING_CONFIG_STATUS_NONE;
+        self.pending_config_proposed_at_secs = 0;
         self.config_params = initialize_instruction.config_params;
+        self.policy_config = PsyBridgePolicyConfig::default();
         self.access_control = PsyBridgeAccessControlHeader {
             operator_pubkey: initialize_instruction.operator_pubkey,
             fee_spender_pubkey: initialize_instruction.fee_spender_pubkey,
+            guardian_pubkey: initialize_instruction.guardian_pubkey,
+            admin_pubkey: initialize_instruction.admin_pubkey,
+            pending_operator_pubkey: [0u8; 32],
+            pending_fee_spender_pubkey: [0u8; 32],
+            pending_guardian_pubkey: [0u8; 32],
         };
     }
     pub fn get_total_finalized_fees(&self) -> u64 {
@@ -111,6 +185,53 @@ impl PsyBridgeProgramState {
     }
     pub fn is_paused(&self, current_unix_timestamp_secs: u32) -> bool {
         self.bridge_header.is_paused(current_unix_timestamp_secs)
+            || self.guardian_paused_until_secs > current_unix_timestamp_secs as u64
+    }
+    pub fn ensure_not_paused(&self, current_unix_timestamp_secs: u32) -> QDogeResult<()> {
+        if self.is_paused(current_unix_timestamp_secs) {
+            return Err(DogeBridgeError::BridgePaused);
+        }
+        Ok(())
+    }
+    // a pause_duration_secs of 0 lifts the guardian pause early; a pause carried in the proven header is not affected
+    pub fn run_guardian_set_pause(
+        &mut self,
+        guardian_pubkey: &[u8; 32],
+        current_unix_timestamp_secs: u32,
+        pause_duration_secs: u32,
+    ) -> QDogeResult<()> {
+        if self.access_control.guardian_pubkey != *guardian_pubkey {
+            return Err(DogeBridgeError::UnauthorizedGuardian);
+        }
+        self.guardian_paused_until_secs = if pause_duration_secs == 0 {
+            0
+        } else {
+            current_unix_timestamp_secs as u64 + pause_duration_secs as u64
+        };
+        Ok(())
+    }
+    pub fn is_operation_enabled(&self, control_mode_flag: u32) -> bool {
+        self.bridge_control_mode & control_mode_flag == 0
+    }
+    pub fn ensure_operation_enabled(&self, control_mode_flag: u32) -> QDogeResult<()> {
+        if !self.is_operation_enabled(control_mode_flag) {
+            return Err(DogeBridgeError::OperationDisabled);
+        }
+        Ok(())
+    }
+    pub fn run_guardian_set_control_mode(
+        &mut self,
+        guardian_pubkey: &[u8; 32],
+        bridge_control_mode: u32,
+    ) -> QDogeResult<()> {
+        if self.access_control.guardian_pubkey != *guardian_pubkey {
+            return Err(DogeBridgeError::UnauthorizedGuardian);
+        }
+        if bridge_control_mode & !BRIDGE_CONTROL_MODE_ALL_FLAGS != 0 {
+            return Err(DogeBridgeError::InvalidBridgeControlMode);
+        }
+        self.bridge_control_mode = bridge_control_mode;
+        Ok(())
     }
     pub fn snapshot_for_withdrawal(&mut self, current_unix_timestamp_secs: u32) {
         self.withdrawal_snapshot = PsyWithdrawalChainSnapshot {
@@ -153,11 +274,16 @@ impl PsyBridgeProgramState {
         address_type: u32,
         address: [u8; 20],
         amount_burned_sats: u64,
-    ) -> bool {
+    ) -> QDogeResult<PsyWithdrawalRequest> {
+        // the withdrawal circuit can't build an output for an unknown address type, the burn would be lost
+        WithdrawalAddressType::try_from(address_type)?;
         let fee_result =
             calcuate_withdrawal_fee(amount_burned_sats, self.config_params.withdrawal_flat_fee_sats as u64, self.config_params.withdrawal_fee_rate_numerator, self.config_params.withdrawal_fee_rate_denominator).unwrap_or(FeeResult{fees_generated:0, amount_after_fees:0});
         if fee_result.fees_generated == 0 || fee_result.amount_after_fees == 0 {
-            return false;
+            return Err(DogeBridgeError::InvalidWithdrawalAmount);
+        }
+        if fee_result.amount_after_fees < self.policy_config.min_withdrawal_sats {
+            return Err(DogeBridgeError::WithdrawalBelowMinimum);
         }
         self.total_withdrawal_fees_sats += fee_result.fees_generated;
 
@@ -165,7 +291,7 @@ impl PsyBridgeProgramState {
         let leaf = withdrawal_request.to_leaf();
         self.requested_withdrawals_tree.append(leaf);
         self.total_requested_withdrawals_sats += amount_burned_sats;
-        true
+        Ok(withdrawal_request)
 
     }
     pub fn update_for_withdrawal(
@@ -231,6 +357,7 @@ impl PsyBridgeProgramState {
         (INVALID_BLOCK_HEIGHT, QHash256::default())
 
     }
+    #[allow(clippy::too_many_arguments)]
     pub fn process_manual_claimed_deposit(
         &mut self,
         tx_hash: QHash256,
@@ -239,10 +366,20 @@ impl PsyBridgeProgramState {
         combined_txo_index: u64,
         depositor_ata: &[u8; 32],
         deposit_amount_sats: u64,
+        finalized_block_history_data: &[u8],
     ) -> QDogeResult<u64> {
         let (block_height, _, _) = get_txo_block_number_tx_number_output_index_from_combined_index(combined_txo_index);
-        let (block_height_for_auto_claim_txo_root, block_merkle_tree_root) =
+        let (mut block_height_for_auto_claim_txo_root, mut block_merkle_tree_root) =
             self.find_recent_auto_claim_txo_tree_root_and_block_merkle_root(recent_auto_claim_txo_root);
+        if block_height_for_auto_claim_txo_root == INVALID_BLOCK_HEIGHT {
+            // proofs against older roots can still be claimed while the history account remembers them
+            if let Some((history_block_height, history_block_merkle_tree_root)) =
+                FinalizedBlockHistory::find_in_account_data(finalized_block_history_data, &recent_auto_claim_txo_root)
+            {
+                block_height_for_auto_claim_txo_root = history_block_height;
+                block_merkle_tree_root = history_block_merkle_tree_root;
+            }
+        }
 
         if block_height_for_auto_claim_txo_root == INVALID_BLOCK_HEIGHT || block_height > block_height_for_auto_claim_txo_root {
             return Err(DogeBridgeError::AutoClaimedDepositTreeRootNotRecentEnough);
@@ -250,6 +387,9 @@ impl PsyBridgeProgramState {
         if block_merkle_tree_root != recent_block_merkle_tree_root {
             return Err(DogeBridgeError::BlockMerkleTreeRootNotRecentEnough);
         }
+        if deposit_amount_sats < self.policy_config.min_deposit_sats {
+            return Err(DogeBridgeError::DepositBelowMinimum);
+        }
         let fee_result = calcuate_deposit_fee(deposit_amount_sats, self.config_params.deposit_flat_fee_sats as u64, self.config_params.deposit_fee_rate_numerator, self.config_params.deposit_fee_rate_denominator).unwrap_or(FeeResult{fees_generated:0, amount_after_fees:0});
         if fee_result.fees_generated == 0 || fee_result.amount_after_fees == 0 {
             return Err(DogeBridgeError::InsufficientBridgeFees);
@@ -267,3 +407,86 @@ impl PsyBridgeProgramState {
         Ok(fee_result.amount_after_fees)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::WITHDRAWAL_ADDRESS_TYPE_TOMBSTONE;
+
+    fn state_with_minimums(min_withdrawal_sats: u64, min_deposit_sats: u64) -> PsyBridgeProgramState {
+        PsyBridgeProgramState {
+            config_params: PsyBridgeConfig {
+                deposit_fee_rate_numerator: 1,
+                deposit_fee_rate_denominator: 100,
+                withdrawal_fee_rate_numerator: 1,
+                withdrawal_fee_rate_denominator: 100,
+                deposit_flat_fee_sats: 1000,
+                withdrawal_flat_fee_sats: 1000,
+            },
+            policy_config: PsyBridgePolicyConfig {
+                min_withdrawal_sats,
+                min_deposit_sats,
+                withdrawal_cancellation_fee_sats: 0,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_withdrawal_below_minimum_is_rejected() {
+        let mut state = state_with_minimums(100_000, 0);
+
+        // 101_000 - (1010 + 1000) = 97_990 sats after fees
+        assert_eq!(
+            state.process_request_withdrawal(0, [1u8; 20], 101_000),
+            Err(DogeBridgeError::WithdrawalBelowMinimum)
+        );
+        assert_eq!(
+            state.process_request_withdrawal(0, [1u8; 20], 500),
+            Err(DogeBridgeError::InvalidWithdrawalAmount)
+        );
+        assert_eq!(state.requested_withdrawals_tree.next_index, 0);
+
+        state.process_request_withdrawal(0, [1u8; 20], 200_000).unwrap();
+        assert_eq!(state.requested_withdrawals_tree.next_index, 1);
+    }
+
+    #[test]
+    fn test_unknown_withdrawal_address_type_is_rejected() {
+        let mut state = state_with_minimums(0, 0);
+
+        // 2 is WithdrawalAddressType::Script, the first unassigned type comes after it
+        assert_eq!(
+            state.process_request_withdrawal(3, [1u8; 20], 200_000),
+            Err(DogeBridgeError::InvalidWithdrawalAddressType)
+        );
+        assert_eq!(
+            state.process_request_withdrawal(WITHDRAWAL_ADDRESS_TYPE_TOMBSTONE, [1u8; 20], 200_000),
+            Err(DogeBridgeError::InvalidWithdrawalAddressType)
+        );
+        assert_eq!(state.requested_withdrawals_tree.next_index, 0);
+        assert_eq!(state.total_withdrawal_fees_sats, 0);
+
+        let request = state
+            .process_request_withdrawal(WithdrawalAddressType::P2SH.into(), [1u8; 20], 200_000)
+            .unwrap();
+        assert_eq!(request.get_address_type(), Ok(WithdrawalAddressType::P2SH));
+        assert_eq!(WithdrawalAddressType::from_address_version(0x1e), Some(WithdrawalAddressType::P2PKH));
+        assert_eq!(WithdrawalAddressType::from_address_version(0xc4), Some(WithdrawalAddressType::P2SH));
+        assert_eq!(WithdrawalAddressType::from_address_version(0x00), None);
+    }
+
+    #[test]
+    fn test_manual_deposit_below_minimum_is_rejected() {
+        let mut state = state_with_minimums(0, 100_000);
+
+        assert_eq!(
+            state.process_manual_claimed_deposit([0u8; 32], [0u8; 32], [0u8; 32], 0, &[2u8; 32], 99_999, &[]),
+            Err(DogeBridgeError::DepositBelowMinimum)
+        );
+        assert_eq!(
+            state.process_manual_claimed_deposit([0u8; 32], [0u8; 32], [0u8; 32], 0, &[2u8; 32], 100_000, &[]),
+            Ok(100_000 - 2000)
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use psy_bridge_core::{
+    custodian_config::Bridge7MultisigCustodianWalletConfig,
+    error::{DogeBridgeError, QDogeResult},
+};
+
+use crate::program_state::PsyBridgeProgramState;
+
+impl PsyBridgeProgramState {
+    pub fn check_custodian_wallet_config(
+        &self,
+        config: &Bridge7MultisigCustodianWalletConfig,
+    ) -> QDogeResult<()> {
+        if config.get_wallet_config_hash() != self.custodian_wallet_config_hash {
+            return Err(DogeBridgeError::CustodianWalletConfigHashMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_custodian_wallet_config() {
+        let config = Bridge7MultisigCustodianWalletConfig::new_basic([[9u8; 32]; 7], 0b1010, 0);
+        let state = PsyBridgeProgramState {
+            custodian_wallet_config_hash: config.get_wallet_config_hash(),
+            ..Default::default()
+        };
+        assert_eq!(state.check_custodian_wallet_config(&config), Ok(()));
+
+        let mut other = config;
+        other.signer_public_keys_y_parity ^= 1;
+        assert_eq!(
+            state.check_custodian_wallet_config(&other),
+            Err(DogeBridgeError::CustodianWalletConfigHashMismatch)
+        );
+    }
+}
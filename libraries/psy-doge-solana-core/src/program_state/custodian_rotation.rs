@@ -0,0 +1,228 @@
+use psy_bridge_core::{
+    common_types::QHash256,
+    crypto::{hash::sha256_impl::hash_impl_sha256_bytes, zk::CompactZKProofVerifier},
+    error::{DogeBridgeError, QDogeResult},
+};
+
+use crate::{
+    constants::CUSTODIAN_ROTATION_DELAY_SECS,
+    program_state::{BridgeProgramStateWithDogeMint, PsyReturnTxOutput},
+    public_inputs::get_custodian_sweep_proof_public_inputs,
+};
+
+// A rotation is scheduled by the settings authority and can be cancelled by it or the guardian until the
+// sweep lands. The sweep is a withdrawal style proof that moves last_return_output to the new custodian
+// script, custodian_wallet_config_hash only switches once it has been verified so block transitions and
+// withdrawals never commit to a custodian that doesn't hold the return output yet.
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct PsyCustodianRotation {
+    // all zeros = no rotation scheduled
+    pub pending_custodian_wallet_config_hash: QHash256,
+    pub scheduled_at_secs: u64,
+    pub sweep_after_secs: u64,
+    pub _padding: [u64; 2],
+}
+const _ASSERT_SIZE_PSY_CUSTODIAN_ROTATION: () =
+    assert!(std::mem::size_of::<PsyCustodianRotation>() == 64);
+
+impl PsyCustodianRotation {
+    pub fn is_scheduled(&self) -> bool {
+        self.pending_custodian_wallet_config_hash != [0u8; 32]
+    }
+}
+
+impl BridgeProgramStateWithDogeMint {
+    pub fn run_schedule_custodian_rotation(
+        &mut self,
+        signer_pubkey: &[u8; 32],
+        new_custodian_wallet_config_hash: &QHash256,
+        current_unix_timestamp_secs: u32,
+    ) -> QDogeResult<()> {
+        if self.core_state.access_control.get_settings_authority() != signer_pubkey {
+            return Err(DogeBridgeError::UnauthorizedCustodianRotation);
+        }
+        if self.custodian_rotation.is_scheduled() {
+            return Err(DogeBridgeError::CustodianRotationAlreadyScheduled);
+        }
+        if *new_custodian_wallet_config_hash == [0u8; 32]
+            || *new_custodian_wallet_config_hash == self.core_state.custodian_wallet_config_hash
+        {
+            return Err(DogeBridgeError::InvalidCustodianRotation);
+        }
+        self.custodian_rotation = PsyCustodianRotation {
+            pending_custodian_wallet_config_hash: *new_custodian_wallet_config_hash,
+            scheduled_at_secs: current_unix_timestamp_secs as u64,
+            sweep_after_secs: current_unix_timestamp_secs as u64 + CUSTODIAN_ROTATION_DELAY_SECS as u64,
+            _padding: [0u64; 2],
+        };
+        Ok(())
+    }
+
+    pub fn run_cancel_custodian_rotation(&mut self, signer_pubkey: &[u8; 32]) -> QDogeResult<()> {
+        if self.core_state.access_control.get_settings_authority() != signer_pubkey
+            && self.core_state.access_control.guardian_pubkey != *signer_pubkey
+        {
+            return Err(DogeBridgeError::UnauthorizedCustodianRotation);
+        }
+        if !self.custodian_rotation.is_scheduled() {
+            return Err(DogeBridgeError::NoCustodianRotationScheduled);
+        }
+        self.custodian_rotation = PsyCustodianRotation::default();
+        Ok(())
+    }
+
+    pub fn get_expected_public_inputs_for_custodian_sweep_proof(
+        &self,
+        new_return_output: &PsyReturnTxOutput,
+        new_spent_txo_tree_root: QHash256,
+    ) -> QHash256 {
+        get_custodian_sweep_proof_public_inputs(
+            &self.core_state.last_return_output.get_hash(),
+            &new_return_output.get_hash(),
+            &self.core_state.spent_txo_tree_root,
+            &new_spent_txo_tree_root,
+            &self.core_state.custodian_wallet_config_hash,
+            &self.custodian_rotation.pending_custodian_wallet_config_hash,
+        )
+    }
+
+    // returns the sweep tx hash, the tx is recorded in sent_transactions_tree like any withdrawal tx
+    pub fn run_process_custodian_sweep<ZKVerfier: CompactZKProofVerifier>(
+        &mut self,
+        proof: &[u8],
+        vk: &[u8],
+        dogecoin_tx: &[u8],
+        new_return_output: PsyReturnTxOutput,
+        new_spent_txo_tree_root: QHash256,
+        current_unix_timestamp_secs: u32,
+    ) -> QDogeResult<QHash256> {
+        if !self.custodian_rotation.is_scheduled() {
+            return Err(DogeBridgeError::NoCustodianRotationScheduled);
+        }
+        if (current_unix_timestamp_secs as u64) < self.custodian_rotation.sweep_after_secs {
+            return Err(DogeBridgeError::CustodianRotationTimelockNotElapsed);
+        }
+
+        let doge_tx_hash = hash_impl_sha256_bytes(dogecoin_tx);
+        if doge_tx_hash != new_return_output.sighash {
+            return Err(DogeBridgeError::InvalidDogeTxHash);
+        }
+        let expected_public_inputs = self
+            .get_expected_public_inputs_for_custodian_sweep_proof(&new_return_output, new_spent_txo_tree_root);
+        if !ZKVerfier::verify_compact_zkp_slice(proof, vk, &expected_public_inputs) {
+            return Err(DogeBridgeError::BridgeZKPError);
+        }
+
+        let next_processed_withdrawals_index = self.core_state.next_processed_withdrawals_index;
+        self.core_state
+            .update_for_withdrawal(new_return_output, new_spent_txo_tree_root, next_processed_withdrawals_index);
+        self.core_state.custodian_wallet_config_hash = self.custodian_rotation.pending_custodian_wallet_config_hash;
+        self.custodian_rotation = PsyCustodianRotation::default();
+        Ok(doge_tx_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "fake_signer")]
+    use psy_bridge_core::crypto::zk::jtmb::FakeZKProof;
+
+    use super::*;
+    #[cfg(feature = "fake_signer")]
+    use crate::fake_zkp::FakeZKProofGenerator;
+
+    const SETTINGS_AUTHORITY: [u8; 32] = [1u8; 32];
+    const GUARDIAN: [u8; 32] = [2u8; 32];
+
+    fn test_state() -> BridgeProgramStateWithDogeMint {
+        let mut state = BridgeProgramStateWithDogeMint::default();
+        state.core_state.access_control.operator_pubkey = SETTINGS_AUTHORITY;
+        state.core_state.access_control.guardian_pubkey = GUARDIAN;
+        state.core_state.custodian_wallet_config_hash = [5u8; 32];
+        state.core_state.next_processed_withdrawals_index = 3;
+        state
+    }
+
+    #[cfg(feature = "fake_signer")]
+    #[test]
+    fn test_custodian_sweep_switches_config_hash() {
+        let mut state = test_state();
+        assert_eq!(
+            state.run_schedule_custodian_rotation(&GUARDIAN, &[6u8; 32], 1_000),
+            Err(DogeBridgeError::UnauthorizedCustodianRotation)
+        );
+        assert_eq!(
+            state.run_schedule_custodian_rotation(&SETTINGS_AUTHORITY, &[5u8; 32], 1_000),
+            Err(DogeBridgeError::InvalidCustodianRotation)
+        );
+        state
+            .run_schedule_custodian_rotation(&SETTINGS_AUTHORITY, &[6u8; 32], 1_000)
+            .unwrap();
+        assert_eq!(
+            state.run_schedule_custodian_rotation(&SETTINGS_AUTHORITY, &[7u8; 32], 1_000),
+            Err(DogeBridgeError::CustodianRotationAlreadyScheduled)
+        );
+
+        let dogecoin_tx = [9u8; 100];
+        let new_return_output = PsyReturnTxOutput::new(hash_impl_sha256_bytes(&dogecoin_tx), 0, 50_000);
+        let new_spent_txo_tree_root = [8u8; 32];
+        let keys = FakeZKProofGenerator::new().unwrap().withdrawal;
+        let proof = keys
+            .generate_fake_zkp(
+                state.get_expected_public_inputs_for_custodian_sweep_proof(&new_return_output, new_spent_txo_tree_root),
+            )
+            .unwrap()
+            .to_compact_zkp();
+
+        let sweep_after = 1_000 + CUSTODIAN_ROTATION_DELAY_SECS;
+        assert_eq!(
+            state.run_process_custodian_sweep::<FakeZKProof>(
+                &proof, &keys.vk, &dogecoin_tx, new_return_output, new_spent_txo_tree_root, sweep_after - 1,
+            ),
+            Err(DogeBridgeError::CustodianRotationTimelockNotElapsed)
+        );
+        assert_eq!(
+            state.run_process_custodian_sweep::<FakeZKProof>(
+                &proof, &keys.vk, &dogecoin_tx[1..], new_return_output, new_spent_txo_tree_root, sweep_after,
+            ),
+            Err(DogeBridgeError::InvalidDogeTxHash)
+        );
+        assert_eq!(
+            state.run_process_custodian_sweep::<FakeZKProof>(
+                &proof, &keys.vk, &dogecoin_tx, new_return_output, [0u8; 32], sweep_after,
+            ),
+            Err(DogeBridgeError::BridgeZKPError)
+        );
+
+        let sighash = state
+            .run_process_custodian_sweep::<FakeZKProof>(
+                &proof, &keys.vk, &dogecoin_tx, new_return_output, new_spent_txo_tree_root, sweep_after,
+            )
+            .unwrap();
+        assert_eq!(sighash, new_return_output.sighash);
+        assert_eq!(state.core_state.custodian_wallet_config_hash, [6u8; 32]);
+        assert_eq!(state.core_state.last_return_output, new_return_output);
+        assert_eq!(state.core_state.spent_txo_tree_root, new_spent_txo_tree_root);
+        assert_eq!(state.core_state.next_processed_withdrawals_index, 3);
+        assert!(!state.custodian_rotation.is_scheduled());
+    }
+
+    #[test]
+    fn test_cancel_custodian_rotation() {
+        let mut state = test_state();
+        assert_eq!(
+            state.run_cancel_custodian_rotation(&GUARDIAN),
+            Err(DogeBridgeError::NoCustodianRotationScheduled)
+        );
+        state
+            .run_schedule_custodian_rotation(&SETTINGS_AUTHORITY, &[6u8; 32], 1_000)
+            .unwrap();
+        assert_eq!(
+            state.run_cancel_custodian_rotation(&[3u8; 32]),
+            Err(DogeBridgeError::UnauthorizedCustodianRotation)
+        );
+        state.run_cancel_custodian_rotation(&GUARDIAN).unwrap();
+        assert_eq!(state.custodian_rotation, PsyCustodianRotation::default());
+        assert_eq!(state.core_state.custodian_wallet_config_hash, [5u8; 32]);
+    }
+}
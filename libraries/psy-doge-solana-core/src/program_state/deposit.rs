@@ -20,7 +20,7 @@ impl PsyBridgeProgramState {
         deposit_amount_sats: u64,
     ) -> QDogeResult<()> {
 
-        let mint_amount = self.process_manual_claimed_deposit(tx_hash, recent_block_merkle_tree_root, recent_auto_claim_txo_root, combined_txo_index, depositor_public_key, deposit_amount_sats)?;
+        let mint_amount = self.process_manual_claimed_deposit(tx_hash, recent_block_merkle_tree_root, recent_auto_claim_txo_root, combined_txo_index, depositor_public_key, deposit_amount_sats, &[])?;
         minter.mint_to(0, depositor_public_key, mint_amount)?;
         Ok(())
     }
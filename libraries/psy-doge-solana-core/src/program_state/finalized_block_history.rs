@@ -0,0 +1,132 @@
+use psy_bridge_core::{
+    error::{DogeBridgeError, QDogeResult},
+    header::PsyBridgeStateCommitment,
+};
+
+use crate::{
+    constants::{FINALIZED_BLOCK_HISTORY_MAX_CAPACITY, FINALIZED_BLOCK_HISTORY_MIN_CAPACITY},
+    data_accounts::finalized_block_history::FinalizedBlockHistory,
+    program_state::PsyBridgeProgramState,
+};
+
+impl PsyBridgeProgramState {
+    pub fn has_finalized_block_history(&self) -> bool {
+        self.finalized_block_history_capacity != 0
+    }
+
+    // checked before every allocation step, the account is grown over several transactions for large capacities
+    pub fn ensure_can_enable_finalized_block_history(
+        &self,
+        signer_pubkey: &[u8; 32],
+        capacity: u32,
+    ) -> QDogeResult<()> {
+        if self.access_control.get_settings_authority() != signer_pubkey {
+            return Err(DogeBridgeError::UnauthorizedFinalizedBlockHistoryUpdate);
+        }
+        if self.has_finalized_block_history() {
+            return Err(DogeBridgeError::FinalizedBlockHistoryAlreadyEnabled);
+        }
+        if !(FINALIZED_BLOCK_HISTORY_MIN_CAPACITY..=FINALIZED_BLOCK_HISTORY_MAX_CAPACITY)
+            .contains(&capacity)
+        {
+            return Err(DogeBridgeError::InvalidFinalizedBlockHistoryCapacity);
+        }
+        Ok(())
+    }
+
+    // called once the account is fully allocated, seeds the history with the blocks the state already remembers
+    pub fn run_enable_finalized_block_history(
+        &mut self,
+        signer_pubkey: &[u8; 32],
+        capacity: u32,
+        finalized_block_history_data: &mut [u8],
+    ) -> QDogeResult<()> {
+        self.ensure_can_enable_finalized_block_history(signer_pubkey, capacity)?;
+
+        let next = self.next_recent_finalized_block_index as usize;
+        let mut seed_blocks: Vec<PsyBridgeStateCommitment> = Vec::with_capacity(9);
+        for block in (0..self.recent_finalized_blocks.len())
+            .map(|i| &self.recent_finalized_blocks[(next + i) % self.recent_finalized_blocks.len()])
+            .chain(std::iter::once(&self.bridge_header.finalized_state))
+        {
+            if seed_blocks.last() != Some(block) {
+                seed_blocks.push(*block);
+            }
+        }
+        FinalizedBlockHistory::initialize_account_data(
+            finalized_block_history_data,
+            capacity,
+            &seed_blocks,
+        )?;
+        self.finalized_block_history_capacity = capacity as u64;
+        Ok(())
+    }
+
+    // appends the current finalized state, called after every successful block transition
+    pub fn record_finalized_block_history(
+        &self,
+        finalized_block_history_data: &mut [u8],
+    ) -> QDogeResult<()> {
+        let mut history = FinalizedBlockHistory::from_account_data(
+            finalized_block_history_data,
+            self.finalized_block_history_capacity as u32,
+        )?;
+        history.append(&self.bridge_header.finalized_state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_accounts::finalized_block_history::finalized_block_history_account_size;
+
+    #[test]
+    fn test_manual_claim_falls_back_to_finalized_block_history() {
+        let mut state = PsyBridgeProgramState::default();
+        state.access_control.operator_pubkey = [1u8; 32];
+        state.config_params.deposit_fee_rate_numerator = 1;
+        state.config_params.deposit_fee_rate_denominator = 100;
+        state.config_params.deposit_flat_fee_sats = 1000;
+
+        let mut history_data = vec![0u8; finalized_block_history_account_size(32)];
+        assert_eq!(
+            state.run_enable_finalized_block_history(&[2u8; 32], 32, &mut history_data),
+            Err(DogeBridgeError::UnauthorizedFinalizedBlockHistoryUpdate)
+        );
+        assert_eq!(
+            state.ensure_can_enable_finalized_block_history(&[1u8; 32], 8),
+            Err(DogeBridgeError::InvalidFinalizedBlockHistoryCapacity)
+        );
+        state
+            .run_enable_finalized_block_history(&[1u8; 32], 32, &mut history_data)
+            .unwrap();
+
+        // finalize 12 blocks, more than recent_finalized_blocks can hold
+        for height in 1..=12u32 {
+            let block = PsyBridgeStateCommitment {
+                auto_claimed_deposits_tree_root: [height as u8; 32],
+                block_merkle_tree_root: [height as u8 + 100; 32],
+                block_height: height,
+                ..Default::default()
+            };
+            state.bridge_header.finalized_state = block;
+            state.recent_finalized_blocks[state.next_recent_finalized_block_index as usize] = block;
+            state.next_recent_finalized_block_index = (state.next_recent_finalized_block_index + 1) % 8;
+            state.record_finalized_block_history(&mut history_data).unwrap();
+        }
+
+        assert_eq!(
+            state.process_manual_claimed_deposit([0u8; 32], [102u8; 32], [2u8; 32], 0, &[3u8; 32], 100_000, &[]),
+            Err(DogeBridgeError::AutoClaimedDepositTreeRootNotRecentEnough)
+        );
+        assert_eq!(
+            state.process_manual_claimed_deposit([0u8; 32], [102u8; 32], [2u8; 32], 0, &[3u8; 32], 100_000, &history_data),
+            Ok(100_000 - 2000)
+        );
+        assert_eq!(
+            state.process_manual_claimed_deposit([0u8; 32], [103u8; 32], [2u8; 32], 0, &[3u8; 32], 100_000, &history_data),
+            Err(DogeBridgeError::BlockMerkleTreeRootNotRecentEnough)
+        );
+    }
+}
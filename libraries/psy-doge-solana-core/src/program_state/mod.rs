@@ -1,5 +1,7 @@
 mod core;
 pub use core::*;
+mod state_version;
+pub use state_version::*;
 mod withdrawal;
 pub use withdrawal::*;
 mod mint_group;
@@ -8,7 +10,16 @@ mod block_update;
 pub use block_update::*;
 mod auto_mint;
 pub use auto_mint::*;
+mod custodian_rotation;
+pub use custodian_rotation::*;
 
 pub mod deposit;
 pub mod proc_withdrawal;
-pub mod operator;
\ No newline at end of file
+pub mod operator;
+pub mod config_update;
+pub mod access_control;
+pub mod withdrawal_limits;
+pub mod finalized_block_history;
+pub mod tx_inclusion;
+pub mod block_hash_archive;
+pub mod custodian_config;
\ No newline at end of file
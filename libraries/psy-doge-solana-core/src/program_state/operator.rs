@@ -3,6 +3,7 @@ use psy_bridge_core::
 ;
 
 use crate::{
+    data_accounts::fee_split::{FeeSplitAmounts, FeeSplitTable},
     generic_cpi::MintCPIHelper,
     program_state::PsyBridgeProgramState,
 };
@@ -35,4 +36,82 @@ impl PsyBridgeProgramState {
         minter.mint_to(0, operator_ata, fees_to_withdraw)?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    pub fn has_fee_split(&self) -> bool {
+        self.fee_split_updated_at_secs != 0
+    }
+
+    // once a fee split is configured the operator can no longer mint the fees to a single account
+    pub fn ensure_no_fee_split(&self) -> QDogeResult<()> {
+        if self.has_fee_split() {
+            return Err(DogeBridgeError::FeesDistributedByFeeSplit);
+        }
+        Ok(())
+    }
+
+    pub fn run_set_fee_split(
+        &mut self,
+        signer_pubkey: &[u8; 32],
+        fee_split: &FeeSplitTable,
+        current_unix_timestamp_secs: u32,
+    ) -> QDogeResult<()> {
+        if &self.access_control.fee_spender_pubkey != signer_pubkey {
+            return Err(DogeBridgeError::UnauthorizedFeeSplitUpdate);
+        }
+        fee_split.validate()?;
+        // the fee split account is never closed, so keep the flag set even if the clock reads 0
+        self.fee_split_updated_at_secs = (current_unix_timestamp_secs as u64).max(1);
+        Ok(())
+    }
+
+    pub fn run_distribute_fees_precheck(
+        &mut self,
+        fee_split: &FeeSplitTable,
+    ) -> QDogeResult<FeeSplitAmounts> {
+        fee_split.validate()?;
+        let fees_to_distribute = self.run_bridge_operator_withdraw_fees_precheck()?;
+        Ok(fee_split.split_fees(fees_to_distribute))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_accounts::fee_split::FeeSplitRecipient;
+
+    #[test]
+    fn test_fee_split_replaces_operator_withdrawal() {
+        let mut state = PsyBridgeProgramState::default();
+        state.access_control.fee_spender_pubkey = [1u8; 32];
+        state.total_withdrawal_fees_sats = 10_000;
+
+        let mut fee_split = FeeSplitTable {
+            fee_spender_token_account: [3u8; 32],
+            recipient_count: 1,
+            ..Default::default()
+        };
+        fee_split.recipients[0] = FeeSplitRecipient {
+            token_account: [4u8; 32],
+            share_bps: 2500,
+        };
+
+        assert_eq!(
+            state.run_set_fee_split(&[2u8; 32], &fee_split, 100),
+            Err(DogeBridgeError::UnauthorizedFeeSplitUpdate)
+        );
+        state.ensure_no_fee_split().unwrap();
+        state.run_set_fee_split(&[1u8; 32], &fee_split, 100).unwrap();
+        assert_eq!(
+            state.ensure_no_fee_split(),
+            Err(DogeBridgeError::FeesDistributedByFeeSplit)
+        );
+
+        let amounts = state.run_distribute_fees_precheck(&fee_split).unwrap();
+        assert_eq!(amounts.recipient_amounts[0], 2500);
+        assert_eq!(amounts.fee_spender_amount, 7500);
+        assert_eq!(
+            state.run_distribute_fees_precheck(&fee_split),
+            Err(DogeBridgeError::NoOperatorFeesToWithdraw)
+        );
+    }
+}
@@ -1,8 +1,13 @@
 use psy_bridge_core::{common_types::QHash256, crypto::{hash::sha256_impl::hash_impl_sha256_bytes, zk::CompactZKProofVerifier}, error::{DogeBridgeError, QDogeResult}};
 
 use crate::{
+    constants::{MAX_WITHDRAWAL_REQUESTS_PER_BATCH, MAX_WITHDRAWAL_SCRIPT_SIZE},
+    data_accounts::withdrawal_receipt::WithdrawalReceipt,
     generic_cpi::BurnCPIHelper,
-    program_state::{PsyBridgeProgramState, PsyReturnTxOutput, PsyWithdrawalRequest},
+    program_state::{
+        get_withdrawal_script_hash, PsyBridgeProgramState, PsyReturnTxOutput, PsyWithdrawalRequest,
+        WithdrawalAddressType,
+    },
 };
 
 
@@ -45,21 +50,318 @@ impl PsyBridgeProgramState {
         burner: &Burner,
         requester: &[u8; 32],
         request: &PsyWithdrawalRequest,
-    ) -> QDogeResult<()> {
-        let ok = self.process_request_withdrawal(
+        current_unix_timestamp_secs: u32,
+    ) -> QDogeResult<PsyWithdrawalRequest> {
+        // only request_script_withdrawal sees the script behind a script hash
+        if request.get_address_type()? == WithdrawalAddressType::Script {
+            return Err(DogeBridgeError::WithdrawalScriptRequired);
+        }
+        self.consume_withdrawal_limit(request.amount_sats, current_unix_timestamp_secs)?;
+
+        let queued_request = self.process_request_withdrawal(
             request.address_type,
             request.recipient_address,
             request.amount_sats
+        )?;
+
+        burner.burn_from(requester, request.amount_sats)?;
+
+        Ok(queued_request)
+        
+    }
+
+    // every request is limited, charged and appended like a single request, the total is burned once
+    pub fn request_withdrawals_batch<Burner: BurnCPIHelper>(
+        &mut self,
+        burner: &Burner,
+        requester: &[u8; 32],
+        requests: &[PsyWithdrawalRequest],
+        current_unix_timestamp_secs: u32,
+    ) -> QDogeResult<Vec<PsyWithdrawalRequest>> {
+        if requests.is_empty() || requests.len() > MAX_WITHDRAWAL_REQUESTS_PER_BATCH {
+            return Err(DogeBridgeError::InvalidWithdrawalBatchSize);
+        }
+
+        let mut total_burned_sats = 0u64;
+        let mut queued_requests = Vec::with_capacity(requests.len());
+        for request in requests {
+            if request.get_address_type()? == WithdrawalAddressType::Script {
+                return Err(DogeBridgeError::WithdrawalScriptRequired);
+            }
+            self.consume_withdrawal_limit(request.amount_sats, current_unix_timestamp_secs)?;
+            queued_requests.push(self.process_request_withdrawal(
+                request.address_type,
+                request.recipient_address,
+                request.amount_sats,
+            )?);
+            total_burned_sats = total_burned_sats
+                .checked_add(request.amount_sats)
+                .ok_or(DogeBridgeError::InvalidWithdrawalAmount)?;
+        }
+
+        burner.burn_from(requester, total_burned_sats)?;
+
+        Ok(queued_requests)
+    }
+
+    // the leaf commits to the script hash, the caller publishes the script so the withdrawal can be built
+    pub fn request_script_withdrawal<Burner: BurnCPIHelper>(
+        &mut self,
+        burner: &Burner,
+        requester: &[u8; 32],
+        script_pubkey: &[u8],
+        amount_sats: u64,
+        current_unix_timestamp_secs: u32,
+    ) -> QDogeResult<PsyWithdrawalRequest> {
+        if script_pubkey.is_empty() || script_pubkey.len() > MAX_WITHDRAWAL_SCRIPT_SIZE {
+            return Err(DogeBridgeError::InvalidWithdrawalScript);
+        }
+        self.consume_withdrawal_limit(amount_sats, current_unix_timestamp_secs)?;
+
+        let queued_request = self.process_request_withdrawal(
+            WithdrawalAddressType::Script.into(),
+            get_withdrawal_script_hash(script_pubkey),
+            amount_sats,
+        )?;
+
+        burner.burn_from(requester, amount_sats)?;
+
+        Ok(queued_request)
+    }
+
+    // a request can be cancelled until a snapshot covers it. its leaf can't be removed from the append-only
+    // tree, so a tombstone leaf pointing at it is appended instead. both land in the same snapshot, and the
+    // withdrawal circuit skips a request whenever its tombstone is in range, see WITHDRAWAL_ADDRESS_TYPE_TOMBSTONE.
+    // returns the amount to re-mint to the requester.
+    pub fn cancel_withdrawal(
+        &mut self,
+        receipt: &mut WithdrawalReceipt,
+        requester: &[u8; 32],
+        current_slot: u64,
+        current_unix_timestamp_secs: u32,
+    ) -> QDogeResult<u64> {
+        if &receipt.requester != requester {
+            return Err(DogeBridgeError::UnauthorizedWithdrawalCancel);
+        }
+        if !receipt.is_pending() {
+            return Err(DogeBridgeError::WithdrawalNotCancellable);
+        }
+        if receipt.withdrawal_index < self.withdrawal_snapshot.next_requested_withdrawals_tree_index {
+            return Err(DogeBridgeError::WithdrawalAlreadySnapshotted);
+        }
+
+        let withdrawal_fee_sats = receipt.amount_burned_sats - receipt.request.amount_sats;
+        let cancellation_fee_sats = self
+            .policy_config
+            .withdrawal_cancellation_fee_sats
+            .min(receipt.amount_burned_sats);
+        // the withdrawal fee goes back to the requester, which is only possible while it is still unclaimed
+        let refunded_fee_sats = withdrawal_fee_sats.saturating_sub(cancellation_fee_sats);
+        if refunded_fee_sats > self.get_operator_withdrawable_fees() {
+            return Err(DogeBridgeError::WithdrawalFeeAlreadyWithdrawn);
+        }
+        self.total_withdrawal_fees_sats =
+            self.total_withdrawal_fees_sats - withdrawal_fee_sats + cancellation_fee_sats;
+        self.total_requested_withdrawals_sats -= receipt.amount_burned_sats;
+        self.refund_withdrawal_limit(receipt.amount_burned_sats, current_unix_timestamp_secs);
+
+        self.requested_withdrawals_tree
+            .append(PsyWithdrawalRequest::new_tombstone(receipt.withdrawal_index).to_leaf());
+        receipt.mark_cancelled(current_slot);
+
+        Ok(receipt.amount_burned_sats - cancellation_fee_sats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use psy_bridge_core::crypto::hash::merkle::fixed_append_tree::FixedMerkleAppendTree;
+
+    use super::*;
+    use crate::program_state::get_payable_withdrawal_requests;
+
+    fn state_with_pending_request(cancellation_fee_sats: u64) -> (PsyBridgeProgramState, WithdrawalReceipt) {
+        let mut state = PsyBridgeProgramState::default();
+        state.config_params.withdrawal_fee_rate_numerator = 1;
+        state.config_params.withdrawal_fee_rate_denominator = 100;
+        state.config_params.withdrawal_flat_fee_sats = 1000;
+        state.policy_config.withdrawal_cancellation_fee_sats = cancellation_fee_sats;
+
+        let request = state.process_request_withdrawal(0, [3u8; 20], 200_000).unwrap();
+        let receipt = WithdrawalReceipt::new([1u8; 32], 0, request, 0, 200_000, 10);
+        (state, receipt)
+    }
+
+    #[test]
+    fn test_cancel_withdrawal_appends_tombstone_and_refunds() {
+        let (mut state, mut receipt) = state_with_pending_request(500);
+        assert_eq!(state.total_withdrawal_fees_sats, 3000);
+
+        assert_eq!(
+            state.cancel_withdrawal(&mut receipt, &[2u8; 32], 20, 100),
+            Err(DogeBridgeError::UnauthorizedWithdrawalCancel)
         );
+        assert_eq!(state.cancel_withdrawal(&mut receipt, &[1u8; 32], 20, 100), Ok(199_500));
+        assert_eq!(state.total_withdrawal_fees_sats, 500);
+        assert_eq!(state.total_requested_withdrawals_sats, 0);
+        assert!(receipt.is_cancelled());
 
-        if !ok {
-            return Err(DogeBridgeError::InvalidWithdrawalAmount);
+        let mut expected_tree = FixedMerkleAppendTree::default();
+        expected_tree.append(receipt.request.to_leaf());
+        expected_tree.append(PsyWithdrawalRequest::new_tombstone(0).to_leaf());
+        assert_eq!(state.requested_withdrawals_tree.get_root(), expected_tree.get_root());
 
+        assert_eq!(
+            state.cancel_withdrawal(&mut receipt, &[1u8; 32], 30, 100),
+            Err(DogeBridgeError::WithdrawalNotCancellable)
+        );
+    }
+
+    #[test]
+    fn test_snapshot_with_tombstone_pays_out_nothing_for_the_cancelled_request() {
+        let (mut state, mut receipt) = state_with_pending_request(0);
+        state.cancel_withdrawal(&mut receipt, &[1u8; 32], 20, 100).unwrap();
+        state.snapshot_for_withdrawal(100);
+
+        let snapshot_leaves = [receipt.request, PsyWithdrawalRequest::new_tombstone(0)];
+        let mut expected_tree = FixedMerkleAppendTree::default();
+        for leaf in snapshot_leaves.iter() {
+            expected_tree.append(leaf.to_leaf());
         }
+        assert_eq!(state.withdrawal_snapshot.requested_withdrawals_tree_root, expected_tree.get_root());
+        assert_eq!(state.withdrawal_snapshot.next_requested_withdrawals_tree_index, 2);
+        assert_eq!(get_payable_withdrawal_requests(&snapshot_leaves, 0), vec![]);
 
-        burner.burn_from(requester, request.amount_sats)?;
+        // a later request is still paid, also when the tombstone follows a batch that stopped before it
+        let later_request = state.process_request_withdrawal(1, [4u8; 20], 300_000).unwrap();
+        let mut later_receipt = WithdrawalReceipt::new([1u8; 32], 1, later_request, 2, 300_000, 10);
+        let other_request = state.process_request_withdrawal(0, [5u8; 20], 400_000).unwrap();
+        state.cancel_withdrawal(&mut later_receipt, &[1u8; 32], 30, 100).unwrap();
+        let snapshot_leaves = [
+            receipt.request,
+            PsyWithdrawalRequest::new_tombstone(0),
+            later_request,
+            other_request,
+            PsyWithdrawalRequest::new_tombstone(2),
+        ];
+        assert_eq!(
+            get_payable_withdrawal_requests(&snapshot_leaves, 2),
+            vec![(3, other_request)]
+        );
+    }
 
-        Ok(())
-        
+    #[test]
+    fn test_cancel_withdrawal_refunds_window_usage() {
+        let (mut state, mut receipt) = state_with_pending_request(0);
+        state.withdrawal_limit_per_window_sats = 1_000_000;
+        state.withdrawal_limit_window_secs = 86400;
+        // the pending request and a later one
+        state.consume_withdrawal_limit(200_000, 100).unwrap();
+        state.consume_withdrawal_limit(50_000, 100).unwrap();
+
+        state.cancel_withdrawal(&mut receipt, &[1u8; 32], 20, 100).unwrap();
+        assert_eq!(state.get_withdrawal_window_used_sats(100), 50_000);
+    }
+
+    #[test]
+    fn test_cannot_cancel_snapshotted_withdrawal() {
+        let (mut state, mut receipt) = state_with_pending_request(0);
+        state.snapshot_for_withdrawal(100);
+
+        assert_eq!(
+            state.cancel_withdrawal(&mut receipt, &[1u8; 32], 20, 100),
+            Err(DogeBridgeError::WithdrawalAlreadySnapshotted)
+        );
+
+        // the fee spender has already claimed the withdrawal fee
+        let (mut state, mut receipt) = state_with_pending_request(0);
+        state.run_bridge_operator_withdraw_fees_precheck().unwrap();
+        assert_eq!(
+            state.cancel_withdrawal(&mut receipt, &[1u8; 32], 20, 100),
+            Err(DogeBridgeError::WithdrawalFeeAlreadyWithdrawn)
+        );
+        assert!(receipt.is_pending());
+    }
+
+    struct RecordingBurner {
+        burns: std::cell::RefCell<Vec<u64>>,
+    }
+    impl BurnCPIHelper for RecordingBurner {
+        fn burn_from(&self, _account: &[u8; 32], amount: u64) -> QDogeResult<()> {
+            self.burns.borrow_mut().push(amount);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_request_withdrawals_batch_burns_total_once() {
+        let (mut state, _) = state_with_pending_request(0);
+        let burner = RecordingBurner { burns: Default::default() };
+        let requests = [
+            PsyWithdrawalRequest::new([4u8; 20], 100_000, 0),
+            PsyWithdrawalRequest::new([5u8; 20], 300_000, 1),
+        ];
+
+        let queued = state
+            .request_withdrawals_batch(&burner, &[1u8; 32], &requests, 10)
+            .unwrap();
+        assert_eq!(*burner.burns.borrow(), vec![400_000]);
+        assert_eq!(queued[0], PsyWithdrawalRequest::new([4u8; 20], 98_000, 0));
+        assert_eq!(queued[1], PsyWithdrawalRequest::new([5u8; 20], 296_000, 1));
+        assert_eq!(state.total_withdrawal_fees_sats, 3000 + 2000 + 4000);
+        assert_eq!(state.total_requested_withdrawals_sats, 600_000);
+        assert_eq!(state.requested_withdrawals_tree.next_index, 3);
+
+        assert_eq!(
+            state.request_withdrawals_batch(&burner, &[1u8; 32], &[], 10),
+            Err(DogeBridgeError::InvalidWithdrawalBatchSize)
+        );
+        assert_eq!(
+            state.request_withdrawals_batch(
+                &burner,
+                &[1u8; 32],
+                &[requests[0]; MAX_WITHDRAWAL_REQUESTS_PER_BATCH + 1],
+                10
+            ),
+            Err(DogeBridgeError::InvalidWithdrawalBatchSize)
+        );
+        assert_eq!(burner.burns.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_request_script_withdrawal_commits_to_script_hash() {
+        let (mut state, _) = state_with_pending_request(0);
+        let burner = RecordingBurner { burns: Default::default() };
+        // OP_RETURN <tag>
+        let script = [0x6a, 0x04, 0xde, 0xad, 0xbe, 0xef];
+
+        let queued = state
+            .request_script_withdrawal(&burner, &[1u8; 32], &script, 100_000, 10)
+            .unwrap();
+        assert_eq!(*burner.burns.borrow(), vec![100_000]);
+        assert_eq!(queued, PsyWithdrawalRequest::new_script(&script, 98_000));
+        assert_eq!(queued.get_address_type(), Ok(WithdrawalAddressType::Script));
+        assert_eq!(queued.recipient_address, get_withdrawal_script_hash(&script));
+        assert_eq!(state.requested_withdrawals_tree.next_index, 2);
+
+        assert_eq!(
+            state.request_script_withdrawal(&burner, &[1u8; 32], &[], 100_000, 10),
+            Err(DogeBridgeError::InvalidWithdrawalScript)
+        );
+        assert_eq!(
+            state.request_script_withdrawal(&burner, &[1u8; 32], &[0x51; MAX_WITHDRAWAL_SCRIPT_SIZE + 1], 100_000, 10),
+            Err(DogeBridgeError::InvalidWithdrawalScript)
+        );
+
+        // a script hash without its script can't be requested through the plain paths
+        assert_eq!(
+            state.request_withdrawal(&burner, &[1u8; 32], &queued, 10),
+            Err(DogeBridgeError::WithdrawalScriptRequired)
+        );
+        assert_eq!(
+            state.request_withdrawals_batch(&burner, &[1u8; 32], &[queued], 10),
+            Err(DogeBridgeError::WithdrawalScriptRequired)
+        );
+        assert_eq!(burner.burns.borrow().len(), 1);
     }
 }
@@ -0,0 +1,272 @@
+use psy_bridge_core::{
+    common_types::QHash256,
+    crypto::hash::merkle::fixed_append_tree::FixedMerkleAppendTree,
+    error::{DogeBridgeError, QDogeResult},
+    header::{PsyBridgeHeader, PsyBridgeStateCommitment},
+};
+
+use crate::program_state::{
+    BridgeProgramStateWithDogeMint, FinalizedBlockMintTxoManager, PsyBridgeAccessControlHeader,
+    PsyBridgeConfig, PsyBridgeProgramState, PsyReturnTxOutput, PsyWithdrawalChainSnapshot,
+};
+
+// Version 0 is the unversioned layout: the core state followed by the doge mint.
+// From version 1 on the account starts with a BridgeStateVersionHeader, migrate_state moves an
+// account forward one version at a time and every reader must go through decode_bridge_state_account.
+pub const BRIDGE_STATE_VERSION_MAGIC: [u8; 4] = *b"PSYS";
+pub const BRIDGE_STATE_LEGACY_VERSION: u32 = 0;
+pub const BRIDGE_STATE_CURRENT_VERSION: u32 = 1;
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct BridgeStateVersionHeader {
+    pub magic: [u8; 4],
+    pub version: u32,
+}
+pub const BRIDGE_STATE_VERSION_HEADER_SIZE: usize = std::mem::size_of::<BridgeStateVersionHeader>();
+const _ASSERT_SIZE_BRIDGE_STATE_VERSION_HEADER: () = assert!(BRIDGE_STATE_VERSION_HEADER_SIZE == 8);
+
+impl BridgeStateVersionHeader {
+    pub fn new(version: u32) -> Self {
+        Self {
+            magic: BRIDGE_STATE_VERSION_MAGIC,
+            version,
+        }
+    }
+
+    pub fn current() -> Self {
+        Self::new(BRIDGE_STATE_CURRENT_VERSION)
+    }
+}
+
+// The layouts of bridge state accounts created before the version header existed, frozen as they were
+// deployed. Version 1 inserted fields in the middle of PsyBridgeProgramState and grew the access control
+// header, so these must not follow later edits to the current structs.
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct PsyBridgeAccessControlHeaderV0 {
+    pub operator_pubkey: [u8; 32],
+    pub fee_spender_pubkey: [u8; 32],
+}
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct PsyBridgeProgramStateV0 {
+    pub bridge_header: PsyBridgeHeader,
+    pub recent_finalized_blocks: [PsyBridgeStateCommitment; 8],
+    pub last_return_output: PsyReturnTxOutput,
+    pub pending_mint_txos: FinalizedBlockMintTxoManager,
+    pub spent_txo_tree_root: QHash256,
+    pub withdrawal_snapshot: PsyWithdrawalChainSnapshot,
+    pub next_processed_withdrawals_index: u64,
+    pub sent_transactions_tree: FixedMerkleAppendTree,
+    pub manual_deposits_tree: FixedMerkleAppendTree,
+    pub requested_withdrawals_tree: FixedMerkleAppendTree,
+
+    pub custodian_wallet_config_hash: QHash256,
+    pub bridge_control_mode: u32,
+    pub next_recent_finalized_block_index: u32,
+    pub last_processed_withdrawals_at_ms: u64,
+    pub total_requested_withdrawals_sats: u64,
+    pub total_fees_withdrawn_sats: u64,
+    pub total_manual_deposit_fees_sats: u64,
+    pub total_withdrawal_fees_sats: u64,
+    pub last_received_block_at_ms: u64,
+    pub last_replayed_withdrawal_at_ms: u64,
+
+    pub config_params: PsyBridgeConfig,
+
+    pub access_control: PsyBridgeAccessControlHeaderV0,
+}
+
+#[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
+pub struct BridgeProgramStateWithDogeMintV0 {
+    pub core_state: PsyBridgeProgramStateV0,
+    pub doge_mint: [u8; 32],
+}
+impl BridgeProgramStateWithDogeMintV0 {
+    pub const SIZE: usize = std::mem::size_of::<BridgeProgramStateWithDogeMintV0>();
+}
+// the size of every deployed unversioned account, get_bridge_state_version recognizes them by it
+const _ASSERT_SIZE_BRIDGE_STATE_V0: () = assert!(BridgeProgramStateWithDogeMintV0::SIZE == 6184);
+
+impl PsyBridgeProgramStateV0 {
+    // copies every v0 field to its v1 counterpart by name, the fields v1 added keep their value in state.
+    // Writes into a destination so the program never builds a whole state on its stack.
+    pub fn migrate_into(&self, state: &mut PsyBridgeProgramState) {
+        state.bridge_header = self.bridge_header;
+        state.recent_finalized_blocks = self.recent_finalized_blocks;
+        state.last_return_output = self.last_return_output;
+        state.pending_mint_txos = self.pending_mint_txos;
+        state.spent_txo_tree_root = self.spent_txo_tree_root;
+        state.withdrawal_snapshot = self.withdrawal_snapshot;
+        state.next_processed_withdrawals_index = self.next_processed_withdrawals_index;
+        state.sent_transactions_tree = self.sent_transactions_tree;
+        state.manual_deposits_tree = self.manual_deposits_tree;
+        state.requested_withdrawals_tree = self.requested_withdrawals_tree;
+        state.custodian_wallet_config_hash = self.custodian_wallet_config_hash;
+        state.bridge_control_mode = self.bridge_control_mode;
+        state.next_recent_finalized_block_index = self.next_recent_finalized_block_index;
+        state.last_processed_withdrawals_at_ms = self.last_processed_withdrawals_at_ms;
+        state.total_requested_withdrawals_sats = self.total_requested_withdrawals_sats;
+        state.total_fees_withdrawn_sats = self.total_fees_withdrawn_sats;
+        state.total_manual_deposit_fees_sats = self.total_manual_deposit_fees_sats;
+        state.total_withdrawal_fees_sats = self.total_withdrawal_fees_sats;
+        state.last_received_block_at_ms = self.last_received_block_at_ms;
+        state.last_replayed_withdrawal_at_ms = self.last_replayed_withdrawal_at_ms;
+        state.config_params = self.config_params;
+        // the defaults initialize uses: the operator doubles as the guardian and there is no admin
+        state.access_control = PsyBridgeAccessControlHeader {
+            operator_pubkey: self.access_control.operator_pubkey,
+            fee_spender_pubkey: self.access_control.fee_spender_pubkey,
+            guardian_pubkey: self.access_control.operator_pubkey,
+            ..Default::default()
+        };
+    }
+}
+
+pub fn bridge_state_account_size(version: u32) -> Option<usize> {
+    match version {
+        BRIDGE_STATE_LEGACY_VERSION => Some(BridgeProgramStateWithDogeMintV0::SIZE),
+        BRIDGE_STATE_CURRENT_VERSION => Some(BridgeProgramStateWithDogeMint::SIZE),
+        _ => None,
+    }
+}
+
+// an unversioned account is recognized by its size, it can't carry a header
+pub fn get_bridge_state_version(data: &[u8]) -> Option<u32> {
+    if data.len() == BridgeProgramStateWithDogeMintV0::SIZE {
+        return Some(BRIDGE_STATE_LEGACY_VERSION);
+    }
+    if data.len() < BRIDGE_STATE_VERSION_HEADER_SIZE {
+        return None;
+    }
+    let header: BridgeStateVersionHeader =
+        bytemuck::pod_read_unaligned(&data[..BRIDGE_STATE_VERSION_HEADER_SIZE]);
+    if header.magic != BRIDGE_STATE_VERSION_MAGIC || bridge_state_account_size(header.version) != Some(data.len()) {
+        return None;
+    }
+    Some(header.version)
+}
+
+// reads an account of any supported version into the current layout, the version header is kept as read
+pub fn decode_bridge_state_account(data: &[u8]) -> QDogeResult<BridgeProgramStateWithDogeMint> {
+    match get_bridge_state_version(data) {
+        Some(BRIDGE_STATE_LEGACY_VERSION) => {
+            let legacy: BridgeProgramStateWithDogeMintV0 = bytemuck::pod_read_unaligned(data);
+            let mut state = BridgeProgramStateWithDogeMint {
+                version_header: BridgeStateVersionHeader::new(BRIDGE_STATE_LEGACY_VERSION),
+                doge_mint: legacy.doge_mint,
+                ..Default::default()
+            };
+            legacy.core_state.migrate_into(&mut state.core_state);
+            Ok(state)
+        }
+        Some(BRIDGE_STATE_CURRENT_VERSION) => Ok(bytemuck::pod_read_unaligned(data)),
+        _ => Err(DogeBridgeError::UnsupportedBridgeStateVersion),
+    }
+}
+
+// data is an account of from_version already grown to the size of the next version, the new bytes zeroed
+pub fn migrate_bridge_state_account_data(data: &mut [u8], from_version: u32) -> QDogeResult<()> {
+    match from_version {
+        BRIDGE_STATE_LEGACY_VERSION => {
+            if data.len() != BridgeProgramStateWithDogeMint::SIZE {
+                return Err(DogeBridgeError::UnsupportedBridgeStateVersion);
+            }
+            // the v0 bytes overlap their new offsets, so they are copied out before the account is rewritten
+            let mut legacy = bytemuck::allocation::zeroed_box::<BridgeProgramStateWithDogeMintV0>();
+            bytemuck::bytes_of_mut(legacy.as_mut())
+                .copy_from_slice(&data[..BridgeProgramStateWithDogeMintV0::SIZE]);
+            data.fill(0);
+            let state = bytemuck::try_from_bytes_mut::<BridgeProgramStateWithDogeMint>(data)
+                .map_err(|_| DogeBridgeError::UnsupportedBridgeStateVersion)?;
+            state.version_header = BridgeStateVersionHeader::new(BRIDGE_STATE_CURRENT_VERSION);
+            state.doge_mint = legacy.doge_mint;
+            legacy.core_state.migrate_into(&mut state.core_state);
+            Ok(())
+        }
+        BRIDGE_STATE_CURRENT_VERSION => Err(DogeBridgeError::BridgeStateVersionUpToDate),
+        _ => Err(DogeBridgeError::UnsupportedBridgeStateVersion),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a v0 account with a distinct value in the fields around the ones v1 inserted
+    fn legacy_state() -> BridgeProgramStateWithDogeMintV0 {
+        let mut legacy = BridgeProgramStateWithDogeMintV0 {
+            doge_mint: [7u8; 32],
+            ..Default::default()
+        };
+        let core_state = &mut legacy.core_state;
+        core_state.bridge_header.finalized_state.block_height = 42;
+        core_state.custodian_wallet_config_hash = [3u8; 32];
+        core_state.total_requested_withdrawals_sats = 1_000;
+        core_state.total_fees_withdrawn_sats = 2_000;
+        core_state.total_manual_deposit_fees_sats = 3_000;
+        core_state.total_withdrawal_fees_sats = 4_000;
+        core_state.last_received_block_at_ms = 5_000;
+        core_state.last_replayed_withdrawal_at_ms = 6_000;
+        core_state.config_params.withdrawal_flat_fee_sats = 7_000;
+        core_state.access_control.operator_pubkey = [1u8; 32];
+        core_state.access_control.fee_spender_pubkey = [2u8; 32];
+        legacy
+    }
+
+    fn assert_migrated(state: &BridgeProgramStateWithDogeMint, legacy: &BridgeProgramStateWithDogeMintV0) {
+        let core_state = &state.core_state;
+        assert_eq!(state.doge_mint, legacy.doge_mint);
+        assert_eq!(core_state.bridge_header, legacy.core_state.bridge_header);
+        assert_eq!(core_state.custodian_wallet_config_hash, [3u8; 32]);
+        assert_eq!(core_state.total_requested_withdrawals_sats, 1_000);
+        assert_eq!(core_state.total_fees_withdrawn_sats, 2_000);
+        assert_eq!(core_state.total_manual_deposit_fees_sats, 3_000);
+        assert_eq!(core_state.total_withdrawal_fees_sats, 4_000);
+        assert_eq!(core_state.last_received_block_at_ms, 5_000);
+        assert_eq!(core_state.last_replayed_withdrawal_at_ms, 6_000);
+        assert_eq!(core_state.config_params, legacy.core_state.config_params);
+        assert_eq!(
+            core_state.access_control,
+            PsyBridgeAccessControlHeader {
+                operator_pubkey: [1u8; 32],
+                fee_spender_pubkey: [2u8; 32],
+                guardian_pubkey: [1u8; 32],
+                ..Default::default()
+            }
+        );
+        // fields added in v1 start out empty
+        assert_eq!(core_state.withdrawal_limit_per_window_sats, 0);
+        assert_eq!(core_state.block_hash_archive_len, 0);
+        assert_eq!(core_state.pending_config_params, PsyBridgeConfig::default());
+        assert_eq!(core_state.policy_config, Default::default());
+        assert_eq!(state.custodian_rotation, Default::default());
+        assert!(!state.has_vk_registry());
+        assert_eq!(state._reserved, [[0u8; 32]; 13]);
+    }
+
+    #[test]
+    fn test_migrate_legacy_bridge_state() {
+        let legacy = legacy_state();
+        let mut data = bytemuck::bytes_of(&legacy).to_vec();
+        // the size of accounts created by the unversioned program
+        assert_eq!(data.len(), 6184);
+        assert_eq!(get_bridge_state_version(&data), Some(BRIDGE_STATE_LEGACY_VERSION));
+        let decoded = decode_bridge_state_account(&data).unwrap();
+        assert_eq!(decoded.version_header.version, BRIDGE_STATE_LEGACY_VERSION);
+        assert_migrated(&decoded, &legacy);
+
+        data.resize(bridge_state_account_size(1).unwrap(), 0xFF);
+        migrate_bridge_state_account_data(&mut data, BRIDGE_STATE_LEGACY_VERSION).unwrap();
+        assert_eq!(get_bridge_state_version(&data), Some(BRIDGE_STATE_CURRENT_VERSION));
+        let migrated = bytemuck::try_from_bytes::<BridgeProgramStateWithDogeMint>(&data).unwrap();
+        assert_migrated(migrated, &legacy);
+        assert_eq!(decode_bridge_state_account(&data).unwrap(), *migrated);
+
+        assert_eq!(
+            migrate_bridge_state_account_data(&mut data, BRIDGE_STATE_CURRENT_VERSION),
+            Err(DogeBridgeError::BridgeStateVersionUpToDate)
+        );
+        data[0] = b'X';
+        assert_eq!(get_bridge_state_version(&data), None);
+    }
+}
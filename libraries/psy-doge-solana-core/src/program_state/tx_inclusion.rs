@@ -0,0 +1,163 @@
+use psy_bridge_core::{
+    common_types::QHash256,
+    crypto::hash::{
+        merkle::merkle_proof::MerkleProofCore,
+        sha256::{btc_hash256_bytes, QBTCHash256Hasher},
+        sha256_impl::hash_impl_sha256_compute_merkle_root,
+    },
+    error::{DogeBridgeError, QDogeResult},
+};
+
+use crate::{
+    constants::{DOGE_BLOCK_MERKLE_TREE_HEIGHT, DOGE_TX_MERKLE_BRANCH_MAX_LENGTH},
+    instructions::doge_bridge::{TxInclusionResult, VerifyTxInclusionFixedData},
+    program_state::PsyBridgeProgramState,
+};
+
+// offset of the tx merkle root in a dogecoin block header
+const BLOCK_HEADER_MERKLE_ROOT_OFFSET: usize = 36;
+// inner nodes of the tx merkle tree are the hash of two 32 byte children
+const TX_MERKLE_NODE_PREIMAGE_SIZE: usize = 64;
+
+impl PsyBridgeProgramState {
+    // only finalized blocks are accepted, every one of them is a leaf of the finalized block merkle tree.
+    // The branch length isn't committed to by the header, the txid is computed from the raw transaction instead,
+    // which can't be an inner node unless it is 64 bytes long.
+    pub fn verify_tx_inclusion(
+        &self,
+        params: &VerifyTxInclusionFixedData,
+        tx_bytes: &[u8],
+        tx_siblings: &[QHash256],
+        block_siblings: &[QHash256],
+    ) -> QDogeResult<TxInclusionResult> {
+        if tx_bytes.is_empty() || tx_bytes.len() == TX_MERKLE_NODE_PREIMAGE_SIZE {
+            return Err(DogeBridgeError::InvalidTxInclusionTxSize);
+        }
+        let finalized_state = &self.bridge_header.finalized_state;
+        let tip_block_height = self.bridge_header.tip_state.block_height;
+        if params.block_height > finalized_state.block_height {
+            return Err(DogeBridgeError::TxInclusionBlockNotFinalized);
+        }
+
+        let block_hash = btc_hash256_bytes(&params.block_header);
+        if block_siblings.len() != DOGE_BLOCK_MERKLE_TREE_HEIGHT
+            || hash_impl_sha256_compute_merkle_root(&block_hash, params.block_height as u64, block_siblings)
+                != finalized_state.block_merkle_tree_root
+        {
+            return Err(DogeBridgeError::InvalidBlockInclusionProof);
+        }
+        if params.block_height == finalized_state.block_height && block_hash != finalized_state.block_hash {
+            return Err(DogeBridgeError::InvalidBlockInclusionProof);
+        }
+
+        let mut tx_merkle_root = [0u8; 32];
+        tx_merkle_root.copy_from_slice(
+            &params.block_header[BLOCK_HEADER_MERKLE_ROOT_OFFSET..BLOCK_HEADER_MERKLE_ROOT_OFFSET + 32],
+        );
+        let txid = btc_hash256_bytes(tx_bytes);
+        let tx_proof = MerkleProofCore {
+            root: tx_merkle_root,
+            value: txid,
+            index: params.tx_index as u64,
+            siblings: tx_siblings.to_vec(),
+        };
+        if tx_siblings.len() > DOGE_TX_MERKLE_BRANCH_MAX_LENGTH
+            || !tx_proof.verify_btc_block_tx_tree::<QBTCHash256Hasher>()
+        {
+            return Err(DogeBridgeError::InvalidTxInclusionProof);
+        }
+
+        let confirmations = tip_block_height - params.block_height + 1;
+        if confirmations < params.min_confirmations {
+            return Err(DogeBridgeError::InsufficientTxConfirmations);
+        }
+        Ok(TxInclusionResult {
+            block_hash,
+            txid,
+            block_height: params.block_height,
+            confirmations,
+            finalized_block_height: finalized_state.block_height,
+            tip_block_height,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use psy_bridge_core::crypto::hash::{
+        sha256::SHA256_ZERO_HASHES, sha256_impl::hash_impl_btc_hash256_two_to_one_bytes,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_verify_tx_inclusion_against_finalized_block_tree() {
+        let txs = [vec![1u8; 60], vec![2u8; 61], vec![3u8; 62]];
+        let txids: Vec<QHash256> = txs.iter().map(|tx| btc_hash256_bytes(tx)).collect();
+        // the odd leaf is paired with itself
+        let left = hash_impl_btc_hash256_two_to_one_bytes(&txids[0], &txids[1]);
+        let right = hash_impl_btc_hash256_two_to_one_bytes(&txids[2], &txids[2]);
+        let tx_merkle_root = hash_impl_btc_hash256_two_to_one_bytes(&left, &right);
+
+        let mut params = VerifyTxInclusionFixedData {
+            block_height: 0,
+            tx_index: 2,
+            tx_siblings_count: 2,
+            min_confirmations: 3,
+            tx_size: txs[2].len() as u32,
+            ..Default::default()
+        };
+        params.block_header[BLOCK_HEADER_MERKLE_ROOT_OFFSET..BLOCK_HEADER_MERKLE_ROOT_OFFSET + 32]
+            .copy_from_slice(&tx_merkle_root);
+        let block_hash = btc_hash256_bytes(&params.block_header);
+
+        let block_siblings: Vec<QHash256> = SHA256_ZERO_HASHES[..DOGE_BLOCK_MERKLE_TREE_HEIGHT].to_vec();
+        let mut state = PsyBridgeProgramState::default();
+        state.bridge_header.finalized_state.block_height = 1;
+        state.bridge_header.finalized_state.block_merkle_tree_root =
+            hash_impl_sha256_compute_merkle_root(&block_hash, 0, &block_siblings);
+        state.bridge_header.tip_state.block_height = 2;
+
+        let result = state
+            .verify_tx_inclusion(&params, &txs[2], &[txids[2], left], &block_siblings)
+            .unwrap();
+        assert_eq!(result.block_hash, block_hash);
+        assert_eq!(result.txid, txids[2]);
+        assert_eq!(result.confirmations, 3);
+
+        // the duplicated last leaf can't be claimed as a fourth tx
+        let mut duplicate = params;
+        duplicate.tx_index = 3;
+        assert_eq!(
+            state.verify_tx_inclusion(&duplicate, &txs[2], &[txids[2], left], &block_siblings),
+            Err(DogeBridgeError::InvalidTxInclusionProof)
+        );
+
+        // the preimage of an inner node is a valid proof one level up, it is rejected by its size
+        let mut inner_node = [0u8; 64];
+        inner_node[..32].copy_from_slice(&txids[0]);
+        inner_node[32..].copy_from_slice(&txids[1]);
+        let mut shortened = params;
+        shortened.tx_index = 0;
+        shortened.tx_siblings_count = 1;
+        assert_eq!(
+            state.verify_tx_inclusion(&shortened, &inner_node, &[right], &block_siblings),
+            Err(DogeBridgeError::InvalidTxInclusionTxSize)
+        );
+
+        assert_eq!(
+            state.verify_tx_inclusion(&params, &txs[2], &[txids[2], left], &block_siblings[1..]),
+            Err(DogeBridgeError::InvalidBlockInclusionProof)
+        );
+        params.min_confirmations = 4;
+        assert_eq!(
+            state.verify_tx_inclusion(&params, &txs[2], &[txids[2], left], &block_siblings),
+            Err(DogeBridgeError::InsufficientTxConfirmations)
+        );
+        params.block_height = 2;
+        assert_eq!(
+            state.verify_tx_inclusion(&params, &txs[2], &[txids[2], left], &block_siblings),
+            Err(DogeBridgeError::TxInclusionBlockNotFinalized)
+        );
+    }
+}
@@ -1,10 +1,79 @@
-use psy_bridge_core::{common_types::QHash256, crypto::hash::sha256_impl::hash_impl_sha256_bytes};
+use psy_bridge_core::{
+    common_types::{QHash160, QHash256},
+    crypto::hash::{ripemd160_impl::hash_impl_btc_hash160_bytes, sha256_impl::hash_impl_sha256_bytes},
+    custodian_config::{doge_p2pkh_address_version, doge_p2sh_address_version, DOGE_NETWORK_TYPE_MAINNET, DOGE_NETWORK_TYPE_REGTEST, DOGE_NETWORK_TYPE_TESTNET},
+    error::{DogeBridgeError, QDogeResult},
+};
+#[cfg(any(feature = "std", feature = "sp1"))]
+use psy_bridge_core::dogecoin::address::{DogeAddress, DogeAddressType};
 
+use crate::constants::WITHDRAWAL_ADDRESS_TYPE_TOMBSTONE;
+
+// the output scripts the withdrawal circuit can build, recipient_address is the 20 byte hash they commit to
+#[derive(PartialEq, Clone, Copy, Debug, Eq, Hash)]
+#[repr(u32)]
+pub enum WithdrawalAddressType {
+    P2PKH = 0,
+    P2SH = 1,
+    // an arbitrary scriptPubKey, recipient_address is get_withdrawal_script_hash(script)
+    Script = 2,
+}
+impl WithdrawalAddressType {
+    // None for script withdrawals, they have no address encoding
+    pub fn address_version(&self, network_type: u32) -> Option<u8> {
+        match self {
+            WithdrawalAddressType::P2PKH => doge_p2pkh_address_version(network_type),
+            WithdrawalAddressType::P2SH => doge_p2sh_address_version(network_type),
+            WithdrawalAddressType::Script => None,
+        }
+    }
+
+    // testnet and regtest share the p2sh version byte, so only the address type is recovered
+    pub fn from_address_version(version: u8) -> Option<Self> {
+        [DOGE_NETWORK_TYPE_MAINNET, DOGE_NETWORK_TYPE_TESTNET, DOGE_NETWORK_TYPE_REGTEST]
+            .into_iter()
+            .find_map(|network_type| {
+                [WithdrawalAddressType::P2PKH, WithdrawalAddressType::P2SH]
+                    .into_iter()
+                    .find(|address_type| address_type.address_version(network_type) == Some(version))
+            })
+    }
+}
+impl TryFrom<u32> for WithdrawalAddressType {
+    type Error = DogeBridgeError;
+
+    fn try_from(address_type: u32) -> QDogeResult<Self> {
+        match address_type {
+            0 => Ok(WithdrawalAddressType::P2PKH),
+            1 => Ok(WithdrawalAddressType::P2SH),
+            2 => Ok(WithdrawalAddressType::Script),
+            _ => Err(DogeBridgeError::InvalidWithdrawalAddressType),
+        }
+    }
+}
+impl From<WithdrawalAddressType> for u32 {
+    fn from(address_type: WithdrawalAddressType) -> Self {
+        address_type as u32
+    }
+}
+#[cfg(any(feature = "std", feature = "sp1"))]
+impl From<DogeAddressType> for WithdrawalAddressType {
+    fn from(address_type: DogeAddressType) -> Self {
+        match address_type {
+            DogeAddressType::P2PKH => WithdrawalAddressType::P2PKH,
+            DogeAddressType::P2SH => WithdrawalAddressType::P2SH,
+        }
+    }
+}
+
+pub fn get_withdrawal_script_hash(script_pubkey: &[u8]) -> QHash160 {
+    hash_impl_btc_hash160_bytes(script_pubkey)
+}
 
 #[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
 pub struct PsyWithdrawalRequest {
     pub amount_sats: u64,
-    pub address_type: u32, // 0 = P2PKH, 1 = P2SH
+    pub address_type: u32, // a WithdrawalAddressType
     pub recipient_address: [u8; 20],
 }
 impl PsyWithdrawalRequest {
@@ -15,6 +84,34 @@ impl PsyWithdrawalRequest {
             address_type,
         }
     }
+    pub fn new_script(script_pubkey: &[u8], amount_sats: u64) -> Self {
+        Self::new(
+            get_withdrawal_script_hash(script_pubkey),
+            amount_sats,
+            WithdrawalAddressType::Script.into(),
+        )
+    }
+    #[cfg(any(feature = "std", feature = "sp1"))]
+    pub fn from_doge_address(address: &DogeAddress, amount_sats: u64) -> Self {
+        Self::new(
+            address.hash,
+            amount_sats,
+            WithdrawalAddressType::from(address.address_type).into(),
+        )
+    }
+    pub fn get_address_type(&self) -> QDogeResult<WithdrawalAddressType> {
+        WithdrawalAddressType::try_from(self.address_type)
+    }
+    pub fn new_tombstone(cancelled_withdrawal_index: u64) -> Self {
+        Self {
+            recipient_address: [0u8; 20],
+            amount_sats: cancelled_withdrawal_index,
+            address_type: WITHDRAWAL_ADDRESS_TYPE_TOMBSTONE,
+        }
+    }
+    pub fn is_tombstone(&self) -> bool {
+        self.address_type == WITHDRAWAL_ADDRESS_TYPE_TOMBSTONE
+    }
     pub fn to_leaf(&self) -> [u8; 32] {
         let mut bytes = [0u8; 32];
         bytes[0..8].copy_from_slice(&self.amount_sats.to_le_bytes());
@@ -35,9 +132,31 @@ impl PsyWithdrawalRequest {
     }
 }
 
+// the requests a withdrawal proof starting at first_index pays out, with their tree index, following the rules
+// at WITHDRAWAL_ADDRESS_TYPE_TOMBSTONE. snapshot_leaves are all the leaves below the snapshot's
+// next_requested_withdrawals_tree_index, tombstones are collected from every one of them.
+pub fn get_payable_withdrawal_requests(
+    snapshot_leaves: &[PsyWithdrawalRequest],
+    first_index: u64,
+) -> Vec<(u64, PsyWithdrawalRequest)> {
+    let cancelled_indices: Vec<u64> = snapshot_leaves
+        .iter()
+        .filter(|leaf| leaf.is_tombstone())
+        .map(|leaf| leaf.amount_sats)
+        .collect();
+    snapshot_leaves
+        .iter()
+        .enumerate()
+        .map(|(index, leaf)| (index as u64, *leaf))
+        .skip_while(|(index, _)| *index < first_index)
+        .filter(|(index, leaf)| !leaf.is_tombstone() && !cancelled_indices.contains(index))
+        .collect()
+}
+
 #[macro_rules_attribute::apply(crate::DeriveCopySerializeDefaultReprC)]
 pub struct PsyWithdrawalChainSnapshot {
     pub auto_claimed_deposits_tree_root: QHash256,
+    // leaves carry their address_type, so script withdrawals reach the withdrawal proof as (Script, script hash)
     pub requested_withdrawals_tree_root: QHash256,
     pub block_merkle_tree_root: QHash256,
     pub manual_deposits_tree_root: QHash256,
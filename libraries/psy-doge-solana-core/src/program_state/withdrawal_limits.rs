@@ -0,0 +1,161 @@
+use psy_bridge_core::error::{DogeBridgeError, QDogeResult};
+
+use crate::program_state::PsyBridgeProgramState;
+
+impl PsyBridgeProgramState {
+    // the window usage drains at withdrawal_limit_per_window_sats per withdrawal_limit_window_secs,
+    // which approximates a rolling window without storing per-request history
+    pub fn get_withdrawal_window_used_sats(&self, current_unix_timestamp_secs: u32) -> u64 {
+        if self.withdrawal_limit_window_secs == 0 {
+            return self.withdrawal_window_used_sats;
+        }
+        let elapsed_secs =
+            (current_unix_timestamp_secs as u64).saturating_sub(self.withdrawal_window_updated_at_secs);
+        let drained_sats = (self.withdrawal_limit_per_window_sats as u128 * elapsed_secs as u128)
+            / self.withdrawal_limit_window_secs as u128;
+        self.withdrawal_window_used_sats
+            .saturating_sub(drained_sats.min(u64::MAX as u128) as u64)
+    }
+
+    pub fn consume_withdrawal_limit(
+        &mut self,
+        amount_sats: u64,
+        current_unix_timestamp_secs: u32,
+    ) -> QDogeResult<()> {
+        if self.withdrawal_limit_per_request_sats != 0
+            && amount_sats > self.withdrawal_limit_per_request_sats
+        {
+            return Err(DogeBridgeError::WithdrawalExceedsPerRequestLimit);
+        }
+        // usage is only tracked while a window limit is set
+        if self.withdrawal_limit_per_window_sats == 0 {
+            return Ok(());
+        }
+        let used_sats = self
+            .get_withdrawal_window_used_sats(current_unix_timestamp_secs)
+            .saturating_add(amount_sats);
+        if used_sats > self.withdrawal_limit_per_window_sats {
+            return Err(DogeBridgeError::WithdrawalWindowLimitExceeded);
+        }
+        self.withdrawal_window_used_sats = used_sats;
+        self.withdrawal_window_updated_at_secs = current_unix_timestamp_secs as u64;
+        Ok(())
+    }
+
+    // a cancelled request no longer counts against the window, the decay up to now is settled first
+    pub fn refund_withdrawal_limit(&mut self, amount_sats: u64, current_unix_timestamp_secs: u32) {
+        if self.withdrawal_limit_per_window_sats == 0 {
+            return;
+        }
+        self.withdrawal_window_used_sats = self
+            .get_withdrawal_window_used_sats(current_unix_timestamp_secs)
+            .saturating_sub(amount_sats);
+        self.withdrawal_window_updated_at_secs = current_unix_timestamp_secs as u64;
+    }
+
+    pub fn run_set_withdrawal_limits(
+        &mut self,
+        signer_pubkey: &[u8; 32],
+        limit_per_request_sats: u64,
+        limit_per_window_sats: u64,
+        limit_window_secs: u64,
+        reset_window_usage: bool,
+        current_unix_timestamp_secs: u32,
+    ) -> QDogeResult<()> {
+        if self.access_control.get_settings_authority() != signer_pubkey {
+            return Err(DogeBridgeError::UnauthorizedWithdrawalLimitUpdate);
+        }
+        if limit_per_window_sats != 0 && limit_window_secs == 0 {
+            return Err(DogeBridgeError::InvalidWithdrawalLimits);
+        }
+        // settle the usage under the old limits so the new drain rate only applies from now on,
+        // a window limit that is being enabled or disabled starts from zero
+        self.withdrawal_window_used_sats = if reset_window_usage
+            || self.withdrawal_limit_per_window_sats == 0
+            || limit_per_window_sats == 0
+        {
+            0
+        } else {
+            self.get_withdrawal_window_used_sats(current_unix_timestamp_secs)
+        };
+        self.withdrawal_window_updated_at_secs = current_unix_timestamp_secs as u64;
+        self.withdrawal_limit_per_request_sats = limit_per_request_sats;
+        self.withdrawal_limit_per_window_sats = limit_per_window_sats;
+        self.withdrawal_limit_window_secs = limit_window_secs;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with_limits(per_request: u64, per_window: u64, window_secs: u64) -> PsyBridgeProgramState {
+        let mut state = PsyBridgeProgramState::default();
+        state.access_control.operator_pubkey = [1u8; 32];
+        state
+            .run_set_withdrawal_limits(&[1u8; 32], per_request, per_window, window_secs, false, 1000)
+            .unwrap();
+        state
+    }
+
+    #[test]
+    fn test_withdrawal_window_limit_drains_over_time() {
+        let mut state = state_with_limits(0, 1000, 100);
+
+        state.consume_withdrawal_limit(600, 1000).unwrap();
+        assert_eq!(
+            state.consume_withdrawal_limit(500, 1000),
+            Err(DogeBridgeError::WithdrawalWindowLimitExceeded)
+        );
+        // half the window later, half the cap has drained
+        assert_eq!(state.get_withdrawal_window_used_sats(1050), 100);
+        state.consume_withdrawal_limit(900, 1050).unwrap();
+        assert_eq!(state.get_withdrawal_window_used_sats(1200), 0);
+    }
+
+    #[test]
+    fn test_withdrawal_per_request_limit_and_reset() {
+        let mut state = state_with_limits(500, 1000, 100);
+        assert_eq!(
+            state.consume_withdrawal_limit(501, 1000),
+            Err(DogeBridgeError::WithdrawalExceedsPerRequestLimit)
+        );
+        state.consume_withdrawal_limit(500, 1000).unwrap();
+        state.consume_withdrawal_limit(500, 1000).unwrap();
+        assert_eq!(
+            state.consume_withdrawal_limit(1, 1000),
+            Err(DogeBridgeError::WithdrawalWindowLimitExceeded)
+        );
+
+        assert_eq!(
+            state.run_set_withdrawal_limits(&[2u8; 32], 500, 1000, 100, true, 1000),
+            Err(DogeBridgeError::UnauthorizedWithdrawalLimitUpdate)
+        );
+        assert_eq!(
+            state.run_set_withdrawal_limits(&[1u8; 32], 500, 1000, 0, true, 1000),
+            Err(DogeBridgeError::InvalidWithdrawalLimits)
+        );
+        state
+            .run_set_withdrawal_limits(&[1u8; 32], 500, 1000, 100, true, 1000)
+            .unwrap();
+        state.consume_withdrawal_limit(500, 1000).unwrap();
+    }
+
+    #[test]
+    fn test_withdrawal_usage_not_tracked_without_window_limit() {
+        let mut state = state_with_limits(0, 0, 0);
+        state.consume_withdrawal_limit(5000, 1000).unwrap();
+        assert_eq!(state.withdrawal_window_used_sats, 0);
+
+        // enabling the window limit later doesn't count requests made before it
+        state
+            .run_set_withdrawal_limits(&[1u8; 32], 0, 1000, 100, false, 1000)
+            .unwrap();
+        state.consume_withdrawal_limit(1000, 1000).unwrap();
+        assert_eq!(
+            state.consume_withdrawal_limit(1, 1000),
+            Err(DogeBridgeError::WithdrawalWindowLimitExceeded)
+        );
+    }
+}
@@ -31,6 +31,32 @@ pub fn get_withdrawal_proof_public_inputs(
     ])
 }
 
+// the sweep spends the return output locked to the old custodian script and pays it to the new one,
+// it doesn't process any withdrawals so there is no snapshot or withdrawal index to commit to
+pub fn get_custodian_sweep_proof_public_inputs(
+    old_return_output_hash: &QHash256,
+    new_return_output_hash: &QHash256,
+    old_spent_txo_tree_root: &QHash256,
+    new_spent_txo_tree_root: &QHash256,
+    old_custodian_wallet_config_hash: &QHash256,
+    new_custodian_wallet_config_hash: &QHash256,
+) -> QHash256 {
+    let return_output_hash_transition =
+        hash_impl_sha256_two_to_one_bytes(old_return_output_hash, new_return_output_hash);
+    let spent_txo_tree_root_transition =
+        hash_impl_sha256_two_to_one_bytes(old_spent_txo_tree_root, new_spent_txo_tree_root);
+    let custodian_wallet_config_hash_transition = hash_impl_sha256_two_to_one_bytes(
+        old_custodian_wallet_config_hash,
+        new_custodian_wallet_config_hash,
+    );
+
+    hashv_impl_sha256_bytes(&[
+        &return_output_hash_transition,
+        &spent_txo_tree_root_transition,
+        &custodian_wallet_config_hash_transition,
+    ])
+}
+
 pub fn get_block_transition_public_inputs(
     previous_header_hash: &QHash256,
     new_header_hash: &QHash256,
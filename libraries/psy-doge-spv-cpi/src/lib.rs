@@ -0,0 +1,123 @@
+// CPI helpers for the doge bridge's tx inclusion oracle.
+// A program passes a raw transaction, the 80 byte block header, the tx merkle branch and the block merkle tree
+// siblings, and gets back the txid and the block's confirmation depth once the bridge has checked the block is finalized.
+use psy_doge_solana_core::constants::DOGE_BLOCK_MERKLE_TREE_HEIGHT;
+use psy_doge_solana_core::programs::DOGE_BRIDGE_PROGRAM_ID_STR;
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    program::{get_return_data, invoke},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+pub use psy_doge_solana_core::instructions::doge_bridge::{
+    TxInclusionResult, VerifyTxInclusionFixedData, DOGE_BRIDGE_INSTRUCTION_VERIFY_TX_INCLUSION,
+};
+
+pub const DOGE_BRIDGE_PROGRAM_ID: Pubkey = Pubkey::from_str_const(DOGE_BRIDGE_PROGRAM_ID_STR);
+
+pub fn get_bridge_state_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"bridge_state"], &DOGE_BRIDGE_PROGRAM_ID).0
+}
+
+// siblings are the tx merkle branch followed by the DOGE_BLOCK_MERKLE_TREE_HEIGHT block merkle tree siblings.
+// When proof_buffer is set, siblings and tx_bytes must be empty and the generic buffer holds them, in that order,
+// after its 32 byte header.
+pub fn verify_tx_inclusion_instruction(
+    params: &VerifyTxInclusionFixedData,
+    siblings: &[[u8; 32]],
+    tx_bytes: &[u8],
+    proof_buffer: Option<Pubkey>,
+) -> Instruction {
+    let mut data = Vec::with_capacity(
+        8 + std::mem::size_of::<VerifyTxInclusionFixedData>() + siblings.len() * 32 + tx_bytes.len(),
+    );
+    data.push(DOGE_BRIDGE_INSTRUCTION_VERIFY_TX_INCLUSION);
+    data.extend_from_slice(&[0u8; 7]);
+    data.extend_from_slice(bytemuck::bytes_of(params));
+    data.extend_from_slice(bytemuck::cast_slice(siblings));
+    data.extend_from_slice(tx_bytes);
+
+    let mut accounts = vec![AccountMeta::new_readonly(get_bridge_state_pda(), false)];
+    if let Some(proof_buffer) = proof_buffer {
+        accounts.push(AccountMeta::new_readonly(proof_buffer, false));
+    }
+    Instruction {
+        program_id: DOGE_BRIDGE_PROGRAM_ID,
+        accounts,
+        data,
+    }
+}
+
+// invokes the bridge and decodes its return data, the call fails unless the tx is included in a finalized
+// block with at least params.min_confirmations confirmations
+pub fn verify_tx_inclusion<'a>(
+    bridge_program: &AccountInfo<'a>,
+    bridge_state: &AccountInfo<'a>,
+    proof_buffer: Option<&AccountInfo<'a>>,
+    params: &VerifyTxInclusionFixedData,
+    siblings: &[[u8; 32]],
+    tx_bytes: &[u8],
+) -> Result<TxInclusionResult, ProgramError> {
+    if *bridge_program.key != DOGE_BRIDGE_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (expected_siblings, expected_tx_size) = if proof_buffer.is_some() {
+        (0, 0)
+    } else {
+        (params.tx_siblings_count as usize + DOGE_BLOCK_MERKLE_TREE_HEIGHT, params.tx_size as usize)
+    };
+    if siblings.len() != expected_siblings || tx_bytes.len() != expected_tx_size {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let instruction =
+        verify_tx_inclusion_instruction(params, siblings, tx_bytes, proof_buffer.map(|buffer| *buffer.key));
+    let mut account_infos = vec![bridge_state.clone()];
+    if let Some(proof_buffer) = proof_buffer {
+        account_infos.push(proof_buffer.clone());
+    }
+    account_infos.push(bridge_program.clone());
+    invoke(&instruction, &account_infos)?;
+
+    match get_return_data() {
+        Some((program_id, data))
+            if program_id == DOGE_BRIDGE_PROGRAM_ID
+                && data.len() == std::mem::size_of::<TxInclusionResult>() =>
+        {
+            Ok(bytemuck::pod_read_unaligned(&data))
+        }
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_tx_inclusion_instruction_layout() {
+        let tx_bytes = [7u8; 61];
+        let params = VerifyTxInclusionFixedData {
+            block_height: 100,
+            tx_siblings_count: 1,
+            tx_size: tx_bytes.len() as u32,
+            ..Default::default()
+        };
+        let siblings = vec![[1u8; 32]; 1 + DOGE_BLOCK_MERKLE_TREE_HEIGHT];
+        let instruction = verify_tx_inclusion_instruction(&params, &siblings, &tx_bytes, None);
+
+        let fixed_size = std::mem::size_of::<VerifyTxInclusionFixedData>();
+        let siblings_end = 8 + fixed_size + siblings.len() * 32;
+        assert_eq!(instruction.data[0], DOGE_BRIDGE_INSTRUCTION_VERIFY_TX_INCLUSION);
+        assert_eq!(&instruction.data[8..8 + fixed_size], bytemuck::bytes_of(&params));
+        assert_eq!(&instruction.data[siblings_end..], &tx_bytes);
+        assert_eq!(instruction.accounts.len(), 1);
+
+        let buffer = Pubkey::new_unique();
+        let instruction = verify_tx_inclusion_instruction(&params, &[], &[], Some(buffer));
+        assert_eq!(instruction.data.len(), 8 + fixed_size);
+        assert_eq!(instruction.accounts[1].pubkey, buffer);
+    }
+}
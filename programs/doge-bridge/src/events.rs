@@ -0,0 +1,9 @@
+use psy_doge_solana_core::events::BridgeEventHeader;
+use solana_program::log::sol_log_data;
+
+/// Logs a typed bridge event as `Program data: <header> <record>` so indexers can decode it
+/// without parsing instruction data, including when the bridge is invoked through a CPI.
+pub fn emit_bridge_event<T: bytemuck::Pod>(kind: u8, event: &T) {
+    let header = BridgeEventHeader::new(kind);
+    sol_log_data(&[bytemuck::bytes_of(&header), bytemuck::bytes_of(event)]);
+}
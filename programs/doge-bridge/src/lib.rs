@@ -1,5 +1,6 @@
 pub mod cpi_impl;
 pub mod error;
+pub mod events;
 pub mod instruction;
 pub mod processor;
 pub mod state;
@@ -1,5 +1,6 @@
 use crate::cpi_impl::*;
 use crate::error::BridgeError;
+use crate::events::emit_bridge_event;
 use crate::instruction::ReorgBlockUpdateReader;
 use crate::program_pub_keys::{
     GENERIC_BUFFER_BUILDER_PROGRAM_ID, MANUAL_CLAIM_PROGRAM_ID,
@@ -7,34 +8,101 @@ use crate::program_pub_keys::{
 };
 use crate::state::BridgeState;
 use bytemuck::from_bytes;
+use std::cell::RefMut;
 use psy_bridge_core::common_types::QHash256;
+use psy_bridge_core::custodian_config::Bridge7MultisigCustodianWalletConfig;
 use psy_bridge_core::crypto::hash::merkle::fixed_append_tree::FixedMerkleAppendTreePartialMerkleProof;
 use psy_bridge_core::crypto::hash::sha256::btc_hash256_bytes;
 use psy_bridge_core::crypto::zk::{CompactBridgeZKProof, CompactBridgeZKVerifierKey};
 use psy_bridge_core::error::DogeBridgeError;
 use psy_bridge_core::header::PsyBridgeHeader;
+use psy_doge_solana_core::constants::{
+    BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY,
+    BRIDGE_CONTROL_MODE_DISABLE_AUTO_MINTS, BRIDGE_CONTROL_MODE_DISABLE_FEE_WITHDRAWALS,
+    BRIDGE_CONTROL_MODE_DISABLE_MANUAL_CLAIMS, BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_PROCESSING,
+    BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_REQUESTS, DOGE_BLOCK_MERKLE_TREE_HEIGHT, VK_KIND_BLOCK_REORG,
+    VK_KIND_SINGLE_BLOCK_UPDATE, VK_KIND_WITHDRAWAL,
+};
+use psy_doge_solana_core::data_accounts::block_hash_archive::{
+    block_hash_archive_segment_size, BLOCK_HASH_ARCHIVE_SEED,
+};
+use psy_doge_solana_core::data_accounts::custodian_config::{
+    CUSTODIAN_WALLET_CONFIG_ACCOUNT_SIZE, CUSTODIAN_WALLET_CONFIG_SEED,
+};
+use psy_doge_solana_core::data_accounts::fee_split::{
+    FeeSplitTable, FEE_SPLIT_SEED, FEE_SPLIT_TABLE_SIZE,
+};
+use psy_doge_solana_core::data_accounts::finalized_block_history::{
+    finalized_block_history_account_size, FINALIZED_BLOCK_HISTORY_SEED,
+};
 use psy_doge_solana_core::data_accounts::pending_mint::{
     PendingMint, PM_DA_PENDING_MINT_SIZE, PM_MAX_PENDING_MINTS_PER_GROUP_U16,
 };
+use psy_doge_solana_core::data_accounts::vk_registry::{
+    AcceptedVerifierKeys, VerifierKeyRegistry, VK_REGISTRY_SEED, VK_REGISTRY_SIZE,
+};
+use psy_doge_solana_core::data_accounts::withdrawal_receipt::{
+    WithdrawalReceipt, WITHDRAWAL_RECEIPT_SEED, WITHDRAWAL_RECEIPT_SIZE,
+};
+use psy_doge_solana_core::events::{
+    BlockTransitionEventData, CustodianRotatedEventData, FeesWithdrawnEventData,
+    ManualDepositClaimedEventData, MintGroupProcessedEventData, WithdrawalCancelledEventData,
+    WithdrawalProcessedEventData, WithdrawalRequestedEventData, WithdrawalScriptEventData,
+    BRIDGE_EVENT_KIND_BLOCK_UPDATE, BRIDGE_EVENT_KIND_CUSTODIAN_ROTATED,
+    BRIDGE_EVENT_KIND_FEES_WITHDRAWN, BRIDGE_EVENT_KIND_MANUAL_DEPOSIT_CLAIMED,
+    BRIDGE_EVENT_KIND_MINT_GROUP_PROCESSED, BRIDGE_EVENT_KIND_REORG,
+    BRIDGE_EVENT_KIND_WITHDRAWAL_CANCELLED, BRIDGE_EVENT_KIND_WITHDRAWAL_PROCESSED,
+    BRIDGE_EVENT_KIND_WITHDRAWAL_REQUESTED, BRIDGE_EVENT_KIND_WITHDRAWAL_SCRIPT,
+};
 use psy_doge_solana_core::generic_cpi::{
     AutoClaimMintBufferAddressHelper, LockAutoClaimMintBufferCPIHelper, MintCPIHelper,
     UnlockAutoClaimMintBufferCPIHelper,
 };
 use psy_doge_solana_core::instructions::doge_bridge::{
-    BlockUpdateFixedData, DOGE_BRIDGE_INSTRUCTION_BLOCK_UPDATE, DOGE_BRIDGE_INSTRUCTION_INITIALIZE, DOGE_BRIDGE_INSTRUCTION_OPERATOR_WITHDRAW_FEES, DOGE_BRIDGE_INSTRUCTION_PROCESS_MANUAL_DEPOSIT, DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP, DOGE_BRIDGE_INSTRUCTION_PROCESS_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_REPLAY_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_SNAPSHOT_WITHDRAWALS, InitializeBridgeInstructionData, ProcessManualDepositInstructionData, ProcessWithdrawalInstructionData, RequestWithdrawalInstructionData
-};
-use psy_doge_solana_core::instructions::doge_bridge::{
+    BlockUpdateFixedData, GuardianSetPauseInstructionData, InitFinalizedBlockHistoryInstructionData,
+    InitializeBridgeInstructionData, ProcessCustodianSweepInstructionData,
+    ProcessManualDepositInstructionData, ProcessWithdrawalInstructionData,
+    ProposeConfigUpdateInstructionData, RequestScriptWithdrawalFixedData,
+    RequestWithdrawalInstructionData, RotateKeyInstructionData,
+    ScheduleCustodianRotationInstructionData, SetBridgeControlModeInstructionData,
+    SetFeeSplitInstructionData, SetPolicyConfigInstructionData, SetWithdrawalLimitsInstructionData,
+    StoreCustodianWalletConfigInstructionData, VerifierKeyUpdateInstructionData,
+    VerifyTxInclusionFixedData, DOGE_BRIDGE_INSTRUCTION_ACCEPT_KEY,
+    DOGE_BRIDGE_INSTRUCTION_ACTIVATE_VK_UPDATE, DOGE_BRIDGE_INSTRUCTION_ADMIN_FORCE_ROTATE_KEY,
+    DOGE_BRIDGE_INSTRUCTION_APPLY_CONFIG_UPDATE, DOGE_BRIDGE_INSTRUCTION_BLOCK_UPDATE,
+    DOGE_BRIDGE_INSTRUCTION_CANCEL_CONFIG_UPDATE, DOGE_BRIDGE_INSTRUCTION_CANCEL_CUSTODIAN_ROTATION,
+    DOGE_BRIDGE_INSTRUCTION_CANCEL_VK_UPDATE, DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL,
+    DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT, DOGE_BRIDGE_INSTRUCTION_DISTRIBUTE_FEES,
+    DOGE_BRIDGE_INSTRUCTION_GUARDIAN_SET_PAUSE, DOGE_BRIDGE_INSTRUCTION_INITIALIZE,
+    DOGE_BRIDGE_INSTRUCTION_INIT_BLOCK_HASH_ARCHIVE,
+    DOGE_BRIDGE_INSTRUCTION_INIT_FINALIZED_BLOCK_HISTORY,
+    DOGE_BRIDGE_INSTRUCTION_MARK_WITHDRAWAL_RECEIPTS_FULFILLED,
+    DOGE_BRIDGE_INSTRUCTION_MIGRATE_STATE, DOGE_BRIDGE_INSTRUCTION_NOMINATE_KEY,
+    DOGE_BRIDGE_INSTRUCTION_OPERATOR_WITHDRAW_FEES, DOGE_BRIDGE_INSTRUCTION_PROCESS_CUSTODIAN_SWEEP,
+    DOGE_BRIDGE_INSTRUCTION_PROCESS_MANUAL_DEPOSIT, DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP,
     DOGE_BRIDGE_INSTRUCTION_PROCESS_MINT_GROUP_AUTO_ADVANCE,
-    DOGE_BRIDGE_INSTRUCTION_PROCESS_REORG_BLOCKS,
+    DOGE_BRIDGE_INSTRUCTION_PROCESS_REORG_BLOCKS, DOGE_BRIDGE_INSTRUCTION_PROCESS_WITHDRAWAL,
+    DOGE_BRIDGE_INSTRUCTION_PROPOSE_CONFIG_UPDATE, DOGE_BRIDGE_INSTRUCTION_PROPOSE_VK_UPDATE,
+    DOGE_BRIDGE_INSTRUCTION_REPLAY_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_REQUEST_SCRIPT_WITHDRAWAL,
+    DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWAL, DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWALS_BATCH,
+    DOGE_BRIDGE_INSTRUCTION_SCHEDULE_CUSTODIAN_ROTATION, DOGE_BRIDGE_INSTRUCTION_SET_CONTROL_MODE,
+    DOGE_BRIDGE_INSTRUCTION_SET_FEE_SPLIT, DOGE_BRIDGE_INSTRUCTION_SET_POLICY_CONFIG,
+    DOGE_BRIDGE_INSTRUCTION_SET_WITHDRAWAL_LIMITS, DOGE_BRIDGE_INSTRUCTION_SNAPSHOT_WITHDRAWALS,
+    DOGE_BRIDGE_INSTRUCTION_STORE_CUSTODIAN_WALLET_CONFIG,
+    DOGE_BRIDGE_INSTRUCTION_VERIFY_TX_INCLUSION,
+};
+use psy_doge_solana_core::program_state::{
+    bridge_state_account_size, get_bridge_state_version, migrate_bridge_state_account_data,
+    BridgeStateVersionHeader, FinalizedBlockMintTxoInfo, PsyBridgeConfig, PsyReturnTxOutput,
+    PsyWithdrawalRequest, BRIDGE_STATE_CURRENT_VERSION,
 };
-use psy_doge_solana_core::program_state::{FinalizedBlockMintTxoInfo, PsyReturnTxOutput, PsyWithdrawalRequest};
 use solana_program::instruction::{AccountMeta, Instruction};
 use solana_program::program_error::ProgramError;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    entrypoint::ProgramResult,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
     msg,
-    program::invoke_signed,
+    program::{invoke_signed, set_return_data},
     pubkey::Pubkey,
     rent::Rent,
     system_instruction,
@@ -131,8 +199,17 @@ pub fn process_instruction(
                 program_id,
                 accounts,
                 params.request,
+                params.receipt_nonce,
             )
         }
+        DOGE_BRIDGE_INSTRUCTION_REQUEST_WITHDRAWALS_BATCH => {
+            let requests: &[RequestWithdrawalInstructionData] =
+                bytemuck::try_cast_slice(data).map_err(|_| BridgeError::SerializationError)?;
+            process_request_withdrawals_batch(program_id, accounts, requests)
+        }
+        DOGE_BRIDGE_INSTRUCTION_REQUEST_SCRIPT_WITHDRAWAL => {
+            process_request_script_withdrawal(program_id, accounts, data)
+        }
         DOGE_BRIDGE_INSTRUCTION_PROCESS_WITHDRAWAL => {
             if data.len() != std::mem::size_of::<ProcessWithdrawalInstructionData>() {
                 return Err(BridgeError::SerializationError.into());
@@ -205,10 +282,186 @@ pub fn process_instruction(
         DOGE_BRIDGE_INSTRUCTION_SNAPSHOT_WITHDRAWALS => {
             process_snapshot_withdrawals(program_id, accounts)
         }
+        DOGE_BRIDGE_INSTRUCTION_GUARDIAN_SET_PAUSE => {
+            if data.len() != std::mem::size_of::<GuardianSetPauseInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &GuardianSetPauseInstructionData = from_bytes(data);
+            process_guardian_set_pause(program_id, accounts, params.pause_duration_secs)
+        }
+        DOGE_BRIDGE_INSTRUCTION_SET_CONTROL_MODE => {
+            if data.len() != std::mem::size_of::<SetBridgeControlModeInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &SetBridgeControlModeInstructionData = from_bytes(data);
+            process_set_control_mode(program_id, accounts, params.bridge_control_mode)
+        }
+        DOGE_BRIDGE_INSTRUCTION_PROPOSE_CONFIG_UPDATE => {
+            if data.len() != std::mem::size_of::<ProposeConfigUpdateInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &ProposeConfigUpdateInstructionData = from_bytes(data);
+            process_propose_config_update(
+                program_id,
+                accounts,
+                &params.new_config_params,
+                params.delay_secs,
+            )
+        }
+        DOGE_BRIDGE_INSTRUCTION_APPLY_CONFIG_UPDATE => {
+            process_apply_config_update(program_id, accounts)
+        }
+        DOGE_BRIDGE_INSTRUCTION_CANCEL_CONFIG_UPDATE => {
+            process_cancel_config_update(program_id, accounts)
+        }
+        DOGE_BRIDGE_INSTRUCTION_SET_POLICY_CONFIG => {
+            if data.len() != std::mem::size_of::<SetPolicyConfigInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &SetPolicyConfigInstructionData = from_bytes(data);
+            process_set_policy_config(program_id, accounts, params)
+        }
+        DOGE_BRIDGE_INSTRUCTION_NOMINATE_KEY
+        | DOGE_BRIDGE_INSTRUCTION_ACCEPT_KEY
+        | DOGE_BRIDGE_INSTRUCTION_ADMIN_FORCE_ROTATE_KEY => {
+            if data.len() != std::mem::size_of::<RotateKeyInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &RotateKeyInstructionData = from_bytes(data);
+            process_rotate_key(
+                program_id,
+                accounts,
+                discriminator,
+                params.key_role,
+                &params.new_pubkey,
+            )
+        }
+        DOGE_BRIDGE_INSTRUCTION_PROPOSE_VK_UPDATE
+        | DOGE_BRIDGE_INSTRUCTION_ACTIVATE_VK_UPDATE
+        | DOGE_BRIDGE_INSTRUCTION_CANCEL_VK_UPDATE => {
+            if data.len() != std::mem::size_of::<VerifierKeyUpdateInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &VerifierKeyUpdateInstructionData = from_bytes(data);
+            process_vk_update(
+                program_id,
+                accounts,
+                discriminator,
+                params.vk_kind,
+                &params.new_vk,
+            )
+        }
+        DOGE_BRIDGE_INSTRUCTION_SET_WITHDRAWAL_LIMITS => {
+            if data.len() != std::mem::size_of::<SetWithdrawalLimitsInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &SetWithdrawalLimitsInstructionData = from_bytes(data);
+            process_set_withdrawal_limits(program_id, accounts, params)
+        }
+        DOGE_BRIDGE_INSTRUCTION_MARK_WITHDRAWAL_RECEIPTS_FULFILLED => {
+            process_mark_withdrawal_receipts_fulfilled(program_id, accounts)
+        }
+        DOGE_BRIDGE_INSTRUCTION_CLOSE_WITHDRAWAL_RECEIPT => {
+            process_close_withdrawal_receipt(program_id, accounts)
+        }
+        DOGE_BRIDGE_INSTRUCTION_CANCEL_WITHDRAWAL => {
+            process_cancel_withdrawal(program_id, accounts)
+        }
+        DOGE_BRIDGE_INSTRUCTION_SET_FEE_SPLIT => {
+            if data.len() != std::mem::size_of::<SetFeeSplitInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &SetFeeSplitInstructionData = from_bytes(data);
+            process_set_fee_split(program_id, accounts, &params.fee_split)
+        }
+        DOGE_BRIDGE_INSTRUCTION_DISTRIBUTE_FEES => process_distribute_fees(program_id, accounts),
+        DOGE_BRIDGE_INSTRUCTION_INIT_FINALIZED_BLOCK_HISTORY => {
+            if data.len() != std::mem::size_of::<InitFinalizedBlockHistoryInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &InitFinalizedBlockHistoryInstructionData = from_bytes(data);
+            process_init_finalized_block_history(program_id, accounts, params.capacity, params.step)
+        }
+        DOGE_BRIDGE_INSTRUCTION_VERIFY_TX_INCLUSION => {
+            process_verify_tx_inclusion(program_id, accounts, data)
+        }
+        DOGE_BRIDGE_INSTRUCTION_INIT_BLOCK_HASH_ARCHIVE => {
+            process_init_block_hash_archive(program_id, accounts)
+        }
+        DOGE_BRIDGE_INSTRUCTION_MIGRATE_STATE => process_migrate_state(program_id, accounts),
+        DOGE_BRIDGE_INSTRUCTION_STORE_CUSTODIAN_WALLET_CONFIG => {
+            if data.len() != std::mem::size_of::<StoreCustodianWalletConfigInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &StoreCustodianWalletConfigInstructionData = from_bytes(data);
+            process_store_custodian_wallet_config(program_id, accounts, params)
+        }
+        DOGE_BRIDGE_INSTRUCTION_SCHEDULE_CUSTODIAN_ROTATION => {
+            if data.len() != std::mem::size_of::<ScheduleCustodianRotationInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &ScheduleCustodianRotationInstructionData = from_bytes(data);
+            process_custodian_rotation_update(
+                program_id,
+                accounts,
+                discriminator,
+                &params.new_custodian_wallet_config_hash,
+            )
+        }
+        DOGE_BRIDGE_INSTRUCTION_CANCEL_CUSTODIAN_ROTATION => {
+            process_custodian_rotation_update(program_id, accounts, discriminator, &[0u8; 32])
+        }
+        DOGE_BRIDGE_INSTRUCTION_PROCESS_CUSTODIAN_SWEEP => {
+            if data.len() != std::mem::size_of::<ProcessCustodianSweepInstructionData>() {
+                return Err(BridgeError::SerializationError.into());
+            }
+            let params: &ProcessCustodianSweepInstructionData = from_bytes(data);
+            process_custodian_sweep(
+                program_id,
+                accounts,
+                &params.proof,
+                params.new_return_output,
+                params.new_spent_txo_tree_root,
+            )
+        }
         _ => Err(BridgeError::SerializationError.into()),
     }
 }
 
+fn current_unix_timestamp_secs() -> Result<u32, ProgramError> {
+    Ok((Clock::get()?.unix_timestamp & 0xFFFFFFFFi64) as u32)
+}
+
+// reads the keys accepted for a proof kind. The registry account is an optional trailing account so callers
+// built before it existed keep working, the built-in key applies until the registry is created.
+fn load_accepted_vks(
+    program_id: &Pubkey,
+    bridge_state: &BridgeState,
+    vk_registry_account: Option<&AccountInfo>,
+    vk_kind: u32,
+    builtin_vk: &CompactBridgeZKVerifierKey,
+) -> Result<AcceptedVerifierKeys, ProgramError> {
+    let vk_registry_account = match vk_registry_account {
+        Some(account) => account,
+        None if !bridge_state.has_vk_registry() => return Ok(AcceptedVerifierKeys::builtin(builtin_vk)),
+        None => return Err(DogeBridgeError::InvalidVerifierKeyRegistry.into()),
+    };
+    let (vk_registry_pda, _bump) = Pubkey::find_program_address(&[VK_REGISTRY_SEED], program_id);
+    if vk_registry_pda != *vk_registry_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    if vk_registry_account.data_len() != 0 && vk_registry_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let data = vk_registry_account.try_borrow_data()?;
+    Ok(VerifierKeyRegistry::get_accepted_vks_from_account_data(
+        &data,
+        vk_kind,
+        builtin_vk,
+        current_unix_timestamp_secs()?,
+    )?)
+}
+
 fn process_initialize(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -249,6 +502,7 @@ fn process_initialize(
 
     let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
         .map_err(|_| BridgeError::SerializationError)?;
+    bridge_state.version_header = BridgeStateVersionHeader::current();
     bridge_state.doge_mint = initialize_instruction.doge_mint;
     bridge_state.core_state.initialize(initialize_instruction);
 
@@ -315,11 +569,22 @@ fn run_block_update_common(
     let auto_claim_mint_buffer = next_account_info(account_info_iter)?;
     let auto_claim_txo_buffer = next_account_info(account_info_iter)?;
     let operator = next_account_info(account_info_iter)?;
-    let _payer = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
 
     // Consume Program accounts
     let mint_buffer_program_account = next_account_info(account_info_iter)?;
     let _txo_buffer_program_account = next_account_info(account_info_iter)?;
+    // only required once the verifier key registry is created
+    let vk_registry_account = next_account_info(account_info_iter).ok();
+    // only required once the finalized block history is enabled
+    let finalized_block_history_account = next_account_info(account_info_iter).ok();
+    // only required once the block hash archive is enabled, the segment the next entry goes into
+    let block_hash_archive_segment = next_account_info(account_info_iter).ok();
+    let system_program = next_account_info(account_info_iter).ok();
+    // only required by a reorg that finalizes more than one height while the block hash archive is enabled,
+    // a generic buffer with the hashes of the heights it skips over and the segment after a full one
+    let reorg_block_hashes_buffer = next_account_info(account_info_iter).ok();
+    let next_block_hash_archive_segment = next_account_info(account_info_iter).ok();
 
     if !operator.is_signer {
         return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
@@ -391,37 +656,56 @@ fn run_block_update_common(
     let mint_buffer_data = auto_claim_mint_buffer.try_borrow_data()?;
     let txo_buffer_data = auto_claim_txo_buffer.try_borrow_data()?;
 
-    let old_index = bridge_state
-        .core_state
-        .bridge_header
-        .finalized_state
-        .auto_claimed_deposits_next_index;
+    let accepted_vks = if is_reorg {
+        load_accepted_vks(
+            program_id,
+            bridge_state,
+            vk_registry_account,
+            VK_KIND_BLOCK_REORG,
+            &BLOCK_REORG_VK,
+        )?
+    } else {
+        load_accepted_vks(
+            program_id,
+            bridge_state,
+            vk_registry_account,
+            VK_KIND_SINGLE_BLOCK_UPDATE,
+            &SINGLE_BLOCK_UPDATE_VK,
+        )?
+    };
+
+    let previous_finalized_state = bridge_state.core_state.bridge_header.finalized_state;
+    let old_index = previous_finalized_state.auto_claimed_deposits_next_index;
 
     if is_reorg {
-        bridge_state
-            .core_state
-            .run_block_transition_reorg::<ZKVerifier>(
-                proof,
-                &BLOCK_REORG_VK,
-                new_header,
-                extra_finalized_blocks,
-                &self_pubkey_bytes,
-                mint_buffer_locker_account_pubkey,
-                &txo_buffer_data,
-                &mint_buffer_data,
-            )?;
+        accepted_vks.verify_with(|vk| {
+            bridge_state
+                .core_state
+                .run_block_transition_reorg::<ZKVerifier>(
+                    proof,
+                    vk,
+                    new_header,
+                    extra_finalized_blocks,
+                    &self_pubkey_bytes,
+                    mint_buffer_locker_account_pubkey,
+                    &txo_buffer_data,
+                    &mint_buffer_data,
+                )
+        })?;
     } else {
-        let res = bridge_state
-            .core_state
-            .run_standard_single_block_transition::<ZKVerifier>(
-                proof,
-                &SINGLE_BLOCK_UPDATE_VK,
-                new_header,
-                &self_pubkey_bytes,
-                mint_buffer_locker_account_pubkey,
-                &txo_buffer_data,
-                &mint_buffer_data,
-            );
+        let res = accepted_vks.verify_with(|vk| {
+            bridge_state
+                .core_state
+                .run_standard_single_block_transition::<ZKVerifier>(
+                    proof,
+                    vk,
+                    new_header,
+                    &self_pubkey_bytes,
+                    mint_buffer_locker_account_pubkey,
+                    &txo_buffer_data,
+                    &mint_buffer_data,
+                )
+        });
         if res.is_err() {
 
         let previous_header_hash = bridge_state.core_state.bridge_header.get_hash_canonical();
@@ -445,6 +729,88 @@ fn run_block_update_common(
         .finalized_state
         .auto_claimed_deposits_next_index;
 
+    if bridge_state.core_state.has_finalized_block_history() {
+        let finalized_block_history_account = finalized_block_history_account
+            .ok_or(DogeBridgeError::InvalidFinalizedBlockHistoryAccount)?;
+        verify_finalized_block_history_account(program_id, finalized_block_history_account)?;
+        let mut history_data = finalized_block_history_account.try_borrow_mut_data()?;
+        bridge_state
+            .core_state
+            .record_finalized_block_history(&mut history_data)?;
+    }
+
+    if bridge_state.core_state.has_block_hash_archive() {
+        let (block_hash_archive_segment, system_program) = block_hash_archive_segment
+            .zip(system_program)
+            .ok_or(DogeBridgeError::InvalidBlockHashArchiveAccount)?;
+        let reorg_block_hashes_data = match reorg_block_hashes_buffer {
+            Some(buffer) => {
+                if buffer.owner != &GENERIC_BUFFER_BUILDER_PROGRAM_ID {
+                    return Err(ProgramError::IllegalOwner);
+                }
+                Some(buffer.try_borrow_data()?)
+            }
+            None => None,
+        };
+        let reorg_block_hashes: &[QHash256] = match &reorg_block_hashes_data {
+            // skip the buffer's authorized writer
+            Some(buffer_data) => buffer_data
+                .get(32..)
+                .and_then(|hashes| bytemuck::try_cast_slice(hashes).ok())
+                .ok_or(DogeBridgeError::InvalidReorgBlockHashes)?,
+            None => &[],
+        };
+        let entries = bridge_state
+            .core_state
+            .get_finalized_block_hash_archive_entries(&previous_finalized_state, reorg_block_hashes)?;
+
+        // a full segment is continued in the next one
+        let (segment_index, index_in_segment) =
+            bridge_state.core_state.get_block_hash_archive_next_position();
+        let (segment_entries, next_segment_entries) = entries.split_at(
+            entries
+                .len()
+                .min((BLOCK_HASH_ARCHIVE_SEGMENT_CAPACITY - index_in_segment) as usize),
+        );
+        let mut segments = vec![(block_hash_archive_segment, segment_index, index_in_segment, segment_entries)];
+        if !next_segment_entries.is_empty() {
+            let next_segment = next_block_hash_archive_segment
+                .ok_or(DogeBridgeError::InvalidBlockHashArchiveAccount)?;
+            segments.push((next_segment, segment_index + 1, 0, next_segment_entries));
+        }
+        for (segment, segment_index, index_in_segment, segment_entries) in segments {
+            grow_block_hash_archive_segment(
+                program_id,
+                payer,
+                segment,
+                system_program,
+                segment_index,
+                index_in_segment,
+                segment_entries.len() as u32,
+            )?;
+            let mut segment_data = segment.try_borrow_mut_data()?;
+            bridge_state
+                .core_state
+                .record_block_hash_archive_entries(&mut segment_data, segment_entries)?;
+        }
+    }
+
+    let finalized_state = &bridge_state.core_state.bridge_header.finalized_state;
+    emit_bridge_event(
+        if is_reorg {
+            BRIDGE_EVENT_KIND_REORG
+        } else {
+            BRIDGE_EVENT_KIND_BLOCK_UPDATE
+        },
+        &BlockTransitionEventData {
+            finalized_block_hash: finalized_state.block_hash,
+            finalized_block_height: finalized_state.block_height,
+            tip_block_height: bridge_state.core_state.bridge_header.tip_state.block_height,
+            auto_claimed_deposits_next_index: new_index,
+            finalized_blocks_count: 1 + extra_finalized_blocks.len() as u32,
+        },
+    );
+
     // Drop borrows
     let _ = bridge_state;
     drop(data);
@@ -512,6 +878,13 @@ fn process_process_mint_group(
         return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
     }
 
+    bridge_state
+        .core_state
+        .ensure_not_paused(current_unix_timestamp_secs()?)?;
+    bridge_state
+        .core_state
+        .ensure_operation_enabled(BRIDGE_CONTROL_MODE_DISABLE_AUTO_MINTS)?;
+
     let (can_unlock, mints_count, start_offset) = bridge_state
         .core_state
         .run_auto_mint_group_precheck(mint_group_index, &auto_claim_mint_buffer.key.to_bytes())?;
@@ -542,12 +915,14 @@ fn process_process_mint_group(
 
     let auto_claim_mint_buffer_data = auto_claim_mint_buffer.try_borrow_data()?;
 
+    let mut total_minted_sats = 0u64;
     for p in 0..mints_count {
         let offset = start_offset + p as usize * PM_DA_PENDING_MINT_SIZE;
         let pending_mint: &PendingMint = bytemuck::from_bytes(
             &auto_claim_mint_buffer_data[offset..(offset + PM_DA_PENDING_MINT_SIZE)],
         );
         minter.mint_to(p as usize, &pending_mint.recipient, pending_mint.amount)?;
+        total_minted_sats += pending_mint.amount;
     }
 
     drop(auto_claim_mint_buffer_data);
@@ -564,6 +939,18 @@ fn process_process_mint_group(
         mint_buffer_locker.unlock_buffer(&PENDING_MINT_BUFFER_BUILDER_PROGRAM_ID.to_bytes())?;
     }
 
+    emit_bridge_event(
+        BRIDGE_EVENT_KIND_MINT_GROUP_PROCESSED,
+        &MintGroupProcessedEventData {
+            mint_buffer: auto_claim_mint_buffer.key.to_bytes(),
+            total_minted_sats,
+            mint_group_index: mint_group_index as u32,
+            mints_count: mints_count as u32,
+            buffer_unlocked: should_unlock as u32,
+            _padding: 0,
+        },
+    );
+
     Ok(())
 }
 
@@ -606,6 +993,13 @@ fn process_process_mint_group_auto_advance(
     let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
         .map_err(|_| BridgeError::SerializationError)?;
 
+    bridge_state
+        .core_state
+        .ensure_not_paused(current_unix_timestamp_secs()?)?;
+    bridge_state
+        .core_state
+        .ensure_operation_enabled(BRIDGE_CONTROL_MODE_DISABLE_AUTO_MINTS)?;
+
     let advance_with_jit = bridge_state
         .core_state
         .pending_mint_txos
@@ -688,12 +1082,14 @@ fn process_process_mint_group_auto_advance(
     }
     let auto_claim_mint_buffer_data = auto_claim_mint_buffer.try_borrow_data()?;
 
+    let mut total_minted_sats = 0u64;
     for p in 0..mints_count {
         let offset = start_offset + p as usize * PM_DA_PENDING_MINT_SIZE;
         let pending_mint: &PendingMint = bytemuck::from_bytes(
             &auto_claim_mint_buffer_data[offset..(offset + PM_DA_PENDING_MINT_SIZE)],
         );
         minter.mint_to(p as usize, &pending_mint.recipient, pending_mint.amount)?;
+        total_minted_sats += pending_mint.amount;
     }
 
     drop(auto_claim_mint_buffer_data);
@@ -707,13 +1103,41 @@ fn process_process_mint_group_auto_advance(
         mint_buffer_locker.unlock_buffer(&PENDING_MINT_BUFFER_BUILDER_PROGRAM_ID.to_bytes())?;
     }
 
+    emit_bridge_event(
+        BRIDGE_EVENT_KIND_MINT_GROUP_PROCESSED,
+        &MintGroupProcessedEventData {
+            mint_buffer: auto_claim_mint_buffer.key.to_bytes(),
+            total_minted_sats,
+            mint_group_index: mint_group_index as u32,
+            mints_count: mints_count as u32,
+            buffer_unlocked: should_unlock as u32,
+            _padding: 0,
+        },
+    );
+
     Ok(())
 }
 
+fn find_withdrawal_receipt_pda(
+    program_id: &Pubkey,
+    requester: &[u8; 32],
+    receipt_nonce: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            WITHDRAWAL_RECEIPT_SEED,
+            requester,
+            &receipt_nonce.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
 fn process_request_withdrawal(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     request: PsyWithdrawalRequest,
+    receipt_nonce: u64,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let bridge_state_account = next_account_info(account_info_iter)?;
@@ -721,103 +1145,497 @@ fn process_request_withdrawal(
     let doge_mint = next_account_info(account_info_iter)?;
     let user_authority = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
+    let withdrawal_receipt_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
 
-    let mut data = bridge_state_account.try_borrow_mut_data()?;
-    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
-        .map_err(|_| BridgeError::SerializationError)?;
+    let (withdrawal_index, queued_request) = {
+        let mut data = bridge_state_account.try_borrow_mut_data()?;
+        let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+            .map_err(|_| BridgeError::SerializationError)?;
 
-    let burner = SolanaBurner {
-        mint: doge_mint,
-        user_token_account,
-        authority: user_authority,
-        token_program,
+        if doge_mint.key.to_bytes() != bridge_state.doge_mint {
+            return Err(BridgeError::InvalidAccountInput.into());
+        }
+        let current_timestamp = current_unix_timestamp_secs()?;
+        bridge_state.core_state.ensure_not_paused(current_timestamp)?;
+        bridge_state
+            .core_state
+            .ensure_operation_enabled(BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_REQUESTS)?;
+
+        let burner = SolanaBurner {
+            mint: doge_mint,
+            user_token_account,
+            authority: user_authority,
+            token_program,
+        };
+        let withdrawal_index = bridge_state.core_state.requested_withdrawals_tree.next_index;
+        let queued_request = bridge_state.core_state.request_withdrawal(
+            &burner,
+            &user_authority.key.to_bytes(),
+            &request,
+            current_timestamp,
+        )?;
+        (withdrawal_index, queued_request)
     };
-    bridge_state.core_state.request_withdrawal(
-        &burner,
-        &user_authority.key.to_bytes(),
-        &request,
-    )?;
 
-    Ok(())
+    create_withdrawal_receipt(
+        program_id,
+        withdrawal_receipt_account,
+        payer,
+        system_program,
+        user_authority.key.to_bytes(),
+        receipt_nonce,
+        withdrawal_index,
+        queued_request,
+        request.amount_sats,
+    )
 }
 
-// Wormhole VAA Discriminator: sha256("global:post_message")[:8]
-const WORMHOLE_VAA_DISCRIMINATOR: [u8; 8] = [214, 50, 100, 209, 38, 34, 7, 76];
-/// Sends a VAA via the Wormhole Shim program using a CPI call.
-/// This function manually constructs the Anchor instruction and invokes it.
-fn send_wormhole_vaa<'a>(
-    withdrawal_nonce: u32,
-    shim_program: &AccountInfo<'a>,
-    bridge_config: &AccountInfo<'a>,
-    message: &AccountInfo<'a>,
-    emitter: &AccountInfo<'a>,
-    sequence: &AccountInfo<'a>,
-    payer: &AccountInfo<'a>,
-    fee_collector: &AccountInfo<'a>,
-    clock: &AccountInfo<'a>,
-    system_program: &AccountInfo<'a>,
-    core_bridge_program: &AccountInfo<'a>,
-    event_authority: &AccountInfo<'a>,
-    emitter_seeds: &[&[u8]],
-    sighash: &[u8],
-    transaction_buffer: &[u8],
+// burns the total once, the receipt accounts are passed in request order after the system program
+fn process_request_withdrawals_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    requests: &[RequestWithdrawalInstructionData],
 ) -> ProgramResult {
-    let payload_len = sighash.len() + transaction_buffer.len();
-    let consistency_level: u8 = 1; // 1 = Finalized
+    let account_info_iter = &mut accounts.iter();
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let doge_mint = next_account_info(account_info_iter)?;
+    let user_authority = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
 
-    let mut ix_data = Vec::with_capacity(8 + 4 + 1 + 4 + payload_len);
-    ix_data.extend_from_slice(&WORMHOLE_VAA_DISCRIMINATOR);
-    ix_data.extend_from_slice(&withdrawal_nonce.to_le_bytes());
-    ix_data.push(consistency_level);
-    ix_data.extend_from_slice(&(payload_len as u32).to_le_bytes());
-    ix_data.extend_from_slice(sighash);
-    ix_data.extend_from_slice(transaction_buffer);
+    let (first_withdrawal_index, queued_requests) = {
+        let mut data = bridge_state_account.try_borrow_mut_data()?;
+        let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+            .map_err(|_| BridgeError::SerializationError)?;
 
-    let accounts = vec![
-        AccountMeta::new(*bridge_config.key, false),
-        AccountMeta::new(*message.key, false),
-        AccountMeta::new_readonly(*emitter.key, true),
-        AccountMeta::new(*sequence.key, false),
-        AccountMeta::new(*payer.key, true),
-        AccountMeta::new(*fee_collector.key, false),
-        AccountMeta::new_readonly(*clock.key, false),
-        AccountMeta::new_readonly(*system_program.key, false),
-        AccountMeta::new_readonly(*core_bridge_program.key, false),
-        AccountMeta::new_readonly(*event_authority.key, false),
-    ];
+        if doge_mint.key.to_bytes() != bridge_state.doge_mint {
+            return Err(BridgeError::InvalidAccountInput.into());
+        }
+        let current_timestamp = current_unix_timestamp_secs()?;
+        bridge_state.core_state.ensure_not_paused(current_timestamp)?;
+        bridge_state
+            .core_state
+            .ensure_operation_enabled(BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_REQUESTS)?;
 
-    let instruction = Instruction {
-        program_id: *shim_program.key,
-        accounts,
-        data: ix_data,
+        let burner = SolanaBurner {
+            mint: doge_mint,
+            user_token_account,
+            authority: user_authority,
+            token_program,
+        };
+        let first_withdrawal_index = bridge_state.core_state.requested_withdrawals_tree.next_index;
+        let batch_requests: Vec<PsyWithdrawalRequest> = requests.iter().map(|r| r.request).collect();
+        let queued_requests = bridge_state.core_state.request_withdrawals_batch(
+            &burner,
+            &user_authority.key.to_bytes(),
+            &batch_requests,
+            current_timestamp,
+        )?;
+        (first_withdrawal_index, queued_requests)
     };
 
-    #[cfg(feature = "wormhole")]
-    invoke_signed(
-        &instruction,
-        &[
-            bridge_config.clone(),
-            message.clone(),
-            emitter.clone(),
-            sequence.clone(),
-            payer.clone(),
-            fee_collector.clone(),
-            clock.clone(),
-            system_program.clone(),
-            core_bridge_program.clone(),
-            event_authority.clone(),
-        ],
-        &[emitter_seeds],
-    )?;
+    let requester = user_authority.key.to_bytes();
+    for (i, (request, queued_request)) in requests.iter().zip(queued_requests).enumerate() {
+        let withdrawal_receipt_account = next_account_info(account_info_iter)?;
+        create_withdrawal_receipt(
+            program_id,
+            withdrawal_receipt_account,
+            payer,
+            system_program,
+            requester,
+            request.receipt_nonce,
+            first_withdrawal_index + i as u64,
+            queued_request,
+            request.request.amount_sats,
+        )?;
+    }
 
-    #[cfg(feature = "noopshim")]
-    invoke_signed(
-        &instruction,
-        &[
-            bridge_config.clone(),
-            message.clone(),
-            emitter.clone(),
-            sequence.clone(),
+    Ok(())
+}
+
+fn process_request_script_withdrawal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let doge_mint = next_account_info(account_info_iter)?;
+    let user_authority = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let withdrawal_receipt_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    // optional, holds the script when it is published ahead of the request
+    let script_buffer = next_account_info(account_info_iter).ok();
+
+    let fixed_size = std::mem::size_of::<RequestScriptWithdrawalFixedData>();
+    if data.len() < fixed_size {
+        return Err(BridgeError::SerializationError.into());
+    }
+    let params: &RequestScriptWithdrawalFixedData = from_bytes(&data[..fixed_size]);
+
+    let buffer_data = match script_buffer {
+        Some(buffer) => {
+            if buffer.owner != &GENERIC_BUFFER_BUILDER_PROGRAM_ID {
+                return Err(ProgramError::IllegalOwner);
+            }
+            if data.len() != fixed_size {
+                return Err(BridgeError::SerializationError.into());
+            }
+            Some(buffer.try_borrow_data()?)
+        }
+        None => None,
+    };
+    let script_pubkey = match &buffer_data {
+        // skip the buffer's authorized writer
+        Some(buffer_data) if buffer_data.len() >= 32 => &buffer_data[32..],
+        Some(_) => return Err(BridgeError::InvalidAccountInput.into()),
+        None => &data[fixed_size..],
+    };
+
+    let (withdrawal_index, queued_request) = {
+        let mut data = bridge_state_account.try_borrow_mut_data()?;
+        let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+            .map_err(|_| BridgeError::SerializationError)?;
+
+        if doge_mint.key.to_bytes() != bridge_state.doge_mint {
+            return Err(BridgeError::InvalidAccountInput.into());
+        }
+        let current_timestamp = current_unix_timestamp_secs()?;
+        bridge_state.core_state.ensure_not_paused(current_timestamp)?;
+        bridge_state
+            .core_state
+            .ensure_operation_enabled(BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_REQUESTS)?;
+
+        let burner = SolanaBurner {
+            mint: doge_mint,
+            user_token_account,
+            authority: user_authority,
+            token_program,
+        };
+        let withdrawal_index = bridge_state.core_state.requested_withdrawals_tree.next_index;
+        let queued_request = bridge_state.core_state.request_script_withdrawal(
+            &burner,
+            &user_authority.key.to_bytes(),
+            script_pubkey,
+            params.amount_sats,
+            current_timestamp,
+        )?;
+        (withdrawal_index, queued_request)
+    };
+
+    create_withdrawal_receipt(
+        program_id,
+        withdrawal_receipt_account,
+        payer,
+        system_program,
+        user_authority.key.to_bytes(),
+        params.receipt_nonce,
+        withdrawal_index,
+        queued_request,
+        params.amount_sats,
+    )?;
+
+    emit_bridge_event(
+        BRIDGE_EVENT_KIND_WITHDRAWAL_SCRIPT,
+        &WithdrawalScriptEventData::new(withdrawal_index, script_pubkey),
+    );
+
+    Ok(())
+}
+
+fn create_withdrawal_receipt<'a>(
+    program_id: &Pubkey,
+    withdrawal_receipt_account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    requester: [u8; 32],
+    receipt_nonce: u64,
+    withdrawal_index: u64,
+    queued_request: PsyWithdrawalRequest,
+    amount_burned_sats: u64,
+) -> ProgramResult {
+    // creating the account fails if the requester still has a receipt under this nonce
+    let (receipt_pda, receipt_bump) =
+        find_withdrawal_receipt_pda(program_id, &requester, receipt_nonce);
+    if receipt_pda != *withdrawal_receipt_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    let rent = Rent::get()?.minimum_balance(WITHDRAWAL_RECEIPT_SIZE);
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            withdrawal_receipt_account.key,
+            rent,
+            WITHDRAWAL_RECEIPT_SIZE as u64,
+            program_id,
+        ),
+        &[
+            payer.clone(),
+            withdrawal_receipt_account.clone(),
+            system_program.clone(),
+        ],
+        &[&[
+            WITHDRAWAL_RECEIPT_SEED,
+            &requester,
+            &receipt_nonce.to_le_bytes(),
+            &[receipt_bump],
+        ]],
+    )?;
+
+    let mut receipt_data = withdrawal_receipt_account.try_borrow_mut_data()?;
+    let receipt = bytemuck::try_from_bytes_mut::<WithdrawalReceipt>(&mut receipt_data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    *receipt = WithdrawalReceipt::new(
+        requester,
+        receipt_nonce,
+        queued_request,
+        withdrawal_index,
+        amount_burned_sats,
+        Clock::get()?.slot,
+    );
+
+    emit_bridge_event(
+        BRIDGE_EVENT_KIND_WITHDRAWAL_REQUESTED,
+        &WithdrawalRequestedEventData {
+            requester,
+            request: queued_request,
+            withdrawal_index,
+            amount_burned_sats,
+            receipt_nonce,
+        },
+    );
+
+    Ok(())
+}
+
+fn load_withdrawal_receipt<'a, 'b>(
+    program_id: &Pubkey,
+    withdrawal_receipt_account: &'a AccountInfo<'b>,
+) -> Result<RefMut<'a, &'b mut [u8]>, ProgramError> {
+    if withdrawal_receipt_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let data = withdrawal_receipt_account.try_borrow_mut_data()?;
+    let receipt = bytemuck::try_from_bytes::<WithdrawalReceipt>(&data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    let (receipt_pda, _bump) =
+        find_withdrawal_receipt_pda(program_id, &receipt.requester, receipt.receipt_nonce);
+    if receipt_pda != *withdrawal_receipt_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    Ok(data)
+}
+
+// permissionless, every account after the bridge state is a receipt to mark
+fn process_mark_withdrawal_receipts_fulfilled(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bridge_state_account = next_account_info(account_info_iter)?;
+
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    let next_processed_withdrawals_index = {
+        let data = bridge_state_account.try_borrow_data()?;
+        let bridge_state = bytemuck::try_from_bytes::<BridgeState>(&data)
+            .map_err(|_| BridgeError::SerializationError)?;
+        bridge_state.core_state.next_processed_withdrawals_index
+    };
+
+    let current_slot = Clock::get()?.slot;
+    for withdrawal_receipt_account in account_info_iter {
+        let mut data = load_withdrawal_receipt(program_id, withdrawal_receipt_account)?;
+        let receipt = bytemuck::try_from_bytes_mut::<WithdrawalReceipt>(&mut data)
+            .map_err(|_| BridgeError::SerializationError)?;
+        receipt.run_mark_fulfilled(next_processed_withdrawals_index, current_slot)?;
+    }
+
+    Ok(())
+}
+
+fn process_close_withdrawal_receipt(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let withdrawal_receipt_account = next_account_info(account_info_iter)?;
+    let requester = next_account_info(account_info_iter)?;
+
+    if !requester.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    {
+        let mut data = load_withdrawal_receipt(program_id, withdrawal_receipt_account)?;
+        let receipt = bytemuck::try_from_bytes::<WithdrawalReceipt>(&data)
+            .map_err(|_| BridgeError::SerializationError)?;
+        receipt.ensure_can_close(&requester.key.to_bytes())?;
+        data.fill(0);
+    }
+
+    // the runtime removes the emptied account at the end of the transaction
+    let receipt_lamports = withdrawal_receipt_account.lamports();
+    **withdrawal_receipt_account.try_borrow_mut_lamports()? = 0;
+    **requester.try_borrow_mut_lamports()? += receipt_lamports;
+
+    Ok(())
+}
+
+fn process_cancel_withdrawal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let withdrawal_receipt_account = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let doge_mint = next_account_info(account_info_iter)?;
+    let requester = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !requester.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (bridge_pda, bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let (refund_amount, withdrawal_index) = {
+        let mut data = bridge_state_account.try_borrow_mut_data()?;
+        let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+            .map_err(|_| BridgeError::SerializationError)?;
+
+        if doge_mint.key.to_bytes() != bridge_state.doge_mint {
+            return Err(BridgeError::InvalidAccountInput.into());
+        }
+        let now = current_unix_timestamp_secs()?;
+        bridge_state.core_state.ensure_not_paused(now)?;
+        bridge_state
+            .core_state
+            .ensure_operation_enabled(BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_REQUESTS)?;
+
+        let mut receipt_data = load_withdrawal_receipt(program_id, withdrawal_receipt_account)?;
+        let receipt = bytemuck::try_from_bytes_mut::<WithdrawalReceipt>(&mut receipt_data)
+            .map_err(|_| BridgeError::SerializationError)?;
+        let refund_amount = bridge_state.core_state.cancel_withdrawal(
+            receipt,
+            &requester.key.to_bytes(),
+            Clock::get()?.slot,
+            now,
+        )?;
+        (refund_amount, receipt.withdrawal_index)
+    };
+
+    emit_bridge_event(
+        BRIDGE_EVENT_KIND_WITHDRAWAL_CANCELLED,
+        &WithdrawalCancelledEventData {
+            requester: requester.key.to_bytes(),
+            withdrawal_index,
+            refund_amount_sats: refund_amount,
+        },
+    );
+
+    if refund_amount == 0 {
+        return Ok(());
+    }
+
+    let seeds = &[b"bridge_state", &[bump][..]];
+    let recipient_map = &[user_token_account.clone()];
+    let minter = SolanaMinter {
+        mint: doge_mint,
+        authority_info: bridge_state_account,
+        authority_seeds: seeds,
+        recipient_map,
+        token_program,
+    };
+    minter.mint_to(0, &user_token_account.key.to_bytes(), refund_amount)?;
+
+    Ok(())
+}
+
+// Wormhole VAA Discriminator: sha256("global:post_message")[:8]
+const WORMHOLE_VAA_DISCRIMINATOR: [u8; 8] = [214, 50, 100, 209, 38, 34, 7, 76];
+/// Sends a VAA via the Wormhole Shim program using a CPI call.
+/// This function manually constructs the Anchor instruction and invokes it.
+fn send_wormhole_vaa<'a>(
+    withdrawal_nonce: u32,
+    shim_program: &AccountInfo<'a>,
+    bridge_config: &AccountInfo<'a>,
+    message: &AccountInfo<'a>,
+    emitter: &AccountInfo<'a>,
+    sequence: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    fee_collector: &AccountInfo<'a>,
+    clock: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    core_bridge_program: &AccountInfo<'a>,
+    event_authority: &AccountInfo<'a>,
+    emitter_seeds: &[&[u8]],
+    sighash: &[u8],
+    transaction_buffer: &[u8],
+) -> ProgramResult {
+    let payload_len = sighash.len() + transaction_buffer.len();
+    let consistency_level: u8 = 1; // 1 = Finalized
+
+    let mut ix_data = Vec::with_capacity(8 + 4 + 1 + 4 + payload_len);
+    ix_data.extend_from_slice(&WORMHOLE_VAA_DISCRIMINATOR);
+    ix_data.extend_from_slice(&withdrawal_nonce.to_le_bytes());
+    ix_data.push(consistency_level);
+    ix_data.extend_from_slice(&(payload_len as u32).to_le_bytes());
+    ix_data.extend_from_slice(sighash);
+    ix_data.extend_from_slice(transaction_buffer);
+
+    let accounts = vec![
+        AccountMeta::new(*bridge_config.key, false),
+        AccountMeta::new(*message.key, false),
+        AccountMeta::new_readonly(*emitter.key, true),
+        AccountMeta::new(*sequence.key, false),
+        AccountMeta::new(*payer.key, true),
+        AccountMeta::new(*fee_collector.key, false),
+        AccountMeta::new_readonly(*clock.key, false),
+        AccountMeta::new_readonly(*system_program.key, false),
+        AccountMeta::new_readonly(*core_bridge_program.key, false),
+        AccountMeta::new_readonly(*event_authority.key, false),
+    ];
+
+    let instruction = Instruction {
+        program_id: *shim_program.key,
+        accounts,
+        data: ix_data,
+    };
+
+    #[cfg(feature = "wormhole")]
+    invoke_signed(
+        &instruction,
+        &[
+            bridge_config.clone(),
+            message.clone(),
+            emitter.clone(),
+            sequence.clone(),
+            payer.clone(),
+            fee_collector.clone(),
+            clock.clone(),
+            system_program.clone(),
+            core_bridge_program.clone(),
+            event_authority.clone(),
+        ],
+        &[emitter_seeds],
+    )?;
+
+    #[cfg(feature = "noopshim")]
+    invoke_signed(
+        &instruction,
+        &[
+            bridge_config.clone(),
+            message.clone(),
+            emitter.clone(),
+            sequence.clone(),
             payer.clone(),
             fee_collector.clone(),
             clock.clone(),
@@ -857,6 +1675,8 @@ fn process_process_withdrawal(
     let system_program = next_account_info(account_info_iter)?;
     let core_bridge_program = next_account_info(account_info_iter)?;
     let event_authority = next_account_info(account_info_iter)?;
+    // only required once the verifier key registry is created
+    let vk_registry_account = next_account_info(account_info_iter).ok();
 
     // Verify Bridge State PDA
     let (bridge_pda, bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
@@ -879,6 +1699,13 @@ fn process_process_withdrawal(
     let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
         .map_err(|_| BridgeError::SerializationError)?;
 
+    bridge_state
+        .core_state
+        .ensure_not_paused(current_unix_timestamp_secs()?)?;
+    bridge_state
+        .core_state
+        .ensure_operation_enabled(BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_PROCESSING)?;
+
     // make sure to check that all the accounts are correct and owned by the right programs
 
     let dogecoin_tx = doge_tx_buffer.try_borrow_data()?;
@@ -887,16 +1714,25 @@ fn process_process_withdrawal(
     }
     let tx_data = &dogecoin_tx[32..];
 
-    let sighash = bridge_state
-        .core_state
-        .run_process_bridge_withdrawal::<ZKVerifier>(
-            proof,
-            &WITHDRAWAL_VK,
-            tx_data,
-            new_return_output,
-            new_spent_txo_tree_root,
-            new_next_processed_withdrawals_index,
-        )?;
+    let accepted_vks = load_accepted_vks(
+        program_id,
+        bridge_state,
+        vk_registry_account,
+        VK_KIND_WITHDRAWAL,
+        &WITHDRAWAL_VK,
+    )?;
+    let sighash = accepted_vks.verify_with(|vk| {
+        bridge_state
+            .core_state
+            .run_process_bridge_withdrawal::<ZKVerifier>(
+                proof,
+                vk,
+                tx_data,
+                new_return_output,
+                new_spent_txo_tree_root,
+                new_next_processed_withdrawals_index,
+            )
+    })?;
 
     let nonce = (bridge_state.core_state.next_processed_withdrawals_index & 0xFFFFFFFF) as u32;
     
@@ -919,6 +1755,16 @@ fn process_process_withdrawal(
         &tx_data,
     )?;
 
+    emit_bridge_event(
+        BRIDGE_EVENT_KIND_WITHDRAWAL_PROCESSED,
+        &WithdrawalProcessedEventData {
+            sighash,
+            new_return_output,
+            new_spent_txo_tree_root,
+            new_next_processed_withdrawals_index,
+        },
+    );
+
     Ok(())
 }
 
@@ -947,7 +1793,7 @@ fn process_operator_withdraw_fees(program_id: &Pubkey, accounts: &[AccountInfo])
         token_program,
     };
 
-    let fees_to_withdraw = {
+    let (fees_to_withdraw, total_fees_withdrawn_sats) = {
         let mut data = bridge_state_account.try_borrow_mut_data()?;
         let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
             .map_err(|_| BridgeError::SerializationError)?;
@@ -959,13 +1805,27 @@ fn process_operator_withdraw_fees(program_id: &Pubkey, accounts: &[AccountInfo])
         if operator.key.to_bytes() != bridge_state.core_state.access_control.operator_pubkey {
             return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
         }
+        bridge_state.core_state.ensure_no_fee_split()?;
 
         bridge_state
             .core_state
-            .run_bridge_operator_withdraw_fees_precheck()?
+            .ensure_operation_enabled(BRIDGE_CONTROL_MODE_DISABLE_FEE_WITHDRAWALS)?;
+
+        let fees_to_withdraw = bridge_state
+            .core_state
+            .run_bridge_operator_withdraw_fees_precheck()?;
+        (fees_to_withdraw, bridge_state.core_state.total_fees_withdrawn_sats)
     };
 
     minter.mint_to(0, &operator_token_account.key.to_bytes(), fees_to_withdraw)?;
+    emit_bridge_event(
+        BRIDGE_EVENT_KIND_FEES_WITHDRAWN,
+        &FeesWithdrawnEventData {
+            token_account: operator_token_account.key.to_bytes(),
+            amount_sats: fees_to_withdraw,
+            total_fees_withdrawn_sats,
+        },
+    );
 
     Ok(())
 }
@@ -986,6 +1846,7 @@ fn process_process_manual_deposit(
     let doge_mint = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
     let manual_claim_program_signer = next_account_info(account_info_iter)?;
+    let finalized_block_history_account = next_account_info(account_info_iter).ok();
 
     if !manual_claim_program_signer.is_signer {
         return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
@@ -997,11 +1858,27 @@ fn process_process_manual_deposit(
         &Pubkey::new_from_array(depositor_solana_public_key),
     )?;
 
+    // a history account that was never created is searched as empty
+    let history_data = match finalized_block_history_account {
+        Some(account) if account.data_len() != 0 => {
+            verify_finalized_block_history_account(program_id, account)?;
+            Some(account.try_borrow_data()?)
+        }
+        _ => None,
+    };
+
     let mint_amount = {
         let mut data = bridge_state_account.try_borrow_mut_data()?;
         let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
             .map_err(|_| BridgeError::SerializationError)?;
 
+        bridge_state
+            .core_state
+            .ensure_not_paused(current_unix_timestamp_secs()?)?;
+        bridge_state
+            .core_state
+            .ensure_operation_enabled(BRIDGE_CONTROL_MODE_DISABLE_MANUAL_CLAIMS)?;
+
         bridge_state.core_state.process_manual_claimed_deposit(
             tx_hash,
             recent_block_merkle_tree_root,
@@ -1009,6 +1886,7 @@ fn process_process_manual_deposit(
             combined_txo_index,
             &recipient_account.key.to_bytes(),
             deposit_amount_sats,
+            history_data.as_ref().map_or(&[][..], |data| &data[..]),
         )?
     };
 
@@ -1027,6 +1905,18 @@ fn process_process_manual_deposit(
 
     minter.mint_to(0, &recipient_account.key.to_bytes(), mint_amount)?;
 
+    emit_bridge_event(
+        BRIDGE_EVENT_KIND_MANUAL_DEPOSIT_CLAIMED,
+        &ManualDepositClaimedEventData {
+            tx_hash,
+            depositor: depositor_solana_public_key,
+            recipient_token_account: recipient_account.key.to_bytes(),
+            combined_txo_index,
+            deposit_amount_sats,
+            minted_amount_sats: mint_amount,
+        },
+    );
+
     Ok(())
 }
 
@@ -1083,7 +1973,7 @@ fn process_replay_withdrawal(
     let tx_data = &proof_and_dogecoin_tx[32 + std::mem::size_of::<FixedMerkleAppendTreePartialMerkleProof>()..];
 
     let sighash = btc_hash256_bytes(&tx_data);
-    let mut current_timestamp = (Clock::get()?.unix_timestamp & 0xFFFFFFFFi64) as u32;
+    let mut current_timestamp = current_unix_timestamp_secs()?;
 
     if bridge_state.core_state.last_processed_withdrawals_at_ms == current_timestamp as u64 {
         current_timestamp = current_timestamp.wrapping_add(1);
@@ -1154,7 +2044,7 @@ fn process_snapshot_withdrawals(
     let mut data = bridge_state_account.try_borrow_mut_data()?;
     let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
         .map_err(|_| BridgeError::SerializationError)?;
-    let current_timestamp = (Clock::get()?.unix_timestamp & 0xFFFFFFFFi64) as u32;
+    let current_timestamp = current_unix_timestamp_secs()?;
     if bridge_state.core_state.access_control.operator_pubkey != operator.key.to_bytes() {
         return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
     }
@@ -1162,4 +2052,990 @@ fn process_snapshot_withdrawals(
         current_timestamp,
     );
     Ok(())
-}
\ No newline at end of file
+}
+
+fn process_guardian_set_pause(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    pause_duration_secs: u32,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let guardian = next_account_info(account_info_iter)?;
+
+    if !guardian.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    bridge_state.core_state.run_guardian_set_pause(
+        &guardian.key.to_bytes(),
+        current_unix_timestamp_secs()?,
+        pause_duration_secs,
+    )?;
+    Ok(())
+}
+
+fn process_set_control_mode(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    bridge_control_mode: u32,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let guardian = next_account_info(account_info_iter)?;
+
+    if !guardian.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    bridge_state
+        .core_state
+        .run_guardian_set_control_mode(&guardian.key.to_bytes(), bridge_control_mode)?;
+    Ok(())
+}
+
+fn process_propose_config_update(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_config_params: &PsyBridgeConfig,
+    delay_secs: u32,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    bridge_state.core_state.run_propose_config_update(
+        &authority.key.to_bytes(),
+        new_config_params,
+        delay_secs,
+        current_unix_timestamp_secs()?,
+    )?;
+    Ok(())
+}
+
+fn process_cancel_config_update(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    // settings authority or guardian
+    let signer = next_account_info(account_info_iter)?;
+
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    bridge_state
+        .core_state
+        .run_cancel_config_update(&signer.key.to_bytes())?;
+    Ok(())
+}
+
+fn process_set_policy_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: &SetPolicyConfigInstructionData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    bridge_state
+        .core_state
+        .run_set_policy_config(&authority.key.to_bytes(), &params.policy_config)?;
+    Ok(())
+}
+
+fn process_apply_config_update(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // permissionless, the config was authorized when it was proposed
+    let bridge_state_account = next_account_info(account_info_iter)?;
+
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    bridge_state
+        .core_state
+        .run_apply_config_update(current_unix_timestamp_secs()?)?;
+    Ok(())
+}
+
+fn process_rotate_key(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    discriminator: u8,
+    key_role: u32,
+    new_pubkey: &[u8; 32],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    // current key holder, nominee or admin depending on the instruction
+    let signer = next_account_info(account_info_iter)?;
+
+    if !signer.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    let signer_pubkey = signer.key.to_bytes();
+    match discriminator {
+        DOGE_BRIDGE_INSTRUCTION_NOMINATE_KEY => bridge_state
+            .core_state
+            .run_nominate_key(&signer_pubkey, key_role, new_pubkey)?,
+        DOGE_BRIDGE_INSTRUCTION_ACCEPT_KEY => bridge_state
+            .core_state
+            .run_accept_key(&signer_pubkey, key_role)?,
+        _ => bridge_state
+            .core_state
+            .run_admin_force_rotate_key(&signer_pubkey, key_role, new_pubkey)?,
+    }
+    Ok(())
+}
+
+fn process_set_withdrawal_limits(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: &SetWithdrawalLimitsInstructionData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    bridge_state.core_state.run_set_withdrawal_limits(
+        &authority.key.to_bytes(),
+        params.limit_per_request_sats,
+        params.limit_per_window_sats,
+        params.limit_window_secs,
+        params.reset_window_usage != 0,
+        current_unix_timestamp_secs()?,
+    )?;
+    Ok(())
+}
+
+fn process_set_fee_split(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_split: &FeeSplitTable,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let fee_split_account = next_account_info(account_info_iter)?;
+    let fee_spender = next_account_info(account_info_iter)?;
+
+    if !fee_spender.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    let (fee_split_pda, fee_split_bump) = Pubkey::find_program_address(&[FEE_SPLIT_SEED], program_id);
+    if fee_split_pda != *fee_split_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    {
+        let mut data = bridge_state_account.try_borrow_mut_data()?;
+        let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+            .map_err(|_| BridgeError::SerializationError)?;
+        bridge_state.core_state.run_set_fee_split(
+            &fee_spender.key.to_bytes(),
+            fee_split,
+            current_unix_timestamp_secs()?,
+        )?;
+    }
+
+    // the table is created by the first update
+    if fee_split_account.data_len() == 0 {
+        let payer = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent = Rent::get()?.minimum_balance(FEE_SPLIT_TABLE_SIZE);
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                fee_split_account.key,
+                rent,
+                FEE_SPLIT_TABLE_SIZE as u64,
+                program_id,
+            ),
+            &[
+                payer.clone(),
+                fee_split_account.clone(),
+                system_program.clone(),
+            ],
+            &[&[FEE_SPLIT_SEED, &[fee_split_bump]]],
+        )?;
+    } else if fee_split_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut data = fee_split_account.try_borrow_mut_data()?;
+    let stored_fee_split = bytemuck::try_from_bytes_mut::<FeeSplitTable>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    *stored_fee_split = *fee_split;
+    Ok(())
+}
+
+// permissionless, accounts after the token program are the fee spender's token account
+// followed by the recipients' token accounts in fee split order
+fn process_distribute_fees(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let fee_split_account = next_account_info(account_info_iter)?;
+    let doge_mint = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let recipient_map = &accounts[4..];
+
+    let (bridge_pda, bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    let (fee_split_pda, _bump) = Pubkey::find_program_address(&[FEE_SPLIT_SEED], program_id);
+    if fee_split_pda != *fee_split_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    if fee_split_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let fee_split = {
+        let data = fee_split_account.try_borrow_data()?;
+        *bytemuck::try_from_bytes::<FeeSplitTable>(&data)
+            .map_err(|_| BridgeError::SerializationError)?
+    };
+
+    let (amounts, total_fees_withdrawn_sats) = {
+        let mut data = bridge_state_account.try_borrow_mut_data()?;
+        let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+            .map_err(|_| BridgeError::SerializationError)?;
+
+        if doge_mint.key.to_bytes() != bridge_state.doge_mint {
+            return Err(BridgeError::InvalidAccountInput.into());
+        }
+        bridge_state
+            .core_state
+            .ensure_operation_enabled(BRIDGE_CONTROL_MODE_DISABLE_FEE_WITHDRAWALS)?;
+
+        let amounts = bridge_state.core_state.run_distribute_fees_precheck(&fee_split)?;
+        (amounts, bridge_state.core_state.total_fees_withdrawn_sats)
+    };
+
+    let seeds = &[b"bridge_state", &[bump][..]];
+    let minter = SolanaMinter {
+        mint: doge_mint,
+        authority_info: bridge_state_account,
+        authority_seeds: seeds,
+        recipient_map,
+        token_program,
+    };
+
+    let payouts = core::iter::once((&fee_split.fee_spender_token_account, amounts.fee_spender_amount))
+        .chain(
+            fee_split
+                .get_recipients()
+                .iter()
+                .zip(amounts.recipient_amounts)
+                .map(|(recipient, amount)| (&recipient.token_account, amount)),
+        );
+    for (i, (token_account, amount)) in payouts.enumerate() {
+        if amount == 0 {
+            continue;
+        }
+        minter.mint_to(i, token_account, amount)?;
+        emit_bridge_event(
+            BRIDGE_EVENT_KIND_FEES_WITHDRAWN,
+            &FeesWithdrawnEventData {
+                token_account: *token_account,
+                amount_sats: amount,
+                total_fees_withdrawn_sats,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+fn process_vk_update(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    discriminator: u8,
+    vk_kind: u32,
+    new_vk: &CompactBridgeZKVerifierKey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let vk_registry_account = next_account_info(account_info_iter)?;
+    let bridge_state_account = next_account_info(account_info_iter)?;
+
+    let (vk_registry_pda, vk_registry_bump) =
+        Pubkey::find_program_address(&[VK_REGISTRY_SEED], program_id);
+    if vk_registry_pda != *vk_registry_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    // activation is permissionless, the key was authorized when it was proposed
+    if discriminator != DOGE_BRIDGE_INSTRUCTION_ACTIVATE_VK_UPDATE {
+        let authority = next_account_info(account_info_iter)?;
+        if !authority.is_signer {
+            return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+        }
+        let data = bridge_state_account.try_borrow_data()?;
+        let bridge_state = bytemuck::try_from_bytes::<BridgeState>(&data)
+            .map_err(|_| BridgeError::SerializationError)?;
+        bridge_state
+            .core_state
+            .access_control
+            .ensure_vk_update_authority(&authority.key.to_bytes())?;
+    }
+
+    // the registry is created by the first proposal, until then the built-in keys are used
+    if vk_registry_account.data_len() == 0 {
+        if discriminator != DOGE_BRIDGE_INSTRUCTION_PROPOSE_VK_UPDATE {
+            return Err(DogeBridgeError::NoPendingVerifierKeyUpdate.into());
+        }
+        let payer = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let rent = Rent::get()?.minimum_balance(VK_REGISTRY_SIZE);
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                vk_registry_account.key,
+                rent,
+                VK_REGISTRY_SIZE as u64,
+                program_id,
+            ),
+            &[
+                payer.clone(),
+                vk_registry_account.clone(),
+                system_program.clone(),
+            ],
+            &[&[VK_REGISTRY_SEED, &[vk_registry_bump]]],
+        )?;
+        let mut data = bridge_state_account.try_borrow_mut_data()?;
+        let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+            .map_err(|_| BridgeError::SerializationError)?;
+        bridge_state.vk_registry_created_at_secs = current_unix_timestamp_secs()? as u64;
+    } else if vk_registry_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut data = vk_registry_account.try_borrow_mut_data()?;
+    let vk_registry = bytemuck::try_from_bytes_mut::<VerifierKeyRegistry>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    let now = current_unix_timestamp_secs()?;
+    match discriminator {
+        DOGE_BRIDGE_INSTRUCTION_PROPOSE_VK_UPDATE => {
+            vk_registry.run_propose_vk_update(vk_kind, new_vk, now)?
+        }
+        DOGE_BRIDGE_INSTRUCTION_ACTIVATE_VK_UPDATE => {
+            vk_registry.run_activate_vk_update(vk_kind, now)?
+        }
+        _ => vk_registry.run_cancel_vk_update(vk_kind)?,
+    }
+    Ok(())
+}
+
+fn verify_finalized_block_history_account(program_id: &Pubkey, account: &AccountInfo) -> ProgramResult {
+    let (history_pda, _bump) =
+        Pubkey::find_program_address(&[FINALIZED_BLOCK_HISTORY_SEED], program_id);
+    if history_pda != *account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    if account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    Ok(())
+}
+
+// accounts can only grow by MAX_PERMITTED_DATA_INCREASE per instruction, so the client repeats this
+// until the account reaches its full size, which is when the history is seeded and enabled
+fn process_init_finalized_block_history(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    capacity: u32,
+    step: u32,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let finalized_block_history_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer || !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    let (history_pda, history_bump) =
+        Pubkey::find_program_address(&[FINALIZED_BLOCK_HISTORY_SEED], program_id);
+    if history_pda != *finalized_block_history_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    {
+        let data = bridge_state_account.try_borrow_data()?;
+        let bridge_state = bytemuck::try_from_bytes::<BridgeState>(&data)
+            .map_err(|_| BridgeError::SerializationError)?;
+        bridge_state
+            .core_state
+            .ensure_can_enable_finalized_block_history(&authority.key.to_bytes(), capacity)?;
+    }
+
+    let target_size = finalized_block_history_account_size(capacity);
+    let current_size = finalized_block_history_account.data_len();
+    if current_size.div_ceil(MAX_PERMITTED_DATA_INCREASE) != step as usize {
+        return Err(DogeBridgeError::InvalidFinalizedBlockHistoryAccount.into());
+    }
+    if current_size == 0 {
+        let size = target_size.min(MAX_PERMITTED_DATA_INCREASE);
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                finalized_block_history_account.key,
+                Rent::get()?.minimum_balance(size),
+                size as u64,
+                program_id,
+            ),
+            &[
+                payer.clone(),
+                finalized_block_history_account.clone(),
+                system_program.clone(),
+            ],
+            &[&[FINALIZED_BLOCK_HISTORY_SEED, &[history_bump]]],
+        )?;
+    } else {
+        if finalized_block_history_account.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if current_size > target_size {
+            return Err(DogeBridgeError::InvalidFinalizedBlockHistoryAccount.into());
+        }
+        if current_size < target_size {
+            let new_size = current_size + (target_size - current_size).min(MAX_PERMITTED_DATA_INCREASE);
+            let rent = Rent::get()?;
+            let lamports_diff = rent
+                .minimum_balance(new_size)
+                .saturating_sub(finalized_block_history_account.lamports());
+            if lamports_diff > 0 {
+                invoke_signed(
+                    &system_instruction::transfer(
+                        payer.key,
+                        finalized_block_history_account.key,
+                        lamports_diff,
+                    ),
+                    &[
+                        payer.clone(),
+                        finalized_block_history_account.clone(),
+                        system_program.clone(),
+                    ],
+                    &[],
+                )?;
+            }
+            finalized_block_history_account.realloc(new_size, true)?;
+        }
+    }
+
+    if finalized_block_history_account.data_len() == target_size {
+        let mut data = bridge_state_account.try_borrow_mut_data()?;
+        let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+            .map_err(|_| BridgeError::SerializationError)?;
+        let mut history_data = finalized_block_history_account.try_borrow_mut_data()?;
+        bridge_state.core_state.run_enable_finalized_block_history(
+            &authority.key.to_bytes(),
+            capacity,
+            &mut history_data,
+        )?;
+    }
+    Ok(())
+}
+
+// read-only, meant to be called over CPI, the TxInclusionResult is set as the return data
+fn process_verify_tx_inclusion(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    // optional, holds the siblings and the transaction when they don't fit in the instruction data
+    let proof_buffer = next_account_info(account_info_iter).ok();
+
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let fixed_size = std::mem::size_of::<VerifyTxInclusionFixedData>();
+    if data.len() < fixed_size {
+        return Err(BridgeError::SerializationError.into());
+    }
+    let params: &VerifyTxInclusionFixedData = from_bytes(&data[..fixed_size]);
+
+    let buffer_data = match proof_buffer {
+        Some(buffer) => {
+            if buffer.owner != &GENERIC_BUFFER_BUILDER_PROGRAM_ID {
+                return Err(ProgramError::IllegalOwner);
+            }
+            if data.len() != fixed_size {
+                return Err(BridgeError::SerializationError.into());
+            }
+            Some(buffer.try_borrow_data()?)
+        }
+        None => None,
+    };
+    let proof_data = match &buffer_data {
+        // skip the buffer's authorized writer
+        Some(buffer_data) if buffer_data.len() >= 32 => &buffer_data[32..],
+        Some(_) => return Err(BridgeError::InvalidAccountInput.into()),
+        None => &data[fixed_size..],
+    };
+    let tx_siblings_count = params.tx_siblings_count as usize;
+    let siblings_size = (tx_siblings_count + DOGE_BLOCK_MERKLE_TREE_HEIGHT) * 32;
+    if proof_data.len() != siblings_size + params.tx_size as usize {
+        return Err(BridgeError::SerializationError.into());
+    }
+    let (siblings_data, tx_bytes) = proof_data.split_at(siblings_size);
+    let siblings = bytemuck::try_cast_slice::<u8, QHash256>(siblings_data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    let (tx_siblings, block_siblings) = siblings.split_at(tx_siblings_count);
+
+    let state_data = bridge_state_account.try_borrow_data()?;
+    let bridge_state = bytemuck::try_from_bytes::<BridgeState>(&state_data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    let result = bridge_state
+        .core_state
+        .verify_tx_inclusion(params, tx_bytes, tx_siblings, block_siblings)?;
+    set_return_data(bytemuck::bytes_of(&result));
+    Ok(())
+}
+
+// makes room for one more entry in the archive segment, creating it for the first entry. The payer covers the rent.
+fn grow_block_hash_archive_segment<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    segment: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    segment_index: u32,
+    index_in_segment: u32,
+    new_entries_count: u32,
+) -> ProgramResult {
+    let segment_index_bytes = segment_index.to_le_bytes();
+    let (segment_pda, segment_bump) = Pubkey::find_program_address(
+        &[BLOCK_HASH_ARCHIVE_SEED, &segment_index_bytes],
+        program_id,
+    );
+    if segment_pda != *segment.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let new_size = block_hash_archive_segment_size(index_in_segment + new_entries_count);
+    let rent = Rent::get()?;
+    if index_in_segment == 0 {
+        if segment.data_len() != 0 {
+            return Err(DogeBridgeError::InvalidBlockHashArchiveAccount.into());
+        }
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                segment.key,
+                rent.minimum_balance(new_size),
+                new_size as u64,
+                program_id,
+            ),
+            &[payer.clone(), segment.clone(), system_program.clone()],
+            &[&[BLOCK_HASH_ARCHIVE_SEED, &segment_index_bytes, &[segment_bump]]],
+        )?;
+        return Ok(());
+    }
+
+    if segment.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if segment.data_len() != block_hash_archive_segment_size(index_in_segment) {
+        return Err(DogeBridgeError::InvalidBlockHashArchiveAccount.into());
+    }
+    let lamports_diff = rent
+        .minimum_balance(new_size)
+        .saturating_sub(segment.lamports());
+    if lamports_diff > 0 {
+        invoke_signed(
+            &system_instruction::transfer(payer.key, segment.key, lamports_diff),
+            &[payer.clone(), segment.clone(), system_program.clone()],
+            &[],
+        )?;
+    }
+    segment.realloc(new_size, true)?;
+    Ok(())
+}
+
+// creates the first archive segment and records the current finalized block in it
+fn process_init_block_hash_archive(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let block_hash_archive_segment = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    bridge_state
+        .core_state
+        .ensure_can_enable_block_hash_archive(&authority.key.to_bytes())?;
+
+    grow_block_hash_archive_segment(
+        program_id,
+        payer,
+        block_hash_archive_segment,
+        system_program,
+        0,
+        0,
+        1,
+    )?;
+    let mut segment_data = block_hash_archive_segment.try_borrow_mut_data()?;
+    bridge_state
+        .core_state
+        .run_enable_block_hash_archive(&authority.key.to_bytes(), &mut segment_data)?;
+    Ok(())
+}
+
+// moves the bridge state account forward one layout version, growing it first if the new layout is larger.
+// Permissionless: nothing in the new layout comes from the caller, the fields v0 lacks (guardian, admin) are
+// derived from the stored operator, and each version can only be migrated once. The payer only covers the
+// extra rent, and since a v0 account is rejected by every other instruction, letting anyone migrate it can't
+// do more than bring the bridge back up sooner.
+fn process_migrate_state(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    if bridge_state_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let from_version = get_bridge_state_version(&bridge_state_account.try_borrow_data()?)
+        .ok_or(DogeBridgeError::UnsupportedBridgeStateVersion)?;
+    if from_version >= BRIDGE_STATE_CURRENT_VERSION {
+        return Err(DogeBridgeError::BridgeStateVersionUpToDate.into());
+    }
+    let new_size = bridge_state_account_size(from_version + 1)
+        .ok_or(DogeBridgeError::UnsupportedBridgeStateVersion)?;
+
+    if new_size > bridge_state_account.data_len() {
+        let lamports_diff = Rent::get()?
+            .minimum_balance(new_size)
+            .saturating_sub(bridge_state_account.lamports());
+        if lamports_diff > 0 {
+            invoke_signed(
+                &system_instruction::transfer(payer.key, bridge_state_account.key, lamports_diff),
+                &[payer.clone(), bridge_state_account.clone(), system_program.clone()],
+                &[],
+            )?;
+        }
+        bridge_state_account.realloc(new_size, true)?;
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    migrate_bridge_state_account_data(&mut data, from_version)?;
+    Ok(())
+}
+
+// permissionless, anyone can publish the preimage of the current custodian_wallet_config_hash
+fn process_store_custodian_wallet_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: &StoreCustodianWalletConfigInstructionData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let custodian_config_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    let (custodian_config_pda, custodian_config_bump) =
+        Pubkey::find_program_address(&[CUSTODIAN_WALLET_CONFIG_SEED], program_id);
+    if custodian_config_pda != *custodian_config_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    {
+        let data = bridge_state_account.try_borrow_data()?;
+        let bridge_state = bytemuck::try_from_bytes::<BridgeState>(&data)
+            .map_err(|_| BridgeError::SerializationError)?;
+        bridge_state.core_state.check_custodian_wallet_config(&params.config)?;
+    }
+
+    if custodian_config_account.data_len() == 0 {
+        let rent = Rent::get()?.minimum_balance(CUSTODIAN_WALLET_CONFIG_ACCOUNT_SIZE);
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                custodian_config_account.key,
+                rent,
+                CUSTODIAN_WALLET_CONFIG_ACCOUNT_SIZE as u64,
+                program_id,
+            ),
+            &[
+                payer.clone(),
+                custodian_config_account.clone(),
+                system_program.clone(),
+            ],
+            &[&[CUSTODIAN_WALLET_CONFIG_SEED, &[custodian_config_bump]]],
+        )?;
+    } else if custodian_config_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut data = custodian_config_account.try_borrow_mut_data()?;
+    let stored_config = bytemuck::try_from_bytes_mut::<Bridge7MultisigCustodianWalletConfig>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    *stored_config = params.config;
+    Ok(())
+}
+
+fn process_custodian_rotation_update(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    discriminator: u8,
+    new_custodian_wallet_config_hash: &QHash256,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    // settings authority, or the guardian when cancelling
+    let signer = next_account_info(account_info_iter)?;
+
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (bridge_pda, _bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_pda != *bridge_state_account.key {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+    let signer_pubkey = signer.key.to_bytes();
+    if discriminator == DOGE_BRIDGE_INSTRUCTION_SCHEDULE_CUSTODIAN_ROTATION {
+        bridge_state.run_schedule_custodian_rotation(
+            &signer_pubkey,
+            new_custodian_wallet_config_hash,
+            current_unix_timestamp_secs()?,
+        )?;
+    } else {
+        bridge_state.run_cancel_custodian_rotation(&signer_pubkey)?;
+    }
+    Ok(())
+}
+
+// uses the same accounts as process_withdrawal, the sweep tx is published over wormhole like a withdrawal tx
+fn process_custodian_sweep(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    proof: &CompactBridgeZKProof,
+    new_return_output: PsyReturnTxOutput,
+    new_spent_txo_tree_root: QHash256,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_state_account = next_account_info(account_info_iter)?;
+    let doge_tx_buffer = next_account_info(account_info_iter)?;
+
+    let shim_program_id = next_account_info(account_info_iter)?;
+    let bridge_config = next_account_info(account_info_iter)?;
+    let message = next_account_info(account_info_iter)?;
+    let sequence = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let fee_collector = next_account_info(account_info_iter)?;
+    let clock = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let core_bridge_program = next_account_info(account_info_iter)?;
+    let event_authority = next_account_info(account_info_iter)?;
+    // only required once the verifier key registry is created
+    let vk_registry_account = next_account_info(account_info_iter).ok();
+
+    let (bridge_pda, bump) = Pubkey::find_program_address(&[b"bridge_state"], program_id);
+    if bridge_state_account.key != &bridge_pda {
+        return Err(BridgeError::InvalidPDA.into());
+    }
+    let seeds = &[b"bridge_state", &[bump][..]];
+
+    if doge_tx_buffer.owner != &GENERIC_BUFFER_BUILDER_PROGRAM_ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut data = bridge_state_account.try_borrow_mut_data()?;
+    let bridge_state = bytemuck::try_from_bytes_mut::<BridgeState>(&mut data)
+        .map_err(|_| BridgeError::SerializationError)?;
+
+    let now = current_unix_timestamp_secs()?;
+    bridge_state.core_state.ensure_not_paused(now)?;
+    bridge_state
+        .core_state
+        .ensure_operation_enabled(BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_PROCESSING)?;
+
+    let dogecoin_tx = doge_tx_buffer.try_borrow_data()?;
+    if dogecoin_tx.len() < 32 {
+        return Err(BridgeError::InvalidAccountInput.into());
+    }
+    let tx_data = &dogecoin_tx[32..];
+
+    let old_custodian_wallet_config_hash = bridge_state.core_state.custodian_wallet_config_hash;
+    let accepted_vks = load_accepted_vks(
+        program_id,
+        bridge_state,
+        vk_registry_account,
+        VK_KIND_WITHDRAWAL,
+        &WITHDRAWAL_VK,
+    )?;
+    let sighash = accepted_vks.verify_with(|vk| {
+        bridge_state.run_process_custodian_sweep::<ZKVerifier>(
+            proof,
+            vk,
+            tx_data,
+            new_return_output,
+            new_spent_txo_tree_root,
+            now,
+        )
+    })?;
+    let new_custodian_wallet_config_hash = bridge_state.core_state.custodian_wallet_config_hash;
+    let nonce = (bridge_state.core_state.next_processed_withdrawals_index & 0xFFFFFFFF) as u32;
+
+    drop(data);
+    send_wormhole_vaa(
+        nonce,
+        shim_program_id,
+        bridge_config,
+        message,
+        bridge_state_account,
+        sequence,
+        payer,
+        fee_collector,
+        clock,
+        system_program,
+        core_bridge_program,
+        event_authority,
+        seeds,
+        &sighash,
+        tx_data,
+    )?;
+
+    emit_bridge_event(
+        BRIDGE_EVENT_KIND_CUSTODIAN_ROTATED,
+        &CustodianRotatedEventData {
+            old_custodian_wallet_config_hash,
+            new_custodian_wallet_config_hash,
+            sighash,
+            new_return_output,
+            new_spent_txo_tree_root,
+        },
+    );
+
+    Ok(())
+}
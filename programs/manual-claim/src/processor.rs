@@ -4,11 +4,13 @@ use bytemuck::{Pod, Zeroable};
 use psy_bridge_core::common_types::QHash256;
 use psy_bridge_core::crypto::zk::{CompactBridgeZKProof, CompactBridgeZKVerifierKey};
 use psy_bridge_core::error::{DogeBridgeError, QDogeResult};
+use psy_doge_solana_core::constants::VK_KIND_MANUAL_CLAIM;
+use psy_doge_solana_core::data_accounts::vk_registry::{AcceptedVerifierKeys, VerifierKeyRegistry, VK_REGISTRY_SEED};
 use psy_doge_solana_core::generic_cpi::ManualDepositMainBridgeCPIHelper;
 use psy_doge_solana_core::program_state::BridgeProgramStateWithDogeMint;
 use psy_doge_solana_core::user_manual_deposit_manager::UserManualDepositManagerProgramState;
 use solana_program::{
-    account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, instruction::{AccountMeta, Instruction}, program::invoke_signed, pubkey::Pubkey, rent::Rent, system_instruction, sysvar::Sysvar
+    account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, instruction::{AccountMeta, Instruction}, program::invoke_signed, pubkey::Pubkey, rent::Rent, system_instruction, sysvar::{clock::Clock, Sysvar}
 };
 pub const DOGE_BRIDGE_INSTRUCTION_PROCESS_MANUAL_DEPOSIT: u8 = 5;
 
@@ -55,6 +57,11 @@ pub fn process_instruction(
             )
 }
 
+// same truncation the bridge program applies, both programs compare against the same u32 timestamps
+fn current_unix_timestamp_secs() -> Result<u32, solana_program::program_error::ProgramError> {
+    Ok((Clock::get()?.unix_timestamp & 0xFFFFFFFFi64) as u32)
+}
+
 fn process_claim(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -76,6 +83,10 @@ fn process_claim(
     let user = next_account_info(account_info_iter)?;
     let payer = next_account_info(account_info_iter)?;
     let system_program = next_account_info(account_info_iter)?;
+    // optional until the bridge creates its verifier key registry
+    let vk_registry_account = next_account_info(account_info_iter).ok();
+    // optional, forwarded to the bridge which also searches it for older finalized blocks
+    let finalized_block_history_account = next_account_info(account_info_iter).ok();
 
     if !user.is_signer {
         return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
@@ -122,12 +133,37 @@ fn process_claim(
     let state = bytemuck::try_from_bytes_mut::<UserManualDepositManagerProgramState>(&mut claim_data)
         .map_err(|_| ManualClaimError::SerializationError)?;
 
-    let custodian_wallet_config_hash = {
+    let (custodian_wallet_config_hash, has_vk_registry) = {
         let bridge_data = bridge_state_account.try_borrow_data()?;
         
         let bridge_state = bytemuck::try_from_bytes::<BridgeProgramStateWithDogeMint>(&bridge_data)
             .map_err(|_| ManualClaimError::SerializationError)?;
-        bridge_state.core_state.custodian_wallet_config_hash
+        (bridge_state.core_state.custodian_wallet_config_hash, bridge_state.has_vk_registry())
+    };
+
+    // the registry lives under the main bridge program, the built-in vk applies until it is created
+    let accepted_vks = if let Some(vk_registry_account) = vk_registry_account {
+        let (vk_registry_pda, _) =
+            Pubkey::find_program_address(&[VK_REGISTRY_SEED], main_bridge_program.key);
+        if vk_registry_pda != *vk_registry_account.key {
+            return Err(ManualClaimError::InvalidPDA.into());
+        }
+        if vk_registry_account.data_len() != 0 && vk_registry_account.owner != main_bridge_program.key {
+            return Err(ManualClaimError::InvalidPDA.into());
+        }
+        let now = current_unix_timestamp_secs()?;
+        let vk_registry_data = vk_registry_account.try_borrow_data()?;
+        VerifierKeyRegistry::get_accepted_vks_from_account_data(
+            &vk_registry_data,
+            VK_KIND_MANUAL_CLAIM,
+            &MANUAL_CLAIM_VK,
+            now,
+        )
+        .map_err(|_| ManualClaimError::CoreError)?
+    } else if !has_vk_registry {
+        AcceptedVerifierKeys::builtin(&MANUAL_CLAIM_VK)
+    } else {
+        return Err(ManualClaimError::InvalidPDA.into());
     };
 
     let helper = SolanaManualDepositHelper {
@@ -138,21 +174,24 @@ fn process_claim(
         token_program,
         claim_pda: claim_state_pda,
         claim_pda_seeds: &[b"manual-claim", user.key.as_ref(), &[bump]],
+        finalized_block_history: finalized_block_history_account,
     };
 
-    state.manual_claim_deposit::<ZKVerifier, SolanaManualDepositHelper>(
-        proof,
-        &MANUAL_CLAIM_VK,
-        &helper,
-        recent_block_merkle_tree_root,
-        recent_auto_claim_txo_root,
-        new_manual_claim_txo_root,
-        custodian_wallet_config_hash,
-        tx_hash,
-        combined_txo_index,
-        user.key.to_bytes(),
-        deposit_amount_sats,
-    ).map_err(|_| ManualClaimError::CoreError)?;
+    accepted_vks.verify_with(|vk| {
+        state.manual_claim_deposit::<ZKVerifier, SolanaManualDepositHelper>(
+            proof,
+            vk,
+            &helper,
+            recent_block_merkle_tree_root,
+            recent_auto_claim_txo_root,
+            new_manual_claim_txo_root,
+            custodian_wallet_config_hash,
+            tx_hash,
+            combined_txo_index,
+            user.key.to_bytes(),
+            deposit_amount_sats,
+        )
+    }).map_err(|_| ManualClaimError::CoreError)?;
 
     Ok(())
 }
@@ -165,6 +204,7 @@ struct SolanaManualDepositHelper<'a, 'b> {
     token_program: &'a AccountInfo<'b>,
     claim_pda: &'a AccountInfo<'b>,
     claim_pda_seeds: &'a [&'a [u8]],
+    finalized_block_history: Option<&'a AccountInfo<'b>>,
 }
 
 impl<'a, 'b> ManualDepositMainBridgeCPIHelper for SolanaManualDepositHelper<'a, 'b> {
@@ -201,27 +241,33 @@ impl<'a, 'b> ManualDepositMainBridgeCPIHelper for SolanaManualDepositHelper<'a,
         }
 
         data.extend_from_slice(bytemuck::bytes_of(&ix_data));
+        let mut accounts = vec![
+            AccountMeta::new(*self.bridge_state.key, false),
+            AccountMeta::new(*self.recipient_account.key, false),
+            AccountMeta::new(*self.doge_mint.key, false),
+            AccountMeta::new_readonly(*self.token_program.key, false),
+            AccountMeta::new_readonly(*self.claim_pda.key, true), // Signer
+        ];
+        let mut account_infos = vec![
+            self.bridge_state.clone(),
+            self.recipient_account.clone(),
+            self.doge_mint.clone(),
+            self.token_program.clone(),
+            self.claim_pda.clone(),
+        ];
+        if let Some(finalized_block_history) = self.finalized_block_history {
+            accounts.push(AccountMeta::new_readonly(*finalized_block_history.key, false));
+            account_infos.push(finalized_block_history.clone());
+        }
         let instruction = Instruction {
             program_id: *self.bridge_program.key,
-            accounts: vec![
-                AccountMeta::new(*self.bridge_state.key, false),
-                AccountMeta::new(*self.recipient_account.key, false),
-                AccountMeta::new(*self.doge_mint.key, false),
-                AccountMeta::new_readonly(*self.token_program.key, false),
-                AccountMeta::new_readonly(*self.claim_pda.key, true), // Signer
-            ],
+            accounts,
             data: data,
         };
 
         invoke_signed(
             &instruction,
-            &[
-                self.bridge_state.clone(),
-                self.recipient_account.clone(),
-                self.doge_mint.clone(),
-                self.token_program.clone(),
-                self.claim_pda.clone(),
-            ],
+            &account_infos,
             &[self.claim_pda_seeds]
         ).map_err(|_| DogeBridgeError::CpiManualDepositCallError)
     }
@@ -67,12 +67,14 @@ async fn test_bridge_extended_flow() {
     let user1 = helper.get_user_account(&user1_pk);
     let withdraw_ix = instructions::request_withdrawal(
         ctx.program_id, 
+        ctx.client.payer.pubkey(),
         user1.pubkey(), 
         ctx.doge_mint, 
         user1_ata, 
         [5u8; 20], 
         burn_amount, 
-        0
+        0,
+        0,
     );
     ctx.client.send_tx(&[withdraw_ix], &[user1]).await;
 
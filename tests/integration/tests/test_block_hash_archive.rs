@@ -0,0 +1,120 @@
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{
+    block_transition_helper::{BTAutoClaimedDeposit, BlockTransitionHelper},
+    test_client::bridge_error,
+    BridgeTestContext,
+};
+use psy_bridge_core::error::DogeBridgeError;
+use psy_doge_solana_core::{
+    data_accounts::block_hash_archive::{block_hash_archive_segment_size, BlockHashArchiveSegment},
+};
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+async fn get_segment_data(ctx: &BridgeTestContext, segment_index: u32) -> Option<Vec<u8>> {
+    let account = ctx
+        .client
+        .client
+        .get_account(instructions::get_block_hash_archive_segment_pda(&ctx.program_id, segment_index))
+        .await
+        .unwrap()?;
+    Some(account.data)
+}
+
+/// Only the settings authority can start the archive, after which every block update grows it by one entry
+#[tokio::test]
+async fn test_block_hash_archive_records_block_updates() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let impostor = Keypair::new();
+    let ix = instructions::init_block_hash_archive(ctx.program_id, impostor.pubkey(), ctx.client.payer.pubkey());
+    assert_eq!(
+        ctx.client.try_send_tx(&[ix], &[&impostor]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedBlockHashArchiveUpdate))
+    );
+    assert!(get_segment_data(&ctx, 0).await.is_none());
+
+    // without an admin the operator is the settings authority
+    let ix = instructions::init_block_hash_archive(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        ctx.client.payer.pubkey(),
+    );
+    ctx.client.send_tx(&[ix], &[&ctx.client.operator]).await;
+    assert_eq!(ctx.client.get_block_hash_archive_len().await, 1);
+    assert_eq!(get_segment_data(&ctx, 0).await.unwrap().len(), block_hash_archive_segment_size(1));
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user_pk = helper.add_user();
+    for _ in 0..2 {
+        helper
+            .mine_and_process_block(vec![BTAutoClaimedDeposit::new(user_pk.to_bytes(), 500_000_000, 100)])
+            .await
+            .unwrap();
+    }
+    assert_eq!(ctx.client.get_block_hash_archive_len().await, 3);
+
+    let finalized_state = ctx.client.get_bridge_state().await.core_state.bridge_header.finalized_state;
+
+    let segment_data = get_segment_data(&ctx, 0).await.unwrap();
+    assert_eq!(segment_data.len(), block_hash_archive_segment_size(3));
+    let entry = BlockHashArchiveSegment::find_in_account_data(&segment_data, finalized_state.block_height).unwrap();
+    assert_eq!(entry.block_hash, finalized_state.block_hash);
+    assert_eq!(entry.block_merkle_tree_root, finalized_state.block_merkle_tree_root);
+
+    // heights that are not finalized yet have no entry
+    assert_eq!(
+        BlockHashArchiveSegment::find_in_account_data(&segment_data, finalized_state.block_height + 1),
+        None
+    );
+}
+
+/// A reorg that fast forwards several heights appends an entry for every height it finalizes
+#[tokio::test]
+async fn test_block_hash_archive_reorg_records_every_finalized_block() {
+    let ctx = BridgeTestContext::new_initialized().await;
+    let ix = instructions::init_block_hash_archive(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        ctx.client.payer.pubkey(),
+    );
+    ctx.client.send_tx(&[ix], &[&ctx.client.operator]).await;
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user_pk = helper.add_user();
+    helper
+        .mine_and_process_block(vec![BTAutoClaimedDeposit::new(user_pk.to_bytes(), 500_000_000, 1)])
+        .await
+        .unwrap();
+    let start_height = helper.bridge_state.core_state.bridge_header.finalized_state.block_height;
+
+    helper
+        .mine_reorg_chain(vec![
+            vec![BTAutoClaimedDeposit::new(user_pk.to_bytes(), 300_000_000, 2)],
+            vec![],
+            vec![BTAutoClaimedDeposit::new(user_pk.to_bytes(), 200_000_000, 4)],
+        ])
+        .await
+        .unwrap();
+    assert_eq!(ctx.client.get_block_hash_archive_len().await, 5);
+
+    let finalized_state = ctx.client.get_bridge_state().await.core_state.bridge_header.finalized_state;
+    assert_eq!(finalized_state.block_height, start_height + 3);
+
+    let segment_data = get_segment_data(&ctx, 0).await.unwrap();
+    assert_eq!(segment_data.len(), block_hash_archive_segment_size(5));
+    let mut previous_root = None;
+    for block_height in start_height + 1..=start_height + 3 {
+        let entry = BlockHashArchiveSegment::find_in_account_data(&segment_data, block_height).unwrap();
+        assert_eq!(entry.block_hash, BlockTransitionHelper::mock_block_hash(block_height));
+        assert_ne!(Some(entry.block_merkle_tree_root), previous_root);
+        previous_root = Some(entry.block_merkle_tree_root);
+    }
+    let entry = BlockHashArchiveSegment::find_in_account_data(&segment_data, start_height + 3).unwrap();
+    assert_eq!(entry.block_hash, finalized_state.block_hash);
+    assert_eq!(entry.block_merkle_tree_root, finalized_state.block_merkle_tree_root);
+}
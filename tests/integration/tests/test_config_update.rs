@@ -0,0 +1,107 @@
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{
+    mock_data::default_initialize_params, test_client::bridge_error, BridgeTestContext,
+};
+use psy_bridge_core::error::DogeBridgeError;
+use psy_doge_solana_core::{
+    constants::{
+        MIN_CONFIG_UPDATE_DELAY_SECS, PENDING_CONFIG_STATUS_NONE, PENDING_CONFIG_STATUS_PROPOSED,
+        PENDING_CONFIG_STATUS_QUEUED,
+    },
+    program_state::PsyBridgeConfig,
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    clock::Clock,
+    signature::{Keypair, Signer},
+};
+
+fn config_with_deposit_flat_fee(deposit_flat_fee_sats: u64) -> PsyBridgeConfig {
+    let mut config = default_initialize_params().config_params;
+    config.deposit_flat_fee_sats = deposit_flat_fee_sats;
+    config
+}
+
+/// Config updates are proposed by the settings authority and can be vetoed by the guardian
+#[tokio::test]
+async fn test_config_update_authority_and_cancel() {
+    let mut ctx = BridgeTestContext::new().await;
+    let guardian = Keypair::new();
+    let admin = Keypair::new();
+
+    let init_ix = instructions::initialize_bridge_with_authorities(
+        ctx.client.payer.pubkey(),
+        ctx.client.operator.pubkey(),
+        ctx.client.fee_spender.pubkey(),
+        guardian.pubkey(),
+        Some(admin.pubkey()),
+        ctx.doge_mint,
+        &default_initialize_params(),
+    );
+    ctx.client.send_tx(&[init_ix], &[]).await;
+
+    // with an admin configured the operator can't change fees
+    let propose_ix = instructions::propose_config_update(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        config_with_deposit_flat_fee(2000),
+        MIN_CONFIG_UPDATE_DELAY_SECS,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[propose_ix], &[&ctx.client.operator]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedConfigUpdate))
+    );
+
+    let propose_ix = instructions::propose_config_update(
+        ctx.program_id,
+        admin.pubkey(),
+        config_with_deposit_flat_fee(2000),
+        MIN_CONFIG_UPDATE_DELAY_SECS,
+    );
+    ctx.client.send_tx(&[propose_ix], &[&admin]).await;
+    assert_eq!(ctx.client.get_bridge_state().await.core_state.pending_config_status, PENDING_CONFIG_STATUS_PROPOSED);
+
+    let impostor = Keypair::new();
+    let cancel_ix = instructions::cancel_config_update(ctx.program_id, impostor.pubkey());
+    assert_eq!(
+        ctx.client.try_send_tx(&[cancel_ix], &[&impostor]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedConfigUpdate))
+    );
+
+    let cancel_ix = instructions::cancel_config_update(ctx.program_id, guardian.pubkey());
+    ctx.client.send_tx(&[cancel_ix], &[&guardian]).await;
+    let bridge_state = ctx.client.get_bridge_state().await;
+    assert_eq!(bridge_state.core_state.pending_config_status, PENDING_CONFIG_STATUS_NONE);
+    assert_eq!(bridge_state.core_state.pending_config_params, PsyBridgeConfig::default());
+
+    let apply_ix = instructions::apply_config_update(ctx.program_id);
+    assert_eq!(
+        ctx.client.try_send_tx(&[apply_ix], &[]).await,
+        Err(bridge_error(DogeBridgeError::NoPendingConfigUpdate))
+    );
+
+    // a queued update can still be dropped before the next block transition applies it
+    let propose_ix = instructions::propose_config_update(
+        ctx.program_id,
+        admin.pubkey(),
+        config_with_deposit_flat_fee(3000),
+        MIN_CONFIG_UPDATE_DELAY_SECS,
+    );
+    ctx.client.send_tx(&[propose_ix], &[&admin]).await;
+
+    let mut clock = ctx.client.client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += MIN_CONFIG_UPDATE_DELAY_SECS as i64 + 1;
+    ctx.context.set_sysvar(&clock);
+    // a fresh blockhash so the apply isn't deduplicated against the failed one above
+    ctx.context.get_new_latest_blockhash().await.unwrap();
+
+    let apply_ix = instructions::apply_config_update(ctx.program_id);
+    ctx.client.send_tx(&[apply_ix], &[]).await;
+    assert_eq!(ctx.client.get_bridge_state().await.core_state.pending_config_status, PENDING_CONFIG_STATUS_QUEUED);
+
+    let cancel_ix = instructions::cancel_config_update(ctx.program_id, admin.pubkey());
+    ctx.client.send_tx(&[cancel_ix], &[&admin]).await;
+    let bridge_state = ctx.client.get_bridge_state().await;
+    assert_eq!(bridge_state.core_state.pending_config_status, PENDING_CONFIG_STATUS_NONE);
+    assert_eq!(bridge_state.core_state.config_params, default_initialize_params().config_params);
+}
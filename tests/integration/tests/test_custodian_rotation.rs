@@ -0,0 +1,108 @@
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{
+    mock_data::generate_withdrawal_fake_proof,
+    test_client::bridge_error,
+    BridgeTestContext,
+};
+use psy_bridge_core::{
+    crypto::hash::sha256_impl::hash_impl_sha256_bytes,
+    error::DogeBridgeError,
+};
+use psy_doge_solana_core::{constants::CUSTODIAN_ROTATION_DELAY_SECS, program_state::PsyReturnTxOutput};
+use solana_program_test::tokio;
+use solana_sdk::{
+    clock::Clock,
+    signature::{Keypair, Signer},
+};
+
+/// The custodian hash only switches once the return output has been swept to the new custodian
+#[tokio::test]
+async fn test_custodian_rotation_sweep() {
+    let mut ctx = BridgeTestContext::new_initialized().await;
+    // the rotation lives in what used to be reserved space, which a new account starts with zeroed
+    let bridge_state = ctx.client.get_bridge_state().await;
+    assert!(!bridge_state.custodian_rotation.is_scheduled());
+    assert_eq!(bridge_state._reserved, [[0u8; 32]; 13]);
+
+    // only the settings authority can schedule, the operator while no admin is set
+    let impostor = Keypair::new();
+    let unauthorized_ix =
+        instructions::schedule_custodian_rotation(ctx.program_id, impostor.pubkey(), [2u8; 32]);
+    assert_eq!(
+        ctx.client.try_send_tx(&[unauthorized_ix], &[&impostor]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedCustodianRotation))
+    );
+
+    let schedule_ix =
+        instructions::schedule_custodian_rotation(ctx.program_id, ctx.client.operator.pubkey(), [2u8; 32]);
+    ctx.client.send_tx(&[schedule_ix], &[&ctx.client.operator]).await;
+    let bridge_state = ctx.client.get_bridge_state().await;
+    assert_eq!(bridge_state.custodian_rotation.pending_custodian_wallet_config_hash, [2u8; 32]);
+
+    let doge_tx_data = vec![0xAB; 120];
+    let new_return_output = PsyReturnTxOutput {
+        sighash: hash_impl_sha256_bytes(&doge_tx_data),
+        output_index: 0,
+        amount_sats: 0,
+    };
+    let new_spent_root = [9u8; 32];
+    let proof = generate_withdrawal_fake_proof(
+        bridge_state.get_expected_public_inputs_for_custodian_sweep_proof(&new_return_output, new_spent_root),
+    );
+    let buffer_pk = ctx.client.create_generic_buffer(&doge_tx_data).await;
+    let fake_wormhole_id = ctx.client.generic_buffer_program_id;
+    let sweep_ix = instructions::process_custodian_sweep(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        buffer_pk,
+        fake_wormhole_id,
+        fake_wormhole_id,
+        proof,
+        new_return_output,
+        new_spent_root,
+    );
+
+    // too early, the guardian still has time to cancel
+    assert_eq!(
+        ctx.client.try_send_tx(&[sweep_ix.clone()], &[]).await,
+        Err(bridge_error(DogeBridgeError::CustodianRotationTimelockNotElapsed))
+    );
+    assert_eq!(ctx.client.get_bridge_state().await.core_state.custodian_wallet_config_hash, [1u8; 32]);
+
+    let mut clock = ctx.client.client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += CUSTODIAN_ROTATION_DELAY_SECS as i64 + 1;
+    ctx.context.set_sysvar(&clock);
+    ctx.context.get_new_latest_blockhash().await.unwrap();
+
+    ctx.client.send_tx(&[sweep_ix], &[]).await;
+    let bridge_state = ctx.client.get_bridge_state().await;
+    assert_eq!(bridge_state.core_state.custodian_wallet_config_hash, [2u8; 32]);
+    assert_eq!(bridge_state.core_state.last_return_output, new_return_output);
+    assert_eq!(bridge_state.core_state.spent_txo_tree_root, new_spent_root);
+    assert!(!bridge_state.custodian_rotation.is_scheduled());
+}
+
+/// The guardian can cancel a scheduled rotation, after which a sweep is rejected
+#[tokio::test]
+async fn test_guardian_cancels_custodian_rotation() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let schedule_ix =
+        instructions::schedule_custodian_rotation(ctx.program_id, ctx.client.operator.pubkey(), [2u8; 32]);
+    ctx.client.send_tx(&[schedule_ix], &[&ctx.client.operator]).await;
+
+    let impostor = Keypair::new();
+    let unauthorized_ix = instructions::cancel_custodian_rotation(ctx.program_id, impostor.pubkey());
+    assert_eq!(
+        ctx.client.try_send_tx(&[unauthorized_ix], &[&impostor]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedCustodianRotation))
+    );
+
+    // the operator is the guardian by default
+    let cancel_ix = instructions::cancel_custodian_rotation(ctx.program_id, ctx.client.operator.pubkey());
+    ctx.client.send_tx(&[cancel_ix], &[&ctx.client.operator]).await;
+
+    let bridge_state = ctx.client.get_bridge_state().await;
+    assert!(!bridge_state.custodian_rotation.is_scheduled());
+    assert_eq!(bridge_state.core_state.custodian_wallet_config_hash, [1u8; 32]);
+}
@@ -0,0 +1,55 @@
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{mock_data::default_initialize_params, test_client::bridge_error, BridgeTestContext};
+use psy_bridge_core::{
+    custodian_config::{Bridge7MultisigCustodianWalletConfig, DOGE_NETWORK_TYPE_MAINNET},
+    error::DogeBridgeError,
+};
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+/// Only the preimage of custodian_wallet_config_hash can be published, by anyone
+#[tokio::test]
+async fn test_store_custodian_wallet_config() {
+    let ctx = BridgeTestContext::new().await;
+
+    let mut signer_public_keys = [[0u8; 32]; 7];
+    for (i, key) in signer_public_keys.iter_mut().enumerate() {
+        key.fill(i as u8 + 1);
+    }
+    let config = Bridge7MultisigCustodianWalletConfig::new_basic(
+        signer_public_keys,
+        0b0101010,
+        DOGE_NETWORK_TYPE_MAINNET,
+    );
+    let mut params = default_initialize_params();
+    params.custodian_wallet_config_hash = config.get_wallet_config_hash();
+    let init_ix = instructions::initialize_bridge(
+        ctx.client.payer.pubkey(),
+        ctx.client.operator.pubkey(),
+        ctx.client.fee_spender.pubkey(),
+        ctx.doge_mint,
+        &params,
+    );
+    ctx.client.send_tx(&[init_ix], &[]).await;
+
+    let mut wrong_config = config;
+    wrong_config.network_type = 1;
+    let wrong_ix = instructions::store_custodian_wallet_config(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        &wrong_config,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[wrong_ix], &[]).await,
+        Err(bridge_error(DogeBridgeError::CustodianWalletConfigHashMismatch))
+    );
+
+    let store_ix = instructions::store_custodian_wallet_config(ctx.program_id, ctx.client.payer.pubkey(), &config);
+    ctx.client.send_tx(&[store_ix], &[]).await;
+
+    let config_pda = instructions::get_custodian_wallet_config_pda(&ctx.program_id);
+    let account = ctx.client.client.get_account(config_pda).await.unwrap().unwrap();
+    let stored: &Bridge7MultisigCustodianWalletConfig = bytemuck::from_bytes(&account.data);
+    assert_eq!(*stored, config);
+    assert_eq!(stored.to_compressed_public_keys()[1][0], 0x03);
+}
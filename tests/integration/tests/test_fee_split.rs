@@ -0,0 +1,98 @@
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{
+    block_transition_helper::BlockTransitionHelper,
+    test_client::bridge_error,
+    BridgeTestContext,
+};
+use psy_bridge_core::error::DogeBridgeError;
+use psy_doge_solana_core::{
+    data_accounts::fee_split::{FeeSplitRecipient, FeeSplitTable},
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+async fn get_token_balance(ctx: &BridgeTestContext, token_account: Pubkey) -> u64 {
+    let account = ctx.client.client.get_account(token_account).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+/// Once the fee spender sets a split, withdrawal fees can only be paid out through distribute_fees
+#[tokio::test]
+async fn test_distribute_fees_follows_fee_split() {
+    let mut ctx = BridgeTestContext::new_initialized().await;
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user_pk = helper.add_funded_user(500_000_000).await.unwrap();
+    let user = helper.get_user_account(&user_pk);
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user_pk, &ctx.doge_mint);
+
+    // the withdrawal fee is what gets distributed
+    let withdraw_ix = instructions::request_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        [0xAB; 20],
+        100_000_000,
+        0,
+        0,
+    );
+    ctx.client.send_tx(&[withdraw_ix], &[user]).await;
+    let withdrawable_fees = ctx.client.get_bridge_state().await.core_state.get_operator_withdrawable_fees();
+    assert!(withdrawable_fees > 0);
+
+    let partner = Keypair::new();
+    let treasury = Keypair::new();
+    let fee_spender = Keypair::from_bytes(&ctx.client.fee_spender.to_bytes()).unwrap();
+    let operator = Keypair::from_bytes(&ctx.client.operator.to_bytes()).unwrap();
+    for owner in [&partner, &treasury, &fee_spender, &operator] {
+        ctx.client.create_token_ata_if_needed(ctx.doge_mint, owner).await;
+    }
+    let ata = |owner: &Keypair| spl_associated_token_account::get_associated_token_address(&owner.pubkey(), &ctx.doge_mint);
+
+    let mut fee_split = FeeSplitTable {
+        fee_spender_token_account: ata(&fee_spender).to_bytes(),
+        recipient_count: 2,
+        ..Default::default()
+    };
+    fee_split.recipients[0] = FeeSplitRecipient { token_account: ata(&partner).to_bytes(), share_bps: 3000 };
+    fee_split.recipients[1] = FeeSplitRecipient { token_account: ata(&treasury).to_bytes(), share_bps: 2000 };
+
+    let impostor = Keypair::new();
+    let set_ix = instructions::set_fee_split(ctx.program_id, impostor.pubkey(), ctx.client.payer.pubkey(), &fee_split);
+    assert_eq!(
+        ctx.client.try_send_tx(&[set_ix], &[&impostor]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedFeeSplitUpdate))
+    );
+
+    let set_ix = instructions::set_fee_split(ctx.program_id, fee_spender.pubkey(), ctx.client.payer.pubkey(), &fee_split);
+    ctx.client.send_tx(&[set_ix], &[&fee_spender]).await;
+
+    // the operator can no longer take the whole fee
+    let withdraw_fees_ix = instructions::operator_withdraw_fees(ctx.program_id, operator.pubkey(), ata(&operator), ctx.doge_mint);
+    assert_eq!(
+        ctx.client.try_send_tx(&[withdraw_fees_ix], &[&operator]).await,
+        Err(bridge_error(DogeBridgeError::FeesDistributedByFeeSplit))
+    );
+
+    let fee_spender_balance_before = get_token_balance(&ctx, ata(&fee_spender)).await;
+    let distribute_ix = instructions::distribute_fees(ctx.program_id, ctx.doge_mint, &fee_split);
+    ctx.client.send_tx(&[distribute_ix], &[]).await;
+
+    let partner_amount = withdrawable_fees * 3000 / 10_000;
+    let treasury_amount = withdrawable_fees * 2000 / 10_000;
+    assert_eq!(get_token_balance(&ctx, ata(&partner)).await, partner_amount);
+    assert_eq!(get_token_balance(&ctx, ata(&treasury)).await, treasury_amount);
+    assert_eq!(
+        get_token_balance(&ctx, ata(&fee_spender)).await - fee_spender_balance_before,
+        withdrawable_fees - partner_amount - treasury_amount
+    );
+    assert_eq!(ctx.client.get_bridge_state().await.core_state.get_operator_withdrawable_fees(), 0);
+}
@@ -0,0 +1,102 @@
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{
+    block_transition_helper::BlockTransitionHelper,
+    test_client::bridge_error,
+    BridgeTestContext,
+};
+use psy_bridge_core::error::DogeBridgeError;
+use psy_doge_solana_core::{
+    data_accounts::finalized_block_history::{
+        finalized_block_history_account_size, FinalizedBlockHistory, FinalizedBlockHistoryHeader,
+        FINALIZED_BLOCK_HISTORY_HEADER_SIZE,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+async fn get_history_data(ctx: &BridgeTestContext) -> Option<Vec<u8>> {
+    let account = ctx
+        .client
+        .client
+        .get_account(instructions::get_finalized_block_history_pda(&ctx.program_id))
+        .await
+        .unwrap()?;
+    Some(account.data)
+}
+
+/// Only the settings authority can enable the history, which is grown over several steps
+/// and from then on records every finalized block
+#[tokio::test]
+async fn test_finalized_block_history_records_block_updates() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let capacity = 512;
+    let steps = instructions::init_finalized_block_history_steps(capacity);
+    assert!(steps > 1);
+
+    let impostor = Keypair::new();
+    let ix = instructions::init_finalized_block_history(
+        ctx.program_id,
+        impostor.pubkey(),
+        ctx.client.payer.pubkey(),
+        capacity,
+        0,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[ix], &[&impostor]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedFinalizedBlockHistoryUpdate))
+    );
+    assert!(get_history_data(&ctx).await.is_none());
+
+    // without an admin the operator is the settings authority
+    for step in 0..steps {
+        let ix = instructions::init_finalized_block_history(
+            ctx.program_id,
+            ctx.client.operator.pubkey(),
+            ctx.client.payer.pubkey(),
+            capacity,
+            step,
+        );
+        ctx.client.send_tx(&[ix], &[&ctx.client.operator]).await;
+    }
+
+    let history_data = get_history_data(&ctx).await.unwrap();
+    assert_eq!(history_data.len(), finalized_block_history_account_size(capacity));
+    let header: FinalizedBlockHistoryHeader =
+        *bytemuck::from_bytes(&history_data[..FINALIZED_BLOCK_HISTORY_HEADER_SIZE]);
+    assert_eq!(header.capacity, capacity);
+    let seeded_len = header.len;
+    assert!(seeded_len > 0);
+
+    // an enabled history can't be re-created with another capacity
+    let ix = instructions::init_finalized_block_history(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        ctx.client.payer.pubkey(),
+        capacity * 2,
+        0,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[ix], &[&ctx.client.operator]).await,
+        Err(bridge_error(DogeBridgeError::FinalizedBlockHistoryAlreadyEnabled))
+    );
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    helper.add_funded_user(500_000_000).await.unwrap();
+
+    let finalized_state = ctx.client.get_bridge_state().await.core_state.bridge_header.finalized_state;
+
+    let history_data = get_history_data(&ctx).await.unwrap();
+    let header: FinalizedBlockHistoryHeader =
+        *bytemuck::from_bytes(&history_data[..FINALIZED_BLOCK_HISTORY_HEADER_SIZE]);
+    assert_eq!(header.len, seeded_len + 1);
+    assert_eq!(
+        FinalizedBlockHistory::find_in_account_data(
+            &history_data,
+            &finalized_state.auto_claimed_deposits_tree_root
+        ),
+        Some((finalized_state.block_height, finalized_state.block_merkle_tree_root))
+    );
+}
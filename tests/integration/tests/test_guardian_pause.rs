@@ -0,0 +1,160 @@
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{
+    block_transition_helper::BlockTransitionHelper,
+    mock_data::default_initialize_params,
+    test_client::bridge_error,
+    BridgeTestContext,
+};
+use psy_bridge_core::error::DogeBridgeError;
+use psy_doge_solana_core::{
+    constants::{BRIDGE_CONTROL_MODE_ALL_FLAGS, BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_REQUESTS},
+};
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Withdrawal requests are rejected while the guardian pause is active and accepted once it is lifted
+#[tokio::test]
+async fn test_guardian_pause_blocks_withdrawal_requests() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user_pk = helper.add_funded_user(500_000_000).await.unwrap();
+    let user = helper.get_user_account(&user_pk);
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user_pk, &ctx.doge_mint);
+
+    // the operator is the guardian by default
+    let pause_ix = instructions::guardian_set_pause(ctx.program_id, ctx.client.operator.pubkey(), 3600);
+    ctx.client.send_tx(&[pause_ix], &[&ctx.client.operator]).await;
+    assert!(ctx.client.get_bridge_state().await.core_state.guardian_paused_until_secs > 0);
+
+    let withdraw_ix = instructions::request_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        [0xAB; 20],
+        100_000_000,
+        0,
+        0,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[withdraw_ix], &[&user]).await,
+        Err(bridge_error(DogeBridgeError::BridgePaused)),
+        "withdrawal request should fail while paused"
+    );
+
+    let unpause_ix = instructions::guardian_set_pause(ctx.program_id, ctx.client.operator.pubkey(), 0);
+    ctx.client.send_tx(&[unpause_ix], &[&ctx.client.operator]).await;
+    assert_eq!(ctx.client.get_bridge_state().await.core_state.guardian_paused_until_secs, 0);
+
+    // use a different amount so the retry is not deduplicated against the failed transaction
+    let withdraw_ix = instructions::request_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        [0xAB; 20],
+        100_000_001,
+        0,
+        0,
+    );
+    ctx.client.send_tx(&[withdraw_ix], &[&user]).await;
+}
+
+/// Only the configured guardian can pause the bridge
+#[tokio::test]
+async fn test_guardian_pause_rejects_other_signers() {
+    let ctx = BridgeTestContext::new().await;
+
+    let guardian = Keypair::new();
+    let init_ix = instructions::initialize_bridge_with_authorities(
+        ctx.client.payer.pubkey(),
+        ctx.client.operator.pubkey(),
+        ctx.client.fee_spender.pubkey(),
+        guardian.pubkey(),
+        None,
+        ctx.doge_mint,
+        &default_initialize_params(),
+    );
+    ctx.client.send_tx(&[init_ix], &[]).await;
+
+    let impostor = Keypair::new();
+    let pause_ix = instructions::guardian_set_pause(ctx.program_id, impostor.pubkey(), 3600);
+    assert_eq!(
+        ctx.client.try_send_tx(&[pause_ix], &[&impostor]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedGuardian))
+    );
+    assert_eq!(ctx.client.get_bridge_state().await.core_state.guardian_paused_until_secs, 0);
+
+    let pause_ix = instructions::guardian_set_pause(ctx.program_id, guardian.pubkey(), 3600);
+    ctx.client.send_tx(&[pause_ix], &[&guardian]).await;
+    assert!(ctx.client.get_bridge_state().await.core_state.guardian_paused_until_secs > 0);
+}
+
+/// Disabling withdrawal requests through the control mode leaves other operations untouched
+#[tokio::test]
+async fn test_control_mode_disables_withdrawal_requests() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let mode_ix = instructions::set_bridge_control_mode(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        BRIDGE_CONTROL_MODE_DISABLE_WITHDRAWAL_REQUESTS,
+    );
+    ctx.client.send_tx(&[mode_ix], &[&ctx.client.operator]).await;
+
+    // deposits keep flowing while withdrawal requests are disabled
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user_pk = helper.add_funded_user(500_000_000).await.unwrap();
+    let user = helper.get_user_account(&user_pk);
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user_pk, &ctx.doge_mint);
+
+    let withdraw_ix = instructions::request_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        [0xAB; 20],
+        100_000_000,
+        0,
+        0,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[withdraw_ix], &[&user]).await,
+        Err(bridge_error(DogeBridgeError::OperationDisabled))
+    );
+
+    // unknown flags are rejected
+    let bad_mode_ix = instructions::set_bridge_control_mode(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        BRIDGE_CONTROL_MODE_ALL_FLAGS + 1,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[bad_mode_ix], &[&ctx.client.operator]).await,
+        Err(bridge_error(DogeBridgeError::InvalidBridgeControlMode))
+    );
+
+    let mode_ix = instructions::set_bridge_control_mode(ctx.program_id, ctx.client.operator.pubkey(), 0);
+    ctx.client.send_tx(&[mode_ix], &[&ctx.client.operator]).await;
+
+    let withdraw_ix = instructions::request_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        [0xAB; 20],
+        100_000_001,
+        0,
+        0,
+    );
+    ctx.client.send_tx(&[withdraw_ix], &[&user]).await;
+}
@@ -0,0 +1,126 @@
+use doge_bridge::{error::BridgeError, state::BridgeState};
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{
+    block_transition_helper::BlockTransitionHelper,
+    test_client::bridge_error,
+    BridgeTestContext,
+};
+use psy_bridge_core::error::DogeBridgeError;
+use psy_doge_solana_core::{
+    program_state::{
+        decode_bridge_state_account, get_bridge_state_version, BridgeProgramStateWithDogeMintV0,
+        PsyBridgeAccessControlHeader, PsyBridgeAccessControlHeaderV0, PsyBridgeProgramStateV0,
+        BRIDGE_STATE_CURRENT_VERSION, BRIDGE_STATE_LEGACY_VERSION,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    instruction::InstructionError,
+    signature::Signer,
+};
+
+// the state as the unversioned program laid it out, field by field since v1 inserted fields mid-struct
+fn to_legacy_state(state: &BridgeState) -> BridgeProgramStateWithDogeMintV0 {
+    let core_state = &state.core_state;
+    BridgeProgramStateWithDogeMintV0 {
+        core_state: PsyBridgeProgramStateV0 {
+            bridge_header: core_state.bridge_header,
+            recent_finalized_blocks: core_state.recent_finalized_blocks,
+            last_return_output: core_state.last_return_output,
+            pending_mint_txos: core_state.pending_mint_txos,
+            spent_txo_tree_root: core_state.spent_txo_tree_root,
+            withdrawal_snapshot: core_state.withdrawal_snapshot,
+            next_processed_withdrawals_index: core_state.next_processed_withdrawals_index,
+            sent_transactions_tree: core_state.sent_transactions_tree,
+            manual_deposits_tree: core_state.manual_deposits_tree,
+            requested_withdrawals_tree: core_state.requested_withdrawals_tree,
+            custodian_wallet_config_hash: core_state.custodian_wallet_config_hash,
+            bridge_control_mode: core_state.bridge_control_mode,
+            next_recent_finalized_block_index: core_state.next_recent_finalized_block_index,
+            last_processed_withdrawals_at_ms: core_state.last_processed_withdrawals_at_ms,
+            total_requested_withdrawals_sats: core_state.total_requested_withdrawals_sats,
+            total_fees_withdrawn_sats: core_state.total_fees_withdrawn_sats,
+            total_manual_deposit_fees_sats: core_state.total_manual_deposit_fees_sats,
+            total_withdrawal_fees_sats: core_state.total_withdrawal_fees_sats,
+            last_received_block_at_ms: core_state.last_received_block_at_ms,
+            last_replayed_withdrawal_at_ms: core_state.last_replayed_withdrawal_at_ms,
+            config_params: core_state.config_params,
+            access_control: PsyBridgeAccessControlHeaderV0 {
+                operator_pubkey: core_state.access_control.operator_pubkey,
+                fee_spender_pubkey: core_state.access_control.fee_spender_pubkey,
+            },
+        },
+        doge_mint: state.doge_mint,
+    }
+}
+
+/// A bridge state account in the unversioned layout is unusable until migrate_state converts it in place
+#[tokio::test]
+async fn test_migrate_state_from_legacy_layout() {
+    let mut ctx = BridgeTestContext::new_initialized().await;
+
+    // rewrite the account as a deployment from before the version header would have it
+    let bridge_state_pda = ctx.client.bridge_state_pda;
+    let account = ctx.client.client.get_account(bridge_state_pda).await.unwrap().unwrap();
+    let current_data = account.data.clone();
+    let current: &BridgeState = bytemuck::from_bytes(&current_data);
+    // v0 had no guardian or admin, the migration makes the operator the guardian and leaves the admin unset
+    let mut expected = *current;
+    expected.core_state.access_control = PsyBridgeAccessControlHeader {
+        operator_pubkey: current.core_state.access_control.operator_pubkey,
+        fee_spender_pubkey: current.core_state.access_control.fee_spender_pubkey,
+        guardian_pubkey: current.core_state.access_control.operator_pubkey,
+        ..Default::default()
+    };
+    let legacy_data = bytemuck::bytes_of(&to_legacy_state(current)).to_vec();
+    assert_eq!(legacy_data.len(), 6184);
+    let rent = ctx.client.client.get_rent().await.unwrap();
+    let legacy_account = Account {
+        lamports: rent.minimum_balance(legacy_data.len()),
+        data: legacy_data,
+        owner: account.owner,
+        executable: false,
+        rent_epoch: account.rent_epoch,
+    };
+    ctx.context.set_account(&bridge_state_pda, &AccountSharedData::from(legacy_account));
+
+    let account = ctx.client.client.get_account(bridge_state_pda).await.unwrap().unwrap();
+    assert_eq!(get_bridge_state_version(&account.data), Some(BRIDGE_STATE_LEGACY_VERSION));
+    // clients can still read it
+    let decoded = decode_bridge_state_account(&account.data).unwrap();
+    assert_eq!(decoded.core_state, expected.core_state);
+    assert_eq!(decoded.doge_mint, ctx.doge_mint.to_bytes());
+
+    let archive_ix = instructions::init_block_hash_archive(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        ctx.client.payer.pubkey(),
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[archive_ix.clone()], &[&ctx.client.operator]).await,
+        Err(InstructionError::Custom(BridgeError::SerializationError as u32))
+    );
+
+    // anyone can migrate, the payer tops up the rent
+    let migrate_ix = instructions::migrate_state(ctx.program_id, ctx.client.payer.pubkey());
+    ctx.client.send_tx(&[migrate_ix.clone()], &[]).await;
+    let account = ctx.client.client.get_account(bridge_state_pda).await.unwrap().unwrap();
+    assert_eq!(get_bridge_state_version(&account.data), Some(BRIDGE_STATE_CURRENT_VERSION));
+    assert_eq!(account.data, bytemuck::bytes_of(&expected));
+    assert!(account.lamports >= rent.minimum_balance(account.data.len()));
+    // a fresh blockhash so the retries below aren't deduplicated against the earlier transactions
+    ctx.context.get_new_latest_blockhash().await.unwrap();
+    assert_eq!(
+        ctx.client.try_send_tx(&[migrate_ix], &[]).await,
+        Err(bridge_error(DogeBridgeError::BridgeStateVersionUpToDate))
+    );
+
+    // the migrated bridge keeps working
+    ctx.client.send_tx(&[archive_ix], &[&ctx.client.operator]).await;
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    helper.add_funded_user(500_000_000).await.unwrap();
+    assert_eq!(ctx.client.get_block_hash_archive_len().await, 2);
+}
@@ -0,0 +1,63 @@
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{
+    block_transition_helper::BlockTransitionHelper,
+    test_client::bridge_error,
+    BridgeTestContext,
+};
+use psy_bridge_core::error::DogeBridgeError;
+use psy_doge_solana_core::program_state::PsyBridgePolicyConfig;
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// The minimums are set by the settings authority without touching the proven config
+#[tokio::test]
+async fn test_policy_config_minimum_withdrawal() {
+    let ctx = BridgeTestContext::new_initialized().await;
+    let config_hash = ctx.client.get_bridge_state().await.core_state.config_params.get_hash();
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user_pk = helper.add_funded_user(500_000_000).await.unwrap();
+    let user = helper.get_user_account(&user_pk);
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user_pk, &ctx.doge_mint);
+    let withdraw_ix = |amount_sats: u64, receipt_nonce: u64| {
+        instructions::request_withdrawal(
+            ctx.program_id,
+            ctx.client.payer.pubkey(),
+            user.pubkey(),
+            ctx.doge_mint,
+            user_ata,
+            [0xAB; 20],
+            amount_sats,
+            0,
+            receipt_nonce,
+        )
+    };
+
+    let policy_config = PsyBridgePolicyConfig {
+        min_withdrawal_sats: 10_000_000,
+        min_deposit_sats: 0,
+        withdrawal_cancellation_fee_sats: 0,
+    };
+    let impostor = Keypair::new();
+    let policy_ix = instructions::set_policy_config(ctx.program_id, impostor.pubkey(), policy_config);
+    assert_eq!(
+        ctx.client.try_send_tx(&[policy_ix], &[&impostor]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedConfigUpdate))
+    );
+
+    let policy_ix =
+        instructions::set_policy_config(ctx.program_id, ctx.client.operator.pubkey(), policy_config);
+    ctx.client.send_tx(&[policy_ix], &[&ctx.client.operator]).await;
+    let bridge_state = ctx.client.get_bridge_state().await;
+    assert_eq!(bridge_state.core_state.policy_config, policy_config);
+    assert_eq!(bridge_state.core_state.config_params.get_hash(), config_hash);
+
+    // 10_000_000 - (100_000 + 1000) sats are left after fees
+    assert_eq!(
+        ctx.client.try_send_tx(&[withdraw_ix(10_000_000, 0)], &[&user]).await,
+        Err(bridge_error(DogeBridgeError::WithdrawalBelowMinimum))
+    );
+    ctx.client.send_tx(&[withdraw_ix(20_000_000, 0)], &[&user]).await;
+}
@@ -1,8 +1,18 @@
 use doge_bridge_client::instructions::{self};
 use doge_bridge_test_utils::{
     block_transition_helper::{BTAutoClaimedDeposit, BlockTransitionHelper},
+    mock_data::default_initialize_params,
     BridgeTestContext,
 };
+use psy_bridge_core::{
+    crypto::hash::merkle::fixed_append_tree::FixedMerkleAppendTree,
+    dogecoin::{
+        chain_tracker::{HeaderChainTracker, HeaderChainUpdate},
+        difficulty::get_block_proof,
+        header::{DogeAuxPowBlockHeader, DogeBlockHeader, DogePowParams, DOGE_AUXPOW_CHAIN_ID},
+        network::DogeNetwork,
+    },
+};
 use psy_bridge_core::header::{PsyBridgeHeader, PsyBridgeStateCommitment, PsyBridgeTipStateCommitment}
 ;
 use psy_doge_solana_core::{
@@ -10,7 +20,7 @@ use psy_doge_solana_core::{
     program_state::{PsyBridgeConfig, PsyReturnTxOutput},
 };
 use solana_program_test::tokio;
-use solana_sdk::{program_pack::Pack, signature::Signer};
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey, signature::Signer};
 
 #[tokio::test]
 async fn test_reorg_with_fast_forward() {
@@ -134,3 +144,110 @@ async fn test_reorg_with_fast_forward() {
 
     println!("Reorg with Auto Advance Test Successful");
 }
+
+// every header meets this target and adds one unit of work
+const EASY_BITS: u32 = 0x2100_ffff;
+
+// fork tells siblings apart
+fn child_headers(parent: &DogeBlockHeader, fork: u8, length: usize) -> Vec<DogeAuxPowBlockHeader> {
+    let mut headers: Vec<DogeAuxPowBlockHeader> = Vec::new();
+    for _ in 0..length {
+        let prev = headers.last().map(|h| &h.header).unwrap_or(parent);
+        headers.push(DogeAuxPowBlockHeader {
+            header: DogeBlockHeader {
+                prev_block_hash: prev.get_hash(),
+                merkle_root: [fork; 32],
+                timestamp: prev.timestamp + 60,
+                ..*prev
+            },
+            aux_pow: None,
+        });
+    }
+    headers
+}
+
+async fn token_balance(ctx: &BridgeTestContext, user: &Pubkey) -> u64 {
+    let ata = spl_associated_token_account::get_associated_token_address(user, &ctx.doge_mint);
+    let account = ctx.client.client.get_account(ata).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+/// A reorg to the fork the header chain tracker switched to leaves the bridge on the tracker's best chain
+#[tokio::test]
+async fn test_reorg_to_header_chain_tracker_best_chain() {
+    let ctx = BridgeTestContext::new().await;
+
+    let finalized_header = DogeBlockHeader {
+        version: (DOGE_AUXPOW_CHAIN_ID << 16) | 4,
+        prev_block_hash: [0u8; 32],
+        merkle_root: [0u8; 32],
+        timestamp: 1_700_000_000,
+        bits: EASY_BITS,
+        nonce: 0,
+    };
+    let mut finalized_block_tree = FixedMerkleAppendTree::new_empty();
+    finalized_block_tree.append(finalized_header.get_hash());
+    let mut tracker = HeaderChainTracker::new(
+        DogePowParams {
+            pow_limit: [0xff; 32],
+            ..DogeNetwork::Regtest.pow_params()
+        },
+        100,
+        0,
+        finalized_header,
+        get_block_proof(EASY_BITS),
+        finalized_block_tree,
+    )
+    .unwrap();
+
+    let mut initialize_params = default_initialize_params();
+    initialize_params.bridge_header.finalized_state.block_hash = tracker.finalized().hash;
+    initialize_params.bridge_header.finalized_state.block_merkle_tree_root = tracker.get_block_merkle_tree_root();
+    let init_ix = instructions::initialize_bridge(
+        ctx.client.payer.pubkey(),
+        ctx.client.operator.pubkey(),
+        ctx.client.fee_spender.pubkey(),
+        ctx.doge_mint,
+        &initialize_params,
+    );
+    ctx.client.send_tx(&[init_ix], &[]).await;
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+
+    // the bridge processes the first block of a two block chain
+    let a = child_headers(&finalized_header, 1, 2);
+    for header in a.iter() {
+        assert_eq!(tracker.append(header), Ok(HeaderChainUpdate::Extended));
+    }
+    helper.header_chain = Some(tracker.clone());
+    let u1 = helper.add_funded_user(500_000_000).await.unwrap();
+
+    // a heavier fork replaces the second block
+    let b = child_headers(&a[0].header, 2, 3);
+    assert_eq!(tracker.append(&b[0]), Ok(HeaderChainUpdate::SideChain));
+    assert_eq!(tracker.append(&b[1]), Ok(HeaderChainUpdate::Reorg { fork_height: 1 }));
+    assert_eq!(tracker.append(&b[2]), Ok(HeaderChainUpdate::Extended));
+    helper.header_chain = Some(tracker.clone());
+
+    let u2 = helper.add_user();
+    let u4 = helper.add_user();
+    helper
+        .mine_reorg_chain(vec![
+            vec![BTAutoClaimedDeposit::new(u2.to_bytes(), 300_000_000, 2)],
+            vec![],
+            vec![BTAutoClaimedDeposit::new(u4.to_bytes(), 200_000_000, 4)],
+        ])
+        .await
+        .unwrap();
+
+    let finalized_state = ctx.client.get_bridge_state().await.core_state.bridge_header.finalized_state;
+    assert_eq!(finalized_state.block_height, tracker.best_tip().height);
+    assert_eq!(finalized_state.block_hash, b[2].get_hash());
+    assert_eq!(finalized_state.block_merkle_tree_root, tracker.get_block_merkle_tree_root());
+
+    assert_eq!(token_balance(&ctx, &u1).await, 500_000_000);
+    assert_eq!(token_balance(&ctx, &u2).await, 300_000_000);
+    assert_eq!(token_balance(&ctx, &u4).await, 200_000_000);
+}
@@ -142,12 +142,14 @@ async fn test_snapshot_withdrawals_with_pending_withdrawals() {
 
     let withdraw_ix = instructions::request_withdrawal(
         ctx.program_id,
+        ctx.client.payer.pubkey(),
         user1.pubkey(),
         ctx.doge_mint,
         user1_ata,
         [0xAB; 20], // Dogecoin recipient address
         100_000_000,
         0, // P2PKH address type
+        0,
     );
     ctx.client.send_tx(&[withdraw_ix], &[user1]).await;
 
@@ -158,12 +160,14 @@ async fn test_snapshot_withdrawals_with_pending_withdrawals() {
 
     let withdraw_ix2 = instructions::request_withdrawal(
         ctx.program_id,
+        ctx.client.payer.pubkey(),
         user2.pubkey(),
         ctx.doge_mint,
         user2_ata,
         [0xCD; 20],
         50_000_000,
         0,
+        0,
     );
     ctx.client.send_tx(&[withdraw_ix2], &[user2]).await;
 
@@ -295,12 +299,14 @@ async fn test_multiple_snapshots() {
 
     let withdraw_ix = instructions::request_withdrawal(
         ctx.program_id,
+        ctx.client.payer.pubkey(),
         user1.pubkey(),
         ctx.doge_mint,
         user1_ata,
         [0x11; 20],
         100_000_000,
         0,
+        0,
     );
     ctx.client.send_tx(&[withdraw_ix], &[user1]).await;
 
@@ -329,12 +335,14 @@ async fn test_multiple_snapshots() {
     let user1 = helper.get_user_account(&user1_pk);
     let withdraw_ix2 = instructions::request_withdrawal(
         ctx.program_id,
+        ctx.client.payer.pubkey(),
         user1.pubkey(),
         ctx.doge_mint,
         user1_ata,
         [0x22; 20],
         200_000_000,
         0,
+        1,
     );
     ctx.client.send_tx(&[withdraw_ix2], &[user1]).await;
 
@@ -0,0 +1,132 @@
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{
+    block_transition_helper::BlockTransitionHelper,
+    test_client::bridge_error,
+    BridgeTestContext,
+};
+use psy_bridge_core::error::DogeBridgeError;
+use psy_doge_solana_core::{
+    constants::{VK_KIND_SINGLE_BLOCK_UPDATE, VK_UPDATE_DELAY_SECS},
+    data_accounts::vk_registry::VerifierKeyRegistry,
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    clock::Clock,
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+};
+
+async fn get_vk_registry(ctx: &BridgeTestContext) -> Option<VerifierKeyRegistry> {
+    let account = ctx
+        .client
+        .client
+        .get_account(instructions::get_vk_registry_pda(&ctx.program_id))
+        .await
+        .unwrap()?;
+    Some(*bytemuck::from_bytes::<VerifierKeyRegistry>(&account.data))
+}
+
+/// Only the vk authority can propose a new key, and the first proposal creates the registry
+#[tokio::test]
+async fn test_vk_update_requires_authority() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let impostor = Keypair::new();
+    let propose_ix = instructions::propose_vk_update(
+        ctx.program_id,
+        impostor.pubkey(),
+        ctx.client.payer.pubkey(),
+        VK_KIND_SINGLE_BLOCK_UPDATE,
+        [7u8; 32],
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[propose_ix], &[&impostor]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedVerifierKeyUpdate))
+    );
+    assert!(get_vk_registry(&ctx).await.is_none());
+
+    // without an admin the operator is the vk authority
+    let propose_ix = instructions::propose_vk_update(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        ctx.client.payer.pubkey(),
+        VK_KIND_SINGLE_BLOCK_UPDATE,
+        [7u8; 32],
+    );
+    ctx.client.send_tx(&[propose_ix], &[&ctx.client.operator]).await;
+
+    let registry = get_vk_registry(&ctx).await.unwrap();
+    let entry = registry.entries[VK_KIND_SINGLE_BLOCK_UPDATE as usize];
+    assert_eq!(entry.pending_vk, [7u8; 32]);
+    assert_eq!(entry.pending_version, 1);
+    assert_eq!(entry.current_version, 0);
+
+    // the timelock has not elapsed yet
+    let activate_ix = instructions::activate_vk_update(ctx.program_id, VK_KIND_SINGLE_BLOCK_UPDATE);
+    assert_eq!(
+        ctx.client.try_send_tx(&[activate_ix], &[]).await,
+        Err(bridge_error(DogeBridgeError::VerifierKeyUpdateTimelockNotElapsed))
+    );
+}
+
+/// Proofs for the replaced key keep verifying during the rollover window
+#[tokio::test]
+async fn test_vk_rollover_accepts_previous_key() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let propose_ix = instructions::propose_vk_update(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        ctx.client.payer.pubkey(),
+        VK_KIND_SINGLE_BLOCK_UPDATE,
+        [7u8; 32],
+    );
+    ctx.client.send_tx(&[propose_ix], &[&ctx.client.operator]).await;
+
+    let mut clock = ctx.client.client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += VK_UPDATE_DELAY_SECS as i64 + 1;
+    ctx.context.set_sysvar(&clock);
+
+    let activate_ix = instructions::activate_vk_update(ctx.program_id, VK_KIND_SINGLE_BLOCK_UPDATE);
+    ctx.client.send_tx(&[activate_ix], &[]).await;
+
+    let entry = get_vk_registry(&ctx).await.unwrap().entries[VK_KIND_SINGLE_BLOCK_UPDATE as usize];
+    assert_eq!(entry.current_vk, [7u8; 32]);
+    assert_eq!(entry.current_version, 1);
+
+    // the helper still proves against the built-in key, which is now the previous key
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    helper.add_funded_user(500_000_000).await.unwrap();
+}
+
+/// A block update without the registry account verifies against the built-in key until the registry is created
+#[tokio::test]
+async fn test_block_update_without_vk_registry_account() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    helper.omit_optional_block_update_accounts = true;
+    helper.add_funded_user(500_000_000).await.unwrap();
+    assert!(!ctx.client.get_bridge_state().await.has_vk_registry());
+
+    let propose_ix = instructions::propose_vk_update(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        ctx.client.payer.pubkey(),
+        VK_KIND_SINGLE_BLOCK_UPDATE,
+        [7u8; 32],
+    );
+    ctx.client.send_tx(&[propose_ix], &[&ctx.client.operator]).await;
+    assert!(ctx.client.get_bridge_state().await.has_vk_registry());
+
+    // once the registry exists leaving it out can't bring back a replaced key
+    let err = helper.mine_and_process_block(vec![]).await.unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<InstructionError>(),
+        Some(&bridge_error(DogeBridgeError::InvalidVerifierKeyRegistry))
+    );
+}
@@ -0,0 +1,86 @@
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{
+    block_transition_helper::BlockTransitionHelper,
+    test_client::bridge_error,
+    BridgeTestContext,
+};
+use psy_bridge_core::error::DogeBridgeError;
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Requests over the per-request or window limit are rejected until the authority raises the limits
+#[tokio::test]
+async fn test_withdrawal_limits() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user_pk = helper.add_funded_user(500_000_000).await.unwrap();
+    let user = helper.get_user_account(&user_pk);
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user_pk, &ctx.doge_mint);
+    let withdraw_ix = |amount_sats: u64, receipt_nonce: u64| {
+        instructions::request_withdrawal(
+            ctx.program_id,
+            ctx.client.payer.pubkey(),
+            user.pubkey(),
+            ctx.doge_mint,
+            user_ata,
+            [0xAB; 20],
+            amount_sats,
+            0,
+            receipt_nonce,
+        )
+    };
+
+    // without an admin the operator sets the limits
+    let impostor = Keypair::new();
+    let limits_ix = instructions::set_withdrawal_limits(
+        ctx.program_id,
+        impostor.pubkey(),
+        100_000_000,
+        150_000_000,
+        86400,
+        false,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[limits_ix], &[&impostor]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedWithdrawalLimitUpdate))
+    );
+
+    let limits_ix = instructions::set_withdrawal_limits(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        100_000_000,
+        150_000_000,
+        86400,
+        false,
+    );
+    ctx.client.send_tx(&[limits_ix], &[&ctx.client.operator]).await;
+
+    assert_eq!(
+        ctx.client.try_send_tx(&[withdraw_ix(100_000_001, 0)], &[&user]).await,
+        Err(bridge_error(DogeBridgeError::WithdrawalExceedsPerRequestLimit)),
+        "request over the per-request limit should fail"
+    );
+    ctx.client.send_tx(&[withdraw_ix(100_000_000, 0)], &[&user]).await;
+    assert_eq!(ctx.client.get_bridge_state().await.core_state.withdrawal_window_used_sats, 100_000_000);
+    assert_eq!(
+        ctx.client.try_send_tx(&[withdraw_ix(60_000_000, 1)], &[&user]).await,
+        Err(bridge_error(DogeBridgeError::WithdrawalWindowLimitExceeded)),
+        "request over the window limit should fail"
+    );
+
+    // resetting the window lets withdrawals through again
+    let limits_ix = instructions::set_withdrawal_limits(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        100_000_000,
+        150_000_000,
+        86400,
+        true,
+    );
+    ctx.client.send_tx(&[limits_ix], &[&ctx.client.operator]).await;
+    assert_eq!(ctx.client.get_bridge_state().await.core_state.withdrawal_window_used_sats, 0);
+    ctx.client.send_tx(&[withdraw_ix(60_000_001, 1)], &[&user]).await;
+}
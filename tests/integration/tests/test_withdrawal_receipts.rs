@@ -0,0 +1,433 @@
+use doge_bridge::error::BridgeError;
+use doge_bridge_client::instructions;
+use doge_bridge_test_utils::{
+    block_transition_helper::{BTAutoClaimedDeposit, BlockTransitionHelper},
+    test_client::bridge_error,
+    BridgeTestContext,
+};
+use psy_bridge_core::error::DogeBridgeError;
+use psy_doge_solana_core::{
+    data_accounts::withdrawal_receipt::WithdrawalReceipt,
+    program_state::{get_withdrawal_script_hash, PsyWithdrawalRequest, WithdrawalAddressType},
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction::SystemError,
+};
+
+async fn get_token_balance(ctx: &BridgeTestContext, token_account: Pubkey) -> u64 {
+    let account = ctx.client.client.get_account(token_account).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+/// A withdrawal request creates a pending receipt which can't be marked or closed before it is processed
+#[tokio::test]
+async fn test_withdrawal_request_creates_receipt() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user_pk = helper.add_funded_user(500_000_000).await.unwrap();
+    let user = helper.get_user_account(&user_pk);
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user_pk, &ctx.doge_mint);
+
+    let withdrawal_index = ctx.client.get_next_withdrawal_index().await;
+    let receipt_nonce = 7;
+    let withdraw_ix = instructions::request_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        [0xAB; 20],
+        100_000_000,
+        0,
+        receipt_nonce,
+    );
+    ctx.client.send_tx(&[withdraw_ix], &[user]).await;
+
+    let receipt_pda = instructions::get_withdrawal_receipt_pda(&ctx.program_id, &user_pk, receipt_nonce);
+    let receipt_account = ctx.client.client.get_account(receipt_pda).await.unwrap().unwrap();
+    let receipt: &WithdrawalReceipt = bytemuck::from_bytes(&receipt_account.data);
+    assert_eq!(receipt.requester, user_pk.to_bytes());
+    assert_eq!(receipt.receipt_nonce, receipt_nonce);
+    assert_eq!(receipt.withdrawal_index, withdrawal_index);
+    assert_eq!(receipt.amount_burned_sats, 100_000_000);
+    assert_eq!(receipt.request.recipient_address, [0xAB; 20]);
+    assert!(!receipt.is_fulfilled());
+
+    // the request has not been processed on dogecoin yet
+    let mark_ix = instructions::mark_withdrawal_receipts_fulfilled(ctx.program_id, &[receipt_pda]);
+    assert_eq!(
+        ctx.client.try_send_tx(&[mark_ix], &[]).await,
+        Err(bridge_error(DogeBridgeError::WithdrawalNotYetProcessed))
+    );
+
+    let close_ix = instructions::close_withdrawal_receipt(ctx.program_id, user_pk, receipt_nonce);
+    assert_eq!(
+        ctx.client.try_send_tx(&[close_ix], &[user]).await,
+        Err(bridge_error(DogeBridgeError::WithdrawalReceiptNotFulfilled))
+    );
+}
+
+/// A request can be cancelled and refunded until a snapshot covers it
+#[tokio::test]
+async fn test_cancel_withdrawal_before_snapshot() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user_pk = helper.add_funded_user(500_000_000).await.unwrap();
+    let user = helper.get_user_account(&user_pk);
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user_pk, &ctx.doge_mint);
+    let balance_before = get_token_balance(&ctx, user_ata).await;
+
+    let withdrawal_index = ctx.client.get_next_withdrawal_index().await;
+    let withdraw_ix = instructions::request_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        [0xAB; 20],
+        100_000_000,
+        0,
+        0,
+    );
+    ctx.client.send_tx(&[withdraw_ix], &[user]).await;
+    assert_eq!(get_token_balance(&ctx, user_ata).await, balance_before - 100_000_000);
+
+    // only the requester can cancel
+    let impostor = Keypair::new();
+    let mut impostor_cancel_ix =
+        instructions::cancel_withdrawal(ctx.program_id, user_pk, ctx.doge_mint, user_ata, 0);
+    impostor_cancel_ix.accounts[4] = AccountMeta::new_readonly(impostor.pubkey(), true);
+    assert_eq!(
+        ctx.client.try_send_tx(&[impostor_cancel_ix], &[&impostor]).await,
+        Err(bridge_error(DogeBridgeError::UnauthorizedWithdrawalCancel))
+    );
+
+    let cancel_ix =
+        instructions::cancel_withdrawal(ctx.program_id, user_pk, ctx.doge_mint, user_ata, 0);
+    ctx.client.send_tx(&[cancel_ix], &[user]).await;
+    assert_eq!(get_token_balance(&ctx, user_ata).await, balance_before);
+    // the tombstone takes the next leaf
+    assert_eq!(ctx.client.get_next_withdrawal_index().await, withdrawal_index + 2);
+
+    let receipt_pda = instructions::get_withdrawal_receipt_pda(&ctx.program_id, &user_pk, 0);
+    let receipt_account = ctx.client.client.get_account(receipt_pda).await.unwrap().unwrap();
+    let receipt: &WithdrawalReceipt = bytemuck::from_bytes(&receipt_account.data);
+    assert!(receipt.is_cancelled());
+
+    let close_ix = instructions::close_withdrawal_receipt(ctx.program_id, user_pk, 0);
+    ctx.client.send_tx(&[close_ix], &[user]).await;
+    assert!(ctx.client.client.get_account(receipt_pda).await.unwrap().is_none());
+
+    // once snapshotted the request is committed to the withdrawal circuit, the closed receipt's nonce is free again
+    let withdraw_ix = instructions::request_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        [0xAB; 20],
+        50_000_000,
+        0,
+        0,
+    );
+    ctx.client.send_tx(&[withdraw_ix], &[user]).await;
+
+    let snapshot_ix = instructions::snapshot_withdrawals(
+        ctx.program_id,
+        ctx.client.operator.pubkey(),
+        ctx.client.payer.pubkey(),
+    );
+    ctx.client.send_tx(&[snapshot_ix], &[&ctx.client.operator]).await;
+
+    let cancel_ix =
+        instructions::cancel_withdrawal(ctx.program_id, user_pk, ctx.doge_mint, user_ata, 0);
+    assert_eq!(
+        ctx.client.try_send_tx(&[cancel_ix], &[user]).await,
+        Err(bridge_error(DogeBridgeError::WithdrawalAlreadySnapshotted))
+    );
+}
+
+/// A request burning tokens of any mint other than the bridge's is rejected
+#[tokio::test]
+async fn test_withdrawal_request_rejects_foreign_mint() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let user = Keypair::new();
+    let (foreign_mint, foreign_ata) = ctx.client.create_foreign_token_account(&user.pubkey(), 500_000_000).await;
+    let withdraw_ix = instructions::request_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        foreign_mint,
+        foreign_ata,
+        [0xAB; 20],
+        100_000_000,
+        0,
+        0,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[withdraw_ix], &[&user]).await,
+        Err(InstructionError::Custom(BridgeError::InvalidAccountInput as u32))
+    );
+    assert_eq!(get_token_balance(&ctx, foreign_ata).await, 500_000_000);
+    assert_eq!(ctx.client.get_next_withdrawal_index().await, 0);
+}
+
+/// A batch burns the total once and creates a receipt for every request
+#[tokio::test]
+async fn test_batched_withdrawal_requests_create_receipts() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user_pk = helper.add_funded_user(500_000_000).await.unwrap();
+    let user = helper.get_user_account(&user_pk);
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user_pk, &ctx.doge_mint);
+    let balance_before = get_token_balance(&ctx, user_ata).await;
+
+    let requests = [
+        PsyWithdrawalRequest::new([0xAB; 20], 100_000_000, 0),
+        PsyWithdrawalRequest::new([0xCD; 20], 50_000_000, 1),
+        PsyWithdrawalRequest::new([0xEF; 20], 20_000_000, 0),
+    ];
+    let first_withdrawal_index = ctx.client.get_next_withdrawal_index().await;
+    let first_receipt_nonce = 10;
+
+    let batch_ix = instructions::request_withdrawals_batch(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        &requests,
+        first_receipt_nonce,
+    );
+
+    // every request needs its receipt account
+    let mut missing_receipt_ix = batch_ix.clone();
+    missing_receipt_ix.accounts.pop();
+    assert_eq!(
+        ctx.client.try_send_tx(&[missing_receipt_ix], &[user]).await,
+        Err(InstructionError::NotEnoughAccountKeys)
+    );
+
+    ctx.client.send_tx(&[batch_ix], &[user]).await;
+    assert_eq!(get_token_balance(&ctx, user_ata).await, balance_before - 170_000_000);
+    assert_eq!(
+        ctx.client.get_next_withdrawal_index().await,
+        first_withdrawal_index + requests.len() as u64
+    );
+
+    for (i, request) in requests.iter().enumerate() {
+        let receipt_nonce = first_receipt_nonce + i as u64;
+        let receipt_pda = instructions::get_withdrawal_receipt_pda(&ctx.program_id, &user_pk, receipt_nonce);
+        let receipt_account = ctx.client.client.get_account(receipt_pda).await.unwrap().unwrap();
+        let receipt: &WithdrawalReceipt = bytemuck::from_bytes(&receipt_account.data);
+        assert_eq!(receipt.receipt_nonce, receipt_nonce);
+        assert_eq!(receipt.withdrawal_index, first_withdrawal_index + i as u64);
+        assert_eq!(receipt.amount_burned_sats, request.amount_sats);
+        assert_eq!(receipt.request.recipient_address, request.recipient_address);
+        assert_eq!(receipt.request.address_type, request.address_type);
+        // each request pays its own flat fee plus 1%
+        assert_eq!(receipt.request.amount_sats, request.amount_sats - 1000 - request.amount_sats / 100);
+    }
+}
+
+/// A batch burning tokens of any mint other than the bridge's is rejected
+#[tokio::test]
+async fn test_batched_withdrawal_requests_reject_foreign_mint() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let user = Keypair::new();
+    let (foreign_mint, foreign_ata) = ctx.client.create_foreign_token_account(&user.pubkey(), 500_000_000).await;
+    let requests = [
+        PsyWithdrawalRequest::new([0xAB; 20], 100_000_000, 0),
+        PsyWithdrawalRequest::new([0xCD; 20], 50_000_000, 1),
+    ];
+    let batch_ix = instructions::request_withdrawals_batch(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        foreign_mint,
+        foreign_ata,
+        &requests,
+        0,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[batch_ix], &[&user]).await,
+        Err(InstructionError::Custom(BridgeError::InvalidAccountInput as u32))
+    );
+    assert_eq!(get_token_balance(&ctx, foreign_ata).await, 500_000_000);
+    assert_eq!(ctx.client.get_next_withdrawal_index().await, 0);
+}
+
+/// A script withdrawal commits to the script hash, the script comes from the instruction data or a generic buffer
+#[tokio::test]
+async fn test_script_withdrawal_commits_to_script_hash() {
+    let mut ctx = BridgeTestContext::new_initialized().await;
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user_pk = helper.add_funded_user(500_000_000).await.unwrap();
+    let user = helper.get_user_account(&user_pk);
+    let user_ata = spl_associated_token_account::get_associated_token_address(&user_pk, &ctx.doge_mint);
+
+    // OP_DUP OP_HASH160 <hash> OP_EQUALVERIFY OP_CHECKSIG followed by a <tag> OP_DROP memo
+    let mut script = vec![0x76, 0xa9, 0x14];
+    script.extend_from_slice(&[0x42; 20]);
+    script.extend_from_slice(&[0x88, 0xac, 0x04, 0x01, 0x02, 0x03, 0x04, 0x75]);
+
+    // a script hash can't be requested without its script
+    let hash_only_ix = instructions::request_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        get_withdrawal_script_hash(&script),
+        100_000_000,
+        WithdrawalAddressType::Script.into(),
+        0,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[hash_only_ix], &[user]).await,
+        Err(bridge_error(DogeBridgeError::WithdrawalScriptRequired))
+    );
+
+    let inline_ix = instructions::request_script_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        &script,
+        100_000_000,
+        0,
+        None,
+    );
+    ctx.client.send_tx(&[inline_ix], &[user]).await;
+
+    let buffer_pk = ctx.client.create_generic_buffer(&script).await;
+    let buffer_ix = instructions::request_script_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        ctx.doge_mint,
+        user_ata,
+        &[],
+        50_000_000,
+        1,
+        Some(buffer_pk),
+    );
+    ctx.client.send_tx(&[buffer_ix], &[user]).await;
+
+    for (receipt_nonce, amount_sats) in [(0, 100_000_000u64), (1, 50_000_000)] {
+        let receipt_pda = instructions::get_withdrawal_receipt_pda(&ctx.program_id, &user_pk, receipt_nonce);
+        let receipt_account = ctx.client.client.get_account(receipt_pda).await.unwrap().unwrap();
+        let receipt: &WithdrawalReceipt = bytemuck::from_bytes(&receipt_account.data);
+        assert_eq!(receipt.amount_burned_sats, amount_sats);
+        assert_eq!(
+            receipt.request,
+            PsyWithdrawalRequest::new_script(&script, amount_sats - 1000 - amount_sats / 100)
+        );
+    }
+}
+
+/// A script withdrawal burning tokens of any mint other than the bridge's is rejected
+#[tokio::test]
+async fn test_script_withdrawal_rejects_foreign_mint() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let user = Keypair::new();
+    let (foreign_mint, foreign_ata) = ctx.client.create_foreign_token_account(&user.pubkey(), 500_000_000).await;
+    let mut script = vec![0x76, 0xa9, 0x14];
+    script.extend_from_slice(&[0x42; 20]);
+    script.extend_from_slice(&[0x88, 0xac]);
+    let script_ix = instructions::request_script_withdrawal(
+        ctx.program_id,
+        ctx.client.payer.pubkey(),
+        user.pubkey(),
+        foreign_mint,
+        foreign_ata,
+        &script,
+        100_000_000,
+        0,
+        None,
+    );
+    assert_eq!(
+        ctx.client.try_send_tx(&[script_ix], &[&user]).await,
+        Err(InstructionError::Custom(BridgeError::InvalidAccountInput as u32))
+    );
+    assert_eq!(get_token_balance(&ctx, foreign_ata).await, 500_000_000);
+    assert_eq!(ctx.client.get_next_withdrawal_index().await, 0);
+}
+
+/// Receipts are keyed by the requester's nonce, requests built against the same tree index don't collide
+#[tokio::test]
+async fn test_concurrent_withdrawal_requests_get_own_receipts() {
+    let ctx = BridgeTestContext::new_initialized().await;
+
+    let mut helper = BlockTransitionHelper::new_from_client(ctx.client.clone())
+        .await
+        .unwrap();
+    let user1_pk = helper.add_user();
+    let user2_pk = helper.add_user();
+    helper
+        .mine_and_process_block(vec![
+            BTAutoClaimedDeposit::new(user1_pk.to_bytes(), 500_000_000, 100),
+            BTAutoClaimedDeposit::new(user2_pk.to_bytes(), 500_000_000, 101),
+        ])
+        .await
+        .unwrap();
+    let user1 = helper.get_user_account(&user1_pk);
+    let user2 = helper.get_user_account(&user2_pk);
+    let user1_ata = spl_associated_token_account::get_associated_token_address(&user1_pk, &ctx.doge_mint);
+    let user2_ata = spl_associated_token_account::get_associated_token_address(&user2_pk, &ctx.doge_mint);
+
+    // both requests are built before either lands, against the same next index
+    let withdrawal_index = ctx.client.get_next_withdrawal_index().await;
+    let withdraw_ix = |user: &Keypair, user_ata: Pubkey, amount_sats: u64, receipt_nonce: u64| {
+        instructions::request_withdrawal(
+            ctx.program_id,
+            ctx.client.payer.pubkey(),
+            user.pubkey(),
+            ctx.doge_mint,
+            user_ata,
+            [0xAB; 20],
+            amount_sats,
+            0,
+            receipt_nonce,
+        )
+    };
+    let user1_ix = withdraw_ix(user1, user1_ata, 100_000_000, 0);
+    let user2_ix = withdraw_ix(user2, user2_ata, 50_000_000, 0);
+    ctx.client.send_tx(&[user1_ix], &[user1]).await;
+    ctx.client.send_tx(&[user2_ix], &[user2]).await;
+
+    for (user_pk, withdrawal_index) in [(user1_pk, withdrawal_index), (user2_pk, withdrawal_index + 1)] {
+        let receipt_pda = instructions::get_withdrawal_receipt_pda(&ctx.program_id, &user_pk, 0);
+        let receipt_account = ctx.client.client.get_account(receipt_pda).await.unwrap().unwrap();
+        let receipt: &WithdrawalReceipt = bytemuck::from_bytes(&receipt_account.data);
+        assert_eq!(receipt.requester, user_pk.to_bytes());
+        assert_eq!(receipt.withdrawal_index, withdrawal_index);
+    }
+
+    // a nonce can't be reused while its receipt is open
+    assert_eq!(
+        ctx.client.try_send_tx(&[withdraw_ix(user1, user1_ata, 20_000_000, 0)], &[user1]).await,
+        Err(InstructionError::Custom(SystemError::AccountAlreadyInUse as u32))
+    );
+}
@@ -833,12 +833,14 @@ async fn test_execute_snapshot_withdrawals_with_pending() {
         .send_tx(
             &[doge_bridge_client::instructions::request_withdrawal(
                 ctx.client.program_ids.doge_bridge,
+                ctx.client.payer.pubkey(),
                 user1,
                 ctx.client.doge_mint,
                 user1_ata,
                 [0xAA; 20],
                 100_000_000,
                 0,
+                0,
             )],
             &[&Keypair::from_bytes(&user1_keypair.to_bytes()).unwrap()],
         )
@@ -852,12 +854,14 @@ async fn test_execute_snapshot_withdrawals_with_pending() {
         .send_tx(
             &[doge_bridge_client::instructions::request_withdrawal(
                 ctx.client.program_ids.doge_bridge,
+                ctx.client.payer.pubkey(),
                 user2,
                 ctx.client.doge_mint,
                 user2_ata,
                 [0xBB; 20],
                 50_000_000,
                 0,
+                0,
             )],
             &[&Keypair::from_bytes(&user2_keypair.to_bytes()).unwrap()],
         )
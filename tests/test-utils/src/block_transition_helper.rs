@@ -2,10 +2,18 @@ use std::collections::HashMap;
 
 use doge_bridge::state::BridgeState;
 use doge_bridge_client::instructions::{
-    block_update, process_mint_group, process_mint_group_auto_advance, process_reorg_blocks,
+    block_hash_archive_accounts, block_update, process_mint_group, process_mint_group_auto_advance, process_reorg_blocks,
+    reorg_block_hash_archive_accounts,
+};
+use psy_bridge_core::{
+    common_types::QHash256,
+    crypto::hash::{merkle::append::update_siblings_append_merkle_tree, sha256::SHA256_ZERO_HASHES, sha256_impl::hash_impl_sha256_bytes},
+    dogecoin::chain_tracker::HeaderChainTracker,
+    error::QDogeResult,
+    header::PsyBridgeTipStateCommitment,
 };
-use psy_bridge_core::{crypto::hash::sha256_impl::hash_impl_sha256_bytes, error::QDogeResult, header::PsyBridgeTipStateCommitment};
 use psy_doge_solana_core::{
+    constants::DOGE_BLOCK_MERKLE_TREE_HEIGHT,
     data_accounts::pending_mint::{PendingMint, PM_MAX_PENDING_MINTS_PER_GROUP},
     generic_cpi::{AutoClaimMintBufferAddressHelper, LockAutoClaimMintBufferCPIHelper},
     program_state::{compute_mint_group_info, FinalizedBlockMintTxoInfo},
@@ -21,6 +29,9 @@ use crate::{
     builders::pending_mints_buffer_builder::PendingMintsGroupsBufferBuilder, mock_data::{generate_block_update_fake_proof, generate_block_update_reorg_fake_proof}, test_client::TestBridgeClient
 };
 
+// bridge state, both buffers, operator, payer and both buffer programs
+const BLOCK_UPDATE_REQUIRED_ACCOUNTS: usize = 7;
+
 #[derive(Debug, Clone, PartialEq, Eq, Copy, Hash)]
 pub struct BTAutoClaimedDeposit {
     pub depositor_pubkey: [u8; 32],
@@ -42,6 +53,13 @@ pub struct BlockTransitionHelper {
     pub client: TestBridgeClient,
     pub user_accounts: HashMap<Pubkey, Keypair>,
     pub current_txo_batch_id: u32,
+    // block merkle tree of the mined blocks, the heights below the block the helper started from are left empty
+    pub block_tree_next_siblings: Vec<QHash256>,
+    pub finalized_block_siblings: Vec<QHash256>,
+    // send block updates with only the accounts a client from before the optional trailing accounts would pass
+    pub omit_optional_block_update_accounts: bool,
+    // when set, mined blocks take their hashes from the tracker's best chain instead of mock_block_hash
+    pub header_chain: Option<HeaderChainTracker>,
 }
 impl BlockTransitionHelper {
     pub fn get_user_account(&mut self, user_pubkey: &Pubkey) -> &Keypair {
@@ -53,6 +71,38 @@ impl BlockTransitionHelper {
         self.user_accounts.insert(user_pubkey, user);
         user_pubkey
     }
+    /// Adds a user and mines a block auto claiming a deposit of amount_sats to them
+    pub async fn add_funded_user(&mut self, amount_sats: u64) -> anyhow::Result<Pubkey> {
+        let user_pubkey = self.add_user();
+        self.mine_and_process_block(vec![BTAutoClaimedDeposit::new(user_pubkey.to_bytes(), amount_sats, 100)])
+            .await?;
+        Ok(user_pubkey)
+    }
+    pub fn mock_block_hash(block_height: u32) -> QHash256 {
+        hash_impl_sha256_bytes(&block_height.to_le_bytes())
+    }
+    fn get_block_hash(&self, block_height: u32) -> anyhow::Result<QHash256> {
+        match &self.header_chain {
+            Some(header_chain) => header_chain
+                .get_block_at_height(block_height)
+                .map(|block| block.hash)
+                .map_err(|e| anyhow::anyhow!("block {} is not on the tracked best chain: {:?}", block_height, e)),
+            None => Ok(Self::mock_block_hash(block_height)),
+        }
+    }
+    // appends the block at block_height to the block merkle tree, returns the new root
+    fn append_block_to_tree(&mut self, block_hash: QHash256, block_height: u32) -> QHash256 {
+        self.finalized_block_siblings = (0..DOGE_BLOCK_MERKLE_TREE_HEIGHT)
+            .map(|i| {
+                if (block_height >> i) & 1 == 1 {
+                    self.block_tree_next_siblings[i]
+                } else {
+                    SHA256_ZERO_HASHES[i]
+                }
+            })
+            .collect();
+        update_siblings_append_merkle_tree(&mut self.block_tree_next_siblings, block_hash, block_height as u64)
+    }
 }
 
 pub struct MockMintBufferLocker {
@@ -83,12 +133,19 @@ impl BlockTransitionHelper {
         let bridge_state: &BridgeState = bytemuck::from_bytes(&bridge_account.data);
         let bridge_state = bridge_state.clone();
 
-        Ok(Self {
+        let finalized_state = bridge_state.core_state.bridge_header.finalized_state;
+        let mut helper = Self {
             bridge_state,
             client,
             user_accounts: HashMap::new(),
             current_txo_batch_id: 0,
-        })
+            block_tree_next_siblings: SHA256_ZERO_HASHES[..DOGE_BLOCK_MERKLE_TREE_HEIGHT].to_vec(),
+            finalized_block_siblings: vec![],
+            omit_optional_block_update_accounts: false,
+            header_chain: None,
+        };
+        helper.append_block_to_tree(finalized_state.block_hash, finalized_state.block_height);
+        Ok(helper)
     }
 
     async fn prepare_block_data_offline(
@@ -150,6 +207,11 @@ impl BlockTransitionHelper {
 
         let mut new_header = self.bridge_state.core_state.bridge_header.clone();
         new_header.finalized_state.block_height += 1;
+        new_header.finalized_state.block_hash = self.get_block_hash(new_header.finalized_state.block_height)?;
+        new_header.finalized_state.block_merkle_tree_root = self.append_block_to_tree(
+            new_header.finalized_state.block_hash,
+            new_header.finalized_state.block_height,
+        );
         new_header.finalized_state.pending_mints_finalized_hash = pending_mints_hash;
         new_header.finalized_state.txo_output_list_finalized_hash = txo_buffer_hash;
         new_header.finalized_state.auto_claimed_deposits_next_index += pending_mints.len() as u32;
@@ -199,7 +261,7 @@ impl BlockTransitionHelper {
             pending_mints.len()
         );
 
-        let update_ix = block_update(
+        let mut update_ix = block_update(
             self.client.program_id,
             self.client.payer.pubkey(),
             proof,
@@ -210,8 +272,15 @@ impl BlockTransitionHelper {
             mint_bump,
             txo_bump,
         );
+        update_ix.accounts.extend(block_hash_archive_accounts(
+            &self.client.program_id,
+            self.client.get_block_hash_archive_len().await,
+        ));
+        if self.omit_optional_block_update_accounts {
+            update_ix.accounts.truncate(BLOCK_UPDATE_REQUIRED_ACCOUNTS);
+        }
         println!("Sending Block Update Transaction...");
-        self.client.send_tx(&[update_ix], &[]).await;
+        self.client.try_send_tx(&[update_ix], &[]).await?;
         println!("Sent block update transaction.");
         self.bridge_state.core_state.bridge_header = new_header;
 
@@ -333,7 +402,16 @@ impl BlockTransitionHelper {
             &self.client.txo_buffer_program_id,
         );
 
+        // the archive needs the hashes of the heights between the previous and the new finalized block
+        let mut reorg_block_hashes = self.finalized_block_siblings.clone();
         let mut new_header = self.bridge_state.core_state.bridge_header.clone();
+        for block_height in start_height..start_height + blocks.len() as u32 {
+            let block_hash = self.get_block_hash(block_height)?;
+            new_header.finalized_state.block_merkle_tree_root = self.append_block_to_tree(block_hash, block_height);
+            new_header.finalized_state.block_hash = block_hash;
+            reorg_block_hashes.push(block_hash);
+        }
+        reorg_block_hashes.pop();
         new_header.finalized_state.block_height = start_height + blocks.len() as u32 - 1;
         new_header.tip_state.block_height = new_header.finalized_state.block_height;
         let last_info = block_infos.last().unwrap();
@@ -367,7 +445,7 @@ impl BlockTransitionHelper {
         );
         let proof = generate_block_update_reorg_fake_proof(pub_inputs);
 
-        let reorg_ix = process_reorg_blocks(
+        let mut reorg_ix = process_reorg_blocks(
             self.client.program_id,
             self.client.payer.pubkey(),
             proof,
@@ -379,6 +457,18 @@ impl BlockTransitionHelper {
             mint_bump,
             txo_bump,
         );
+        let block_hash_archive_len = self.client.get_block_hash_archive_len().await;
+        let reorg_block_hashes_buffer = if block_hash_archive_len != 0 && blocks.len() > 1 {
+            Some(self.client.create_generic_buffer(bytemuck::cast_slice(&reorg_block_hashes)).await)
+        } else {
+            None
+        };
+        reorg_ix.accounts.extend(reorg_block_hash_archive_accounts(
+            &self.client.program_id,
+            block_hash_archive_len,
+            blocks.len() as u32,
+            reorg_block_hashes_buffer,
+        ));
         self.client.send_tx(&[reorg_ix], &[]).await;
 
         self.bridge_state.core_state.bridge_header = new_header;
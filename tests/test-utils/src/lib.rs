@@ -102,4 +102,18 @@ impl BridgeTestContext {
             client: test_client,
         }
     }
+
+    /// Context with the bridge initialized from default_initialize_params, payer as operator and fee spender
+    pub async fn new_initialized() -> Self {
+        let ctx = Self::new().await;
+        let init_ix = doge_bridge_client::instructions::initialize_bridge(
+            ctx.client.payer.pubkey(),
+            ctx.client.operator.pubkey(),
+            ctx.client.fee_spender.pubkey(),
+            ctx.doge_mint,
+            &mock_data::default_initialize_params(),
+        );
+        ctx.client.send_tx(&[init_ix], &[]).await;
+        ctx
+    }
 }
\ No newline at end of file
@@ -4,7 +4,35 @@ use psy_bridge_core::{
         hash::sha256_impl::hash_impl_sha256_bytes, zk::CompactBridgeZKProof
     }, header::{PsyBridgeHeader, PsyBridgeStateCommitment, PsyBridgeTipStateCommitment}
 };
-use psy_doge_solana_core::{data_accounts::pending_mint::{PM_DA_DEFAULT_PENDING_MINTS_BUFFER_HASH, PM_TXO_DEFAULT_BUFFER_HASH, PendingMint}, fake_zkp::FakeZKProofGenerator};
+use psy_doge_solana_core::{data_accounts::pending_mint::{PM_DA_DEFAULT_PENDING_MINTS_BUFFER_HASH, PM_TXO_DEFAULT_BUFFER_HASH, PendingMint}, fake_zkp::FakeZKProofGenerator, instructions::doge_bridge::InitializeBridgeParams, program_state::{PsyBridgeConfig, PsyReturnTxOutput}};
+
+/// Bridge at height 0 with 1% + 1000 sat fees, the config the feature tests initialize with
+pub fn default_initialize_params() -> InitializeBridgeParams {
+    InitializeBridgeParams {
+        bridge_header: PsyBridgeHeader {
+            tip_state: PsyBridgeTipStateCommitment::default(),
+            finalized_state: PsyBridgeStateCommitment::default(),
+            bridge_state_hash: [0u8; 32],
+            last_rollback_at_secs: 0,
+            paused_until_secs: 0,
+            total_finalized_fees_collected_chain_history: 0,
+        },
+        start_return_txo_output: PsyReturnTxOutput {
+            sighash: [0u8; 32],
+            output_index: 0,
+            amount_sats: 0,
+        },
+        config_params: PsyBridgeConfig {
+            deposit_fee_rate_numerator: 1,
+            deposit_fee_rate_denominator: 100,
+            withdrawal_fee_rate_numerator: 1,
+            withdrawal_fee_rate_denominator: 100,
+            deposit_flat_fee_sats: 1000,
+            withdrawal_flat_fee_sats: 1000,
+        },
+        custodian_wallet_config_hash: [1u8; 32],
+    }
+}
 
 pub fn generate_fake_header(height: u32) -> PsyBridgeHeader {
     let empty_hash = [0u8; 32];
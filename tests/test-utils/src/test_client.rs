@@ -1,20 +1,27 @@
 use std::collections::HashMap;
 
+use doge_bridge::state::BridgeState;
 use doge_bridge_client::instructions;
 use psy_doge_solana_core::data_accounts::pending_mint::{
     PendingMint, PM_DA_PENDING_MINT_SIZE as PENDING_MINT_SIZE, PM_MAX_PENDING_MINTS_PER_GROUP,
 };
+use psy_bridge_core::error::DogeBridgeError;
 use solana_program_test::BanksClient;
 use solana_sdk::{
-    instruction::Instruction,
+    instruction::{Instruction, InstructionError},
     program_pack::Pack,
     pubkey::Pubkey,
     signature::{Keypair, Signer},
     system_instruction,
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
 use spl_token::instruction::{set_authority, AuthorityType};
 
+/// The instruction error the bridge program fails with for a core bridge error
+pub fn bridge_error(error: DogeBridgeError) -> InstructionError {
+    InstructionError::Custom(error as u32)
+}
+
 pub struct UserManager {
     pub user_map: HashMap<Pubkey, Keypair>,
 }
@@ -66,6 +73,21 @@ impl TestBridgeClient {
         println!("tx_size: {}", tx.message.serialize().len());
         self.client.process_transaction(tx).await.unwrap();
     }
+
+    /// Like send_tx, but returns the error of the failing instruction so tests can assert on it
+    pub async fn try_send_tx(&self, ixs: &[Instruction], extra_signers: &[&Keypair]) -> Result<(), InstructionError> {
+        let recent_blockhash = self.client.get_latest_blockhash().await.unwrap();
+        let mut signers = vec![&self.payer];
+        signers.extend_from_slice(extra_signers);
+        let tx = Transaction::new_signed_with_payer(ixs, Some(&self.payer.pubkey()), &signers, recent_blockhash);
+        match self.client.process_transaction(tx).await {
+            Ok(()) => Ok(()),
+            Err(err) => match err.unwrap() {
+                TransactionError::InstructionError(_, instruction_error) => Err(instruction_error),
+                other => panic!("transaction failed outside of an instruction: {:?}", other),
+            },
+        }
+    }
     
     pub async fn create_token_ata_if_needed(&mut self, mint: Pubkey, user_authority: &Keypair) -> u64 {
         let user_token_account = spl_associated_token_account::get_associated_token_address(&user_authority.pubkey(), &mint);
@@ -85,6 +107,24 @@ impl TestBridgeClient {
         }
     }
 
+    /// Creates a mint the bridge doesn't control and a token account for owner holding amount of it
+    pub async fn create_foreign_token_account(&self, owner: &Pubkey, amount: u64) -> (Pubkey, Pubkey) {
+        let mint = Keypair::new();
+        let rent = self.client.get_rent().await.unwrap();
+        let create_mint_ixs = [
+            system_instruction::create_account(&self.payer.pubkey(), &mint.pubkey(), rent.minimum_balance(spl_token::state::Mint::LEN), spl_token::state::Mint::LEN as u64, &spl_token::id()),
+            spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &self.payer.pubkey(), None, 8).unwrap(),
+        ];
+        self.send_tx(&create_mint_ixs, &[&mint]).await;
+        let token_account = spl_associated_token_account::get_associated_token_address(owner, &mint.pubkey());
+        let fund_ixs = [
+            spl_associated_token_account::instruction::create_associated_token_account(&self.payer.pubkey(), owner, &mint.pubkey(), &spl_token::id()),
+            spl_token::instruction::mint_to(&spl_token::id(), &mint.pubkey(), &token_account, &self.payer.pubkey(), &[], amount).unwrap(),
+        ];
+        self.send_tx(&fund_ixs, &[]).await;
+        (mint.pubkey(), token_account)
+    }
+
     pub async fn create_generic_buffer(&mut self, data: &[u8]) -> Pubkey {
         let buffer_account = Keypair::new();
         let buffer_pubkey = buffer_account.pubkey();
@@ -178,6 +218,22 @@ impl TestBridgeClient {
         buffer_pubkey
     }
 
+    /// Current on-chain bridge state
+    pub async fn get_bridge_state(&self) -> BridgeState {
+        let bridge_account = self.client.get_account(self.bridge_state_pda).await.unwrap().unwrap();
+        *bytemuck::from_bytes::<BridgeState>(&bridge_account.data)
+    }
+
+    /// Index the next withdrawal request will be assigned, it keys the request's receipt PDA
+    pub async fn get_next_withdrawal_index(&self) -> u64 {
+        self.get_bridge_state().await.core_state.requested_withdrawals_tree.next_index
+    }
+
+    /// Number of blocks in the block hash archive, picks the segment a block update appends to
+    pub async fn get_block_hash_archive_len(&self) -> u64 {
+        self.get_bridge_state().await.core_state.block_hash_archive_len
+    }
+
     /// Get mint buffer PDA (derived from operator key)
     pub fn get_mint_buffer_pda(&self) -> Pubkey {
         let operator_pubkey = self.operator.pubkey().to_bytes();